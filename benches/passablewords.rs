@@ -1,25 +1,232 @@
-#![feature(test)]
-extern crate test;
+#[macro_use]
+extern crate criterion;
 extern crate passablewords;
 
-use passablewords::{check_entropy, check_length, check_password, check_uniqueness};
+use criterion::{black_box, BenchmarkId, Criterion, Throughput};
+use passablewords::{
+    check_entropy, check_entropy_with, check_length, check_password, check_uniqueness,
+    AnalysisProfile, CheckContext, Normalizer, PasswordChecker, Policy, Wordlist,
+};
+use std::sync::Arc;
 
-#[bench]
-fn bench_check_short_password(b: &mut test::Bencher) {
-    b.iter(|| check_length("short"));
+const PASSWORDS: &[&str] = &[
+    "password",
+    "letmein",
+    "Th1s iS a Sup3rR4ndom PassW0rd!",
+    "correct-horse-battery-staple",
+    "!password2024!",
+];
+
+fn bench_check_short_password(c: &mut Criterion) {
+    c.bench_function("check_short_password", |b| {
+        b.iter(|| check_length(black_box("short")))
+    });
+}
+
+fn bench_check_common_password(c: &mut Criterion) {
+    // Load the bundled list up front so the first sample doesn't include reading it
+    Wordlist::bundled();
+
+    c.bench_function("check_common_password", |b| {
+        b.iter(|| check_uniqueness(black_box("password")))
+    });
+}
+
+fn bench_check_common_variant_password(c: &mut Criterion) {
+    let checker = PasswordChecker::new().with_policy(Policy::new().strip_common_affixes(true));
+
+    c.bench_function("check_common_variant_password", |b| {
+        b.iter(|| checker.check_uniqueness(black_box("!password2024!")))
+    });
 }
 
-#[bench]
-fn bench_check_common_password(b: &mut test::Bencher) {
-    b.iter(|| check_uniqueness("password"));
+fn bench_check_denied_password(c: &mut Criterion) {
+    let context = CheckContext::new().deny_exact(&["Temp Pass 2931"]);
+
+    c.bench_function("check_denied_password", |b| {
+        b.iter(|| context.check_denied(black_box("TEMPPASS2931")))
+    });
+}
+
+fn bench_check_simple_password(c: &mut Criterion) {
+    c.bench_function("check_simple_password", |b| {
+        b.iter(|| check_entropy(black_box("Not Too Random")))
+    });
 }
 
-#[bench]
-fn bench_check_simple_password(b: &mut test::Bencher) {
-    b.iter(|| check_entropy("Not Too Random"));
+fn bench_check_ok_password(c: &mut Criterion) {
+    c.bench_function("check_ok_password", |b| {
+        b.iter(|| check_password(black_box("Th1s iS a Sup3rR4ndom PassW0rd!")))
+    });
 }
 
-#[bench]
-fn bench_check_ok_password(b: &mut test::Bencher) {
-    b.iter(|| check_password("Th1s iS a Sup3rR4ndom PassW0rd!"));
+fn bench_entropy_profiles(c: &mut Criterion) {
+    let long = "Th1s iS a Sup3rR4ndom PassW0rd!".repeat(4);
+    let passwords = [
+        ("weak", "aaaaaaaaaaaaaaab"),
+        ("ok", "Th1s iS a Sup3rR4ndom PassW0rd!"),
+        ("long", long.as_str()),
+    ];
+    let mut group = c.benchmark_group("entropy_profile");
+
+    for &(name, password) in passwords.iter() {
+        group.bench_with_input(
+            BenchmarkId::new("thorough", name),
+            password,
+            |b, password| {
+                b.iter(|| check_entropy_with(black_box(password), AnalysisProfile::Thorough))
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("fast", name), password, |b, password| {
+            b.iter(|| check_entropy_with(black_box(password), AnalysisProfile::Fast))
+        });
+    }
+    group.finish();
+}
+
+fn bench_wordlist_lookups(c: &mut Criterion) {
+    let hash_set = Wordlist::bundled().unwrap();
+    let constant_time = hash_set.constant_time();
+    let mut group = c.benchmark_group("wordlist_lookup");
+
+    group.throughput(Throughput::Elements(PASSWORDS.len() as u64));
+    group.bench_function(BenchmarkId::new("hash_set", PASSWORDS.len()), |b| {
+        b.iter(|| {
+            for password in PASSWORDS {
+                black_box(hash_set.contains(black_box(password)));
+            }
+        })
+    });
+    group.bench_function(BenchmarkId::new("constant_time", PASSWORDS.len()), |b| {
+        b.iter(|| {
+            for password in PASSWORDS {
+                black_box(constant_time.contains(black_box(password)));
+            }
+        })
+    });
+    group.finish();
 }
+
+fn bench_multiple_wordlists(c: &mut Criterion) {
+    let contents = include_str!("../passablewords-data/src/common-passwords.txt");
+    let wordlists: Vec<Arc<Wordlist>> = contents
+        .lines()
+        .collect::<Vec<_>>()
+        .chunks(20_000)
+        .take(5)
+        .map(|chunk| Arc::new(chunk.iter().cloned().collect()))
+        .collect();
+    let checker = wordlists.iter().enumerate().fold(
+        PasswordChecker::with_wordlist(Wordlist::default()),
+        |checker, (index, wordlist)| {
+            checker.with_labelled_wordlist(&format!("list {}", index), wordlist.clone())
+        },
+    );
+    let mut group = c.benchmark_group("multiple_wordlists");
+
+    group.throughput(Throughput::Elements(PASSWORDS.len() as u64));
+    // Hashes every password once per list
+    group.bench_function(BenchmarkId::new("separately", wordlists.len()), |b| {
+        b.iter(|| {
+            for password in PASSWORDS {
+                black_box(
+                    wordlists
+                        .iter()
+                        .any(|wordlist| wordlist.contains(black_box(password))),
+                );
+            }
+        })
+    });
+    // Hashes every password once, and reuses the hash for each list
+    group.bench_function(BenchmarkId::new("checker", wordlists.len()), |b| {
+        b.iter(|| {
+            for password in PASSWORDS {
+                black_box(checker.check_uniqueness(black_box(password)).is_err());
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_audit_normalization(c: &mut Criterion) {
+    let contents = include_str!("../passablewords-data/src/common-passwords.txt");
+    // Audited passwords are mostly mixed case, unlike the list, and some are passphrases
+    let rows: Vec<String> = contents
+        .lines()
+        .take(100_000)
+        .enumerate()
+        .map(|(index, line)| match index % 3 {
+            0 => line.to_uppercase(),
+            1 => format!("{}Summer2024!", line),
+            _ => format!("{}CorrectHorseBatteryStaple", line),
+        })
+        .collect();
+    let mut group = c.benchmark_group("audit_normalize");
+
+    group.sample_size(10);
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    // The default substitutions are all single characters, so ASCII rows use the lookup table,
+    // and with the `simd` feature lowercase runs of letters eight at a time
+    group.bench_function("ascii", |b| {
+        let normalizer = Normalizer::new();
+
+        b.iter(|| {
+            for row in &rows {
+                black_box(normalizer.normalize(black_box(row)));
+            }
+        })
+    });
+    // A substitution of more than one character sends every row down the general path
+    group.bench_function("per_character", |b| {
+        let normalizer = Normalizer::new().with_substitutions(vec![("()", 'o')]);
+
+        b.iter(|| {
+            for row in &rows {
+                black_box(normalizer.normalize(black_box(row)));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_wordlist_loading(c: &mut Criterion) {
+    let contents = include_str!("../passablewords-data/src/common-passwords.txt");
+    let mut group = c.benchmark_group("wordlist_load");
+
+    group.sample_size(10);
+    group.bench_function("from_file", |b| {
+        b.iter(|| {
+            Wordlist::from_file(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/passablewords-data/src/common-passwords.txt"
+            ))
+            .unwrap()
+        })
+    });
+    group.bench_function("parse", |b| b.iter(|| Wordlist::parse(black_box(contents))));
+    group.bench_function("constant_time", |b| {
+        let wordlist = Wordlist::parse(contents);
+
+        b.iter(|| wordlist.constant_time())
+    });
+    group.finish();
+}
+
+criterion_group!(
+    checks,
+    bench_check_short_password,
+    bench_check_common_password,
+    bench_check_common_variant_password,
+    bench_check_denied_password,
+    bench_check_simple_password,
+    bench_check_ok_password,
+    bench_entropy_profiles
+);
+criterion_group!(
+    wordlists,
+    bench_wordlist_lookups,
+    bench_multiple_wordlists,
+    bench_audit_normalization,
+    bench_wordlist_loading
+);
+criterion_main!(checks, wordlists);
@@ -0,0 +1,27 @@
+//! Checks each password passed on the command line, exiting with an error if any are rejected.
+//! It runs anywhere, but exists to prove that the crate works under WASI runtimes like wasmtime.
+//! See the README for how to build and run it.
+
+extern crate passablewords;
+
+use passablewords::check_password;
+use std::env;
+use std::process;
+
+fn main() {
+    let mut rejected = false;
+
+    for (index, password) in env::args().skip(1).enumerate() {
+        match check_password(&password) {
+            Ok(()) => println!("password {}: ok", index + 1),
+            Err(err) => {
+                println!("password {}: {}", index + 1, err.explanation());
+                rejected = true;
+            }
+        }
+    }
+
+    if rejected {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,22 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate passablewords;
+
+use passablewords::{check_password, CheckContext, PasswordChecker, Policy};
+use std::str;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(password) = str::from_utf8(data) {
+        let checker = PasswordChecker::new().with_policy(
+            Policy::new()
+                .min_length_or_words(16, 3)
+                .strip_common_affixes(true),
+        );
+        let context = CheckContext::new().deny_exact(&[password]);
+
+        let _ = check_password(password);
+        let _ = checker.check_password(password);
+        let _ = checker.check_password_with_context(password, &context);
+    }
+});
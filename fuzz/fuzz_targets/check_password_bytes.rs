@@ -0,0 +1,20 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate passablewords;
+
+use passablewords::{check_password_bytes, check_password_utf16, InvalidUtf8};
+
+fuzz_target!(|data: &[u8]| {
+    let _ = check_password_bytes(data, InvalidUtf8::Reject);
+    let _ = check_password_bytes(data, InvalidUtf8::Replace);
+    let _ = check_password_bytes(data, InvalidUtf8::Strip);
+
+    let utf16: Vec<u16> = data
+        .chunks(2)
+        .map(|pair| u16::from(pair[0]) | pair.get(1).map_or(0, |&high| u16::from(high) << 8))
+        .collect();
+
+    let _ = check_password_utf16(&utf16, InvalidUtf8::Reject);
+    let _ = check_password_utf16(&utf16, InvalidUtf8::Strip);
+});
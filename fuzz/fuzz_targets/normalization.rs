@@ -0,0 +1,38 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate passablewords;
+
+use passablewords::{CheckContext, PasswordChecker, PasswordError, Policy, Wordlist};
+use std::str;
+
+/// Normalizing is cheap enough to also try much larger inputs than libFuzzer generates, so each
+/// input is checked again after being repeated up to this many bytes.
+const MAX_REPEATED_LENGTH: usize = 4 * 1024 * 1024;
+
+fn check(password: &str, checker: &PasswordChecker) {
+    let context = CheckContext::new().deny_exact(&[password]);
+    let denied = context.check_denied(password);
+
+    // A value always matches itself, unless normalizing leaves nothing to compare
+    if !password.chars().all(char::is_whitespace) {
+        assert_eq!(denied, Err(PasswordError::MatchesDeniedValue));
+    }
+
+    let _ = checker.check_uniqueness(password);
+}
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(password) = str::from_utf8(data) {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein\n123456"))
+            .with_policy(Policy::new().strip_common_affixes(true));
+
+        check(password, &checker);
+
+        if !password.is_empty() {
+            let repeated = password.repeat(MAX_REPEATED_LENGTH / password.len());
+
+            check(&repeated, &checker);
+        }
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate passablewords;
+
+use passablewords::Wordlist;
+use std::str;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(contents) = str::from_utf8(data) {
+        let wordlist = Wordlist::parse(contents);
+        let constant_time = wordlist.constant_time();
+
+        assert_eq!(wordlist.len(), constant_time.len());
+
+        for line in contents.lines().filter(|line| !line.is_empty()) {
+            assert!(wordlist.contains(line));
+            assert!(constant_time.contains(line));
+        }
+    }
+});
@@ -0,0 +1,247 @@
+//! Estimating how hard a password would be to guess, so that zxcvbn can be swapped out for another
+//! estimator without waiting for a new release of passablewords.
+
+use std::fmt;
+#[cfg(feature = "zxcvbn")]
+use std::panic;
+use std::sync::Arc;
+#[cfg(feature = "zxcvbn")]
+use zxcvbn::{zxcvbn, ZxcvbnError};
+
+use compat::Compat;
+#[cfg(not(feature = "zxcvbn"))]
+use quick_entropy::check_quick_entropy_bits;
+use {PassablewordResult, PasswordError};
+
+/// How hard an `EntropyAnalyzer` thinks a password would be to guess.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Analysis {
+    /// How strong the password is on zxcvbn's scale from 0 to 4, where 3 means it would take at
+    /// least `10^8` guesses. Passwords scoring at least 3 pass, unless the policy is pinned with
+    /// `Policy::pin_compat`.
+    pub score: u8,
+    /// The base 10 logarithm of how many guesses it would take to find the password. Policies
+    /// pinned to `Compat::V1` pass passwords where this is at least 8, whatever the score.
+    pub guesses_log10: f64,
+}
+
+/// Estimates how hard passwords are to guess for the entropy check. zxcvbn's estimate is used
+/// unless `PasswordChecker::with_analyzer` gives another, such as a newer major version of zxcvbn
+/// or an estimator tuned to your own users. Implementations are shared between threads, so they
+/// need to be `Send` and `Sync`, and any closure with the same signature as `analyze` is one.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{Analysis, EntropyAnalyzer, PasswordChecker, PasswordError};
+///
+/// struct LengthOnly;
+///
+/// impl EntropyAnalyzer for LengthOnly {
+///     fn analyze(&self, password: &str, _inputs: &[&str]) -> Result<Analysis, PasswordError> {
+///         let guesses_log10 = password.chars().count() as f64 / 2.0;
+///
+///         Ok(Analysis {
+///             score: (guesses_log10 / 3.0).min(4.0) as u8,
+///             guesses_log10,
+///         })
+///     }
+/// }
+///
+/// let checker = PasswordChecker::new().with_analyzer(LengthOnly);
+///
+/// assert_eq!(checker.check_password("Zq8!mWp2"), Err(PasswordError::TooSimple));
+/// assert_eq!(checker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"), Ok(()));
+/// ```
+pub trait EntropyAnalyzer: Send + Sync {
+    /// Estimate how hard `password` would be to guess. `inputs` are words which are easy to guess
+    /// for this particular password, like the user's name or email address, from
+    /// `CheckContext::with_user_inputs`. Errors, like `PasswordError::NonAsciiPassword` for
+    /// passwords the analyzer can't handle, are returned from the entropy check as they are.
+    fn analyze(&self, password: &str, inputs: &[&str]) -> Result<Analysis, PasswordError>;
+}
+
+impl<F: Fn(&str, &[&str]) -> Result<Analysis, PasswordError> + Send + Sync> EntropyAnalyzer for F {
+    fn analyze(&self, password: &str, inputs: &[&str]) -> Result<Analysis, PasswordError> {
+        self(password, inputs)
+    }
+}
+
+/// A shared `EntropyAnalyzer`, so that `PasswordChecker` can still be cloned cheaply and debugged.
+#[derive(Clone)]
+pub struct SharedAnalyzer(pub Arc<dyn EntropyAnalyzer>);
+
+impl fmt::Debug for SharedAnalyzer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("EntropyAnalyzer")
+    }
+}
+
+/// zxcvbn's estimate, which the entropy check uses by default. Only available with the `zxcvbn`
+/// feature.
+#[cfg(feature = "zxcvbn")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ZxcvbnAnalyzer;
+
+#[cfg(feature = "zxcvbn")]
+impl EntropyAnalyzer for ZxcvbnAnalyzer {
+    fn analyze(&self, password: &str, inputs: &[&str]) -> Result<Analysis, PasswordError> {
+        // zxcvbn is a large library, so make sure a bug in it can't take down the caller
+        match panic::catch_unwind(|| zxcvbn(password, inputs)) {
+            Ok(Ok(entropy)) => Ok(Analysis {
+                score: entropy.score,
+                guesses_log10: f64::from(entropy.guesses_log10),
+            }),
+            Ok(Err(ZxcvbnError::NonAsciiPassword)) => Err(PasswordError::NonAsciiPassword),
+            Ok(Err(_)) | Err(_) => Err(PasswordError::InternalError),
+        }
+    }
+}
+
+/// Check a password's entropy with `analyzer`, or with zxcvbn if there isn't one, deciding whether
+/// it passes as `compat` says to, and whether it scores at least `score_floor`. Without the
+/// `zxcvbn` feature, passwords are checked with `quick_entropy_bits` when there's no analyzer,
+/// which has no score to compare.
+pub(crate) fn check_entropy_analyzed(
+    password: &str,
+    inputs: &[&str],
+    compat: Compat,
+    score_floor: u8,
+    analyzer: Option<&dyn EntropyAnalyzer>,
+) -> PassablewordResult {
+    let analysis = match analyzer {
+        Some(analyzer) => analyzer.analyze(password, inputs)?,
+        #[cfg(feature = "zxcvbn")]
+        None => ZxcvbnAnalyzer.analyze(password, inputs)?,
+        #[cfg(not(feature = "zxcvbn"))]
+        None => return check_quick_entropy_bits(password, compat.min_quick_entropy_bits()),
+    };
+
+    if compat.accepts(analysis.score, analysis.guesses_log10) && analysis.score >= score_floor {
+        Ok(())
+    } else {
+        Err(PasswordError::TooSimple)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_entropy_analyzed, Analysis, EntropyAnalyzer};
+    use checker::PasswordChecker;
+    use compat::Compat;
+    use context::CheckContext;
+    use policy::{CharsetMode, Policy};
+    use profile::AnalysisProfile;
+    use std::sync::{Arc, Mutex};
+    use PasswordError;
+
+    fn fixed(score: u8, guesses_log10: f64) -> impl EntropyAnalyzer {
+        move |_: &str, _: &[&str]| {
+            Ok(Analysis {
+                score,
+                guesses_log10,
+            })
+        }
+    }
+
+    #[test]
+    fn it_decides_with_the_analysis() {
+        let weak = fixed(2, 9.0);
+
+        assert_eq!(
+            check_entropy_analyzed("anything", &[], Compat::Latest, 0, Some(&weak)),
+            Err(PasswordError::TooSimple)
+        );
+        assert_eq!(
+            check_entropy_analyzed("anything", &[], Compat::V1, 0, Some(&weak)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_passes_errors_through() {
+        let failing = |_: &str, _: &[&str]| Err(PasswordError::InternalError);
+
+        assert_eq!(
+            check_entropy_analyzed("anything", &[], Compat::Latest, 0, Some(&failing)),
+            Err(PasswordError::InternalError)
+        );
+    }
+
+    #[test]
+    fn it_gives_the_analyzer_the_user_inputs() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let checker =
+            PasswordChecker::new().with_analyzer(move |password: &str, inputs: &[&str]| {
+                recorder.lock().unwrap().push((
+                    password.to_string(),
+                    inputs.iter().map(|input| input.to_string()).collect(),
+                ));
+
+                Ok(Analysis {
+                    score: 4,
+                    guesses_log10: 12.0,
+                })
+            });
+        let context = CheckContext::new().with_user_inputs(&["mike", "mike@example.com"]);
+
+        assert_eq!(
+            checker.check_password_with_context("Th1s iS a Sup3rR4ndom PassW0rd!", &context),
+            Ok(())
+        );
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![(
+                "Th1s iS a Sup3rR4ndom PassW0rd!".to_string(),
+                vec!["mike".to_string(), "mike@example.com".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn it_analyzes_unicode_passwords_with_a_custom_analyzer() {
+        let checker = PasswordChecker::new()
+            .with_policy(Policy::new().charset_mode(CharsetMode::Unicode))
+            .with_analyzer(fixed(0, 1.0));
+
+        assert_eq!(
+            checker.check_password("ünïcödé pässwörd wïth lötš öf lëttërs"),
+            Err(PasswordError::TooSimple)
+        );
+    }
+
+    #[test]
+    fn it_bounds_long_passwords_in_the_fast_profile() {
+        let longest = Arc::new(Mutex::new(0));
+        let recorder = longest.clone();
+        let checker = PasswordChecker::new().with_analyzer(move |password: &str, _: &[&str]| {
+            let mut longest = recorder.lock().unwrap();
+
+            *longest = password.len().max(*longest);
+
+            Ok(Analysis {
+                score: 4,
+                guesses_log10: 12.0,
+            })
+        });
+        let context = CheckContext::new().with_profile(AnalysisProfile::Fast);
+        let long = "Th1s iS a Sup3rR4ndom PassW0rd!".repeat(30);
+
+        assert_eq!(checker.check_password_with_context(&long, &context), Ok(()));
+        assert_eq!(*longest.lock().unwrap(), 32);
+    }
+
+    #[cfg(feature = "zxcvbn")]
+    #[test]
+    fn it_analyzes_with_zxcvbn() {
+        use super::ZxcvbnAnalyzer;
+
+        let analysis = ZxcvbnAnalyzer
+            .analyze("Th1s iS a Sup3rR4ndom PassW0rd!", &[])
+            .unwrap();
+
+        assert!(analysis.score >= 3);
+        assert!(analysis.guesses_log10 >= 8.0);
+    }
+}
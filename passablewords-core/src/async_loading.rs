@@ -0,0 +1,263 @@
+//! Loading wordlists from async applications without blocking the runtime's worker threads. Only
+//! available with the `tokio` feature.
+
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::fs;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+use bundled_passwords;
+use checker::PasswordChecker;
+use passablewords_data;
+use wordlist::Wordlist;
+#[cfg(not(feature = "embedded-wordlist"))]
+use FILE_CONTENTS;
+
+type ReadFuture = Pin<Box<dyn Future<Output = io::Result<String>> + Send>>;
+type BuildFn = fn(Option<String>) -> io::Result<Arc<Wordlist>>;
+
+/// A future which reads a wordlist with `tokio::fs` and then builds it on the blocking thread
+/// pool, returned by `Wordlist::from_file_async`. It must be polled from within a tokio runtime,
+/// or it resolves to an error.
+#[must_use = "futures do nothing unless polled"]
+pub struct LoadWordlist {
+    state: State,
+    /// Another file to read if the first can't be.
+    fallback: Option<&'static str>,
+    build: BuildFn,
+}
+
+enum State {
+    Reading(ReadFuture),
+    Building(Option<String>),
+    Waiting(JoinHandle<io::Result<Arc<Wordlist>>>),
+    Done,
+}
+
+impl LoadWordlist {
+    fn bundled() -> LoadWordlist {
+        #[cfg(not(feature = "embedded-wordlist"))]
+        let state = match FILE_CONTENTS.get() {
+            Some(_) => State::Building(None),
+            None => State::Reading(Box::pin(fs::read_to_string("src/common-passwords.txt"))),
+        };
+        #[cfg(feature = "embedded-wordlist")]
+        let state = State::Building(None);
+
+        LoadWordlist {
+            state,
+            fallback: Some(passablewords_data::COMMON_PASSWORDS_PATH),
+            build: build_bundled,
+        }
+    }
+}
+
+/// Share the contents that were just read with the rest of the crate, then build the bundled
+/// list from them.
+fn build_bundled(contents: Option<String>) -> io::Result<Arc<Wordlist>> {
+    #[cfg(not(feature = "embedded-wordlist"))]
+    {
+        if let Some(contents) = contents {
+            let _ = FILE_CONTENTS.set(Ok(contents));
+        }
+    }
+    #[cfg(feature = "embedded-wordlist")]
+    let _ = contents;
+
+    bundled_passwords().cloned().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "the bundled list of common passwords couldn't be read",
+        )
+    })
+}
+
+fn build_parsed(contents: Option<String>) -> io::Result<Arc<Wordlist>> {
+    Ok(Arc::new(Wordlist::parse(
+        contents.as_ref().map_or("", |contents| contents),
+    )))
+}
+
+impl Future for LoadWordlist {
+    type Output = io::Result<Arc<Wordlist>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if Handle::try_current().is_err() {
+            self.state = State::Done;
+
+            return Poll::Ready(Err(io::Error::other(
+                "wordlists can only be loaded asynchronously within a tokio runtime",
+            )));
+        }
+
+        loop {
+            let next = match self.state {
+                State::Reading(ref mut read) => match read.as_mut().poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(contents)) => State::Building(Some(contents)),
+                    Poll::Ready(Err(err)) => match self.fallback.take() {
+                        Some(fallback) => State::Reading(Box::pin(fs::read_to_string(fallback))),
+                        None => {
+                            self.state = State::Done;
+
+                            return Poll::Ready(Err(err));
+                        }
+                    },
+                },
+                State::Building(ref mut contents) => {
+                    let contents = contents.take();
+                    let build = self.build;
+
+                    State::Waiting(tokio::task::spawn_blocking(move || build(contents)))
+                }
+                State::Waiting(ref mut handle) => match Pin::new(handle).poll(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(result) => {
+                        self.state = State::Done;
+
+                        return Poll::Ready(
+                            result.unwrap_or_else(|err| Err(io::Error::other(err))),
+                        );
+                    }
+                },
+                State::Done => {
+                    return Poll::Ready(Err(io::Error::other(
+                        "the wordlist has already been loaded",
+                    )))
+                }
+            };
+
+            self.state = next;
+        }
+    }
+}
+
+impl fmt::Debug for LoadWordlist {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("LoadWordlist")
+    }
+}
+
+/// A future which resolves to a `PasswordChecker` once the bundled list of common passwords has
+/// been loaded, returned by `PasswordChecker::load_async`.
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct LoadChecker {
+    wordlist: LoadWordlist,
+}
+
+impl Future for LoadChecker {
+    type Output = io::Result<PasswordChecker>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.wordlist)
+            .poll(cx)
+            .map(|wordlist| wordlist.map(PasswordChecker::with_wordlist))
+    }
+}
+
+impl Wordlist {
+    /// Read a wordlist from a file containing one password per line, like `from_file`, without
+    /// blocking the current tokio worker. The file is read with `tokio::fs` and parsed on the
+    /// blocking thread pool. Only available with the `tokio` feature.
+    pub fn from_file_async<P: AsRef<Path>>(path: P) -> LoadWordlist {
+        LoadWordlist {
+            state: State::Reading(Box::pin(fs::read_to_string(path.as_ref().to_path_buf()))),
+            fallback: None,
+            build: build_parsed,
+        }
+    }
+}
+
+impl PasswordChecker {
+    /// Create a checker which uses the bundled list of common passwords, like `new`, without
+    /// blocking the current tokio worker while the list is read and built. The list is shared with
+    /// the rest of the crate, so once this resolves, the free functions and `new` can use it
+    /// straight away. Unlike `new`, an error is returned if the list can't be read. Only
+    /// available with the `tokio` feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate passablewords_core;
+    /// # extern crate tokio;
+    /// use passablewords_core::{PasswordChecker, PasswordError};
+    ///
+    /// # fn main() {
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let checker = runtime.block_on(PasswordChecker::load_async()).unwrap();
+    ///
+    /// assert_eq!(checker.check_uniqueness("password"), Err(PasswordError::TooCommon));
+    /// # }
+    /// ```
+    pub fn load_async() -> LoadChecker {
+        LoadChecker {
+            wordlist: LoadWordlist::bundled(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LoadWordlist;
+    use checker::PasswordChecker;
+    use passablewords_data;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Waker};
+    use tokio::runtime::Builder;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn it_loads_the_bundled_list() {
+        let checker = block_on(PasswordChecker::load_async()).unwrap();
+
+        assert_eq!(
+            checker.check_uniqueness("password"),
+            Err(PasswordError::TooCommon)
+        );
+        assert!(Arc::ptr_eq(
+            &block_on(LoadWordlist::bundled()).unwrap(),
+            &Wordlist::bundled().unwrap()
+        ));
+    }
+
+    #[test]
+    fn it_loads_a_wordlist_from_a_file() {
+        let wordlist = block_on(Wordlist::from_file_async(
+            passablewords_data::COMMON_PASSWORDS_PATH,
+        ))
+        .unwrap();
+
+        assert!(wordlist.contains("password"));
+        assert!(block_on(Wordlist::from_file_async("does/not/exist")).is_err());
+    }
+
+    #[test]
+    fn it_fails_outside_of_a_runtime() {
+        let waker = Waker::noop();
+        let mut context = Context::from_waker(waker);
+        let mut load = PasswordChecker::load_async();
+
+        match Pin::new(&mut load).poll(&mut context) {
+            Poll::Ready(result) => assert!(result.is_err()),
+            Poll::Pending => panic!("expected an error"),
+        }
+    }
+}
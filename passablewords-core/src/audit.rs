@@ -0,0 +1,769 @@
+//! Tools for auditing the passwords of an existing user population, such as finding accounts
+//! which share a password or use variants of the same one.
+
+use std::cmp::Reverse;
+use std::collections::hash_map::{Entry, RandomState};
+use std::collections::{BinaryHeap, HashMap};
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::hash::BuildHasher;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::mem;
+use std::path::PathBuf;
+use std::process;
+use std::sync::{mpsc, Arc, Mutex, PoisonError};
+use std::thread;
+
+use PasswordError;
+
+/// A group of users who all have the same password.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SharedPassword<U> {
+    /// The users sharing the password, in the order they were given.
+    pub users: Vec<U>,
+}
+
+/// A group of users whose passwords are the same or nearly the same, like `Summer2024!github` and
+/// `Summer2024!gitlab`. Credential stuffing attacks try variants like these, so one breached
+/// password puts the whole group at risk.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimilarPasswords<U> {
+    /// The users in the group, in the order they were given.
+    pub users: Vec<U>,
+}
+
+/// What an audit found about one account's password, for deciding which accounts need a new
+/// password first.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::audit::AccountRisk;
+/// use passablewords_core::PasswordError;
+///
+/// let reused = AccountRisk {
+///     shared_with: 3,
+///     error: Some(PasswordError::TooCommon),
+///     ..AccountRisk::default()
+/// };
+/// let breached = AccountRisk {
+///     breached: true,
+///     ..reused.clone()
+/// };
+///
+/// assert!(breached.priority() > reused.priority());
+/// assert_eq!(AccountRisk::default().priority(), 0);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountRisk {
+    /// The password has been seen in a breach.
+    pub breached: bool,
+    /// How many other accounts have exactly the same password.
+    pub shared_with: usize,
+    /// The password is a variant of another account's, as found by `find_similar_passwords`.
+    pub similar: bool,
+    /// Why the password was rejected, if it was.
+    pub error: Option<PasswordError>,
+}
+
+impl AccountRisk {
+    /// How urgently the account needs a new password, from 0 when nothing was found to 100.
+    ///
+    /// A breached password is the most urgent, since attackers already have it, and is worth
+    /// half the score. Reuse is next, since one breach exposes every account sharing the
+    /// password, and a weak password is last, with common passwords counting the most.
+    pub fn priority(&self) -> u8 {
+        let breached = if self.breached || self.error == Some(PasswordError::Breached) {
+            50
+        } else {
+            0
+        };
+        let reused = if self.shared_with > 0 {
+            20 + self.shared_with.min(5) as u8
+        } else if self.similar {
+            15
+        } else {
+            0
+        };
+        let weak = match self.error {
+            Some(PasswordError::TooCommon)
+            | Some(PasswordError::TooCommonIn(_))
+            | Some(PasswordError::TooCommonVariant)
+            | Some(PasswordError::TooCommonLocally) => 25,
+            Some(PasswordError::TooShort)
+            | Some(PasswordError::TooSimple)
+            | Some(PasswordError::KeyboardWalk)
+            | Some(PasswordError::TemporalPattern)
+            | Some(PasswordError::BannedPattern(_))
+            | Some(PasswordError::MatchesDeniedValue)
+            | Some(PasswordError::MatchesPreviousPassword)
+            | Some(PasswordError::MatchesUsername)
+            | Some(PasswordError::SingleDictionaryWord) => 20,
+            Some(PasswordError::TooFewUniqueChars)
+            | Some(PasswordError::InsufficientVariety)
+            | Some(PasswordError::NonAsciiPassword) => 10,
+            Some(PasswordError::Breached)
+            | Some(PasswordError::InvalidFormat)
+            | Some(PasswordError::InvalidEncoding)
+            | Some(PasswordError::InputTooLarge)
+            | Some(PasswordError::Cancelled)
+            | Some(PasswordError::WordlistUnavailable { .. })
+            | Some(PasswordError::InternalError)
+            | None => 0,
+        };
+
+        breached + reused + weak
+    }
+}
+
+/// Find the groups of users who share a password. Only groups of two or more users are returned,
+/// largest first.
+///
+/// Passwords aren't kept around. Each one is reduced to a 128-bit salted hash as soon as it's
+/// read, using a random salt that only lives as long as this call, so the result can't be used
+/// to learn anything about the passwords themselves.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::audit::find_shared_passwords;
+///
+/// let accounts = vec![
+///     ("alice", "correct horse"),
+///     ("bob", "hunter2"),
+///     ("carol", "correct horse"),
+///     ("dave", "tr0ub4dor"),
+/// ];
+/// let shared = find_shared_passwords(accounts);
+///
+/// assert_eq!(shared.len(), 1);
+/// assert_eq!(shared[0].users, vec!["alice", "carol"]);
+/// ```
+pub fn find_shared_passwords<U, P, I>(accounts: I) -> Vec<SharedPassword<U>>
+where
+    I: IntoIterator<Item = (U, P)>,
+    P: AsRef<str>,
+{
+    let salts = (RandomState::new(), RandomState::new());
+    let mut clusters: Vec<SharedPassword<U>> = Vec::new();
+    let mut indices: HashMap<(u64, u64), usize> = HashMap::new();
+
+    for (user, password) in accounts {
+        let password = password.as_ref();
+        let hash = (salts.0.hash_one(password), salts.1.hash_one(password));
+
+        match indices.entry(hash) {
+            Entry::Occupied(entry) => clusters[*entry.get()].users.push(user),
+            Entry::Vacant(entry) => {
+                entry.insert(clusters.len());
+                clusters.push(SharedPassword { users: vec![user] });
+            }
+        }
+    }
+
+    clusters.retain(|cluster| cluster.users.len() > 1);
+    // A stable sort keeps groups of the same size in the order they were first seen
+    clusters.sort_by_key(|cluster| Reverse(cluster.users.len()));
+    clusters
+}
+
+/// A salted hash of a password, and the user it belongs to.
+type Record = (u64, u64, u64);
+
+/// How much memory a `SharedPasswordFinder` uses for hashes by default before spilling them to
+/// disk.
+const DEFAULT_MEMORY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Finds the groups of users who share a password, like `find_shared_passwords`, but for more
+/// accounts than fit in memory. Users are identified by number, such as their row in an export.
+///
+/// Each password is reduced to a salted 128-bit hash as soon as it's added. Hashes are kept in
+/// memory up to a limit, then sorted and written to a temporary file, and the files are merged
+/// at the end. Memory use stays the same however many accounts there are, apart from the groups
+/// which are found. The temporary files are deleted when the finder is dropped.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::audit::SharedPasswordFinder;
+///
+/// let mut finder = SharedPasswordFinder::new();
+///
+/// finder.add(1, "correct horse").unwrap();
+/// finder.add(2, "hunter2").unwrap();
+/// finder.add(3, "correct horse").unwrap();
+///
+/// let shared = finder.finish().unwrap();
+///
+/// assert_eq!(shared.len(), 1);
+/// assert_eq!(shared[0].users, vec![1, 3]);
+/// ```
+#[derive(Debug)]
+pub struct SharedPasswordFinder {
+    salts: (RandomState, RandomState),
+    records: Vec<Record>,
+    capacity: usize,
+    spilled: Vec<PathBuf>,
+}
+
+impl SharedPasswordFinder {
+    /// Create a finder which keeps up to 64MB of hashes in memory.
+    pub fn new() -> SharedPasswordFinder {
+        SharedPasswordFinder {
+            salts: (RandomState::new(), RandomState::new()),
+            records: Vec::new(),
+            capacity: DEFAULT_MEMORY_LIMIT / mem::size_of::<Record>(),
+            spilled: Vec::new(),
+        }
+    }
+
+    /// Keep up to about `bytes` of hashes in memory before spilling them to disk. Each account
+    /// takes 24 bytes.
+    pub fn with_memory_limit(mut self, bytes: usize) -> SharedPasswordFinder {
+        self.capacity = (bytes / mem::size_of::<Record>()).max(1);
+        self
+    }
+
+    /// Add a user's password. Only fails if the hashes can't be spilled to disk.
+    pub fn add(&mut self, user: u64, password: &str) -> io::Result<()> {
+        if self.records.len() == self.capacity {
+            self.spill()?;
+        }
+        if self.records.len() == self.records.capacity() {
+            // Grow in steps rather than doubling, so the limit isn't overshot
+            let step = self.records.capacity().max(1024);
+
+            self.records
+                .reserve_exact(step.min(self.capacity - self.records.len()));
+        }
+
+        self.records.push((
+            self.salts.0.hash_one(password),
+            self.salts.1.hash_one(password),
+            user,
+        ));
+
+        Ok(())
+    }
+
+    /// Find the groups of users who share a password. Only groups of two or more users are
+    /// returned, largest first, with the users in each in order.
+    pub fn finish(mut self) -> io::Result<Vec<SharedPassword<u64>>> {
+        if self.spilled.is_empty() {
+            let mut records = mem::take(&mut self.records);
+
+            records.sort_unstable();
+
+            return group_records(records.into_iter().map(Ok));
+        }
+
+        self.spill()?;
+
+        let mut merge = Merge {
+            files: Vec::with_capacity(self.spilled.len()),
+            next: BinaryHeap::new(),
+        };
+
+        for path in &self.spilled {
+            let mut file = BufReader::new(File::open(path)?);
+
+            if let Some(record) = read_record(&mut file)? {
+                merge.next.push(Reverse((record, merge.files.len())));
+            }
+
+            merge.files.push(file);
+        }
+
+        group_records(merge)
+    }
+
+    /// Sort the hashes in memory and write them to a new temporary file.
+    fn spill(&mut self) -> io::Result<()> {
+        let path = env::temp_dir().join(format!(
+            "passablewords-{}-{:016x}.tmp",
+            process::id(),
+            RandomState::new().hash_one(self.spilled.len())
+        ));
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        self.spilled.push(path);
+        self.records.sort_unstable();
+
+        let mut out = BufWriter::new(file);
+
+        for &(first, second, user) in &self.records {
+            out.write_all(&first.to_le_bytes())?;
+            out.write_all(&second.to_le_bytes())?;
+            out.write_all(&user.to_le_bytes())?;
+        }
+
+        out.flush()?;
+        self.records.clear();
+
+        Ok(())
+    }
+}
+
+impl Default for SharedPasswordFinder {
+    fn default() -> SharedPasswordFinder {
+        SharedPasswordFinder::new()
+    }
+}
+
+impl Drop for SharedPasswordFinder {
+    fn drop(&mut self) {
+        for path in &self.spilled {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Merges sorted files of hashes into one sorted stream.
+struct Merge {
+    files: Vec<BufReader<File>>,
+    next: BinaryHeap<Reverse<(Record, usize)>>,
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<io::Result<Record>> {
+        let Reverse((record, file)) = self.next.pop()?;
+
+        match read_record(&mut self.files[file]) {
+            Ok(Some(next)) => self.next.push(Reverse((next, file))),
+            Ok(None) => {}
+            Err(err) => return Some(Err(err)),
+        }
+
+        Some(Ok(record))
+    }
+}
+
+fn read_record(file: &mut BufReader<File>) -> io::Result<Option<Record>> {
+    let mut bytes = [0; 24];
+
+    match file.read_exact(&mut bytes) {
+        Ok(()) => {}
+        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+
+    let word = |index: usize| {
+        let mut word = [0; 8];
+
+        word.copy_from_slice(&bytes[index * 8..index * 8 + 8]);
+        u64::from_le_bytes(word)
+    };
+
+    Ok(Some((word(0), word(1), word(2))))
+}
+
+/// Collect the users sharing each hash from records sorted by hash.
+fn group_records<I>(records: I) -> io::Result<Vec<SharedPassword<u64>>>
+where
+    I: IntoIterator<Item = io::Result<Record>>,
+{
+    let mut groups = Vec::new();
+    let mut hash = None;
+    let mut users = Vec::new();
+
+    for record in records {
+        let (first, second, user) = record?;
+
+        if hash != Some((first, second)) {
+            if users.len() > 1 {
+                groups.push(SharedPassword { users });
+            }
+
+            hash = Some((first, second));
+            users = Vec::new();
+        }
+
+        users.push(user);
+    }
+
+    if users.len() > 1 {
+        groups.push(SharedPassword { users });
+    }
+
+    groups.sort_by_key(|group| (Reverse(group.users.len()), group.users[0]));
+
+    Ok(groups)
+}
+
+/// How many accounts can be waiting to be checked, and how many results can be waiting to be
+/// collected, for each worker.
+const QUEUE_PER_WORKER: usize = 64;
+
+/// Check a stream of accounts on `workers` threads, for audits of more accounts than fit in
+/// memory. `check` is called on the workers with each password, then `checked` is called on the
+/// calling thread with the account's user, password, and result. Results arrive in the order
+/// they're finished rather than the order they were given.
+///
+/// The queues between the threads are bounded, so `accounts` isn't read from while the workers
+/// or `checked` are behind, and only a few accounts per worker are held in memory at once. If a
+/// worker panics, the panic is passed on once the others have finished.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::audit::check_stream;
+/// use passablewords_core::check_length;
+///
+/// let accounts = vec![(1, "hunter2"), (2, "correct horse"), (3, "letmein")];
+/// let mut rejected = Vec::new();
+///
+/// check_stream(
+///     accounts,
+///     2,
+///     |password| check_length(password),
+///     |user, _, result| {
+///         if result.is_err() {
+///             rejected.push(user);
+///         }
+///     },
+/// );
+///
+/// rejected.sort();
+/// assert_eq!(rejected, vec![1, 3]);
+/// ```
+pub fn check_stream<U, P, R, I, C, F>(accounts: I, workers: usize, check: C, mut checked: F)
+where
+    I: IntoIterator<Item = (U, P)>,
+    I::IntoIter: Send,
+    U: Send,
+    P: Send,
+    R: Send,
+    C: Fn(&P) -> R + Sync,
+    F: FnMut(U, P, R),
+{
+    let workers = workers.max(1);
+    let (account_sender, account_receiver) = mpsc::sync_channel(workers * QUEUE_PER_WORKER);
+    let (result_sender, result_receiver) = mpsc::sync_channel(workers * QUEUE_PER_WORKER);
+    // Shared by the workers, and dropped along with the last of them, so that the reader stops
+    // if they all panic rather than waiting for room in the queue forever
+    let account_receiver = Arc::new(Mutex::new(account_receiver));
+    let accounts = accounts.into_iter();
+    let check = &check;
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for account in accounts {
+                if account_sender.send(account).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..workers {
+            let account_receiver = Arc::clone(&account_receiver);
+            let result_sender = result_sender.clone();
+
+            scope.spawn(move || loop {
+                // The lock is only held while waiting for an account, not while checking it
+                let next = account_receiver
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .recv();
+                let (user, password) = match next {
+                    Ok(account) => account,
+                    Err(_) => break,
+                };
+                let result = check(&password);
+
+                if result_sender.send((user, password, result)).is_err() {
+                    break;
+                }
+            });
+        }
+
+        drop(account_receiver);
+        drop(result_sender);
+
+        for (user, password, result) in result_receiver {
+            checked(user, password, result);
+        }
+    });
+}
+
+/// Find the groups of users whose passwords are within `max_distance` edits of each other, where
+/// an edit inserts, removes, or replaces a character. A user is in a group if their password is
+/// close to any other password in it. Only groups with at least two different passwords are
+/// returned, largest first, since `find_shared_passwords` already finds users sharing one.
+///
+/// Every pair of passwords with similar lengths is compared, so this is meant for a vault or a
+/// team's accounts rather than a whole user population. The passwords are only kept for the
+/// duration of the call.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::audit::find_similar_passwords;
+///
+/// let accounts = vec![
+///     ("github", "Summer2024!gh"),
+///     ("gitlab", "Summer2024!gl"),
+///     ("bank", "correct horse"),
+///     ("mail", "Summer2024!"),
+/// ];
+/// let similar = find_similar_passwords(accounts, 2);
+///
+/// assert_eq!(similar.len(), 1);
+/// assert_eq!(similar[0].users, vec!["github", "gitlab", "mail"]);
+/// ```
+pub fn find_similar_passwords<U, P, I>(accounts: I, max_distance: usize) -> Vec<SimilarPasswords<U>>
+where
+    I: IntoIterator<Item = (U, P)>,
+    P: AsRef<str>,
+{
+    let (users, passwords): (Vec<U>, Vec<P>) = accounts.into_iter().unzip();
+    let mut distinct: Vec<Vec<char>> = Vec::new();
+    let mut nodes = Vec::with_capacity(passwords.len());
+    let mut indices: HashMap<&str, usize> = HashMap::new();
+
+    for password in &passwords {
+        let password = password.as_ref();
+        let node = *indices.entry(password).or_insert_with(|| {
+            distinct.push(password.chars().collect());
+            distinct.len() - 1
+        });
+
+        nodes.push(node);
+    }
+
+    let mut by_length: Vec<usize> = (0..distinct.len()).collect();
+    let mut parents: Vec<usize> = (0..distinct.len()).collect();
+
+    by_length.sort_by_key(|&node| distinct[node].len());
+
+    for (position, &first) in by_length.iter().enumerate() {
+        for &second in &by_length[position + 1..] {
+            if distinct[second].len() - distinct[first].len() > max_distance {
+                break;
+            }
+            if within_distance(&distinct[first], &distinct[second], max_distance) {
+                let first_root = find_root(&mut parents, first);
+                let second_root = find_root(&mut parents, second);
+
+                parents[first_root] = second_root;
+            }
+        }
+    }
+
+    let mut groups: Vec<(Vec<U>, Vec<usize>)> = Vec::new();
+    let mut group_indices: HashMap<usize, usize> = HashMap::new();
+
+    for (user, node) in users.into_iter().zip(nodes) {
+        let root = find_root(&mut parents, node);
+        let index = *group_indices.entry(root).or_insert_with(|| {
+            groups.push((Vec::new(), Vec::new()));
+            groups.len() - 1
+        });
+        let group = &mut groups[index];
+
+        group.0.push(user);
+        if !group.1.contains(&node) {
+            group.1.push(node);
+        }
+    }
+
+    let mut groups: Vec<SimilarPasswords<U>> = groups
+        .into_iter()
+        .filter(|group| group.1.len() > 1)
+        .map(|(users, _)| SimilarPasswords { users })
+        .collect();
+
+    groups.sort_by_key(|group| Reverse(group.users.len()));
+    groups
+}
+
+fn find_root(parents: &mut [usize], node: usize) -> usize {
+    let mut root = node;
+
+    while parents[root] != root {
+        root = parents[root];
+    }
+
+    // Point everything on the way straight at the root, so later lookups are quick
+    let mut node = node;
+
+    while parents[node] != root {
+        let next = parents[node];
+
+        parents[node] = root;
+        node = next;
+    }
+
+    root
+}
+
+/// Whether the Levenshtein distance between `first` and `second` is at most `max_distance`. It
+/// stops as soon as a row shows the distance is too large.
+pub(crate) fn within_distance(first: &[char], second: &[char], max_distance: usize) -> bool {
+    if first.len().abs_diff(second.len()) > max_distance {
+        return false;
+    }
+
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+    let mut current = vec![0; second.len() + 1];
+
+    for (row, &character) in first.iter().enumerate() {
+        current[0] = row + 1;
+
+        let mut smallest = current[0];
+
+        for column in 0..second.len() {
+            let substitution = previous[column] + usize::from(character != second[column]);
+
+            current[column + 1] = substitution
+                .min(previous[column + 1] + 1)
+                .min(current[column] + 1);
+            smallest = smallest.min(current[column + 1]);
+        }
+
+        if smallest > max_distance {
+            return false;
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[second.len()] <= max_distance
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_stream, find_shared_passwords, find_similar_passwords, within_distance, AccountRisk,
+        SharedPasswordFinder,
+    };
+    use {check_length, PasswordError};
+
+    #[test]
+    fn it_finds_shared_passwords() {
+        let accounts = vec![
+            (1, "password".to_string()),
+            (2, "letmein".to_string()),
+            (3, "unique".to_string()),
+            (4, "letmein".to_string()),
+            (5, "password".to_string()),
+            (6, "letmein".to_string()),
+        ];
+        let shared = find_shared_passwords(accounts);
+
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared[0].users, vec![2, 4, 6]);
+        assert_eq!(shared[1].users, vec![1, 5]);
+        assert!(find_shared_passwords(vec![(1, "a"), (2, "b")]).is_empty());
+    }
+
+    #[test]
+    fn it_finds_shared_passwords_on_disk() {
+        let accounts = [
+            "password", "letmein", "unique", "letmein", "password", "letmein", "other",
+        ];
+        // Only room for two hashes at a time, so it spills to a few files
+        let mut finder = SharedPasswordFinder::new().with_memory_limit(48);
+
+        for (user, password) in accounts.iter().enumerate() {
+            finder.add(user as u64 + 1, password).unwrap();
+        }
+
+        let shared = finder.finish().unwrap();
+
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared[0].users, vec![2, 4, 6]);
+        assert_eq!(shared[1].users, vec![1, 5]);
+    }
+
+    #[test]
+    fn it_checks_streams() {
+        let accounts = (0..1000).map(|user| (user, "x".repeat(user % 16)));
+        let mut results = Vec::new();
+
+        check_stream(
+            accounts,
+            4,
+            |password| check_length(password),
+            |user, password, result| results.push((user, password.len(), result.is_ok())),
+        );
+        results.sort();
+
+        assert_eq!(results.len(), 1000);
+        assert!(results
+            .iter()
+            .all(|&(user, length, ok)| length == user % 16 && ok == (length >= 8)));
+    }
+
+    #[test]
+    fn it_finds_similar_passwords() {
+        let accounts = vec![
+            (1, "hunter22"),
+            (2, "correct horse"),
+            (3, "hunter22"),
+            (4, "hunter2"),
+            (5, "letmein"),
+            (6, "letmein"),
+            (7, "hunter2!!"),
+        ];
+        let similar = find_similar_passwords(accounts.clone(), 1);
+
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].users, vec![1, 3, 4]);
+        assert_eq!(
+            find_similar_passwords(accounts.clone(), 2)[0].users,
+            vec![1, 3, 4, 7]
+        );
+        assert!(find_similar_passwords(accounts, 0).is_empty());
+    }
+
+    #[test]
+    fn it_measures_edit_distance() {
+        let chars = |text: &str| text.chars().collect::<Vec<_>>();
+
+        assert!(within_distance(&chars("kitten"), &chars("sitting"), 3));
+        assert!(!within_distance(&chars("kitten"), &chars("sitting"), 2));
+        assert!(within_distance(&chars("pässword"), &chars("password"), 1));
+        assert!(within_distance(&chars(""), &chars("ab"), 2));
+        assert!(!within_distance(&chars("abcdef"), &chars("ghijkl"), 2));
+    }
+
+    #[test]
+    fn it_prioritizes_risks() {
+        let weak = AccountRisk {
+            error: Some(PasswordError::TooShort),
+            ..AccountRisk::default()
+        };
+        let similar = AccountRisk {
+            similar: true,
+            ..weak.clone()
+        };
+        let shared = AccountRisk {
+            shared_with: 1,
+            ..similar.clone()
+        };
+        let worst = AccountRisk {
+            breached: true,
+            shared_with: 100,
+            similar: true,
+            error: Some(PasswordError::TooCommon),
+        };
+
+        assert_eq!(weak.priority(), 20);
+        assert_eq!(similar.priority(), 35);
+        assert_eq!(shared.priority(), 41);
+        assert_eq!(worst.priority(), 100);
+        assert_eq!(
+            AccountRisk {
+                error: Some(PasswordError::Breached),
+                ..AccountRisk::default()
+            }
+            .priority(),
+            50
+        );
+    }
+}
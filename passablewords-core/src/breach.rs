@@ -0,0 +1,456 @@
+//! Checks against lists of breached passwords that are too large to ship, like Have I Been Pwned,
+//! and a cache to avoid asking for the same passwords over and over.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use sha1::{Digest, Sha1};
+
+use risk::RiskLevel;
+use {PassablewordResult, PasswordError};
+
+/// A source of breached passwords, usually a remote service. Implementations are shared between
+/// threads, so they need to be `Send` and `Sync`.
+pub trait BreachProvider: Send + Sync {
+    /// How many times `password` has been seen in breaches, or 0 if it hasn't been.
+    fn breach_count(&self, password: &str) -> io::Result<u64>;
+
+    /// What `check_breached` should do when `breach_count` fails. By default, passwords are
+    /// rejected.
+    fn fail_mode(&self) -> FailMode {
+        FailMode::Closed
+    }
+}
+
+/// What to do with a password when a `BreachProvider` can't say whether it's been breached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailMode {
+    /// Accept the password, so that signups keep working while the provider is down.
+    Open,
+    /// Reject the password with `PasswordError::InternalError`.
+    Closed,
+}
+
+/// Check that a password hasn't been seen in any of the breaches `provider` knows about. If the
+/// provider can't be reached, the password is accepted or rejected with
+/// `PasswordError::InternalError` depending on its `fail_mode`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_breached, BreachProvider, PasswordError};
+/// use std::io;
+///
+/// struct Leaked;
+///
+/// impl BreachProvider for Leaked {
+///     fn breach_count(&self, password: &str) -> io::Result<u64> {
+///         Ok(if password == "password" { 9_659_365 } else { 0 })
+///     }
+/// }
+///
+/// assert_eq!(check_breached("password", &Leaked), Err(PasswordError::Breached));
+/// ```
+pub fn check_breached<P: BreachProvider + ?Sized>(
+    password: &str,
+    provider: &P,
+) -> PassablewordResult {
+    match provider.breach_count(password) {
+        Ok(0) => Ok(()),
+        Ok(_) => Err(PasswordError::Breached),
+        Err(_) => match provider.fail_mode() {
+            FailMode::Open => Ok(()),
+            FailMode::Closed => Err(PasswordError::InternalError),
+        },
+    }
+}
+
+/// Check a password like `check_breached`, but only reject it if it's been seen in more breaches
+/// than `risk` allows, so that low risk accounts aren't turned away for a password that's turned up
+/// once or twice.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_breached_with_risk, BreachProvider, PasswordError, RiskLevel};
+/// use std::io;
+///
+/// struct Leaked;
+///
+/// impl BreachProvider for Leaked {
+///     fn breach_count(&self, _password: &str) -> io::Result<u64> {
+///         Ok(3)
+///     }
+/// }
+///
+/// assert_eq!(check_breached_with_risk("Qd7#vL2p!x", &Leaked, RiskLevel::Low), Ok(()));
+/// assert_eq!(
+///     check_breached_with_risk("Qd7#vL2p!x", &Leaked, RiskLevel::High),
+///     Err(PasswordError::Breached)
+/// );
+/// ```
+pub fn check_breached_with_risk<P: BreachProvider + ?Sized>(
+    password: &str,
+    provider: &P,
+    risk: RiskLevel,
+) -> PassablewordResult {
+    match provider.breach_count(password) {
+        Ok(count) if count > risk.max_breach_count() => Err(PasswordError::Breached),
+        Ok(_) => Ok(()),
+        Err(_) => match provider.fail_mode() {
+            FailMode::Open => Ok(()),
+            FailMode::Closed => Err(PasswordError::InternalError),
+        },
+    }
+}
+
+/// A `BreachProvider` which remembers the answers of another one for a while. The most popular
+/// weak passwords come up again and again at signup, so this saves a round trip to the provider
+/// for most of them.
+///
+/// Passwords are never kept in the cache. Entries are keyed by a hash of the password and a salt
+/// that's picked at random for each cache. Failed lookups aren't cached, and the cache isn't
+/// locked while the provider is asked, so two threads missing on the same password at the same
+/// time will both ask.
+///
+/// # Example
+///
+/// ```
+/// use std::io;
+/// use std::time::Duration;
+/// use passablewords_core::{check_breached, BreachProvider, CachedBreachProvider};
+///
+/// struct Leaked;
+///
+/// impl BreachProvider for Leaked {
+///     fn breach_count(&self, password: &str) -> io::Result<u64> {
+///         Ok(if password == "password" { 9_659_365 } else { 0 })
+///     }
+/// }
+///
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let provider = CachedBreachProvider::new(Leaked, 10_000, day);
+///
+/// check_breached("password", &provider).ok();
+/// check_breached("password", &provider).ok();
+///
+/// assert_eq!(provider.stats().hits, 1);
+/// ```
+#[derive(Debug)]
+pub struct CachedBreachProvider<P> {
+    provider: P,
+    ttl: Duration,
+    salt: [u8; 16],
+    cache: Mutex<Lru>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// How well a `CachedBreachProvider` is doing, for exporting as metrics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheStats {
+    /// Lookups answered from the cache.
+    pub hits: u64,
+    /// Lookups that went to the provider, including ones for entries which had expired.
+    pub misses: u64,
+    /// The number of entries in the cache, including any which have expired but haven't been
+    /// evicted yet.
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// The fraction of lookups answered from the cache, from 0 to 1. It's 0 before any lookups.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 / total as f64
+        }
+    }
+}
+
+impl<P: BreachProvider> CachedBreachProvider<P> {
+    /// Cache up to `capacity` answers from `provider`, each for at most `ttl`. Once it's full,
+    /// the entry that was used longest ago is evicted to make room. Answers are kept until they're
+    /// evicted when `ttl` is too long for an `Instant` to hold, like `Duration::MAX`.
+    pub fn new(provider: P, capacity: usize, ttl: Duration) -> CachedBreachProvider<P> {
+        CachedBreachProvider {
+            provider,
+            ttl,
+            salt: random_salt(),
+            cache: Mutex::new(Lru::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// The provider whose answers are cached.
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// The hits, misses, and size of the cache so far.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.lock().entries.len(),
+        }
+    }
+
+    fn key(&self, password: &str) -> Key {
+        let mut hasher = Sha1::new();
+
+        hasher.update(self.salt);
+        hasher.update(password.as_bytes());
+        hasher.finalize().into()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+impl<P: BreachProvider> BreachProvider for CachedBreachProvider<P> {
+    fn breach_count(&self, password: &str) -> io::Result<u64> {
+        let key = self.key(password);
+
+        if let Some(count) = self.lock().get(&key, Instant::now()) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(count);
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let count = self.provider.breach_count(password)?;
+
+        self.lock()
+            .insert(key, count, Instant::now().checked_add(self.ttl));
+        Ok(count)
+    }
+
+    fn fail_mode(&self) -> FailMode {
+        self.provider.fail_mode()
+    }
+}
+
+type Key = [u8; 20];
+
+/// A salt that's unpredictable outside of this process, from the random keys the standard
+/// library picks for each `HashMap`.
+fn random_salt() -> [u8; 16] {
+    let state = RandomState::new();
+    let mut salt = [0; 16];
+
+    for (index, chunk) in salt.chunks_mut(8).enumerate() {
+        let mut hasher = state.build_hasher();
+
+        hasher.write_usize(index);
+        chunk.copy_from_slice(&hasher.finish().to_le_bytes());
+    }
+
+    salt
+}
+
+/// A cached answer. It never expires if `expires` is `None`.
+#[derive(Debug)]
+struct Entry {
+    count: u64,
+    expires: Option<Instant>,
+    used: u64,
+}
+
+/// A least recently used cache. Entries are ordered by a counter that's bumped on every use, so
+/// the oldest one can be found without scanning them all.
+#[derive(Debug)]
+struct Lru {
+    capacity: usize,
+    entries: HashMap<Key, Entry>,
+    order: BTreeMap<u64, Key>,
+    clock: u64,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Lru {
+        Lru {
+            capacity,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, key: &Key, now: Instant) -> Option<u64> {
+        let (count, expires, used) = match self.entries.get(key) {
+            Some(entry) => (entry.count, entry.expires, entry.used),
+            None => return None,
+        };
+
+        self.order.remove(&used);
+
+        if expires.is_some_and(|expires| expires <= now) {
+            self.entries.remove(key);
+            return None;
+        }
+
+        self.clock += 1;
+        self.order.insert(self.clock, *key);
+
+        if let Some(entry) = self.entries.get_mut(key) {
+            entry.used = self.clock;
+        }
+
+        Some(count)
+    }
+
+    fn insert(&mut self, key: Key, count: u64, expires: Option<Instant>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if let Some(entry) = self.entries.remove(&key) {
+            self.order.remove(&entry.used);
+        } else if self.entries.len() >= self.capacity {
+            let oldest = self.order.keys().next().cloned();
+
+            if let Some(oldest) = oldest.and_then(|used| self.order.remove(&used)) {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.clock += 1;
+        self.order.insert(self.clock, key);
+        self.entries.insert(
+            key,
+            Entry {
+                count,
+                expires,
+                used: self.clock,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_breached, BreachProvider, CachedBreachProvider, FailMode, Lru};
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, Instant};
+    use PasswordError;
+
+    #[derive(Debug, Default)]
+    struct Counting {
+        calls: AtomicUsize,
+        fail_open: bool,
+    }
+
+    impl BreachProvider for Counting {
+        fn breach_count(&self, password: &str) -> io::Result<u64> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+
+            match password {
+                "offline" => Err(io::Error::other("offline")),
+                _ => Ok(password.len() as u64 - 8),
+            }
+        }
+
+        fn fail_mode(&self) -> FailMode {
+            if self.fail_open {
+                FailMode::Open
+            } else {
+                FailMode::Closed
+            }
+        }
+    }
+
+    #[test]
+    fn it_checks_breaches() {
+        let provider = Counting::default();
+
+        assert_eq!(check_breached("12345678", &provider), Ok(()));
+        assert_eq!(
+            check_breached("123456789", &provider),
+            Err(PasswordError::Breached)
+        );
+        assert_eq!(
+            check_breached("offline", &provider),
+            Err(PasswordError::InternalError)
+        );
+
+        let failing_open = Counting {
+            fail_open: true,
+            ..Counting::default()
+        };
+
+        assert_eq!(check_breached("offline", &failing_open), Ok(()));
+        assert_eq!(
+            check_breached(
+                "offline",
+                &CachedBreachProvider::new(failing_open, 1, Duration::from_secs(1))
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_caches_answers() {
+        let cached = CachedBreachProvider::new(Counting::default(), 2, Duration::from_secs(60));
+
+        assert_eq!(cached.breach_count("123456789").unwrap(), 1);
+        assert_eq!(cached.breach_count("123456789").unwrap(), 1);
+        assert!(cached.breach_count("offline").is_err());
+        assert!(cached.breach_count("offline").is_err());
+        assert_eq!(cached.provider().calls.load(Ordering::Relaxed), 3);
+
+        let stats = cached.stats();
+
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 3, 1));
+        assert_eq!(stats.hit_rate(), 0.25);
+        assert_ne!(
+            cached.key("123456789"),
+            CachedBreachProvider::new(Counting::default(), 2, Duration::from_secs(60))
+                .key("123456789")
+        );
+    }
+
+    #[test]
+    fn it_evicts_old_entries() {
+        let now = Instant::now();
+        let later = now + Duration::from_secs(60);
+        let mut lru = Lru::new(2);
+
+        lru.insert([1; 20], 1, Some(later));
+        lru.insert([2; 20], 2, Some(later));
+        assert_eq!(lru.get(&[1; 20], now), Some(1));
+
+        lru.insert([3; 20], 3, Some(later));
+        assert_eq!(lru.get(&[2; 20], now), None);
+        assert_eq!(lru.get(&[1; 20], now), Some(1));
+        assert_eq!(lru.get(&[3; 20], now), Some(3));
+        assert_eq!(lru.get(&[3; 20], later), None);
+        assert_eq!((lru.entries.len(), lru.order.len()), (1, 1));
+
+        lru.insert([4; 20], 4, None);
+        assert_eq!(lru.get(&[4; 20], later + Duration::from_secs(60)), Some(4));
+    }
+
+    #[test]
+    fn it_caches_answers_forever() {
+        let cached = CachedBreachProvider::new(Counting::default(), 2, Duration::MAX);
+
+        assert_eq!(cached.breach_count("123456789").unwrap(), 1);
+        assert_eq!(cached.breach_count("123456789").unwrap(), 1);
+        assert_eq!(cached.provider().calls.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,130 @@
+//! Cooperative cancellation, for throwing away checks that are no longer needed, like the checks
+//! for a password the user has carried on typing since.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use checker::PasswordChecker;
+use {check_entropy, PassablewordResult, PasswordError};
+
+/// A flag shared between the code running a check and the code that wants to stop it. Clones
+/// share the same flag, so one can be handed to the check while another is kept to cancel it.
+///
+/// Cancelling is cooperative: a check that's already running isn't interrupted, but no more
+/// checks are started, and the result is `PasswordError::Cancelled` rather than a stale answer.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{CancellationToken, PasswordChecker, PasswordError};
+///
+/// let checker = PasswordChecker::new();
+/// let token = CancellationToken::new();
+///
+/// // The user typed another character, so the last check is out of date
+/// token.cancel();
+///
+/// assert_eq!(
+///     checker.check_password_cancellable("Th1s iS a Sup3rR4ndom PassW0rd!", &token),
+///     Err(PasswordError::Cancelled)
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token which hasn't been cancelled.
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel every check using this token or one of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether or not `cancel` has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Return `PasswordError::Cancelled` if the token has been cancelled, for use with `?`
+    /// between steps.
+    pub(crate) fn check(&self) -> PassablewordResult {
+        if self.is_cancelled() {
+            Err(PasswordError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Check a password's entropy like `check_entropy` does, unless `token` is cancelled first. The
+/// entropy check is by far the slowest, so if the token is cancelled while it runs, its result is
+/// thrown away and `PasswordError::Cancelled` is returned instead.
+pub fn check_entropy_cancellable(password: &str, token: &CancellationToken) -> PassablewordResult {
+    token.check()?;
+
+    let result = check_entropy(password);
+
+    token.check()?;
+    result
+}
+
+impl PasswordChecker {
+    /// Check a password like `check_password` does, but give up with `PasswordError::Cancelled`
+    /// as soon as `token` is cancelled. The token is looked at before each check and once more
+    /// at the end, so a password that changed while it was being checked never gets an answer.
+    pub fn check_password_cancellable(
+        &self,
+        password: &str,
+        token: &CancellationToken,
+    ) -> PassablewordResult {
+        let result = self.decide(password, None, Some(token));
+
+        token.check()?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_entropy_cancellable, CancellationToken};
+    use checker::PasswordChecker;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    #[test]
+    fn it_cancels_checks() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"));
+        let token = CancellationToken::new();
+        let clone = token.clone();
+
+        assert_eq!(
+            checker.check_password_cancellable("x7#Kq9!zLm2$vB4&wP", &token),
+            Ok(())
+        );
+        assert_eq!(
+            checker.check_password_cancellable("short", &token),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            check_entropy_cancellable("x7#Kq9!zLm2$vB4&wP", &token),
+            Ok(())
+        );
+
+        clone.cancel();
+
+        assert!(token.is_cancelled());
+        assert_eq!(
+            checker.check_password_cancellable("x7#Kq9!zLm2$vB4&wP", &token),
+            Err(PasswordError::Cancelled)
+        );
+        assert_eq!(
+            check_entropy_cancellable("x7#Kq9!zLm2$vB4&wP", &token),
+            Err(PasswordError::Cancelled)
+        );
+    }
+}
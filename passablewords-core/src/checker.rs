@@ -0,0 +1,1098 @@
+//! A reusable checker for applications that check passwords from many threads at once, such as
+//! web servers.
+
+use std::cmp::Reverse;
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+#[cfg(feature = "zxcvbn")]
+use analyzer::ZxcvbnAnalyzer;
+use analyzer::{EntropyAnalyzer, SharedAnalyzer};
+use cancel::CancellationToken;
+use composition::Composition;
+use context::CheckContext;
+use decision::{Check, DecisionHook, DecisionRecord, Outcome, SharedHook};
+use frequency::FrequencySketch;
+use hashed::HashedCandidate;
+use normalize::Normalizer;
+use policy::{CharsetMode, Policy};
+use profile::{check_entropy_profiled, AnalysisProfile};
+use quick_entropy::check_quick_entropy_bits;
+use report::{monotonic_time, PassDetails, StrengthReport};
+use scrub::Scrubbed;
+use variants::{affix_stripped, single_words, word_shell};
+use wordlist::Wordlist;
+use {bundled_unavailable, PassablewordResult, PasswordError};
+
+/// Every check, in the order they're run.
+pub(crate) const CHECKS: [Check; 12] = [
+    Check::Charset,
+    Check::Length,
+    Check::UniqueCharacters,
+    Check::Variety,
+    Check::DeniedValues,
+    Check::BannedPatterns,
+    Check::KeyboardWalks,
+    Check::TemporalPatterns,
+    Check::Uniqueness,
+    Check::WordShells,
+    Check::SingleDictionaryWord,
+    Check::Entropy,
+];
+
+/// Checks passwords against a policy and a wordlist. Unless they're replaced, the default policy
+/// and the bundled list of 1,000,000 common passwords are used.
+///
+/// A `PasswordChecker` is `Send + Sync` and everything it owns lives behind an `Arc`, so cloning
+/// it only bumps a reference count. Build one when the application starts and hand a clone to
+/// each worker, or share a single one through your framework's state (axum's `State`, actix's
+/// `Data`, etc.). None of the checks need mutable access, so there's no need to wrap it in a
+/// `Mutex` or `RwLock`.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use passablewords_core::PasswordChecker;
+///
+/// let checker = PasswordChecker::new();
+/// let worker = checker.clone();
+///
+/// thread::spawn(move || worker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"))
+///     .join()
+///     .unwrap()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PasswordChecker {
+    wordlist: Option<Arc<Wordlist>>,
+    labelled_wordlists: Vec<LabelledWordlist>,
+    dictionary: Option<Arc<Wordlist>>,
+    frequency_sketch: Option<Arc<FrequencySketch>>,
+    policy: Arc<Policy>,
+    decision_hook: Option<SharedHook>,
+    analyzer: Option<SharedAnalyzer>,
+}
+
+impl PasswordChecker {
+    /// Create a checker which uses the bundled list of common passwords. If the list can't be
+    /// read, uniqueness checks fail with `PasswordError::WordlistUnavailable`, saying why.
+    pub fn new() -> PasswordChecker {
+        PasswordChecker {
+            wordlist: Wordlist::bundled(),
+            labelled_wordlists: Vec::new(),
+            dictionary: None,
+            frequency_sketch: None,
+            policy: Arc::new(Policy::default()),
+            decision_hook: None,
+            analyzer: None,
+        }
+    }
+
+    /// Create a checker which uses the given wordlist instead of the bundled one. Both an owned
+    /// `Wordlist` and an `Arc<Wordlist>` that's already shared elsewhere are accepted.
+    pub fn with_wordlist<W: Into<Arc<Wordlist>>>(wordlist: W) -> PasswordChecker {
+        PasswordChecker {
+            wordlist: Some(wordlist.into()),
+            labelled_wordlists: Vec::new(),
+            dictionary: None,
+            frequency_sketch: None,
+            policy: Arc::new(Policy::default()),
+            decision_hook: None,
+            analyzer: None,
+        }
+    }
+
+    /// Also reject passwords within `wordlist`, such as passwords from a breach your organization
+    /// has dealt with, reporting them as `PasswordError::TooCommonIn` with the given label. This
+    /// can be called more than once to layer several lists on top of the main one, which is
+    /// checked first. Use `Wordlist::merge` instead if the label isn't needed, or
+    /// `with_prioritized_wordlist` to check a list before the main one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Wordlist};
+    ///
+    /// let checker = PasswordChecker::new()
+    ///     .with_labelled_wordlist("2019 incident", Wordlist::parse("Acme2019!"));
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness("Acme2019!"),
+    ///     Err(PasswordError::TooCommonIn("2019 incident".to_string()))
+    /// );
+    /// assert_eq!(checker.check_uniqueness("password"), Err(PasswordError::TooCommon));
+    /// ```
+    pub fn with_labelled_wordlist<W: Into<Arc<Wordlist>>>(
+        self,
+        label: &str,
+        wordlist: W,
+    ) -> PasswordChecker {
+        self.with_prioritized_wordlist(label, wordlist, 0)
+    }
+
+    /// Also reject passwords within `wordlist` like `with_labelled_wordlist` does, reporting a
+    /// password found in several lists with the label of the one with the highest `priority`.
+    /// Lists with a priority above 0 are checked before the main wordlist, and the rest after it,
+    /// so a password in both the main list and a list with a higher priority is reported as
+    /// `TooCommonIn` rather than `TooCommon`. Lists with the same priority are checked in the
+    /// order they were added. `with_labelled_wordlist` adds lists with a priority of 0.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Wordlist};
+    ///
+    /// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nAcme2019!"))
+    ///     .with_prioritized_wordlist("offline HIBP", Wordlist::parse("Acme2019!"), 1)
+    ///     .with_prioritized_wordlist("company", Wordlist::parse("Acme2019!"), 2);
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness("Acme2019!"),
+    ///     Err(PasswordError::TooCommonIn("company".to_string()))
+    /// );
+    /// assert_eq!(checker.check_uniqueness("password"), Err(PasswordError::TooCommon));
+    /// ```
+    pub fn with_prioritized_wordlist<W: Into<Arc<Wordlist>>>(
+        mut self,
+        label: &str,
+        wordlist: W,
+        priority: i32,
+    ) -> PasswordChecker {
+        let index = self
+            .labelled_wordlists
+            .iter()
+            .position(|labelled| labelled.priority < priority)
+            .unwrap_or(self.labelled_wordlists.len());
+
+        self.labelled_wordlists.insert(
+            index,
+            LabelledWordlist {
+                label: label.to_string(),
+                wordlist: wordlist.into(),
+                priority,
+            },
+        );
+        self
+    }
+
+    /// Add several labelled lists at once, each with a priority, like `with_prioritized_wordlist`
+    /// does, such as the bundled list, an offline copy of Have I Been Pwned, and a company list.
+    /// Since a password is only ever reported with the label of the first list it's found in,
+    /// every other list can forget it, so each password is only kept in the list that would
+    /// report it. That includes the main wordlist and the lists already added, which are left as
+    /// they are. Only lists created with `parse`, `from_file`, or `collect` can forget passwords,
+    /// and other kinds are added as they are.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Wordlist};
+    ///
+    /// let sources = vec![
+    ///     ("breaches", Wordlist::parse("password\nhunter2\nAcme2019!"), 0),
+    ///     ("company", Wordlist::parse("Acme2019!\nAcme2020!"), 1),
+    /// ];
+    /// let checker =
+    ///     PasswordChecker::with_wordlist(Wordlist::parse("password")).with_wordlist_sources(sources);
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness("Acme2019!"),
+    ///     Err(PasswordError::TooCommonIn("company".to_string()))
+    /// );
+    /// assert_eq!(
+    ///     checker.check_uniqueness("hunter2"),
+    ///     Err(PasswordError::TooCommonIn("breaches".to_string()))
+    /// );
+    /// ```
+    pub fn with_wordlist_sources<I, S>(mut self, sources: I) -> PasswordChecker
+    where
+        I: IntoIterator<Item = (S, Wordlist, i32)>,
+        S: AsRef<str>,
+    {
+        let mut sources: Vec<(S, Wordlist, i32)> = sources.into_iter().collect();
+
+        // Stable, so lists with the same priority keep the order they were given in
+        sources.sort_by_key(|&(_, _, priority)| Reverse(priority));
+
+        for (label, mut wordlist, priority) in sources {
+            let main = self.wordlist.as_ref().filter(|_| priority <= 0);
+            let earlier: Vec<&Wordlist> = self
+                .labelled_wordlists
+                .iter()
+                .filter(|labelled| labelled.priority >= priority)
+                .map(|labelled| &*labelled.wordlist)
+                .chain(main.map(|main| &**main))
+                .collect();
+
+            wordlist.retain(|password| !earlier.iter().any(|list| list.contains(password)));
+            self = self.with_prioritized_wordlist(label.as_ref(), wordlist, priority);
+        }
+        self
+    }
+
+    /// Also treat the words in `dictionary` as dictionary words for
+    /// `Policy::reject_single_dictionary_words`, on top of the wordlist. The list of common
+    /// passwords is missing plenty of long words, like `antidisestablishmentarianism`, that are
+    /// in any dictionary an attacker would use.
+    pub fn with_dictionary<W: Into<Arc<Wordlist>>>(mut self, dictionary: W) -> PasswordChecker {
+        self.dictionary = Some(dictionary.into());
+        self
+    }
+
+    /// Also reject passwords that at least `FrequencySketch::threshold` of the organization's own
+    /// users have already chosen, reporting them as `PasswordError::TooCommonLocally`. The sketch
+    /// is usually shared, so that passwords recorded by one part of the application are counted
+    /// by every checker.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use passablewords_core::{FrequencySketch, PasswordChecker, PasswordError};
+    ///
+    /// let sketch = Arc::new(FrequencySketch::new(b"a secret only the servers know", 1));
+    /// let checker = PasswordChecker::new().with_frequency_sketch(sketch.clone());
+    ///
+    /// sketch.record("AcmeSummer2024!");
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness("AcmeSummer2024!"),
+    ///     Err(PasswordError::TooCommonLocally)
+    /// );
+    /// ```
+    pub fn with_frequency_sketch<S: Into<Arc<FrequencySketch>>>(
+        mut self,
+        sketch: S,
+    ) -> PasswordChecker {
+        self.frequency_sketch = Some(sketch.into());
+        self
+    }
+
+    /// Use the given policy instead of the default one.
+    pub fn with_policy(mut self, policy: Policy) -> PasswordChecker {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Use a policy that's shared with other checkers, without copying it.
+    pub(crate) fn with_shared_policy(mut self, policy: Arc<Policy>) -> PasswordChecker {
+        self.policy = policy;
+        self
+    }
+
+    /// Call `hook` with a `DecisionRecord` after every call to `check_password` or
+    /// `check_password_with_context`, replacing any previous hook.
+    pub fn with_decision_hook<H: DecisionHook + 'static>(mut self, hook: H) -> PasswordChecker {
+        self.decision_hook = Some(SharedHook(Arc::new(hook)));
+        self
+    }
+
+    /// Estimate how hard passwords are to guess with `analyzer` instead of zxcvbn, replacing any
+    /// previous analyzer. Unlike zxcvbn, it's given passwords with non-ASCII characters as well,
+    /// even when the policy allows Unicode.
+    pub fn with_analyzer<A: EntropyAnalyzer + 'static>(mut self, analyzer: A) -> PasswordChecker {
+        self.analyzer = Some(SharedAnalyzer(Arc::new(analyzer)));
+        self
+    }
+
+    /// The policy this checker enforces.
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// The wordlist this checker uses for uniqueness checks, or `None` if it was supposed to use
+    /// the bundled list but that couldn't be read.
+    pub fn wordlist(&self) -> Option<&Wordlist> {
+        self.wordlist.as_deref()
+    }
+
+    /// Check a password to make sure it's not within this checker's wordlists, and, if the
+    /// policy asks for it, that it isn't a common password with a few characters added to either
+    /// end, with a few characters substituted, or with a common password somewhere inside it.
+    pub fn check_uniqueness(&self, password: &str) -> PassablewordResult {
+        let wordlist = match self.wordlist {
+            Some(ref wordlist) => wordlist,
+            None => return Err(bundled_unavailable()),
+        };
+
+        self.check_lists(wordlist, password)?;
+
+        if self.policy.strips_common_affixes() && wordlist.contains_any(affix_stripped(password)) {
+            return Err(PasswordError::TooCommonVariant);
+        }
+
+        if let Some(normalizer) = self.policy.normalizer() {
+            let normalized = Scrubbed::from(normalizer.normalize(password));
+
+            if &*normalized != password && wordlist.contains(&normalized) {
+                return Err(PasswordError::TooCommonVariant);
+            }
+            if self.policy.strips_common_affixes()
+                && wordlist.contains_any(
+                    affix_stripped(password)
+                        .map(|variant| Scrubbed::from(normalizer.normalize(variant))),
+                )
+            {
+                return Err(PasswordError::TooCommonVariant);
+            }
+        }
+
+        if let Some(min_length) = self.policy.embedded_common_password_length() {
+            if wordlist.contains_embedded(password, min_length) == Some(true) {
+                return Err(PasswordError::TooCommonVariant);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Look a password up in the labelled lists, the wordlist, and the frequency sketch, in the
+    /// order of their priorities.
+    fn check_lists(&self, wordlist: &Wordlist, password: &str) -> PassablewordResult {
+        // Hash the password once for every list, rather than once per list
+        let candidate = HashedCandidate::new(password);
+
+        let (before, after) = self.labelled_wordlists.split_at(
+            self.labelled_wordlists
+                .iter()
+                .position(|labelled| labelled.priority <= 0)
+                .unwrap_or(self.labelled_wordlists.len()),
+        );
+
+        for labelled in before {
+            labelled.check(&candidate)?;
+        }
+
+        if wordlist.contains_hashed(&candidate) {
+            return Err(PasswordError::TooCommon);
+        }
+
+        for labelled in after {
+            labelled.check(&candidate)?;
+        }
+
+        if let Some(ref sketch) = self.frequency_sketch {
+            sketch.check(password)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that a password isn't a word from this checker's wordlist with only a few digits and
+    /// a symbol around it, like `Summer2024!`, if the policy rejects those. A match is reported
+    /// as `PasswordError::TooCommonVariant`.
+    pub fn check_word_shell(&self, password: &str) -> PassablewordResult {
+        if !self.policy.rejects_word_shells() {
+            return Ok(());
+        }
+
+        match (word_shell(password).map(Scrubbed::from), self.wordlist.as_ref()) {
+            (Some(ref word), Some(wordlist)) if wordlist.contains(word) => {
+                Err(PasswordError::TooCommonVariant)
+            }
+            (_, Some(_)) => Ok(()),
+            (_, None) => Err(bundled_unavailable()),
+        }
+    }
+
+    /// Check that a password isn't a single word from this checker's wordlist or dictionary, in
+    /// any case and with the policy's normalizer, or the default one, undoing substitutions,
+    /// unless it has the digits or symbols around it that the policy requires. Does nothing
+    /// unless the policy rejects single dictionary words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Policy, Wordlist};
+    ///
+    /// let checker = PasswordChecker::new()
+    ///     .with_dictionary(Wordlist::parse("antidisestablishmentarianism"))
+    ///     .with_policy(Policy::new().reject_single_dictionary_words(4, 2));
+    ///
+    /// assert_eq!(
+    ///     checker.check_single_dictionary_word("Ant1disestablishmentarianism!"),
+    ///     Err(PasswordError::SingleDictionaryWord)
+    /// );
+    /// assert_eq!(
+    ///     checker.check_single_dictionary_word("Antidisestablishmentarianism!1987"),
+    ///     Ok(())
+    /// );
+    /// ```
+    pub fn check_single_dictionary_word(&self, password: &str) -> PassablewordResult {
+        let (min_digits, min_symbols) = match self.policy.single_dictionary_word_extras() {
+            Some(extras) => extras,
+            None => return Ok(()),
+        };
+        let wordlist = match self.wordlist {
+            Some(ref wordlist) => wordlist,
+            None => return Err(bundled_unavailable()),
+        };
+        let default_normalizer;
+        let normalizer = match self.policy.normalizer() {
+            Some(normalizer) => normalizer,
+            None => {
+                default_normalizer = Normalizer::new();
+                &default_normalizer
+            }
+        };
+
+        for word in single_words(password) {
+            if (min_digits > 0 && word.digits >= min_digits)
+                || (min_symbols > 0 && word.symbols >= min_symbols)
+            {
+                continue;
+            }
+
+            let normalized = Scrubbed::from(normalizer.normalize(word.core));
+            let candidate = HashedCandidate::new(&normalized);
+
+            if normalized.chars().all(char::is_alphabetic)
+                && (wordlist.contains_hashed(&candidate)
+                    || self
+                        .dictionary
+                        .as_ref()
+                        .is_some_and(|dictionary| dictionary.contains_hashed(&candidate)))
+            {
+                return Err(PasswordError::SingleDictionaryWord);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check several variations of a password against this checker's lists at once, such as the
+    /// forms produced by a custom normalizer. Each candidate is looked up in the same lists as
+    /// `check_uniqueness` looks the password up in, in the same order. The candidates are assumed
+    /// to be derived from the password rather than being the password itself, so a match in the
+    /// wordlist is reported as `PasswordError::TooCommonVariant`, while matches in labelled lists
+    /// and the frequency sketch are reported like they are by `check_uniqueness`. Use
+    /// `check_uniqueness` for the password as it was entered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use passablewords_core::{PasswordChecker, PasswordError};
+    ///
+    /// let checker = PasswordChecker::new();
+    /// let password = "P@SSW0RD";
+    /// let candidates = vec![
+    ///     Cow::Owned(password.to_lowercase()),
+    ///     Cow::Owned(password.to_lowercase().replace('@', "a").replace('0', "o")),
+    /// ];
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness_any(candidates),
+    ///     Err(PasswordError::TooCommonVariant)
+    /// );
+    /// ```
+    pub fn check_uniqueness_any<I>(&self, candidates: I) -> PassablewordResult
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let wordlist = match self.wordlist {
+            Some(ref wordlist) => wordlist,
+            None => return Err(bundled_unavailable()),
+        };
+
+        for candidate in candidates {
+            self.check_lists(wordlist, candidate.as_ref())
+                .map_err(|err| match err {
+                    PasswordError::TooCommon => PasswordError::TooCommonVariant,
+                    err => err,
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Check a password's length against this checker's policy, splitting runs of letters into
+    /// the words of this checker's wordlist when the policy counts words.
+    pub fn check_length(&self, password: &str) -> PassablewordResult {
+        self.policy
+            .check_length_in(password, self.wordlist.as_deref())
+    }
+
+    /// Check a password's length, variety of characters, uniqueness, and entropy all in a single
+    /// call.
+    pub fn check_password(&self, password: &str) -> PassablewordResult {
+        self.decide(password, None, None)
+    }
+
+    /// Check a password like `check_password` does, while also taking the information in
+    /// `context` into account.
+    pub fn check_password_with_context(
+        &self,
+        password: &str,
+        context: &CheckContext,
+    ) -> PassablewordResult {
+        self.decide(password, Some(context), None)
+    }
+
+    /// Check a password like `check_password` does, returning a report stamped with the version
+    /// of this checker's policy and describing what the password is made of, instead of only the
+    /// result. Its score is estimated separately from the checks, so the analyzer runs twice for
+    /// passwords that reach the entropy check. The report also records how long the checks took
+    /// and when they ran on a monotonic clock, to line slow checks up with application logs, and,
+    /// for accepted passwords, what they did well.
+    pub fn report(&self, password: &str) -> StrengthReport {
+        let started = Instant::now();
+        let outcome = match self.check_password(password) {
+            Ok(()) => Outcome::Accepted,
+            Err(err) => Outcome::Rejected(err),
+        };
+        let check_duration = started.elapsed();
+        let score = self.score(password);
+        let pass_details = if outcome == Outcome::Accepted {
+            Some(PassDetails {
+                length_margin: password
+                    .chars()
+                    .count()
+                    .saturating_sub(self.policy.required_length()),
+                score_margin: score.map(|score| score.saturating_sub(self.policy.min_score())),
+                lists_checked: self.wordlist.iter().count() + self.labelled_wordlists.len(),
+            })
+        } else {
+            None
+        };
+
+        StrengthReport {
+            schema_version: StrengthReport::SCHEMA_VERSION,
+            policy_version: self.policy.version().map(str::to_string),
+            outcome,
+            checked_at: SystemTime::now(),
+            composition: Composition::of(password),
+            score,
+            check_duration: Some(check_duration),
+            monotonic_time: Some(monotonic_time(started)),
+            pass_details,
+        }
+    }
+
+    /// The score this checker's analyzer gives `password`, if it has one and the password isn't
+    /// too large to analyze.
+    pub(crate) fn score(&self, password: &str) -> Option<u8> {
+        self.policy.check_input_size(password).ok()?;
+
+        let analysis = match self.analyzer() {
+            Some(analyzer) => analyzer.analyze(password, &[]),
+            #[cfg(feature = "zxcvbn")]
+            None => ZxcvbnAnalyzer.analyze(password, &[]),
+            #[cfg(not(feature = "zxcvbn"))]
+            None => return None,
+        };
+
+        analysis.ok().map(|analysis| analysis.score)
+    }
+
+    /// Run every check and, if there's a decision hook, tell it what happened.
+    pub(crate) fn decide(
+        &self,
+        password: &str,
+        context: Option<&CheckContext>,
+        cancel: Option<&CancellationToken>,
+    ) -> PassablewordResult {
+        if self.decision_hook.is_none() {
+            return self.run_checks(password, context, cancel, &mut |_| ());
+        }
+
+        let mut checks = Vec::new();
+        let result = self.run_checks(password, context, cancel, &mut |check| {
+            checks.push(check)
+        });
+        let outcome = match result {
+            Ok(()) => Outcome::Accepted,
+            Err(ref err) => Outcome::Rejected(err.clone()),
+        };
+
+        self.record_decision(context, checks, outcome);
+        result
+    }
+
+    /// Run every check without telling the decision hook, for simulations.
+    pub(crate) fn verdict(&self, password: &str) -> PassablewordResult {
+        self.run_checks(password, None, None, &mut |_| ())
+    }
+
+    /// Run each check in turn until one fails, calling `ran` with each one that's run. Failures of
+    /// checks the policy only warns about are ignored. Once `cancel` is cancelled, no more checks
+    /// are started. Passwords over the policy's input limit are rejected before any check runs.
+    fn run_checks(
+        &self,
+        password: &str,
+        context: Option<&CheckContext>,
+        cancel: Option<&CancellationToken>,
+        ran: &mut dyn FnMut(Check),
+    ) -> PassablewordResult {
+        self.policy.check_input_size(password)?;
+
+        for &check in CHECKS.iter() {
+            if let Some(cancel) = cancel {
+                cancel.check()?;
+            }
+
+            let result = match self.run_check(check, password, context) {
+                Some(result) => result,
+                None => continue,
+            };
+
+            ran(check);
+
+            if let Err(err) = result {
+                if !self.policy.is_warning_only(check) {
+                    return Err(err);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run a single check, or return `None` if it doesn't apply, like `Check::DeniedValues`
+    /// without a context.
+    pub(crate) fn run_check(
+        &self,
+        check: Check,
+        password: &str,
+        context: Option<&CheckContext>,
+    ) -> Option<PassablewordResult> {
+        Some(match check {
+            Check::Charset => self.policy.check_charset(password),
+            Check::Length => self.check_length(password),
+            Check::UniqueCharacters => self.policy.check_unique_chars(password),
+            Check::Variety => self.policy.check_variety(password),
+            Check::DeniedValues => context?.check_denied(password),
+            Check::BannedPatterns => self.policy.check_banned_patterns(password),
+            Check::KeyboardWalks => self.policy.check_keyboard_walks(password),
+            Check::TemporalPatterns => self.policy.check_temporal_patterns(password),
+            Check::Uniqueness => self.check_uniqueness(password),
+            Check::WordShells => self.check_word_shell(password),
+            Check::SingleDictionaryWord => self.check_single_dictionary_word(password),
+            Check::Entropy => self.check_entropy(password, context),
+        })
+    }
+
+    /// Check a password's entropy with this checker's analyzer, as thoroughly as the context asks
+    /// for. zxcvbn can't analyze non-ASCII passwords, so without an analyzer of its own, the quick
+    /// estimate is used for those when the policy allows Unicode.
+    fn check_entropy(&self, password: &str, context: Option<&CheckContext>) -> PassablewordResult {
+        let compat = self.policy.compat();
+        let analyzer = self.analyzer.as_ref().map(|analyzer| &*analyzer.0);
+
+        match self.policy.charset() {
+            CharsetMode::Unicode if !password.is_ascii() && analyzer.is_none() => {
+                check_quick_entropy_bits(password, compat.min_quick_entropy_bits())
+            }
+            CharsetMode::Unicode | CharsetMode::AsciiOnly => {
+                let inputs: Vec<&str> = context
+                    .map(|context| context.user_inputs().iter().map(String::as_str).collect())
+                    .unwrap_or_default();
+
+                check_entropy_profiled(
+                    password,
+                    &inputs,
+                    context.map_or(AnalysisProfile::default(), CheckContext::profile),
+                    compat,
+                    self.policy.score_floor(),
+                    analyzer,
+                )
+            }
+        }
+    }
+
+    /// The analyzer given with `with_analyzer`, if any.
+    pub(crate) fn analyzer(&self) -> Option<&dyn EntropyAnalyzer> {
+        self.analyzer.as_ref().map(|analyzer| &*analyzer.0)
+    }
+
+    /// The labels of the lists given with `with_labelled_wordlist` and the like, in the order
+    /// they're tried.
+    pub(crate) fn wordlist_labels(&self) -> impl Iterator<Item = &str> {
+        self.labelled_wordlists
+            .iter()
+            .map(|labelled| labelled.label.as_str())
+    }
+
+    /// The sketch given with `with_frequency_sketch`, if any.
+    pub(crate) fn frequency_sketch(&self) -> Option<&FrequencySketch> {
+        self.frequency_sketch.as_deref()
+    }
+
+    /// Whether there's a decision hook, so callers only collect the checks that were run for it
+    /// when someone's listening.
+    pub(crate) fn has_decision_hook(&self) -> bool {
+        self.decision_hook.is_some()
+    }
+
+    /// Tell the decision hook, if there is one, about a decision made outside of `decide`.
+    pub(crate) fn record_decision(
+        &self,
+        context: Option<&CheckContext>,
+        checks: Vec<Check>,
+        outcome: Outcome,
+    ) {
+        if let Some(ref hook) = self.decision_hook {
+            hook.0.on_decision(&DecisionRecord {
+                timestamp: SystemTime::now(),
+                policy_version: self.policy.version().map(str::to_string),
+                request_id: context
+                    .and_then(|context| context.request_id())
+                    .map(str::to_string),
+                checks,
+                outcome,
+            });
+        }
+    }
+}
+
+impl Default for PasswordChecker {
+    fn default() -> PasswordChecker {
+        PasswordChecker::new()
+    }
+}
+
+/// A list given with `with_labelled_wordlist` or `with_prioritized_wordlist`.
+#[derive(Debug, Clone)]
+struct LabelledWordlist {
+    label: String,
+    wordlist: Arc<Wordlist>,
+    priority: i32,
+}
+
+impl LabelledWordlist {
+    fn check(&self, candidate: &HashedCandidate) -> PassablewordResult {
+        if self.wordlist.contains_hashed(candidate) {
+            Err(PasswordError::TooCommonIn(self.label.clone()))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PasswordChecker;
+    use context::CheckContext;
+    use decision::{Check, DecisionRecord};
+    use frequency::FrequencySketch;
+    use normalize::Normalizer;
+    use policy::{CharsetMode, Policy};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn it_is_send_and_sync() {
+        assert_send_sync::<PasswordChecker>();
+    }
+
+    #[test]
+    fn it_shares_the_wordlist_between_clones() {
+        let checker = PasswordChecker::new();
+        let clone = checker.clone();
+
+        assert!(Arc::ptr_eq(
+            checker.wordlist.as_ref().unwrap(),
+            clone.wordlist.as_ref().unwrap()
+        ));
+        assert!(Arc::ptr_eq(&checker.policy, &clone.policy));
+        assert!(Arc::ptr_eq(
+            checker.wordlist.as_ref().unwrap(),
+            &Wordlist::bundled().unwrap()
+        ));
+    }
+
+    #[test]
+    fn it_checks_passwords_across_threads() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("correcthorse"));
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let checker = checker.clone();
+
+                thread::spawn(move || checker.check_uniqueness("correcthorse"))
+            })
+            .collect();
+
+        for worker in workers {
+            assert_eq!(worker.join().unwrap(), Err(PasswordError::TooCommon));
+        }
+        assert_eq!(checker.check_uniqueness("password"), Ok(()));
+    }
+
+    #[test]
+    fn it_validates_word_shells() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("summer"))
+            .with_policy(Policy::new().reject_word_shells(true));
+
+        assert_eq!(
+            checker.check_word_shell("Summer2024!"),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(checker.check_word_shell("Winter2024!"), Ok(()));
+        assert_eq!(checker.check_word_shell("Summer2024!x"), Ok(()));
+        assert_eq!(
+            PasswordChecker::with_wordlist(Wordlist::parse("summer")).check_word_shell("Summer1!"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_validates_single_dictionary_words() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("sunshine"))
+            .with_dictionary(Wordlist::parse("antidisestablishmentarianism"))
+            .with_policy(Policy::new().reject_single_dictionary_words(4, 2));
+
+        assert_eq!(
+            checker.check_single_dictionary_word("ANTIDISESTABLISHMENTARIANISM"),
+            Err(PasswordError::SingleDictionaryWord)
+        );
+        assert_eq!(
+            checker.check_single_dictionary_word("$unsh1ne!99"),
+            Err(PasswordError::SingleDictionaryWord)
+        );
+        assert_eq!(
+            checker.check_single_dictionary_word("$unsh1ne!1999"),
+            Ok(())
+        );
+        assert_eq!(checker.check_single_dictionary_word("!!sunshine"), Ok(()));
+        assert_eq!(
+            checker.check_single_dictionary_word("sunshine-mango"),
+            Ok(())
+        );
+        assert_eq!(
+            checker
+                .clone()
+                .with_policy(Policy::new().reject_single_dictionary_words(0, 2))
+                .check_single_dictionary_word("sunshine2024"),
+            Err(PasswordError::SingleDictionaryWord)
+        );
+        assert_eq!(
+            checker
+                .with_policy(
+                    Policy::new()
+                        .reject_single_dictionary_words(4, 2)
+                        .warn_only(Check::SingleDictionaryWord)
+                )
+                .check_password("Antidisestablishmentarianism"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_validates_common_variants() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password"));
+        let stripping = checker
+            .clone()
+            .with_policy(Policy::new().strip_common_affixes(true));
+
+        assert_eq!(checker.check_uniqueness("password2024!"), Ok(()));
+        assert_eq!(
+            stripping.check_uniqueness("password2024!"),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(
+            stripping.check_uniqueness("password"),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(stripping.check_uniqueness("passwords2024!"), Ok(()));
+    }
+
+    #[test]
+    fn it_validates_normalized_variants() {
+        let normalizing = PasswordChecker::with_wordlist(Wordlist::parse("password"))
+            .with_policy(Policy::new().with_normalizer(Normalizer::new()));
+        let stripping = normalizing.clone().with_policy(
+            Policy::new()
+                .with_normalizer(Normalizer::new())
+                .strip_common_affixes(true),
+        );
+
+        assert_eq!(
+            normalizing.check_uniqueness("P@$$w0rd"),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(normalizing.check_uniqueness("P@$$w0rd2024"), Ok(()));
+        assert_eq!(
+            stripping.check_uniqueness("P@$$w0rd2024"),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(stripping.check_uniqueness("P@$$w0rds"), Ok(()));
+    }
+
+    #[test]
+    fn it_looks_candidates_up_in_every_list() {
+        let sketch = Arc::new(FrequencySketch::new(b"key", 1));
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password"))
+            .with_labelled_wordlist("company", Wordlist::parse("acme2020"))
+            .with_frequency_sketch(sketch.clone());
+
+        sketch.record("hunter22");
+
+        assert_eq!(
+            checker.check_uniqueness_any(&["P4ssword", "password"]),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(
+            checker.check_uniqueness_any(&["Acme2020", "acme2020"]),
+            Err(PasswordError::TooCommonIn("company".to_string()))
+        );
+        assert_eq!(
+            checker.check_uniqueness_any(&["hunter22"]),
+            Err(PasswordError::TooCommonLocally)
+        );
+        assert_eq!(checker.check_uniqueness_any(&["correcthorse"]), Ok(()));
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn it_validates_embedded_common_passwords() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse_fst("password").unwrap())
+            .with_policy(Policy::new().reject_embedded_common_passwords(6));
+
+        assert_eq!(
+            checker.check_uniqueness("xXpasswordXx"),
+            Err(PasswordError::TooCommonVariant)
+        );
+        assert_eq!(checker.check_uniqueness("xXpassXx"), Ok(()));
+    }
+
+    #[test]
+    fn it_layers_wordlists_by_priority() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+            .with_labelled_wordlist("existing", Wordlist::parse("hunter2"))
+            .with_wordlist_sources(vec![
+                (
+                    "offline",
+                    Wordlist::parse("password\nhunter2\nqwerty\nAcme2019!"),
+                    0,
+                ),
+                (
+                    "company",
+                    Wordlist::parse("Acme2019!\nAcme2020!\nletmein"),
+                    5,
+                ),
+                ("also company", Wordlist::parse("Acme2020!\nAcme2021!"), 5),
+            ]);
+        let lists: Vec<(&str, usize)> = checker
+            .labelled_wordlists
+            .iter()
+            .map(|labelled| (labelled.label.as_str(), labelled.wordlist.len()))
+            .collect();
+
+        assert_eq!(
+            lists,
+            vec![
+                ("company", 3),
+                ("also company", 1),
+                ("existing", 1),
+                ("offline", 1),
+            ]
+        );
+        assert_eq!(checker.wordlist_labels().next(), Some("company"));
+        assert_eq!(
+            checker.check_uniqueness("letmein"),
+            Err(PasswordError::TooCommonIn("company".to_string()))
+        );
+        assert_eq!(
+            checker.check_uniqueness("Acme2020!"),
+            Err(PasswordError::TooCommonIn("company".to_string()))
+        );
+        assert_eq!(
+            checker.check_uniqueness("hunter2"),
+            Err(PasswordError::TooCommonIn("existing".to_string()))
+        );
+        assert_eq!(
+            checker.check_uniqueness("qwerty"),
+            Err(PasswordError::TooCommonIn("offline".to_string()))
+        );
+        assert_eq!(
+            checker.check_uniqueness("password"),
+            Err(PasswordError::TooCommon)
+        );
+    }
+
+    #[test]
+    fn it_records_decisions() {
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let recorded = records.clone();
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+            .with_policy(Policy::new().with_version("v2"))
+            .with_decision_hook(move |record: &DecisionRecord| {
+                recorded.lock().unwrap().push((
+                    record.policy_version.clone(),
+                    record.request_id.clone(),
+                    record.checks.clone(),
+                ));
+            });
+        let context = CheckContext::new().with_request_id("req-1");
+
+        assert_eq!(
+            checker.check_password_with_context("password123", &context),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            checker.check_password("short"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            *records.lock().unwrap(),
+            vec![
+                (
+                    Some("v2".to_string()),
+                    Some("req-1".to_string()),
+                    vec![
+                        Check::Charset,
+                        Check::Length,
+                        Check::UniqueCharacters,
+                        Check::Variety,
+                        Check::DeniedValues,
+                        Check::BannedPatterns,
+                        Check::KeyboardWalks,
+                        Check::TemporalPatterns,
+                        Check::Uniqueness,
+                    ],
+                ),
+                (
+                    Some("v2".to_string()),
+                    None,
+                    vec![Check::Charset, Check::Length]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_uses_the_policy() {
+        let checker = PasswordChecker::new().with_policy(Policy::new().min_length(40));
+
+        assert_eq!(
+            checker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Err(PasswordError::TooShort)
+        );
+    }
+
+    #[test]
+    fn it_uses_the_charset_mode() {
+        let ascii = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"))
+            .with_policy(Policy::new().charset_mode(CharsetMode::AsciiOnly));
+        let unicode = ascii
+            .clone()
+            .with_policy(Policy::new().charset_mode(CharsetMode::Unicode));
+
+        assert_eq!(
+            ascii.check_password("ü"),
+            Err(PasswordError::NonAsciiPassword)
+        );
+        assert_eq!(unicode.check_password("ü"), Err(PasswordError::TooShort));
+        assert_eq!(unicode.check_password("x7#Kq9!zLm2$vB4&wPü"), Ok(()));
+        assert_eq!(
+            unicode.check_password("üüüüüüüü"),
+            Err(PasswordError::TooSimple)
+        );
+    }
+}
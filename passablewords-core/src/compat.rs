@@ -0,0 +1,90 @@
+//! Pinning the entropy check's behavior to a major version of passablewords, so that upgrading
+//! zxcvbn doesn't change which passwords pass.
+
+use quick_entropy::QUICK_ENTROPY_MIN_BITS;
+use MIN_ENTROPY_SCORE;
+
+/// How a policy decides whether a password's entropy is high enough.
+///
+/// zxcvbn's scores can shift a little between its releases, which silently changes which
+/// passwords pass and breaks golden tests. A pinned mode instead compares zxcvbn's estimate of
+/// how many guesses a password would take against a threshold that only changes with a new
+/// major version of passablewords. zxcvbn's estimates can still move, but the line drawn through
+/// them doesn't.
+///
+/// | Mode | With zxcvbn | Without zxcvbn |
+/// |------|-------------|----------------|
+/// | `Latest` | zxcvbn's own score is at least 3 out of 4 | `QUICK_ENTROPY_MIN_BITS` |
+/// | `V1` | At least 10^8 guesses, which is a score of 3 in zxcvbn 0.6 | At least 50 bits |
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{Compat, PasswordChecker, Policy};
+///
+/// let checker = PasswordChecker::new().with_policy(Policy::new().pin_compat(Compat::V1));
+///
+/// assert!(checker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!").is_ok());
+/// assert!(checker.check_password("aaaaaaaaaaab").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Compat {
+    /// Go by the score of whichever zxcvbn release is in use. This is the default.
+    #[default]
+    Latest,
+    /// Decide the way passablewords 1.x does, whichever zxcvbn release is in use.
+    V1,
+}
+
+/// The fewest guesses, as a power of ten, that pass under `Compat::V1`.
+pub(crate) const V1_MIN_GUESSES_LOG10: f64 = 8.0;
+
+/// The lowest zxcvbn score a password that passes under `Compat::V1` can have. zxcvbn 0.6 scores
+/// passwords just over 10^8 guesses as 2, and other releases draw the line a little differently.
+const V1_MIN_SCORE: u8 = 2;
+
+/// The fewest bits of `quick_entropy_bits` that pass under `Compat::V1`.
+const V1_MIN_QUICK_ENTROPY_BITS: f64 = 50.0;
+
+impl Compat {
+    /// Whether zxcvbn's analysis of a password passes.
+    pub(crate) fn accepts(self, score: u8, guesses_log10: f64) -> bool {
+        match self {
+            Compat::Latest => score >= MIN_ENTROPY_SCORE,
+            Compat::V1 => guesses_log10 >= V1_MIN_GUESSES_LOG10,
+        }
+    }
+
+    /// The lowest zxcvbn score a password that passes can have, for frontends and reports that
+    /// only know the score.
+    pub(crate) fn min_score(self) -> u8 {
+        match self {
+            Compat::Latest => MIN_ENTROPY_SCORE,
+            Compat::V1 => V1_MIN_SCORE,
+        }
+    }
+
+    /// The fewest bits of `quick_entropy_bits` that pass.
+    pub(crate) fn min_quick_entropy_bits(self) -> f64 {
+        match self {
+            Compat::Latest => QUICK_ENTROPY_MIN_BITS,
+            Compat::V1 => V1_MIN_QUICK_ENTROPY_BITS,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Compat;
+
+    #[test]
+    fn it_pins_thresholds() {
+        assert!(Compat::V1.accepts(0, 8.0));
+        assert!(!Compat::V1.accepts(4, 7.9));
+        assert!(Compat::Latest.accepts(3, 0.0));
+        assert!(!Compat::Latest.accepts(2, 20.0));
+        assert_eq!(Compat::V1.min_quick_entropy_bits(), 50.0);
+        assert_eq!(Compat::Latest.min_score(), 3);
+        assert_eq!(Compat::V1.min_score(), 2);
+    }
+}
@@ -0,0 +1,226 @@
+//! What a password is made of, for giving targeted advice and debugging problems with how
+//! passwords are encoded or normalized before they're checked.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// The writing systems, character classes, and lengths of a password. It's included in every
+/// `StrengthReport`, and doesn't contain the password itself.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{CharacterClass, Composition, Script};
+///
+/// let composition = Composition::of("Пароль 123 é");
+///
+/// assert_eq!(composition.scripts, vec![Script::Cyrillic, Script::Latin]);
+/// assert_eq!(
+///     composition.classes,
+///     vec![
+///         CharacterClass::Uppercase,
+///         CharacterClass::Lowercase,
+///         CharacterClass::Space,
+///         CharacterClass::Digit,
+///     ]
+/// );
+/// assert_eq!((composition.bytes, composition.chars, composition.graphemes), (19, 12, 12));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Composition {
+    /// The writing systems of the letters in the password, in the order they first appear.
+    /// Digits, punctuation, and other characters shared between scripts aren't counted.
+    pub scripts: Vec<Script>,
+    /// The classes of characters used, in the order they first appear.
+    pub classes: Vec<CharacterClass>,
+    /// The length of the password in UTF-8 bytes.
+    pub bytes: usize,
+    /// The length of the password in Unicode scalar values, which is what the length rules count.
+    pub chars: usize,
+    /// The length of the password in extended grapheme clusters, which is closest to the number
+    /// of characters a user sees. An accented letter written as a letter followed by a combining
+    /// accent is two chars but one grapheme, for example.
+    pub graphemes: usize,
+}
+
+/// A writing system. Only the most widely used scripts are told apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Script {
+    /// Latin letters, including accented ones.
+    Latin,
+    /// Greek letters.
+    Greek,
+    /// Cyrillic letters.
+    Cyrillic,
+    /// Armenian letters.
+    Armenian,
+    /// Hebrew letters.
+    Hebrew,
+    /// Arabic letters.
+    Arabic,
+    /// Devanagari, used for Hindi among others.
+    Devanagari,
+    /// Thai letters.
+    Thai,
+    /// Hangul, used for Korean.
+    Hangul,
+    /// Hiragana, used for Japanese.
+    Hiragana,
+    /// Katakana, used for Japanese.
+    Katakana,
+    /// Han ideographs, used for Chinese and Japanese.
+    Han,
+    /// Letters from any other script.
+    Other,
+}
+
+/// A class of characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CharacterClass {
+    /// Lowercase letters.
+    Lowercase,
+    /// Uppercase and titlecase letters.
+    Uppercase,
+    /// Letters from scripts without case, like Han or Arabic.
+    Uncased,
+    /// Decimal digits from any script.
+    Digit,
+    /// Whitespace.
+    Space,
+    /// Punctuation, symbols, emoji, and anything else.
+    Symbol,
+}
+
+impl Composition {
+    /// Work out what `password` is made of.
+    pub fn of(password: &str) -> Composition {
+        let mut scripts = Vec::new();
+        let mut classes = Vec::new();
+        let mut chars = 0;
+
+        for character in password.chars() {
+            chars += 1;
+
+            let class = CharacterClass::of(character);
+
+            if !classes.contains(&class) {
+                classes.push(class);
+            }
+            if character.is_alphabetic() {
+                let script = Script::of(character);
+
+                if !scripts.contains(&script) {
+                    scripts.push(script);
+                }
+            }
+        }
+
+        Composition {
+            scripts,
+            classes,
+            bytes: password.len(),
+            chars,
+            graphemes: password.graphemes(true).count(),
+        }
+    }
+}
+
+impl Script {
+    /// The script a letter belongs to, based on the Unicode block it's in.
+    fn of(letter: char) -> Script {
+        match u32::from(letter) {
+            0x0041..=0x024F
+            | 0x1E00..=0x1EFF
+            | 0x2C60..=0x2C7F
+            | 0xA720..=0xA7FF
+            | 0xFF21..=0xFF5A => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F | 0x2DE0..=0x2DFF | 0xA640..=0xA69F | 0x1C80..=0x1C8F => {
+                Script::Cyrillic
+            }
+            0x0530..=0x058F => Script::Armenian,
+            0x0590..=0x05FF => Script::Hebrew,
+            0x0600..=0x06FF
+            | 0x0750..=0x077F
+            | 0x08A0..=0x08FF
+            | 0xFB50..=0xFDFF
+            | 0xFE70..=0xFEFF => Script::Arabic,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0E00..=0x0E7F => Script::Thai,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+            0x3040..=0x309F => Script::Hiragana,
+            0x30A0..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Script::Katakana,
+            0x2E80..=0x2FDF
+            | 0x3005
+            | 0x3007
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xF900..=0xFAFF
+            | 0x20000..=0x3134F => Script::Han,
+            _ => Script::Other,
+        }
+    }
+}
+
+impl CharacterClass {
+    fn of(character: char) -> CharacterClass {
+        if character.is_lowercase() {
+            CharacterClass::Lowercase
+        } else if character.is_uppercase() {
+            CharacterClass::Uppercase
+        } else if character.is_alphabetic() {
+            CharacterClass::Uncased
+        } else if character.is_numeric() {
+            CharacterClass::Digit
+        } else if character.is_whitespace() {
+            CharacterClass::Space
+        } else {
+            CharacterClass::Symbol
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharacterClass, Composition, Script};
+
+    #[test]
+    fn it_detects_scripts() {
+        assert_eq!(Composition::of("password1!").scripts, vec![Script::Latin]);
+        assert_eq!(
+            Composition::of("пароль密码パスワード비밀번호").scripts,
+            vec![
+                Script::Cyrillic,
+                Script::Han,
+                Script::Katakana,
+                Script::Hangul
+            ]
+        );
+        assert_eq!(Composition::of("12345678").scripts, vec![]);
+        assert_eq!(
+            Composition::of("密码").classes,
+            vec![CharacterClass::Uncased]
+        );
+    }
+
+    #[test]
+    fn it_counts_lengths() {
+        let composed = Composition::of("caf\u{e9}");
+        let decomposed = Composition::of("cafe\u{301}");
+
+        assert_eq!(
+            (composed.bytes, composed.chars, composed.graphemes),
+            (5, 4, 4)
+        );
+        assert_eq!(
+            (decomposed.bytes, decomposed.chars, decomposed.graphemes),
+            (6, 5, 4)
+        );
+        assert_eq!(
+            decomposed.classes,
+            vec![CharacterClass::Lowercase, CharacterClass::Symbol]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Policies read from TOML files, for the integrations that are set up by system administrators
+//! rather than in code, like the PAM module and the Active Directory password filter. Only
+//! available with the `config` feature.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use toml;
+
+use checker::PasswordChecker;
+use policy::Policy;
+use wordlist::Wordlist;
+use PasswordError;
+
+/// A policy and wordlist, as written in a TOML file. Every key is optional, and anything left out
+/// keeps the library's defaults. Unknown keys are an error, so a typo doesn't quietly loosen the
+/// policy.
+///
+/// ```toml
+/// # A list of common passwords to use instead of the bundled one
+/// wordlist = "/usr/share/passablewords/common-passwords.txt"
+/// min_length = 12
+/// min_words = 4
+/// min_unique_chars = 5
+/// reject_keyboard_walks = 4
+/// ```
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{PolicyConfig, Policy};
+///
+/// let config = PolicyConfig::parse("min_length = 12\nmin_words = 4")?;
+///
+/// assert_eq!(config.policy(), Policy::new().min_length_or_words(12, 4));
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyConfig {
+    /// A file of common passwords, one per line, to use instead of the bundled list.
+    pub wordlist: Option<PathBuf>,
+    /// The minimum length in characters. With `min_words`, passwords only need to meet one of
+    /// the two, like `Policy::min_length_or_words`.
+    pub min_length: Option<usize>,
+    /// The minimum number of words, for passphrases.
+    pub min_words: Option<usize>,
+    /// The minimum number of different characters, like `Policy::min_unique_chars`.
+    pub min_unique_chars: Option<usize>,
+    /// Whether to reject passwords made of only digits.
+    pub reject_all_digits: Option<bool>,
+    /// Whether to reject passwords made of only letters.
+    pub reject_all_letters: Option<bool>,
+    /// Reject runs of at least this many neighbouring keys, like `Policy::reject_keyboard_walks`.
+    pub reject_keyboard_walks: Option<usize>,
+    /// The most bytes a password can be before it's rejected without being checked.
+    pub max_input_bytes: Option<usize>,
+}
+
+impl PolicyConfig {
+    /// Read the configuration at `path`. A missing file is the default configuration, so an
+    /// integration works without one, but a file that can't be read or parsed is an error.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<PolicyConfig> {
+        match fs::read_to_string(path) {
+            Ok(contents) => PolicyConfig::parse(&contents),
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(PolicyConfig::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Parse a configuration from TOML. Errors are `io::ErrorKind::InvalidData`.
+    pub fn parse(contents: &str) -> io::Result<PolicyConfig> {
+        toml::from_str(contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// The policy the configuration describes.
+    pub fn policy(&self) -> Policy {
+        let mut policy = Policy::new();
+
+        policy = match (self.min_length, self.min_words) {
+            (Some(length), Some(words)) => policy.min_length_or_words(length, words),
+            (Some(length), None) => policy.min_length(length),
+            (None, Some(words)) => policy.min_words(words),
+            (None, None) => policy,
+        };
+        if let Some(count) = self.min_unique_chars {
+            policy = policy.min_unique_chars(count);
+        }
+        if let Some(reject) = self.reject_all_digits {
+            policy = policy.reject_all_digits(reject);
+        }
+        if let Some(reject) = self.reject_all_letters {
+            policy = policy.reject_all_letters(reject);
+        }
+        if let Some(length) = self.reject_keyboard_walks {
+            policy = policy.reject_keyboard_walks(length);
+        }
+        if let Some(bytes) = self.max_input_bytes {
+            policy = policy.max_input_bytes(bytes);
+        }
+        policy
+    }
+
+    /// A checker with the configured policy and wordlist. Fails with
+    /// `PasswordError::WordlistUnavailable` if the list can't be read, rather than letting every
+    /// password through the uniqueness check.
+    pub fn checker(&self) -> Result<PasswordChecker, PasswordError> {
+        let checker = match self.wordlist {
+            Some(ref path) => match Wordlist::from_file(path) {
+                Ok(wordlist) => PasswordChecker::with_wordlist(wordlist),
+                Err(err) => {
+                    return Err(PasswordError::WordlistUnavailable {
+                        path: Some(path.display().to_string()),
+                        source: err.to_string(),
+                    })
+                }
+            },
+            None => PasswordChecker::new(),
+        };
+
+        if checker.wordlist().is_none() {
+            Wordlist::load_bundled()?;
+        }
+
+        Ok(checker.with_policy(self.policy()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn it_parses_the_config() {
+        let config = PolicyConfig::parse(
+            "wordlist = \"/usr/share/passablewords/common-passwords.txt\"\n\
+             min_length = 12\n\
+             min_words = 4\n\
+             reject_keyboard_walks = 4\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.wordlist,
+            Some(PathBuf::from(
+                "/usr/share/passablewords/common-passwords.txt"
+            ))
+        );
+        assert_eq!(
+            config.policy(),
+            Policy::new()
+                .min_length_or_words(12, 4)
+                .reject_keyboard_walks(4)
+        );
+        assert_eq!(
+            PolicyConfig::parse("min_lenght = 12").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            PolicyConfig::load("/nonexistent/passablewords.toml").unwrap(),
+            PolicyConfig::default()
+        );
+    }
+
+    #[test]
+    fn it_checks_with_the_config() {
+        let path = env::temp_dir().join(format!("passablewords-config-{}.txt", process::id()));
+
+        fs::write(&path, "password123\npassword123password123\n").unwrap();
+
+        let config = PolicyConfig {
+            wordlist: Some(path.clone()),
+            min_length: Some(12),
+            ..PolicyConfig::default()
+        };
+        let checker = config.checker().unwrap();
+
+        assert_eq!(
+            checker.check_password("password123"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            checker.check_password("password123password123"),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            checker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Ok(())
+        );
+
+        fs::remove_file(&path).unwrap();
+
+        match config.checker() {
+            Err(PasswordError::WordlistUnavailable { path: Some(_), .. }) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
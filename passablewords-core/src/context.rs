@@ -0,0 +1,178 @@
+//! Information about a single check, such as values that the password must not be equal to.
+
+use credentials::check_credential_pair;
+use profile::AnalysisProfile;
+use small_string::SmallString;
+use {PassablewordResult, PasswordError};
+
+/// Extra information to take into account when checking one particular password, as opposed to the
+/// `Policy` which applies to every password.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{CheckContext, PasswordChecker, PasswordError};
+///
+/// let checker = PasswordChecker::new();
+/// let context = CheckContext::new().deny_exact(&["Kx4-93Fz-Q2r8-Lm0p"]);
+///
+/// assert_eq!(
+///     checker.check_password_with_context("kx4-93fz-q2r8-lm0p", &context),
+///     Err(PasswordError::MatchesDeniedValue)
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckContext {
+    denied: Vec<String>,
+    username: Option<String>,
+    request_id: Option<String>,
+    profile: AnalysisProfile,
+    user_inputs: Vec<String>,
+}
+
+impl CheckContext {
+    /// Create an empty context.
+    pub fn new() -> CheckContext {
+        CheckContext::default()
+    }
+
+    /// Reject passwords equal to any of `values`, such as an account recovery code or a temporary
+    /// password that was sent by email. Values are compared after normalizing both sides, which
+    /// ignores case and whitespace, so `ABCD 1234` and `abcd1234` count as a match. Empty values
+    /// are ignored.
+    pub fn deny_exact(mut self, values: &[&str]) -> CheckContext {
+        self.denied.extend(
+            values
+                .iter()
+                .map(|value| normalize(value).as_str().to_string())
+                .filter(|value| !value.is_empty()),
+        );
+        self
+    }
+
+    /// Reject passwords that are `username`, or trivially derived from it, the way
+    /// `check_credential_pair` does. Email addresses work too.
+    pub fn with_username(mut self, username: &str) -> CheckContext {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    /// Attach the ID of the request this check is part of, which is passed along to the checker's
+    /// decision hook so that log entries can be matched up with the rest of the request.
+    pub fn with_request_id(mut self, request_id: &str) -> CheckContext {
+        self.request_id = Some(request_id.to_string());
+        self
+    }
+
+    /// The ID of the request this check is part of, if one was given.
+    pub fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+
+    /// Analyze the password's entropy as thoroughly as `profile` asks for, instead of with the
+    /// default `AnalysisProfile::Thorough`.
+    pub fn with_profile(mut self, profile: AnalysisProfile) -> CheckContext {
+        self.profile = profile;
+        self
+    }
+
+    /// How thoroughly the password's entropy is analyzed.
+    pub fn profile(&self) -> AnalysisProfile {
+        self.profile
+    }
+
+    /// Tell the entropy check about words that are easy to guess for this particular password, like
+    /// the user's name, email address, or the name of the site, so that passwords built from them
+    /// score lower. They're passed to the `EntropyAnalyzer` as its `inputs`. Empty values are
+    /// ignored.
+    pub fn with_user_inputs(mut self, inputs: &[&str]) -> CheckContext {
+        self.user_inputs.extend(
+            inputs
+                .iter()
+                .filter(|input| !input.is_empty())
+                .map(|input| input.to_string()),
+        );
+        self
+    }
+
+    /// The words given to `with_user_inputs`.
+    pub fn user_inputs(&self) -> &[String] {
+        &self.user_inputs
+    }
+
+    /// How many values are denied.
+    pub(crate) fn denied_count(&self) -> usize {
+        self.denied.len()
+    }
+
+    /// Check a password to make sure it isn't equal to any of the denied values, or derived from
+    /// the username if there is one. Without a username, this doesn't allocate for passwords of up
+    /// to 64 bytes.
+    pub fn check_denied(&self, password: &str) -> PassablewordResult {
+        if let Some(ref username) = self.username {
+            check_credential_pair(username, password)?;
+        }
+
+        if self.denied.is_empty() {
+            return Ok(());
+        }
+
+        let password = normalize(password);
+
+        if self.denied.iter().any(|value| value == password.as_str()) {
+            Err(PasswordError::MatchesDeniedValue)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn normalize(value: &str) -> SmallString {
+    let mut normalized = SmallString::new();
+
+    for character in value.chars().filter(|character| !character.is_whitespace()) {
+        for lowercase in character.to_lowercase() {
+            normalized.push(lowercase);
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CheckContext;
+    use PasswordError;
+
+    #[test]
+    fn it_validates_denied_values() {
+        let context = CheckContext::new().deny_exact(&["TempPass-2931", "ABCD 1234", ""]);
+
+        assert_eq!(
+            context.check_denied("TempPass-2931"),
+            Err(PasswordError::MatchesDeniedValue)
+        );
+        assert_eq!(
+            context.check_denied("temppass-2931"),
+            Err(PasswordError::MatchesDeniedValue)
+        );
+        assert_eq!(
+            context.check_denied("abcd1234"),
+            Err(PasswordError::MatchesDeniedValue)
+        );
+        assert_eq!(context.check_denied("TempPass-2932"), Ok(()));
+        assert_eq!(context.check_denied(""), Ok(()));
+        assert_eq!(CheckContext::new().check_denied("anything"), Ok(()));
+    }
+
+    #[test]
+    fn it_validates_the_username() {
+        let context = CheckContext::new().with_username("mallory@example.com");
+
+        assert_eq!(
+            context.check_denied("Mallory1!"),
+            Err(PasswordError::MatchesUsername)
+        );
+        assert_eq!(context.check_denied("not mallory at all"), Ok(()));
+    }
+}
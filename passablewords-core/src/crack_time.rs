@@ -0,0 +1,241 @@
+//! Turning an estimate of how many guesses a password would take into how long that would take,
+//! at a rate of guessing that matches the attacker an organization is actually worried about.
+
+use std::time::Duration;
+
+#[cfg(feature = "zxcvbn")]
+use analyzer::{EntropyAnalyzer, ZxcvbnAnalyzer};
+use checker::PasswordChecker;
+use policy::CharsetMode;
+use quick_entropy::quick_entropy_bits;
+use PasswordError;
+
+/// How many passwords a single RTX 4090 tries per second against some common hashes, from
+/// hashcat's published benchmarks. Real rigs are usually several of them, so scale these with
+/// `HashRate::gpus`.
+const MD5_PER_GPU: f64 = 164.1e9;
+const SHA1_PER_GPU: f64 = 50.6e9;
+const NTLM_PER_GPU: f64 = 288.5e9;
+/// At a cost of 5, which hashcat benchmarks. Every step up in cost halves it.
+const BCRYPT_COST_5_PER_GPU: f64 = 184e3;
+/// Iterations of HMAC-SHA256 per second, so divide by the number of iterations per password.
+const PBKDF2_SHA256_ITERATIONS_PER_GPU: f64 = 8.8e9;
+
+/// How many guesses an attacker makes per second, which decides how long a password that takes a
+/// given number of guesses holds out for.
+///
+/// The constants are the four scenarios zxcvbn reports crack times for. The rest are calibrated
+/// against published benchmarks for a single high-end GPU, so an organization can match its own
+/// threat model, like a rig of eight GPUs against a leaked bcrypt table, without owning one.
+/// Numbers measured on the organization's own hardware can be used with `HashRate::measured`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use passablewords_core::HashRate;
+///
+/// let rig = HashRate::bcrypt(12).gpus(8);
+///
+/// assert!(rig.guesses_per_second() > 10_000.0);
+/// assert_eq!(HashRate::per_second(1e4).crack_time(8.0), Duration::from_secs(10_000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct HashRate(f64);
+
+impl HashRate {
+    /// Guessing through a login form that's rate limited, at 100 guesses an hour.
+    pub const ONLINE_THROTTLED: HashRate = HashRate(100.0 / 3600.0);
+    /// Guessing through a login form that isn't rate limited, at 10 guesses a second.
+    pub const ONLINE_UNTHROTTLED: HashRate = HashRate(10.0);
+    /// Cracking a stolen table of slow hashes, like bcrypt, at 10,000 guesses a second. This is
+    /// what crack times are estimated with unless the policy says otherwise.
+    pub const OFFLINE_SLOW_HASHING: HashRate = HashRate(1e4);
+    /// Cracking a stolen table of fast hashes, like unsalted SHA-1, at 10 billion guesses a
+    /// second.
+    pub const OFFLINE_FAST_HASHING: HashRate = HashRate(1e10);
+
+    /// A rate of `guesses` per second. Anything that isn't a positive number is treated as the
+    /// slowest rate possible.
+    pub fn per_second(guesses: f64) -> HashRate {
+        if guesses > 0.0 && guesses.is_finite() {
+            HashRate(guesses)
+        } else {
+            HashRate(f64::MIN_POSITIVE)
+        }
+    }
+
+    /// The rate of a benchmark that made `guesses` in `elapsed`, like a run of `hashcat -b`
+    /// against the hash the organization stores passwords with.
+    pub fn measured(guesses: u64, elapsed: Duration) -> HashRate {
+        HashRate::per_second(guesses as f64 / elapsed.as_secs_f64())
+    }
+
+    /// A single GPU cracking raw MD5.
+    pub fn md5() -> HashRate {
+        HashRate(MD5_PER_GPU)
+    }
+
+    /// A single GPU cracking raw SHA-1.
+    pub fn sha1() -> HashRate {
+        HashRate(SHA1_PER_GPU)
+    }
+
+    /// A single GPU cracking NTLM, the hash Windows stores passwords with.
+    pub fn ntlm() -> HashRate {
+        HashRate(NTLM_PER_GPU)
+    }
+
+    /// A single GPU cracking bcrypt at the given cost, where each step up in cost halves the
+    /// rate.
+    pub fn bcrypt(cost: u32) -> HashRate {
+        HashRate::per_second(BCRYPT_COST_5_PER_GPU * 2f64.powi(5 - cost as i32))
+    }
+
+    /// A single GPU cracking PBKDF2 with HMAC-SHA256 and the given number of iterations.
+    pub fn pbkdf2_sha256(iterations: u32) -> HashRate {
+        HashRate::per_second(PBKDF2_SHA256_ITERATIONS_PER_GPU / f64::from(iterations.max(1)))
+    }
+
+    /// This rate on a rig of `count` GPUs, or machines, working together.
+    pub fn gpus(self, count: u32) -> HashRate {
+        HashRate::per_second(self.0 * f64::from(count))
+    }
+
+    /// How many guesses are made per second.
+    pub fn guesses_per_second(self) -> f64 {
+        self.0
+    }
+
+    /// How long it would take to make `10^guesses_log10` guesses at this rate, which is the
+    /// longest it could take to find a password needing that many. Times too long to represent
+    /// are `Duration::MAX`.
+    pub fn crack_time(self, guesses_log10: f64) -> Duration {
+        let seconds = 10f64.powf(guesses_log10) / self.0;
+
+        Duration::try_from_secs_f64(seconds).unwrap_or(if seconds > 0.0 {
+            Duration::MAX
+        } else {
+            Duration::ZERO
+        })
+    }
+}
+
+impl Default for HashRate {
+    fn default() -> HashRate {
+        HashRate::OFFLINE_SLOW_HASHING
+    }
+}
+
+impl PasswordChecker {
+    /// Estimate the longest it would take to crack `password` at the rate set with
+    /// `Policy::assume_hash_rate`, using this checker's analyzer, or zxcvbn, to estimate how many
+    /// guesses it would take. Where the entropy check would fall back to `quick_entropy_bits`,
+    /// like for passwords that aren't ASCII under `CharsetMode::Unicode`, so does this.
+    ///
+    /// This only says how well the password stands up to guessing. Whether it should be accepted
+    /// is still up to `check_password`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use passablewords_core::{HashRate, PasswordChecker, Policy};
+    ///
+    /// let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+    /// let bcrypt = Policy::new().assume_hash_rate(HashRate::bcrypt(12).gpus(8));
+    /// let md5 = Policy::new().assume_hash_rate(HashRate::md5().gpus(8));
+    /// let slow = PasswordChecker::new().with_policy(bcrypt).estimate_crack_time(password);
+    /// let fast = PasswordChecker::new().with_policy(md5).estimate_crack_time(password);
+    ///
+    /// assert!(slow.unwrap() > Duration::from_secs(60 * 60 * 24 * 365));
+    /// assert!(fast.unwrap() < Duration::from_secs(60 * 60 * 24 * 365));
+    /// ```
+    pub fn estimate_crack_time(&self, password: &str) -> Result<Duration, PasswordError> {
+        let guesses_log10 = self.guesses_log10(password)?;
+
+        Ok(self.policy().hash_rate().crack_time(guesses_log10))
+    }
+
+    fn guesses_log10(&self, password: &str) -> Result<f64, PasswordError> {
+        let quick = || quick_entropy_bits(password) * 2f64.log10();
+
+        match self.analyzer() {
+            Some(analyzer) => Ok(analyzer.analyze(password, &[])?.guesses_log10),
+            None if self.policy().charset() == CharsetMode::Unicode && !password.is_ascii() => {
+                Ok(quick())
+            }
+            #[cfg(feature = "zxcvbn")]
+            None => Ok(ZxcvbnAnalyzer.analyze(password, &[])?.guesses_log10),
+            #[cfg(not(feature = "zxcvbn"))]
+            None => Ok(quick()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashRate;
+    use analyzer::Analysis;
+    use checker::PasswordChecker;
+    use policy::Policy;
+    use std::time::Duration;
+    use PasswordError;
+
+    #[test]
+    fn it_converts_guesses_to_durations() {
+        assert_eq!(
+            HashRate::OFFLINE_SLOW_HASHING.crack_time(6.0),
+            Duration::from_secs(100)
+        );
+        assert_eq!(
+            HashRate::ONLINE_THROTTLED.crack_time(2.0),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(HashRate::md5().crack_time(400.0), Duration::MAX);
+        assert_eq!(HashRate::per_second(-1.0).crack_time(1.0), Duration::MAX);
+        assert_eq!(
+            HashRate::measured(5_000, Duration::from_millis(500)),
+            HashRate::per_second(10_000.0)
+        );
+    }
+
+    #[test]
+    fn it_calibrates_against_real_hashes() {
+        assert_eq!(
+            HashRate::bcrypt(6).guesses_per_second() * 2.0,
+            HashRate::bcrypt(5).guesses_per_second()
+        );
+        assert_eq!(
+            HashRate::sha1().gpus(4).guesses_per_second(),
+            HashRate::sha1().guesses_per_second() * 4.0
+        );
+        assert!(HashRate::bcrypt(12) < HashRate::pbkdf2_sha256(600_000).gpus(2));
+        assert!(HashRate::ntlm() > HashRate::md5());
+    }
+
+    #[test]
+    fn it_estimates_crack_times_with_the_policy_rate() {
+        let checker = PasswordChecker::new()
+            .with_analyzer(|password: &str, _: &[&str]| {
+                if password.is_empty() {
+                    return Err(PasswordError::InternalError);
+                }
+
+                Ok(Analysis {
+                    score: 3,
+                    guesses_log10: 10.0,
+                })
+            })
+            .with_policy(Policy::new().assume_hash_rate(HashRate::per_second(1e6)));
+
+        assert_eq!(
+            checker.estimate_crack_time("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Ok(Duration::from_secs(10_000))
+        );
+        assert_eq!(
+            checker.estimate_crack_time(""),
+            Err(PasswordError::InternalError)
+        );
+    }
+}
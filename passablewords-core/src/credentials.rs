@@ -0,0 +1,143 @@
+//! Checking a password against the username or email address it's paired with, which is the
+//! first thing anyone guessing at an account will try.
+
+use normalize::Normalizer;
+use policy::{check_input_size, DEFAULT_MAX_INPUT_BYTES};
+use scrub::Scrubbed;
+use variants::affix_stripped;
+use {PassablewordResult, PasswordError};
+
+/// Check that a password isn't the account's username or email address, or trivially derived from
+/// it. A match is reported as `PasswordError::MatchesUsername`.
+///
+/// Both are lowercased, with whitespace and the usual substitutions, like `0` for `o`, undone
+/// before they're compared. A password matches if it's the username, the username backwards, or
+/// either of those with a few digits or a symbol at either end, like `Alice2024!`. For email
+/// addresses, the part before the `@` is used, with and without the dots, dashes, and underscores
+/// in it. Only whole passwords are compared, so this is cheap enough to run on every password,
+/// and it doesn't reject passwords that merely contain the username somewhere. If either is over
+/// `DEFAULT_MAX_INPUT_BYTES`, it's rejected as `PasswordError::InputTooLarge` before anything
+/// else.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_credential_pair, PasswordError};
+///
+/// assert_eq!(
+///     check_credential_pair("jane.doe@example.com", "EOD.ENAJ"),
+///     Err(PasswordError::MatchesUsername)
+/// );
+/// assert_eq!(
+///     check_credential_pair("jane.doe@example.com", "J4nedoe1987!"),
+///     Err(PasswordError::MatchesUsername)
+/// );
+/// assert_eq!(
+///     check_credential_pair("jane.doe@example.com", "Th1s iS a Sup3rR4ndom PassW0rd!"),
+///     Ok(())
+/// );
+/// ```
+pub fn check_credential_pair(username: &str, password: &str) -> PassablewordResult {
+    check_input_size(username, DEFAULT_MAX_INPUT_BYTES)?;
+    check_input_size(password, DEFAULT_MAX_INPUT_BYTES)?;
+
+    let normalizer = Normalizer::new();
+    let local_part = username
+        .rsplit_once('@')
+        .map_or(username, |(local, _)| local);
+    let joined: String = local_part
+        .chars()
+        .filter(|&character| !matches!(character, '.' | '-' | '_'))
+        .collect();
+    let names: Vec<String> = [username, local_part, joined.as_str()]
+        .iter()
+        .map(|name| normalizer.normalize(&without_whitespace(name)))
+        .filter(|name| !name.is_empty())
+        .collect();
+
+    if names.is_empty() {
+        return Ok(());
+    }
+
+    let password = Scrubbed::from(without_whitespace(password));
+    let matches = ::std::iter::once(&*password)
+        .chain(affix_stripped(&password))
+        .any(|candidate| {
+            let candidate = Scrubbed::from(normalizer.normalize(candidate));
+
+            names.iter().any(|name| {
+                name.as_str() == &*candidate || name.chars().rev().eq(candidate.chars())
+            })
+        });
+
+    if matches {
+        Err(PasswordError::MatchesUsername)
+    } else {
+        Ok(())
+    }
+}
+
+fn without_whitespace(value: &str) -> String {
+    value
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_credential_pair;
+    use PasswordError;
+
+    #[test]
+    fn it_rejects_passwords_derived_from_the_username() {
+        for &(username, password) in &[
+            ("alice", "alice"),
+            ("alice", "ALICE"),
+            ("alice", "ecila"),
+            ("alice", "@l1ce"),
+            ("alice", "Alice2024!"),
+            ("alice", "!1ecilA"),
+            ("Bob Smith", "bobsmith"),
+            ("jane.doe@example.com", "jane.doe@example.com"),
+            ("jane.doe@example.com", "jane.doe"),
+            ("jane_doe@example.com", "JaneDoe99"),
+        ] {
+            assert_eq!(
+                check_credential_pair(username, password),
+                Err(PasswordError::MatchesUsername),
+                "{} as the password of {}",
+                password,
+                username
+            );
+        }
+    }
+
+    #[test]
+    fn it_accepts_unrelated_passwords() {
+        assert_eq!(
+            check_credential_pair("alice", "alice in wonderland"),
+            Ok(())
+        );
+        assert_eq!(
+            check_credential_pair("alice", "Th1s iS a Sup3rR4ndom"),
+            Ok(())
+        );
+        assert_eq!(check_credential_pair("", ""), Ok(()));
+        assert_eq!(check_credential_pair(" @example.com", "example"), Ok(()));
+    }
+
+    #[test]
+    fn it_rejects_oversized_inputs() {
+        let huge = "a".repeat(2048);
+
+        assert_eq!(
+            check_credential_pair(&huge, "alice"),
+            Err(PasswordError::InputTooLarge)
+        );
+        assert_eq!(
+            check_credential_pair("alice", &huge),
+            Err(PasswordError::InputTooLarge)
+        );
+    }
+}
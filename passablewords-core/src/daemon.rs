@@ -0,0 +1,468 @@
+//! A long-running checker behind a Unix socket, for PAM modules and shell tools that can't afford
+//! to load the wordlist every time they check a password. Only available on Unix with the
+//! `daemon` feature.
+//!
+//! # Protocol
+//!
+//! Every message in either direction is a frame: its length in bytes as a big-endian `u32`,
+//! followed by that many bytes. A connection can send any number of requests, one after the
+//! other, and gets a response to each before the next is read.
+//!
+//! - A request is the password, as UTF-8. Passwords that aren't valid UTF-8 are rejected with
+//!   `InvalidEncoding`. Passwords longer than the policy's `input_limit` are rejected with
+//!   `InputTooLarge` without being read, and the connection is closed after the response.
+//! - A response is a JSON object with whether the password was `accepted`, and if it wasn't, the
+//!   `error`, as `PasswordError` serializes it, and the `reason` from
+//!   `PasswordError::explanation`:
+//!
+//! ```json
+//! { "accepted": false, "error": "TooShort", "reason": "Your password is too short." }
+//! ```
+
+use std::convert::TryFrom;
+use std::fs::{self, Permissions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use checker::PasswordChecker;
+#[cfg(feature = "zeroize")]
+use scrub::scrub_bytes;
+use {PassablewordResult, PasswordError};
+
+/// The largest response a `DaemonClient` reads. Responses are far smaller than this, so anything
+/// bigger means the other end of the socket isn't a daemon.
+const MAX_RESPONSE_BYTES: u32 = 64 * 1024;
+
+/// How many connections a `Daemon` handles at once, unless `Daemon::max_connections` says
+/// otherwise.
+const DEFAULT_MAX_CONNECTIONS: usize = 64;
+
+/// How long a `Daemon` waits on a client to send or read a frame, unless `Daemon::timeout` says
+/// otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Serialize, Deserialize)]
+struct Response {
+    accepted: bool,
+    error: Option<PasswordError>,
+    reason: Option<String>,
+}
+
+/// Serves a `PasswordChecker` on a Unix socket, following the protocol described in the module.
+/// Each connection is handled on its own thread, sharing the checker and its wordlist, so the
+/// list is only loaded once for the life of the daemon. By default, at most 64 connections are
+/// handled at once, and a client that sends or reads nothing for 30 seconds is hung up on, so idle
+/// clients can't hold every slot.
+///
+/// # Example
+///
+/// ```no_run
+/// use passablewords_core::{Daemon, PasswordChecker};
+///
+/// Daemon::bind("/run/passablewords.sock", PasswordChecker::new())?.serve()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Daemon {
+    listener: UnixListener,
+    checker: PasswordChecker,
+    max_connections: usize,
+    timeout: Duration,
+}
+
+impl Daemon {
+    /// Listen on a socket at `path`, which only the user running the daemon can connect to. Use
+    /// `fs::set_permissions` afterwards to let other users in. A socket left behind by a daemon
+    /// that's no longer running is replaced, but if another daemon is still listening on it, or
+    /// something other than a socket is at `path`, this fails with `io::ErrorKind::AddrInUse`.
+    pub fn bind<P: AsRef<Path>>(path: P, checker: PasswordChecker) -> io::Result<Daemon> {
+        let path = path.as_ref();
+        let listener = match UnixListener::bind(path) {
+            Err(ref err) if err.kind() == io::ErrorKind::AddrInUse => {
+                if !fs::symlink_metadata(path)?.file_type().is_socket() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("{} exists and isn't a socket", path.display()),
+                    ));
+                }
+                if UnixStream::connect(path).is_ok() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::AddrInUse,
+                        format!("a daemon is already listening on {}", path.display()),
+                    ));
+                }
+
+                fs::remove_file(path)?;
+                UnixListener::bind(path)?
+            }
+            result => result?,
+        };
+
+        fs::set_permissions(path, Permissions::from_mode(0o600))?;
+        Ok(Daemon::from_listener(listener, checker))
+    }
+
+    /// Serve on a socket that's already listening, such as one passed in by systemd. Its
+    /// permissions are left as they are.
+    pub fn from_listener(listener: UnixListener, checker: PasswordChecker) -> Daemon {
+        Daemon {
+            listener,
+            checker,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Handle at most `connections` at once. Once that many are open, new ones wait to be
+    /// accepted until another closes. There's always room for at least one.
+    pub fn max_connections(mut self, connections: usize) -> Daemon {
+        self.max_connections = connections.max(1);
+        self
+    }
+
+    /// Hang up on clients that take longer than `timeout` to send a request or read a response,
+    /// including ones that sit idle between requests. The timeout is at least a millisecond.
+    pub fn timeout(mut self, timeout: Duration) -> Daemon {
+        self.timeout = timeout.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Accept connections and answer their requests until accepting one fails. Problems with a
+    /// single connection, like a client hanging up halfway through a request, only end that
+    /// connection.
+    pub fn serve(self) -> io::Result<()> {
+        let open = Arc::new(Connections::default());
+
+        loop {
+            open.wait_for_room(self.max_connections);
+
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(ref err)
+                    if err.kind() == io::ErrorKind::Interrupted
+                        || err.kind() == io::ErrorKind::ConnectionAborted =>
+                {
+                    continue
+                }
+                Err(err) => return Err(err),
+            };
+            let checker = self.checker.clone();
+            let slot = Slot::take(&open);
+            let timeout = self.timeout;
+
+            thread::spawn(move || {
+                let _slot = slot;
+
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                handle(stream, &checker)
+            });
+        }
+    }
+}
+
+/// How many connections are being handled, shared between `serve` and the threads it starts.
+#[derive(Debug, Default)]
+struct Connections {
+    open: Mutex<usize>,
+    closed: Condvar,
+}
+
+impl Connections {
+    /// Block until fewer than `max` connections are open.
+    fn wait_for_room(&self, max: usize) {
+        let mut open = self
+            .open
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        while *open >= max {
+            open = self
+                .closed
+                .wait(open)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+    }
+}
+
+/// One open connection, which makes room for another when it's dropped.
+struct Slot(Arc<Connections>);
+
+impl Slot {
+    fn take(connections: &Arc<Connections>) -> Slot {
+        *connections
+            .open
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += 1;
+        Slot(connections.clone())
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        *self
+            .0
+            .open
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) -= 1;
+        self.0.closed.notify_one();
+    }
+}
+
+/// Answer requests on `stream` until the client hangs up or something goes wrong.
+fn handle(mut stream: UnixStream, checker: &PasswordChecker) -> io::Result<()> {
+    let limit = checker.policy().input_limit();
+
+    loop {
+        let length = match read_length(&mut stream)? {
+            Some(length) => length as usize,
+            None => return Ok(()),
+        };
+
+        if length > limit {
+            return write_response(&mut stream, Err(PasswordError::InputTooLarge));
+        }
+
+        let mut password = vec![0; length];
+        let read = stream.read_exact(&mut password);
+        let result = read.as_ref().map(|_| {
+            str::from_utf8(&password)
+                .map_err(|_| PasswordError::InvalidEncoding)
+                .and_then(|password| checker.check_password(password))
+        });
+
+        #[cfg(feature = "zeroize")]
+        scrub_bytes(&mut password);
+
+        match result {
+            Ok(result) => write_response(&mut stream, result)?,
+            Err(_) => return read,
+        }
+    }
+}
+
+/// Connects to a `Daemon` and checks passwords with it.
+///
+/// # Example
+///
+/// ```no_run
+/// use passablewords_core::DaemonClient;
+///
+/// let mut client = DaemonClient::connect("/run/passablewords.sock")?;
+///
+/// if let Err(err) = client.check_password("hunter2")? {
+///     eprintln!("{}", err.explanation());
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct DaemonClient {
+    stream: UnixStream,
+}
+
+impl DaemonClient {
+    /// Connect to the daemon listening at `path`.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<DaemonClient> {
+        UnixStream::connect(path).map(|stream| DaemonClient { stream })
+    }
+
+    /// Check a password with the daemon's checker. The outer result is whether the daemon could
+    /// be asked, and the inner one is its answer. After a password over the daemon's
+    /// `input_limit`, the daemon hangs up, so connect again to check another.
+    pub fn check_password(&mut self, password: &str) -> io::Result<PassablewordResult> {
+        write_frame(&mut self.stream, password.as_bytes())?;
+
+        let length = read_length(&mut self.stream)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "the daemon hung up"))?;
+
+        if length > MAX_RESPONSE_BYTES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the daemon's response is too large",
+            ));
+        }
+
+        let mut body = vec![0; length as usize];
+
+        self.stream.read_exact(&mut body)?;
+
+        let response: Response = ::serde_json::from_slice(&body)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        Ok(match response.error {
+            Some(error) => Err(error),
+            None if response.accepted => Ok(()),
+            None => Err(PasswordError::InternalError),
+        })
+    }
+}
+
+/// Read the length at the start of a frame, or `None` if the other end hung up before sending one.
+fn read_length(stream: &mut UnixStream) -> io::Result<Option<u32>> {
+    let mut length = [0; 4];
+
+    match stream.read_exact(&mut length) {
+        Ok(()) => Ok(Some(u32::from_be_bytes(length))),
+        Err(ref err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+fn write_frame(stream: &mut UnixStream, body: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(body.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the frame is too large"))?;
+    let mut frame = Vec::with_capacity(4 + body.len());
+
+    frame.extend_from_slice(&length.to_be_bytes());
+    frame.extend_from_slice(body);
+    let written = stream.write_all(&frame);
+
+    #[cfg(feature = "zeroize")]
+    scrub_bytes(&mut frame);
+
+    written
+}
+
+fn write_response(stream: &mut UnixStream, result: PassablewordResult) -> io::Result<()> {
+    let response = Response {
+        accepted: result.is_ok(),
+        reason: result
+            .as_ref()
+            .err()
+            .map(|error| error.explanation().to_string()),
+        error: result.err(),
+    };
+    let body = ::serde_json::to_vec(&response)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    write_frame(stream, &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use policy::Policy;
+    use std::env;
+    use std::process;
+    use wordlist::Wordlist;
+
+    fn start(name: &str, checker: PasswordChecker) -> ::std::path::PathBuf {
+        let path = env::temp_dir().join(format!("passablewords-{}-{}.sock", name, process::id()));
+        let daemon = Daemon::bind(&path, checker).unwrap();
+
+        thread::spawn(move || daemon.serve());
+        path
+    }
+
+    #[test]
+    fn it_checks_passwords() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+        let path = start("checks", checker);
+        let mut client = DaemonClient::connect(&path).unwrap();
+
+        assert_eq!(
+            client.check_password("password123").unwrap(),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            client
+                .check_password("Th1s iS a Sup3rR4ndom PassW0rd!")
+                .unwrap(),
+            Ok(())
+        );
+        assert_eq!(
+            client.check_password("short").unwrap(),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            Daemon::bind(&path, PasswordChecker::new())
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::AddrInUse
+        );
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn it_rejects_bad_requests() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+            .with_policy(Policy::new().max_input_bytes(16));
+        let path = start("bad-requests", checker);
+        let mut client = DaemonClient::connect(&path).unwrap();
+
+        write_frame(&mut client.stream, b"caf\xe9 au lait!!").unwrap();
+        let mut length = [0; 4];
+        client.stream.read_exact(&mut length).unwrap();
+        let mut body = vec![0; u32::from_be_bytes(length) as usize];
+        client.stream.read_exact(&mut body).unwrap();
+        assert_eq!(
+            ::serde_json::from_slice::<Response>(&body).unwrap().error,
+            Some(PasswordError::InvalidEncoding)
+        );
+
+        assert_eq!(
+            client
+                .check_password("correct horse battery staple")
+                .unwrap(),
+            Err(PasswordError::InputTooLarge)
+        );
+        assert!(client.check_password("password123").is_err());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn it_replaces_stale_sockets() {
+        let path = env::temp_dir().join(format!("passablewords-stale-{}.sock", process::id()));
+
+        drop(UnixListener::bind(&path).unwrap());
+
+        let daemon = Daemon::bind(&path, PasswordChecker::new());
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+
+        assert!(daemon.is_ok());
+        assert_eq!(mode & 0o777, 0o600);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn it_leaves_other_files_alone() {
+        let path = env::temp_dir().join(format!("passablewords-file-{}.sock", process::id()));
+
+        fs::write(&path, "not a socket").unwrap();
+
+        let error = Daemon::bind(&path, PasswordChecker::new()).unwrap_err();
+        let contents = fs::read_to_string(&path).unwrap();
+
+        let _ = fs::remove_file(path);
+        assert_eq!(error.kind(), io::ErrorKind::AddrInUse);
+        assert_eq!(contents, "not a socket");
+    }
+
+    #[test]
+    fn it_hangs_up_on_idle_clients() {
+        let path = env::temp_dir().join(format!("passablewords-idle-{}.sock", process::id()));
+        let daemon = Daemon::bind(&path, PasswordChecker::new())
+            .unwrap()
+            .max_connections(1)
+            .timeout(Duration::from_millis(100));
+
+        thread::spawn(move || daemon.serve());
+
+        let mut idle = UnixStream::connect(&path).unwrap();
+        let mut client = DaemonClient::connect(&path).unwrap();
+        let checked = client.check_password("short");
+        let hung_up = idle.read(&mut [0; 1]);
+
+        let _ = fs::remove_file(path);
+        assert_eq!(checked.unwrap(), Err(PasswordError::TooShort));
+        assert_eq!(hung_up.unwrap(), 0);
+    }
+}
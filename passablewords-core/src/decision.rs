@@ -0,0 +1,139 @@
+//! Records of the decisions made by a `PasswordChecker`, for applications which have to show
+//! auditors that their password policy was enforced.
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use PasswordError;
+
+/// One of the checks a password goes through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Check {
+    /// The policy's `CharsetMode`.
+    Charset,
+    /// The policy's length rule.
+    Length,
+    /// The policy's minimum number of unique characters.
+    UniqueCharacters,
+    /// The policy's rules against passwords made of only digits or only letters.
+    Variety,
+    /// The values denied by the `CheckContext`, and the username it was given.
+    DeniedValues,
+    /// The policy's banned patterns.
+    BannedPatterns,
+    /// The policy's rule against keyboard walks.
+    KeyboardWalks,
+    /// The policy's rule against passwords built around the date.
+    TemporalPatterns,
+    /// The wordlist of common passwords.
+    Uniqueness,
+    /// The policy's rule against a single common word with a few digits and a symbol around it.
+    WordShells,
+    /// The policy's rule against a single dictionary word without enough digits or symbols
+    /// around it.
+    SingleDictionaryWord,
+    /// The zxcvbn entropy check.
+    Entropy,
+}
+
+/// Whether a password was accepted, and if not, why.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Outcome {
+    /// The password passed every check.
+    Accepted,
+    /// The password failed the last check that was run.
+    Rejected(PasswordError),
+}
+
+/// A record of a single password check. It never contains the password or anything derived from
+/// it, so it's safe to write to logs. With the `serde` feature it can be serialized into whatever
+/// format your logging or auditing system expects.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct DecisionRecord {
+    /// When the check finished.
+    pub timestamp: SystemTime,
+    /// The version of the policy that was enforced, if it was given one.
+    pub policy_version: Option<String>,
+    /// The request ID passed in through the `CheckContext`, if there was one.
+    pub request_id: Option<String>,
+    /// The checks that were run, in order. Checks stop at the first one that fails.
+    pub checks: Vec<Check>,
+    /// The result of the check.
+    pub outcome: Outcome,
+}
+
+/// Receives a `DecisionRecord` every time a `PasswordChecker` makes a decision. Any
+/// `Fn(&DecisionRecord)` that's `Send + Sync` can be used as a hook.
+///
+/// Hooks are called on the thread doing the check, before the result is returned, so they should
+/// hand the record off to a logger or channel rather than doing slow work themselves.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::mpsc;
+/// use std::sync::Mutex;
+/// use passablewords_core::{CheckContext, DecisionRecord, Outcome, PasswordChecker, PasswordError};
+///
+/// let (sender, receiver) = mpsc::channel();
+/// let sender = Mutex::new(sender);
+/// let checker = PasswordChecker::new().with_decision_hook(move |record: &DecisionRecord| {
+///     let _ = sender.lock().unwrap().send(record.request_id.clone());
+/// });
+/// let context = CheckContext::new().with_request_id("req-42");
+///
+/// assert_eq!(
+///     checker.check_password_with_context("short", &context),
+///     Err(PasswordError::TooShort)
+/// );
+/// assert_eq!(receiver.recv().unwrap(), Some("req-42".to_string()));
+/// ```
+pub trait DecisionHook: Send + Sync {
+    /// Called with the record of a decision.
+    fn on_decision(&self, record: &DecisionRecord);
+}
+
+impl<F: Fn(&DecisionRecord) + Send + Sync> DecisionHook for F {
+    fn on_decision(&self, record: &DecisionRecord) {
+        self(record)
+    }
+}
+
+/// A shared `DecisionHook`, so that `PasswordChecker` can still be cloned cheaply and debugged.
+#[derive(Clone)]
+pub struct SharedHook(pub Arc<dyn DecisionHook>);
+
+impl fmt::Debug for SharedHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("DecisionHook")
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{Check, DecisionRecord, Outcome};
+    use std::time::UNIX_EPOCH;
+    use PasswordError;
+
+    #[test]
+    fn it_serializes_records() {
+        let record = DecisionRecord {
+            timestamp: UNIX_EPOCH,
+            policy_version: Some("v1".to_string()),
+            request_id: None,
+            checks: vec![Check::Length],
+            outcome: Outcome::Rejected(PasswordError::TooShort),
+        };
+
+        assert_eq!(
+            ::serde_json::to_string(&record).unwrap(),
+            "{\"timestamp\":{\"secs_since_epoch\":0,\"nanos_since_epoch\":0},\
+             \"policy_version\":\"v1\",\"request_id\":null,\"checks\":[\"Length\"],\
+             \"outcome\":{\"Rejected\":\"TooShort\"}}"
+        );
+    }
+}
@@ -0,0 +1,414 @@
+//! Describing a policy's rules to the people picking passwords, in their own language, so that
+//! the copy next to a password field can't drift from what's actually enforced.
+
+use decision::Check;
+use policy::{CharsetMode, Policy};
+
+/// A language `Policy::describe` can write requirements in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    /// English, which is the default.
+    #[default]
+    English,
+    /// French.
+    French,
+    /// German.
+    German,
+    /// Spanish.
+    Spanish,
+}
+
+impl Locale {
+    /// The locale for a language tag like `de`, `fr-CA`, or `es_MX`, going by the language and
+    /// ignoring the region, or `None` if the language isn't supported.
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let language = tag.split(&['-', '_'][..]).next().unwrap_or("");
+
+        match &*language.to_ascii_lowercase() {
+            "en" => Some(Locale::English),
+            "fr" => Some(Locale::French),
+            "de" => Some(Locale::German),
+            "es" => Some(Locale::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The language tag for this locale, like `de`.
+    pub fn tag(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::French => "fr",
+            Locale::German => "de",
+            Locale::Spanish => "es",
+        }
+    }
+}
+
+/// One thing a policy requires of every password.
+enum Requirement {
+    MinLength(usize),
+    MinLengthOrWords(usize, usize),
+    MinWords(usize),
+    AsciiOnly,
+    MinUniqueChars(usize),
+    NotAllDigits,
+    NotAllLetters,
+    NotBannedPattern(String),
+    NoKeyboardWalks,
+    NoTemporalPatterns,
+    NotCommon,
+    NotCommonVariant,
+    NoEmbeddedCommon,
+    NoWordShells,
+    NotSingleDictionaryWord,
+    HardToGuess,
+}
+
+impl Policy {
+    /// The rules this policy enforces, as a list of short requirements in `locale`, like
+    /// `At least 12 characters` and `Not a common password`, in the order they're checked. Show
+    /// them next to the password field, or join them into a sentence, and they'll change along
+    /// with the policy. Checks the policy only warns about aren't requirements, so they're left
+    /// out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Locale, Policy};
+    ///
+    /// let policy = Policy::new().min_length(12).min_unique_chars(5);
+    ///
+    /// assert_eq!(
+    ///     policy.describe(Locale::English).join("; "),
+    ///     "Only ASCII letters, numbers, and symbols; At least 12 characters; At least 5 \
+    ///      different characters; Not a common password; Hard to guess"
+    /// );
+    /// assert!(policy
+    ///     .describe(Locale::German)
+    ///     .contains(&"Mindestens 12 Zeichen".to_string()));
+    /// ```
+    pub fn describe(&self, locale: Locale) -> Vec<String> {
+        self.requirements()
+            .iter()
+            .map(|(_, requirement)| requirement.text(locale))
+            .collect()
+    }
+
+    fn requirements(&self) -> Vec<(Check, Requirement)> {
+        let rules = self.to_frontend_rules();
+        let mut requirements = Vec::new();
+
+        if self.charset() == CharsetMode::AsciiOnly {
+            requirements.push((Check::Charset, Requirement::AsciiOnly));
+        }
+        requirements.push((
+            Check::Length,
+            match rules.min_words {
+                Some(words) => Requirement::MinLengthOrWords(rules.min_length, words),
+                None => Requirement::MinLength(rules.min_length),
+            },
+        ));
+        if let Some(words) = rules.required_words {
+            requirements.push((Check::Length, Requirement::MinWords(words)));
+        }
+        if let Some(count) = rules.min_unique_chars {
+            requirements.push((Check::UniqueCharacters, Requirement::MinUniqueChars(count)));
+        }
+        if rules.rejects_all_digits {
+            requirements.push((Check::Variety, Requirement::NotAllDigits));
+        }
+        if rules.rejects_all_letters {
+            requirements.push((Check::Variety, Requirement::NotAllLetters));
+        }
+        for pattern in rules.blocked_patterns {
+            requirements.push((
+                Check::BannedPatterns,
+                Requirement::NotBannedPattern(pattern.name),
+            ));
+        }
+        if rules.rejects_keyboard_walks.is_some() {
+            requirements.push((Check::KeyboardWalks, Requirement::NoKeyboardWalks));
+        }
+        if self.temporal_tokens().is_some() {
+            requirements.push((Check::TemporalPatterns, Requirement::NoTemporalPatterns));
+        }
+        requirements.push((
+            Check::Uniqueness,
+            if rules.rejects_common_variants || self.normalizer().is_some() {
+                Requirement::NotCommonVariant
+            } else {
+                Requirement::NotCommon
+            },
+        ));
+        if rules.rejects_embedded_common_passwords.is_some() {
+            requirements.push((Check::Uniqueness, Requirement::NoEmbeddedCommon));
+        }
+        if rules.rejects_word_shells {
+            requirements.push((Check::WordShells, Requirement::NoWordShells));
+        }
+        if self.single_dictionary_word_extras().is_some() {
+            requirements.push((
+                Check::SingleDictionaryWord,
+                Requirement::NotSingleDictionaryWord,
+            ));
+        }
+        requirements.push((Check::Entropy, Requirement::HardToGuess));
+
+        requirements.retain(|&(check, _)| !self.is_warning_only(check));
+        requirements
+    }
+}
+
+impl Requirement {
+    fn text(&self, locale: Locale) -> String {
+        match locale {
+            Locale::English => self.english(),
+            Locale::French => self.french(),
+            Locale::German => self.german(),
+            Locale::Spanish => self.spanish(),
+        }
+    }
+
+    fn english(&self) -> String {
+        match *self {
+            Requirement::MinLength(length) => {
+                format!("At least {}", count(length, "character", "characters"))
+            }
+            Requirement::MinLengthOrWords(length, words) => format!(
+                "At least {}, or {}",
+                count(length, "character", "characters"),
+                count(words, "word", "words")
+            ),
+            Requirement::MinWords(words) => format!("At least {}", count(words, "word", "words")),
+            Requirement::AsciiOnly => "Only ASCII letters, numbers, and symbols".to_string(),
+            Requirement::MinUniqueChars(count) => {
+                format!("At least {} different characters", count)
+            }
+            Requirement::NotAllDigits => "Not only numbers".to_string(),
+            Requirement::NotAllLetters => "Not only letters".to_string(),
+            Requirement::NotBannedPattern(ref name) => {
+                format!("Doesn't match the \"{}\" rule", name)
+            }
+            Requirement::NoKeyboardWalks => "No keyboard patterns like \"qwerty\"".to_string(),
+            Requirement::NoTemporalPatterns => "Not based on a month, season, or year".to_string(),
+            Requirement::NotCommon => "Not a common password".to_string(),
+            Requirement::NotCommonVariant => {
+                "Not a common password, even with a few characters added or swapped".to_string()
+            }
+            Requirement::NoEmbeddedCommon => "Doesn't contain a common password".to_string(),
+            Requirement::NoWordShells => {
+                "Not a single word with a few numbers or symbols around it".to_string()
+            }
+            Requirement::NotSingleDictionaryWord => "Not just one dictionary word".to_string(),
+            Requirement::HardToGuess => "Hard to guess".to_string(),
+        }
+    }
+
+    fn french(&self) -> String {
+        match *self {
+            Requirement::MinLength(length) => {
+                format!("Au moins {}", count(length, "caractère", "caractères"))
+            }
+            Requirement::MinLengthOrWords(length, words) => format!(
+                "Au moins {}, ou {}",
+                count(length, "caractère", "caractères"),
+                count(words, "mot", "mots")
+            ),
+            Requirement::MinWords(words) => format!("Au moins {}", count(words, "mot", "mots")),
+            Requirement::AsciiOnly => {
+                "Uniquement des lettres, chiffres et symboles ASCII".to_string()
+            }
+            Requirement::MinUniqueChars(count) => {
+                format!("Au moins {} caractères différents", count)
+            }
+            Requirement::NotAllDigits => "Pas uniquement des chiffres".to_string(),
+            Requirement::NotAllLetters => "Pas uniquement des lettres".to_string(),
+            Requirement::NotBannedPattern(ref name) => {
+                format!("Ne correspond pas à la règle « {} »", name)
+            }
+            Requirement::NoKeyboardWalks => "Pas de suite de touches comme « azerty »".to_string(),
+            Requirement::NoTemporalPatterns => {
+                "Pas basé sur un mois, une saison ou une année".to_string()
+            }
+            Requirement::NotCommon => "Pas un mot de passe courant".to_string(),
+            Requirement::NotCommonVariant => {
+                "Pas un mot de passe courant, même avec quelques caractères ajoutés ou remplacés"
+                    .to_string()
+            }
+            Requirement::NoEmbeddedCommon => "Ne contient pas de mot de passe courant".to_string(),
+            Requirement::NoWordShells => {
+                "Pas un seul mot entouré de quelques chiffres ou symboles".to_string()
+            }
+            Requirement::NotSingleDictionaryWord => "Pas un seul mot du dictionnaire".to_string(),
+            Requirement::HardToGuess => "Difficile à deviner".to_string(),
+        }
+    }
+
+    fn german(&self) -> String {
+        match *self {
+            Requirement::MinLength(length) => format!("Mindestens {} Zeichen", length),
+            Requirement::MinLengthOrWords(length, words) => format!(
+                "Mindestens {} Zeichen oder {}",
+                length,
+                count(words, "Wort", "Wörter")
+            ),
+            Requirement::MinWords(words) => {
+                format!("Mindestens {}", count(words, "Wort", "Wörter"))
+            }
+            Requirement::AsciiOnly => {
+                "Nur ASCII-Buchstaben, -Ziffern und -Sonderzeichen".to_string()
+            }
+            Requirement::MinUniqueChars(count) => {
+                format!("Mindestens {} verschiedene Zeichen", count)
+            }
+            Requirement::NotAllDigits => "Nicht nur Ziffern".to_string(),
+            Requirement::NotAllLetters => "Nicht nur Buchstaben".to_string(),
+            Requirement::NotBannedPattern(ref name) => {
+                format!("Entspricht nicht der Regel „{}“", name)
+            }
+            Requirement::NoKeyboardWalks => "Keine Tastaturmuster wie „qwertz“".to_string(),
+            Requirement::NoTemporalPatterns => {
+                "Nicht auf Monat, Jahreszeit oder Jahr aufgebaut".to_string()
+            }
+            Requirement::NotCommon => "Kein häufig verwendetes Passwort".to_string(),
+            Requirement::NotCommonVariant => {
+                "Kein häufig verwendetes Passwort, auch nicht mit ein paar hinzugefügten oder \
+                 ersetzten Zeichen"
+                    .to_string()
+            }
+            Requirement::NoEmbeddedCommon => "Enthält kein häufig verwendetes Passwort".to_string(),
+            Requirement::NoWordShells => {
+                "Kein einzelnes Wort mit ein paar Ziffern oder Sonderzeichen drumherum".to_string()
+            }
+            Requirement::NotSingleDictionaryWord => {
+                "Nicht nur ein einzelnes Wort aus dem Wörterbuch".to_string()
+            }
+            Requirement::HardToGuess => "Schwer zu erraten".to_string(),
+        }
+    }
+
+    fn spanish(&self) -> String {
+        match *self {
+            Requirement::MinLength(length) => {
+                format!("Al menos {}", count(length, "carácter", "caracteres"))
+            }
+            Requirement::MinLengthOrWords(length, words) => format!(
+                "Al menos {}, o {}",
+                count(length, "carácter", "caracteres"),
+                count(words, "palabra", "palabras")
+            ),
+            Requirement::MinWords(words) => {
+                format!("Al menos {}", count(words, "palabra", "palabras"))
+            }
+            Requirement::AsciiOnly => "Solo letras, números y símbolos ASCII".to_string(),
+            Requirement::MinUniqueChars(count) => {
+                format!("Al menos {} caracteres distintos", count)
+            }
+            Requirement::NotAllDigits => "No solo números".to_string(),
+            Requirement::NotAllLetters => "No solo letras".to_string(),
+            Requirement::NotBannedPattern(ref name) => {
+                format!("No coincide con la regla «{}»", name)
+            }
+            Requirement::NoKeyboardWalks => "Sin secuencias de teclado como «qwerty»".to_string(),
+            Requirement::NoTemporalPatterns => {
+                "No basada en un mes, una estación o un año".to_string()
+            }
+            Requirement::NotCommon => "No es una contraseña común".to_string(),
+            Requirement::NotCommonVariant => {
+                "No es una contraseña común, ni siquiera con algunos caracteres añadidos o \
+                 cambiados"
+                    .to_string()
+            }
+            Requirement::NoEmbeddedCommon => "No contiene una contraseña común".to_string(),
+            Requirement::NoWordShells => {
+                "No es una sola palabra con algunos números o símbolos alrededor".to_string()
+            }
+            Requirement::NotSingleDictionaryWord => {
+                "No es solo una palabra del diccionario".to_string()
+            }
+            Requirement::HardToGuess => "Difícil de adivinar".to_string(),
+        }
+    }
+}
+
+/// `number` followed by the singular or plural of a noun.
+fn count(number: usize, one: &str, other: &str) -> String {
+    format!("{} {}", number, if number == 1 { one } else { other })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Locale;
+    use decision::Check;
+    use policy::{CharsetMode, Policy};
+    use temporal::TemporalTokens;
+
+    #[test]
+    fn it_describes_policies() {
+        let policy = Policy::new()
+            .charset_mode(CharsetMode::Unicode)
+            .min_length_or_words(16, 3)
+            .reject_all_digits(true)
+            .strip_common_affixes(true)
+            .reject_keyboard_walks(4)
+            .reject_temporal_patterns(TemporalTokens::new())
+            .warn_only(Check::Entropy);
+
+        assert_eq!(
+            policy.describe(Locale::English),
+            vec![
+                "At least 16 characters, or 3 words",
+                "Not only numbers",
+                "No keyboard patterns like \"qwerty\"",
+                "Not based on a month, season, or year",
+                "Not a common password, even with a few characters added or swapped",
+            ]
+        );
+        assert_eq!(
+            Policy::new()
+                .min_length(1)
+                .min_words(1)
+                .describe(Locale::Spanish)[1..3],
+            [
+                "Al menos 1 carácter".to_string(),
+                "Al menos 1 palabra".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn it_describes_every_requirement_in_every_locale() {
+        let policy = Policy::new()
+            .min_length_or_words(12, 4)
+            .min_words(2)
+            .min_unique_chars(6)
+            .reject_all_digits(true)
+            .reject_all_letters(true)
+            .reject_embedded_common_passwords(5)
+            .reject_word_shells(true)
+            .reject_single_dictionary_words(1, 1)
+            .reject_keyboard_walks(4)
+            .reject_temporal_patterns(TemporalTokens::new());
+        let english = policy.describe(Locale::English);
+
+        for &locale in &[Locale::French, Locale::German, Locale::Spanish] {
+            let described = policy.describe(locale);
+
+            assert_eq!(described.len(), english.len());
+            for (text, english) in described.iter().zip(&english) {
+                assert_ne!(text, english, "{:?}", locale);
+            }
+        }
+    }
+
+    #[test]
+    fn it_parses_language_tags() {
+        assert_eq!(Locale::from_tag("de"), Some(Locale::German));
+        assert_eq!(Locale::from_tag("fr-CA"), Some(Locale::French));
+        assert_eq!(Locale::from_tag("ES_mx"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("en-GB"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("ja"), None);
+        assert_eq!(Locale::from_tag(""), None);
+        assert_eq!(Locale::from_tag(Locale::German.tag()), Some(Locale::German));
+    }
+}
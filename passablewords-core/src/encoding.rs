@@ -0,0 +1,273 @@
+//! Checks for passwords that aren't guaranteed to be valid UTF-8, such as those handed over by PAM
+//! modules, read from legacy systems, or converted from Windows' UTF-16.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::str;
+
+use scrub::Scrubbed;
+use {check_password, PassablewordResult, PasswordError};
+
+/// What to do with a password that isn't valid UTF-8, or valid UTF-16 for
+/// `check_password_utf16`. Embedded NUL characters are treated as invalid too, since C APIs and
+/// some databases silently cut the password short at the first one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidUtf8 {
+    /// Reject the password with `PasswordError::InvalidEncoding`.
+    Reject,
+    /// Replace any invalid sequences with `U+FFFD REPLACEMENT CHARACTER` and check the result.
+    /// Since the replacement character isn't ascii, the entropy check will then fail with
+    /// `PasswordError::NonAsciiPassword`, but the length and uniqueness checks still run first.
+    /// `PasswordChecker` policies can choose how to treat it with `CharsetMode`.
+    Replace,
+    /// Remove any invalid sequences, lone surrogates, and NUL characters, and check what's left.
+    /// This is a repair for passwords known to have been mangled on the way in, so only opt into
+    /// it if the same repair is applied wherever the password is later verified.
+    Strip,
+}
+
+/// Build a password from `units`, where `None` stands for anything that couldn't be decoded, and
+/// handle those and any NUL characters according to `invalid`. Passwords with neither are
+/// borrowed as they are. Otherwise the repaired copy is given room for `capacity` bytes up front,
+/// which must be enough that it never grows, so no half-built copy is freed without being wiped.
+fn repair<'a, I>(
+    password: Option<&'a str>,
+    units: I,
+    capacity: usize,
+    invalid: InvalidUtf8,
+) -> Result<Scrubbed<'a>, PasswordError>
+where
+    I: Iterator<Item = Option<char>> + Clone,
+{
+    let is_invalid = |unit: &Option<char>| matches!(*unit, None | Some('\0'));
+
+    match password {
+        Some(password) if !password.contains('\0') => {
+            return Ok(Scrubbed::from(Cow::Borrowed(password)))
+        }
+        _ if invalid == InvalidUtf8::Reject && units.clone().any(|unit| is_invalid(&unit)) => {
+            return Err(PasswordError::InvalidEncoding)
+        }
+        _ => {}
+    }
+
+    let mut repaired = String::with_capacity(capacity);
+
+    for unit in units {
+        match unit {
+            Some(character) if !is_invalid(&unit) => repaired.push(character),
+            _ if invalid == InvalidUtf8::Replace => repaired.push(char::REPLACEMENT_CHARACTER),
+            _ => {}
+        }
+    }
+
+    Ok(Scrubbed::from(repaired))
+}
+
+fn decode<'a>(password: &'a [u8], invalid: InvalidUtf8) -> Result<Scrubbed<'a>, PasswordError> {
+    let units = password.utf8_chunks().flat_map(|chunk| {
+        let replacement = if chunk.invalid().is_empty() {
+            None
+        } else {
+            Some(None)
+        };
+
+        chunk.valid().chars().map(Some).chain(replacement)
+    });
+
+    // Each invalid sequence is at least one byte, and becomes at most three
+    repair(
+        str::from_utf8(password).ok(),
+        units,
+        password.len() * 3,
+        invalid,
+    )
+}
+
+fn decode_utf16(
+    password: &[u16],
+    invalid: InvalidUtf8,
+) -> Result<Scrubbed<'static>, PasswordError> {
+    let units = char::decode_utf16(password.iter().cloned()).map(Result::ok);
+
+    // Each unit becomes at most three bytes, and a surrogate pair four
+    repair(None, units, password.len() * 3, invalid)
+}
+
+/// Check a password given as raw bytes. Valid UTF-8 is checked exactly like `check_password`
+/// would, while anything else is handled according to `invalid`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_password_bytes, InvalidUtf8, PasswordError};
+///
+/// assert_eq!(
+///     check_password_bytes(b"Th1s iS a Sup3rR4ndom PassW0rd!", InvalidUtf8::Reject),
+///     Ok(())
+/// );
+/// assert_eq!(
+///     check_password_bytes(b"\xffpassword", InvalidUtf8::Reject),
+///     Err(PasswordError::InvalidEncoding)
+/// );
+/// ```
+pub fn check_password_bytes(password: &[u8], invalid: InvalidUtf8) -> PassablewordResult {
+    decode(password, invalid).and_then(|password| check_password(&password))
+}
+
+/// Check a password given as UTF-16, such as one from a Windows API or a JavaScript string. Lone
+/// surrogates, which are left behind when a string is cut in the middle of a character, are
+/// handled according to `invalid`, like NUL characters are.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_password_utf16, InvalidUtf8, PasswordError};
+///
+/// let password: Vec<u16> = "Th1s iS a Sup3rR4ndom PassW0rd!".encode_utf16().collect();
+/// let mut mangled = password.clone();
+///
+/// mangled.push(0xd800);
+///
+/// assert_eq!(check_password_utf16(&password, InvalidUtf8::Reject), Ok(()));
+/// assert_eq!(
+///     check_password_utf16(&mangled, InvalidUtf8::Reject),
+///     Err(PasswordError::InvalidEncoding)
+/// );
+/// assert_eq!(check_password_utf16(&mangled, InvalidUtf8::Strip), Ok(()));
+/// ```
+pub fn check_password_utf16(password: &[u16], invalid: InvalidUtf8) -> PassablewordResult {
+    decode_utf16(password, invalid).and_then(|password| check_password(&password))
+}
+
+/// Check that a password which has already been converted to a `str` didn't lose anything on the
+/// way, rejecting it with `PasswordError::InvalidEncoding` if it contains a NUL character or
+/// `U+FFFD REPLACEMENT CHARACTER`. A lossy conversion, like `String::from_utf16_lossy`, leaves
+/// the replacement character wherever it found a lone surrogate or an invalid sequence, so the
+/// password the user typed can't be recovered and checking the rest would be misleading.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_encoding, PasswordError};
+///
+/// let converted = String::from_utf16_lossy(&[0x0070, 0x0061, 0xd800, 0x0073, 0x0073]);
+///
+/// assert_eq!(check_encoding(&converted), Err(PasswordError::InvalidEncoding));
+/// assert_eq!(check_encoding("pässwörd"), Ok(()));
+/// ```
+pub fn check_encoding(password: &str) -> PassablewordResult {
+    if password.contains(['\0', char::REPLACEMENT_CHARACTER]) {
+        Err(PasswordError::InvalidEncoding)
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a password given as an `OsStr`, such as one read from the environment or the command
+/// line. Anything that can't be represented as UTF-8 is handled according to `invalid`.
+pub fn check_password_os_str(password: &OsStr, invalid: InvalidUtf8) -> PassablewordResult {
+    decode(password.as_encoded_bytes(), invalid).and_then(|password| check_password(&password))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_encoding, check_password_bytes, check_password_os_str, check_password_utf16,
+        InvalidUtf8,
+    };
+    use std::ffi::OsStr;
+    use PasswordError;
+
+    #[test]
+    fn it_validates_a_byte_password() {
+        let ok_password =
+            check_password_bytes(b"Th1s iS a Sup3rR4ndom PassW0rd!", InvalidUtf8::Reject);
+        let too_common = check_password_bytes(b"password", InvalidUtf8::Reject);
+        let rejected = check_password_bytes(b"Th1s iS \xff Sup3rR4ndom", InvalidUtf8::Reject);
+        #[cfg(feature = "zxcvbn")]
+        let replaced = check_password_bytes(b"Th1s iS \xff Sup3rR4ndom", InvalidUtf8::Replace);
+        let replaced_short = check_password_bytes(b"\xff\xfe", InvalidUtf8::Replace);
+
+        assert_eq!(ok_password, Ok(()));
+        assert_eq!(too_common, Err(PasswordError::TooCommon));
+        assert_eq!(rejected, Err(PasswordError::InvalidEncoding));
+        #[cfg(feature = "zxcvbn")]
+        assert_eq!(replaced, Err(PasswordError::NonAsciiPassword));
+        assert_eq!(replaced_short, Err(PasswordError::TooShort));
+    }
+
+    #[test]
+    fn it_validates_an_os_str_password() {
+        let ok_password = check_password_os_str(
+            OsStr::new("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            InvalidUtf8::Reject,
+        );
+        let too_common = check_password_os_str(OsStr::new("password"), InvalidUtf8::Replace);
+
+        assert_eq!(ok_password, Ok(()));
+        assert_eq!(too_common, Err(PasswordError::TooCommon));
+    }
+
+    #[test]
+    fn it_handles_embedded_nul_characters() {
+        let password = b"Th1s iS a\0 Sup3rR4ndom PassW0rd!";
+
+        assert_eq!(
+            check_password_bytes(password, InvalidUtf8::Reject),
+            Err(PasswordError::InvalidEncoding)
+        );
+        assert_eq!(check_password_bytes(password, InvalidUtf8::Strip), Ok(()));
+        assert_eq!(
+            check_password_bytes(b"password\0", InvalidUtf8::Strip),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            check_password_os_str(OsStr::new("pass\0word"), InvalidUtf8::Reject),
+            Err(PasswordError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn it_strips_invalid_sequences() {
+        assert_eq!(
+            check_password_bytes(b"pass\xffword\xed\xa0\x80", InvalidUtf8::Strip),
+            Err(PasswordError::TooCommon)
+        );
+    }
+
+    #[test]
+    fn it_validates_a_utf16_password() {
+        let utf16 = |password: &str| password.encode_utf16().collect::<Vec<u16>>();
+        let mut lone_surrogate = utf16("password");
+
+        lone_surrogate.insert(4, 0xdc00);
+
+        assert_eq!(
+            check_password_utf16(&utf16("password"), InvalidUtf8::Reject),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            check_password_utf16(&lone_surrogate, InvalidUtf8::Reject),
+            Err(PasswordError::InvalidEncoding)
+        );
+        assert_eq!(
+            check_password_utf16(&lone_surrogate, InvalidUtf8::Strip),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            check_password_utf16(&utf16("pass\0word"), InvalidUtf8::Reject),
+            Err(PasswordError::InvalidEncoding)
+        );
+    }
+
+    #[test]
+    fn it_detects_lossy_conversions() {
+        assert_eq!(
+            check_encoding(&String::from_utf16_lossy(&[0x0061, 0xd800])),
+            Err(PasswordError::InvalidEncoding)
+        );
+        assert_eq!(check_encoding("a\0b"), Err(PasswordError::InvalidEncoding));
+        assert_eq!(check_encoding("contraseña"), Ok(()));
+    }
+}
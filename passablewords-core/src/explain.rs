@@ -0,0 +1,401 @@
+//! Describing what a checker would do with a password without doing it, so that the way a policy
+//! is wired up can be checked in tests and shown in admin pages.
+
+use checker::{PasswordChecker, CHECKS};
+use compat::{Compat, V1_MIN_GUESSES_LOG10};
+use context::CheckContext;
+use decision::Check;
+use frequency::FrequencySketch;
+use policy::CharsetMode;
+use temporal::TemporalTokens;
+use variants::{MAX_SHELL_DIGITS, MAX_SHELL_SYMBOLS};
+use MIN_ENTROPY_SCORE;
+
+/// Every check a `PasswordChecker` would put a password through, in the order it would run them,
+/// returned by `PasswordChecker::explain`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{Check, PasswordChecker, Policy, Threshold};
+///
+/// let checker = PasswordChecker::new()
+///     .with_policy(Policy::new().min_length_or_words(16, 3).warn_only(Check::Variety));
+/// let explanation = checker.explain("correct horse battery staple");
+///
+/// assert_eq!(explanation.checks[0].check, Check::Charset);
+/// assert_eq!(
+///     explanation.get(Check::Length).unwrap().threshold,
+///     Some(Threshold::Length {
+///         min_length: 16,
+///         min_words: Some(3),
+///         required_words: None,
+///     })
+/// );
+/// assert!(explanation.get(Check::Variety).unwrap().warning_only);
+/// assert!(!explanation.get(Check::KeyboardWalks).unwrap().enabled);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Explanation {
+    /// The version of the checker's policy, if it was given one.
+    pub policy_version: Option<String>,
+    /// Every check, including the ones the policy leaves off, in the order they're run.
+    pub checks: Vec<ExplainedCheck>,
+}
+
+impl Explanation {
+    /// How `check` would be run.
+    pub fn get(&self, check: Check) -> Option<&ExplainedCheck> {
+        self.checks
+            .iter()
+            .find(|explained| explained.check == check)
+    }
+
+    /// The checks that would actually be run, in order.
+    pub fn enabled(&self) -> impl Iterator<Item = &ExplainedCheck> {
+        self.checks.iter().filter(|explained| explained.enabled)
+    }
+}
+
+/// How a single check would be run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ExplainedCheck {
+    /// The check.
+    pub check: Check,
+    /// Whether the check would be run at all. Checks the policy doesn't turn on pass every
+    /// password without looking at it.
+    pub enabled: bool,
+    /// Whether a failure would only be a warning, as set with `Policy::warn_only`.
+    pub warning_only: bool,
+    /// What the check would hold the password to, or `None` for checks that aren't enabled.
+    pub threshold: Option<Threshold>,
+}
+
+/// What a check would hold a password to.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Threshold {
+    /// Which characters passwords can contain.
+    Charset(CharsetMode),
+    /// The policy's length rule.
+    Length {
+        /// The minimum number of characters.
+        min_length: usize,
+        /// The number of words accepted in place of `min_length`, if any.
+        min_words: Option<usize>,
+        /// The number of words every password needs, if any.
+        required_words: Option<usize>,
+    },
+    /// The minimum number of different characters.
+    MinUniqueChars(usize),
+    /// Which passwords made of a single kind of character are rejected.
+    Variety {
+        /// Whether passwords made of only digits are rejected.
+        rejects_all_digits: bool,
+        /// Whether passwords made of only letters are rejected.
+        rejects_all_letters: bool,
+    },
+    /// How many values the `CheckContext` denies.
+    DeniedValues(usize),
+    /// The names of the banned patterns, in the order they're tried.
+    BannedPatterns(Vec<String>),
+    /// The minimum length of keyboard walks that are rejected.
+    MinKeyboardWalkLength(usize),
+    /// The tokens that date passwords.
+    TemporalTokens(TemporalTokens),
+    /// The wordlists passwords are looked up in.
+    Wordlists {
+        /// How many passwords the checker's wordlist holds, or `None` if it couldn't be loaded,
+        /// in which case every password fails with `PasswordError::WordlistUnavailable`.
+        entries: Option<usize>,
+        /// The labels of the lists given with `with_labelled_wordlist`, in the order they're
+        /// tried.
+        labelled: Vec<String>,
+        /// How many of the organization's own users have to choose a password for it to be
+        /// rejected, if the checker has a `FrequencySketch`.
+        local_threshold: Option<u32>,
+        /// Whether common passwords with a few characters added to either end are rejected.
+        rejects_common_variants: bool,
+        /// Whether substitutions are undone with the policy's `Normalizer` before looking
+        /// passwords up.
+        normalizes: bool,
+        /// The minimum length of common passwords that are rejected anywhere inside a password,
+        /// if they are.
+        min_embedded_length: Option<usize>,
+    },
+    /// The most digits and symbols around a common word for it to be rejected.
+    WordShell {
+        /// The most digits around the word.
+        max_digits: usize,
+        /// The most symbols around the word.
+        max_symbols: usize,
+    },
+    /// The digits or symbols a single dictionary word needs around it.
+    DictionaryWordExtras {
+        /// The fewest digits that make a dictionary word acceptable, or 0 if digits don't.
+        min_digits: usize,
+        /// The fewest symbols that make a dictionary word acceptable, or 0 if symbols don't.
+        min_symbols: usize,
+    },
+    /// The lowest score, out of 4, the checker's analyzer or zxcvbn has to give.
+    MinScore(u8),
+    /// The fewest guesses, as a power of ten, the checker's analyzer or zxcvbn has to estimate,
+    /// under `Compat::V1`.
+    MinGuessesLog10(f64),
+    /// The fewest bits `quick_entropy_bits` has to estimate, for passwords zxcvbn can't analyze
+    /// or builds without it.
+    MinQuickEntropyBits(f64),
+}
+
+impl PasswordChecker {
+    /// Describe every check `check_password` would put `password` through, in order, with what
+    /// each one would hold it to, without running any of them. Use it to make sure a policy is
+    /// wired up the way it was meant to be, in tests or in an admin page.
+    ///
+    /// The password is only looked at to tell which estimate the entropy check would use, since
+    /// under `CharsetMode::Unicode` passwords that aren't ASCII are estimated with
+    /// `quick_entropy_bits`. Values denied by a `CheckContext` are never checked without one, so
+    /// use `explain_with_context` to see those.
+    pub fn explain(&self, password: &str) -> Explanation {
+        self.explain_checks(password, None)
+    }
+
+    /// Describe the checks `check_password_with_context` would put `password` through, like
+    /// `explain` does.
+    pub fn explain_with_context(&self, password: &str, context: &CheckContext) -> Explanation {
+        self.explain_checks(password, Some(context))
+    }
+
+    fn explain_checks(&self, password: &str, context: Option<&CheckContext>) -> Explanation {
+        Explanation {
+            policy_version: self.policy().version().map(str::to_string),
+            checks: CHECKS
+                .iter()
+                .map(|&check| {
+                    let threshold = self.threshold(check, password, context);
+
+                    ExplainedCheck {
+                        check,
+                        enabled: threshold.is_some(),
+                        warning_only: self.policy().is_warning_only(check),
+                        threshold,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// What `check` would hold `password` to, or `None` if it wouldn't be run.
+    fn threshold(
+        &self,
+        check: Check,
+        password: &str,
+        context: Option<&CheckContext>,
+    ) -> Option<Threshold> {
+        let policy = self.policy();
+        let rules = policy.to_frontend_rules();
+
+        match check {
+            Check::Charset => Some(Threshold::Charset(policy.charset())),
+            Check::Length => Some(Threshold::Length {
+                min_length: rules.min_length,
+                min_words: rules.min_words,
+                required_words: rules.required_words,
+            }),
+            Check::UniqueCharacters => rules.min_unique_chars.map(Threshold::MinUniqueChars),
+            Check::Variety if rules.rejects_all_digits || rules.rejects_all_letters => {
+                Some(Threshold::Variety {
+                    rejects_all_digits: rules.rejects_all_digits,
+                    rejects_all_letters: rules.rejects_all_letters,
+                })
+            }
+            Check::Variety => None,
+            Check::DeniedValues => {
+                context.map(|context| Threshold::DeniedValues(context.denied_count()))
+            }
+            Check::BannedPatterns if !rules.blocked_patterns.is_empty() => {
+                Some(Threshold::BannedPatterns(
+                    rules
+                        .blocked_patterns
+                        .into_iter()
+                        .map(|pattern| pattern.name)
+                        .collect(),
+                ))
+            }
+            Check::BannedPatterns => None,
+            Check::KeyboardWalks => rules
+                .rejects_keyboard_walks
+                .map(Threshold::MinKeyboardWalkLength),
+            Check::TemporalPatterns => policy
+                .temporal_tokens()
+                .cloned()
+                .map(Threshold::TemporalTokens),
+            Check::Uniqueness => Some(Threshold::Wordlists {
+                entries: self.wordlist().map(|wordlist| wordlist.len()),
+                labelled: self.wordlist_labels().map(str::to_string).collect(),
+                local_threshold: self.frequency_sketch().map(FrequencySketch::threshold),
+                rejects_common_variants: policy.strips_common_affixes(),
+                normalizes: policy.normalizer().is_some(),
+                min_embedded_length: policy.embedded_common_password_length(),
+            }),
+            Check::WordShells if policy.rejects_word_shells() => Some(Threshold::WordShell {
+                max_digits: MAX_SHELL_DIGITS,
+                max_symbols: MAX_SHELL_SYMBOLS,
+            }),
+            Check::WordShells => None,
+            Check::SingleDictionaryWord => {
+                policy
+                    .single_dictionary_word_extras()
+                    .map(
+                        |(min_digits, min_symbols)| Threshold::DictionaryWordExtras {
+                            min_digits,
+                            min_symbols,
+                        },
+                    )
+            }
+            Check::Entropy => Some(self.entropy_threshold(password)),
+        }
+    }
+
+    /// What the entropy check would hold `password` to, following the same path as
+    /// `check_entropy`.
+    fn entropy_threshold(&self, password: &str) -> Threshold {
+        let compat = self.policy().compat();
+        let unicode = self.policy().charset() == CharsetMode::Unicode && !password.is_ascii();
+        let analyzed = self.analyzer().is_some() || (cfg!(feature = "zxcvbn") && !unicode);
+
+        match compat {
+            _ if !analyzed => Threshold::MinQuickEntropyBits(compat.min_quick_entropy_bits()),
+            Compat::Latest => Threshold::MinScore(MIN_ENTROPY_SCORE),
+            Compat::V1 => Threshold::MinGuessesLog10(V1_MIN_GUESSES_LOG10),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Threshold;
+    use analyzer::Analysis;
+    use checker::{PasswordChecker, CHECKS};
+    use compat::Compat;
+    use context::CheckContext;
+    use decision::Check;
+    use policy::{CharsetMode, Policy};
+    use wordlist::Wordlist;
+
+    #[test]
+    fn it_explains_every_check_in_order() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+            .with_policy(
+                Policy::new()
+                    .with_version("v3")
+                    .min_unique_chars(6)
+                    .reject_keyboard_walks(5)
+                    .reject_single_dictionary_words(2, 1)
+                    .warn_only(Check::UniqueCharacters),
+            );
+        let explanation = checker.explain("anything at all");
+        let checks: Vec<Check> = explanation.checks.iter().map(|c| c.check).collect();
+        let enabled: Vec<Check> = explanation.enabled().map(|c| c.check).collect();
+
+        assert_eq!(explanation.policy_version, Some("v3".to_string()));
+        assert_eq!(checks, CHECKS.to_vec());
+        assert_eq!(
+            enabled,
+            vec![
+                Check::Charset,
+                Check::Length,
+                Check::UniqueCharacters,
+                Check::KeyboardWalks,
+                Check::Uniqueness,
+                Check::SingleDictionaryWord,
+                Check::Entropy,
+            ]
+        );
+        assert!(
+            explanation
+                .get(Check::UniqueCharacters)
+                .unwrap()
+                .warning_only
+        );
+        assert_eq!(
+            explanation.get(Check::KeyboardWalks).unwrap().threshold,
+            Some(Threshold::MinKeyboardWalkLength(5))
+        );
+        assert_eq!(
+            explanation.get(Check::Uniqueness).unwrap().threshold,
+            Some(Threshold::Wordlists {
+                entries: Some(2),
+                labelled: Vec::new(),
+                local_threshold: None,
+                rejects_common_variants: false,
+                normalizes: false,
+                min_embedded_length: None,
+            })
+        );
+        assert_eq!(
+            explanation
+                .get(Check::SingleDictionaryWord)
+                .unwrap()
+                .threshold,
+            Some(Threshold::DictionaryWordExtras {
+                min_digits: 2,
+                min_symbols: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn it_explains_the_context_and_entropy() {
+        let checker = PasswordChecker::new()
+            .with_analyzer(|_: &str, _: &[&str]| {
+                Ok(Analysis {
+                    score: 4,
+                    guesses_log10: 20.0,
+                })
+            })
+            .with_policy(Policy::new().pin_compat(Compat::V1));
+        let context = CheckContext::new().deny_exact(&["ABCD 1234", ""]);
+
+        assert_eq!(
+            checker
+                .explain_with_context("anything", &context)
+                .get(Check::DeniedValues)
+                .unwrap()
+                .threshold,
+            Some(Threshold::DeniedValues(1))
+        );
+        assert!(
+            !checker
+                .explain("anything")
+                .get(Check::DeniedValues)
+                .unwrap()
+                .enabled
+        );
+        assert_eq!(
+            checker
+                .explain("anything")
+                .get(Check::Entropy)
+                .unwrap()
+                .threshold,
+            Some(Threshold::MinGuessesLog10(8.0))
+        );
+
+        let unicode =
+            PasswordChecker::new().with_policy(Policy::new().charset_mode(CharsetMode::Unicode));
+
+        assert_eq!(
+            unicode
+                .explain("contraseña")
+                .get(Check::Entropy)
+                .unwrap()
+                .threshold,
+            Some(Threshold::MinQuickEntropyBits(
+                Compat::Latest.min_quick_entropy_bits()
+            ))
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Errors as the `extensions` of GraphQL errors, for APIs built with async-graphql or juniper.
+//! Only available with the `serde` feature.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value;
+
+use policy::Policy;
+use PasswordError;
+
+impl PasswordError {
+    /// The error as the `extensions` of a GraphQL error. It has a `code`, like `TOO_SHORT`, along
+    /// with the `list` of a `TooCommonIn` error and the `pattern` of a `BannedPattern` error.
+    /// `WordlistUnavailable`'s path and cause are left out, since they're for logs rather than
+    /// clients. `Policy::to_extension_map` adds the limits that the password missed.
+    ///
+    /// async-graphql's `Value::from_json` and juniper's `Value` can be built from the values.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::PasswordError;
+    ///
+    /// let extensions = PasswordError::TooCommonIn("breach-2024".to_string()).to_extension_map();
+    ///
+    /// assert_eq!(extensions["code"], "TOO_COMMON_IN");
+    /// assert_eq!(extensions["list"], "breach-2024");
+    /// ```
+    pub fn to_extension_map(&self) -> BTreeMap<String, Value> {
+        let mut extensions = BTreeMap::new();
+
+        extensions.insert("code".to_string(), Value::from(self.code()));
+        match *self {
+            PasswordError::TooCommonIn(ref list) => {
+                extensions.insert("list".to_string(), Value::from(list.as_str()));
+            }
+            PasswordError::BannedPattern(ref pattern) => {
+                extensions.insert("pattern".to_string(), Value::from(pattern.as_str()));
+            }
+            _ => {}
+        }
+        extensions
+    }
+}
+
+impl Policy {
+    /// `error.to_extension_map()`, along with the limits of this policy that `password` missed
+    /// and where it ended up, in camelCase:
+    ///
+    /// - `TooShort` adds `minLength` and `actualLength`, in characters, and `minWords` and
+    ///   `requiredWords` when the policy has them.
+    /// - `TooFewUniqueChars` adds `minUniqueChars` and `actualUniqueChars`.
+    /// - `InputTooLarge` adds `maxBytes` and `actualBytes`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordError, Policy};
+    ///
+    /// let extensions = Policy::new()
+    ///     .min_length(12)
+    ///     .to_extension_map(&PasswordError::TooShort, "hunter2");
+    ///
+    /// assert_eq!(extensions["code"], "TOO_SHORT");
+    /// assert_eq!(extensions["minLength"], 12);
+    /// assert_eq!(extensions["actualLength"], 7);
+    /// ```
+    pub fn to_extension_map(
+        &self,
+        error: &PasswordError,
+        password: &str,
+    ) -> BTreeMap<String, Value> {
+        let mut extensions = error.to_extension_map();
+        let rules = self.to_frontend_rules();
+        let mut insert = |key: &str, value: usize| {
+            extensions.insert(key.to_string(), Value::from(value));
+        };
+
+        match *error {
+            PasswordError::TooShort => {
+                insert("minLength", rules.min_length);
+                insert("actualLength", password.chars().count());
+                if let Some(words) = rules.min_words {
+                    insert("minWords", words);
+                }
+                if let Some(words) = rules.required_words {
+                    insert("requiredWords", words);
+                }
+            }
+            PasswordError::TooFewUniqueChars => {
+                if let Some(count) = rules.min_unique_chars {
+                    insert("minUniqueChars", count);
+                }
+                insert(
+                    "actualUniqueChars",
+                    password.chars().collect::<BTreeSet<_>>().len(),
+                );
+            }
+            PasswordError::InputTooLarge => {
+                insert("maxBytes", self.input_limit());
+                insert("actualBytes", password.len());
+            }
+            _ => {}
+        }
+        extensions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use policy::Policy;
+    use serde_json::Value;
+    use PasswordError;
+
+    #[test]
+    fn it_describes_errors() {
+        let extensions =
+            PasswordError::BannedPattern("company name".to_string()).to_extension_map();
+
+        assert_eq!(extensions.len(), 2);
+        assert_eq!(extensions["code"], "BANNED_PATTERN");
+        assert_eq!(extensions["pattern"], "company name");
+
+        let extensions = PasswordError::WordlistUnavailable {
+            path: Some("/etc/passablewords/list.txt".to_string()),
+            source: "No such file or directory".to_string(),
+        }
+        .to_extension_map();
+
+        assert_eq!(extensions.keys().collect::<Vec<_>>(), vec!["code"]);
+        assert_eq!(extensions["code"], "WORDLIST_UNAVAILABLE");
+    }
+
+    #[test]
+    fn it_adds_the_policy_limits() {
+        let policy = Policy::new()
+            .min_length_or_words(16, 4)
+            .min_unique_chars(6)
+            .max_input_bytes(32);
+
+        let extensions = policy.to_extension_map(&PasswordError::TooShort, "çorrect horse");
+
+        assert_eq!(extensions["minLength"], 16);
+        assert_eq!(extensions["minWords"], 4);
+        assert_eq!(extensions["actualLength"], 13);
+        assert!(!extensions.contains_key("requiredWords"));
+
+        let extensions = policy.to_extension_map(&PasswordError::TooFewUniqueChars, "abababab");
+
+        assert_eq!(extensions["minUniqueChars"], 6);
+        assert_eq!(extensions["actualUniqueChars"], 2);
+
+        let extensions = policy.to_extension_map(&PasswordError::InputTooLarge, &"é".repeat(20));
+
+        assert_eq!(extensions["maxBytes"], 32);
+        assert_eq!(extensions["actualBytes"], 40);
+        assert_eq!(
+            policy.to_extension_map(&PasswordError::TooCommon, "password"),
+            vec![("code".to_string(), Value::from("TOO_COMMON"))]
+                .into_iter()
+                .collect()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Feedback for showing next to a password field, and a quick yes or no for when that's all that's
+//! needed. Neither allocates when a password passes every check, apart from whatever the entropy
+//! estimate allocates, so both are cheap enough to run on every keystroke.
+
+use std::borrow::Cow;
+
+use checker::PasswordChecker;
+use decision::Check;
+use outcome::CheckOutcome;
+use PasswordError;
+
+/// What's wrong with a password, if anything, returned by `PasswordChecker::feedback`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Feedback {
+    /// Whether the password should be accepted, even if there are warnings.
+    pub accepted: bool,
+    /// The checks that rejected the password, or the ones the policy only warns about if it was
+    /// accepted, in the order they're run. This is empty when the password passed every check.
+    pub items: Vec<FeedbackItem>,
+}
+
+/// A check that a password failed, with messages for the user.
+///
+/// The messages are the canned ones from `PasswordError::explanation` and
+/// `PasswordError::advice`, borrowed rather than copied. Replace them with owned strings to
+/// translate or reword them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct FeedbackItem {
+    /// The check that failed.
+    pub check: Check,
+    /// Why it failed.
+    pub error: PasswordError,
+    /// Whether the failure rejects the password, rather than the policy only warning about it.
+    pub blocking: bool,
+    /// A short explanation of why the check failed, suitable for showing next to the field.
+    pub explanation: Cow<'static, str>,
+    /// Longer advice on picking a better password.
+    pub advice: Cow<'static, str>,
+}
+
+impl FeedbackItem {
+    fn new(check: Check, error: PasswordError, blocking: bool) -> FeedbackItem {
+        FeedbackItem {
+            check,
+            explanation: Cow::Borrowed(error.explanation()),
+            advice: Cow::Borrowed(error.advice()),
+            error,
+            blocking,
+        }
+    }
+}
+
+impl From<CheckOutcome> for Feedback {
+    fn from(outcome: CheckOutcome) -> Feedback {
+        match outcome {
+            CheckOutcome::Pass => Feedback {
+                accepted: true,
+                items: Vec::new(),
+            },
+            CheckOutcome::PassWithWarnings(warnings) => Feedback {
+                accepted: true,
+                items: warnings
+                    .into_iter()
+                    .map(|warning| FeedbackItem::new(warning.check, warning.error, false))
+                    .collect(),
+            },
+            CheckOutcome::Fail(failures) => Feedback {
+                accepted: false,
+                items: failures
+                    .into_iter()
+                    .map(|failure| FeedbackItem::new(failure.check, failure.error, true))
+                    .collect(),
+            },
+        }
+    }
+}
+
+impl PasswordChecker {
+    /// Whether a password should be accepted, like `check_password(password).is_ok()`. Nothing is
+    /// allocated unless the checker has a decision hook, the policy needs to copy the password to
+    /// check it, like it does to normalize it, or the entropy estimate allocates. zxcvbn does, so
+    /// use `with_analyzer` or the quick estimate where that matters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::PasswordChecker;
+    ///
+    /// let checker = PasswordChecker::new();
+    ///
+    /// assert!(checker.accepts("Th1s iS a Sup3rR4ndom PassW0rd!"));
+    /// assert!(!checker.accepts("password"));
+    /// ```
+    pub fn accepts(&self, password: &str) -> bool {
+        self.check_password(password).is_ok()
+    }
+
+    /// Check a password like `check_password_detailed` does, with messages for every check that
+    /// failed. A password that passes every check allocates no more than `accepts` does.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Check, PasswordChecker};
+    ///
+    /// let checker = PasswordChecker::new();
+    /// let feedback = checker.feedback("qwerty");
+    ///
+    /// assert!(!feedback.accepted);
+    /// assert_eq!(feedback.items[0].check, Check::Length);
+    /// assert_eq!(feedback.items[0].explanation, "Your password is too short.");
+    /// assert!(checker.feedback("Th1s iS a Sup3rR4ndom PassW0rd!").items.is_empty());
+    /// ```
+    pub fn feedback(&self, password: &str) -> Feedback {
+        Feedback::from(self.check_password_detailed(password))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Feedback, FeedbackItem};
+    use checker::PasswordChecker;
+    use decision::Check;
+    use policy::Policy;
+    use std::borrow::Cow;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    #[test]
+    fn it_gives_feedback() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"))
+            .with_policy(Policy::new().min_length(16).warn_only(Check::Uniqueness));
+        let feedback = checker.feedback("x7#Kq9!zLm2$");
+
+        assert_eq!(
+            feedback,
+            Feedback {
+                accepted: false,
+                items: vec![FeedbackItem {
+                    check: Check::Length,
+                    error: PasswordError::TooShort,
+                    blocking: true,
+                    explanation: Cow::Borrowed("Your password is too short."),
+                    advice: Cow::Borrowed(PasswordError::TooShort.advice()),
+                }],
+            }
+        );
+        assert!(!checker.accepts("x7#Kq9!zLm2$"));
+    }
+
+    #[test]
+    fn it_gives_feedback_on_warnings() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"))
+            .with_policy(Policy::new().warn_only(Check::Uniqueness));
+        let feedback = checker.feedback("x7#Kq9!zLm2$");
+
+        assert!(feedback.accepted);
+        assert_eq!(feedback.items.len(), 1);
+        assert!(!feedback.items[0].blocking);
+        assert!(checker.accepts("x7#Kq9!zLm2$"));
+    }
+}
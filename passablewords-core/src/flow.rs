@@ -0,0 +1,405 @@
+//! The checks behind "set password" and "change password" endpoints, for applications that would
+//! rather use a vetted flow than wire `check_password`, `check_history`, and the rest together
+//! themselves.
+
+use std::fmt;
+use std::iter;
+use std::sync::Arc;
+
+use audit::within_distance;
+use checker::PasswordChecker;
+use context::CheckContext;
+use history::{check_history, PasswordVerifier};
+use PasswordError;
+
+/// How many characters a new password has to differ from the current one by, unless
+/// `PasswordFlow::min_changes` says otherwise.
+pub const DEFAULT_MIN_CHANGES: usize = 4;
+
+/// The body of a request to set an account's password without giving the old one, such as when
+/// signing up or following a reset link. The passwords are left out of `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SetPassword {
+    /// The new password.
+    pub password: String,
+    /// The new password again, typed into a second field.
+    pub confirmation: String,
+}
+
+/// The body of a request to change the password of an account that's signed in. The passwords
+/// are left out of `Debug` output.
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ChangePassword {
+    /// The account's password as it is now.
+    pub current_password: String,
+    /// The password to change it to.
+    pub new_password: String,
+    /// The new password again, typed into a second field.
+    pub confirmation: String,
+}
+
+impl fmt::Debug for SetPassword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SetPassword")
+            .field("password", &"<redacted>")
+            .field("confirmation", &"<redacted>")
+            .finish()
+    }
+}
+
+impl fmt::Debug for ChangePassword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ChangePassword")
+            .field("current_password", &"<redacted>")
+            .field("new_password", &"<redacted>")
+            .field("confirmation", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Why a `PasswordFlow` turned a request down.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum FlowError {
+    /// The new password and its confirmation are different.
+    ConfirmationMismatch,
+    /// The current password sent with a change isn't the account's password.
+    IncorrectCurrentPassword,
+    /// The new password is only a few characters different from the current one, like the same
+    /// password with the number at the end bumped.
+    TooSimilarToCurrent,
+    /// The new password was rejected by the checker, or is one of the account's previous
+    /// passwords.
+    Rejected(PasswordError),
+}
+
+impl FlowError {
+    /// A short, user-facing explanation of why the request was turned down, like
+    /// `PasswordError::explanation`.
+    pub fn explanation(&self) -> &'static str {
+        match *self {
+            FlowError::ConfirmationMismatch => "The passwords you entered don't match.",
+            FlowError::IncorrectCurrentPassword => "Your current password isn't right.",
+            FlowError::TooSimilarToCurrent => {
+                "Your new password is too similar to your current one."
+            }
+            FlowError::Rejected(ref error) => error.explanation(),
+        }
+    }
+
+    /// The name of the error in SCREAMING_SNAKE_CASE, like `CONFIRMATION_MISMATCH`, for response
+    /// bodies. Rejections use the code of their `PasswordError`, like `TOO_COMMON`.
+    #[cfg(any(feature = "serde", feature = "problem"))]
+    pub fn code(&self) -> &'static str {
+        match *self {
+            FlowError::ConfirmationMismatch => "CONFIRMATION_MISMATCH",
+            FlowError::IncorrectCurrentPassword => "INCORRECT_CURRENT_PASSWORD",
+            FlowError::TooSimilarToCurrent => "TOO_SIMILAR_TO_CURRENT",
+            FlowError::Rejected(ref error) => error.code(),
+        }
+    }
+}
+
+impl From<PasswordError> for FlowError {
+    fn from(error: PasswordError) -> FlowError {
+        FlowError::Rejected(error)
+    }
+}
+
+/// Checks the requests behind "set password" and "change password" endpoints in the order that
+/// leaks the least and costs the least: the current password first, then the confirmation, then
+/// how much the password changed, then the checker's policy, and the slow password hashes of the
+/// account's history last.
+///
+/// The flow only decides. Looking up the account's hashes beforehand, and hashing and storing the
+/// new password afterwards, are left to the application, so it works with any framework or
+/// database.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{ChangePassword, FlowError, PasswordChecker, PasswordFlow, Wordlist};
+///
+/// // A real application would use one of the built-in verifiers, like `BcryptVerifier`
+/// let verifier = |password: &str, hash: &str| password == hash;
+/// let flow = PasswordFlow::new(PasswordChecker::with_wordlist(Wordlist::parse("")), verifier);
+/// let request = ChangePassword {
+///     current_password: "Correct Horse Battery 1".to_string(),
+///     new_password: "Correct Horse Battery 2".to_string(),
+///     confirmation: "Correct Horse Battery 2".to_string(),
+/// };
+///
+/// assert_eq!(
+///     flow.change_password("mike", &request, "Correct Horse Battery 1", Vec::<String>::new()),
+///     Err(FlowError::TooSimilarToCurrent)
+/// );
+/// ```
+#[derive(Clone)]
+pub struct PasswordFlow {
+    checker: PasswordChecker,
+    verifier: Arc<dyn PasswordVerifier>,
+    min_changes: usize,
+}
+
+impl PasswordFlow {
+    /// Create a flow which checks new passwords with `checker`, and the current and previous
+    /// passwords against their stored hashes with `verifier`.
+    pub fn new<V: PasswordVerifier + 'static>(
+        checker: PasswordChecker,
+        verifier: V,
+    ) -> PasswordFlow {
+        PasswordFlow {
+            checker,
+            verifier: Arc::new(verifier),
+            min_changes: DEFAULT_MIN_CHANGES,
+        }
+    }
+
+    /// Reject new passwords that are fewer than `changes` characters added, removed, or replaced
+    /// away from the current one, ignoring case. Setting it to 0 only rejects the current password
+    /// itself.
+    pub fn min_changes(mut self, changes: usize) -> PasswordFlow {
+        self.min_changes = changes;
+        self
+    }
+
+    /// The checker new passwords are checked with.
+    pub fn checker(&self) -> &PasswordChecker {
+        &self.checker
+    }
+
+    /// Check a request to set `username`'s password, where there's no current password to compare
+    /// it with.
+    pub fn set_password(&self, username: &str, request: &SetPassword) -> Result<(), FlowError> {
+        if request.password != request.confirmation {
+            return Err(FlowError::ConfirmationMismatch);
+        }
+
+        self.check_new_password(username, &request.password)
+    }
+
+    /// Check a request to change `username`'s password, given the hash of their current password
+    /// and the hashes of their previous ones. The new password can't be the current one or any of
+    /// the previous ones.
+    pub fn change_password<I>(
+        &self,
+        username: &str,
+        request: &ChangePassword,
+        current_hash: &str,
+        previous_hashes: I,
+    ) -> Result<(), FlowError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        if !self
+            .verifier
+            .verify(&request.current_password, current_hash)
+        {
+            return Err(FlowError::IncorrectCurrentPassword);
+        }
+
+        if request.new_password != request.confirmation {
+            return Err(FlowError::ConfirmationMismatch);
+        }
+
+        if self.min_changes > 0
+            && self.is_too_similar(&request.current_password, &request.new_password)
+        {
+            return Err(FlowError::TooSimilarToCurrent);
+        }
+
+        self.check_new_password(username, &request.new_password)?;
+
+        let previous: Vec<I::Item> = previous_hashes.into_iter().collect();
+        let hashes = iter::once(current_hash).chain(previous.iter().map(AsRef::as_ref));
+
+        check_history(&request.new_password, hashes, &*self.verifier)?;
+        Ok(())
+    }
+
+    fn check_new_password(&self, username: &str, password: &str) -> Result<(), FlowError> {
+        let context = CheckContext::new().with_username(username);
+
+        self.checker
+            .check_password_with_context(password, &context)
+            .map_err(FlowError::from)
+    }
+
+    fn is_too_similar(&self, current: &str, new: &str) -> bool {
+        let current: Vec<char> = current.to_lowercase().chars().collect();
+        let new: Vec<char> = new.to_lowercase().chars().collect();
+
+        within_distance(&current, &new, self.min_changes - 1)
+    }
+}
+
+impl fmt::Debug for PasswordFlow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PasswordFlow")
+            .field("checker", &self.checker)
+            .field("verifier", &"PasswordVerifier")
+            .field("min_changes", &self.min_changes)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    const STRONG: &str = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+    fn flow() -> PasswordFlow {
+        let verifier = |password: &str, hash: &str| format!("hashed:{}", password) == hash;
+
+        PasswordFlow::new(
+            PasswordChecker::with_wordlist(Wordlist::parse("password123")),
+            verifier,
+        )
+    }
+
+    fn change(current: &str, new: &str) -> ChangePassword {
+        ChangePassword {
+            current_password: current.to_string(),
+            new_password: new.to_string(),
+            confirmation: new.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_sets_passwords() {
+        let request = SetPassword {
+            password: STRONG.to_string(),
+            confirmation: STRONG.to_string(),
+        };
+
+        assert_eq!(flow().set_password("mike", &request), Ok(()));
+        assert_eq!(
+            flow().set_password(
+                "mike",
+                &SetPassword {
+                    confirmation: "something else".to_string(),
+                    ..request
+                }
+            ),
+            Err(FlowError::ConfirmationMismatch)
+        );
+        assert_eq!(
+            flow().set_password(
+                "mike",
+                &SetPassword {
+                    password: "password123".to_string(),
+                    confirmation: "password123".to_string(),
+                }
+            ),
+            Err(FlowError::Rejected(PasswordError::TooCommon))
+        );
+        assert_eq!(
+            flow().set_password(
+                "mikeengel1984",
+                &SetPassword {
+                    password: "mikeengel1984!".to_string(),
+                    confirmation: "mikeengel1984!".to_string(),
+                }
+            ),
+            Err(FlowError::Rejected(PasswordError::MatchesUsername))
+        );
+    }
+
+    #[test]
+    fn it_changes_passwords() {
+        let current = "Correct Horse Battery Staple";
+        let current_hash = format!("hashed:{}", current);
+        let previous = [format!("hashed:{}", STRONG)];
+
+        assert_eq!(
+            flow().change_password(
+                "mike",
+                &change("wrong", "Tr0ub4dor & 3 Horses"),
+                &current_hash,
+                &previous
+            ),
+            Err(FlowError::IncorrectCurrentPassword)
+        );
+        assert_eq!(
+            flow().change_password(
+                "mike",
+                &ChangePassword {
+                    confirmation: "something else".to_string(),
+                    ..change(current, "Tr0ub4dor & 3 Horses")
+                },
+                &current_hash,
+                &previous
+            ),
+            Err(FlowError::ConfirmationMismatch)
+        );
+        assert_eq!(
+            flow().change_password("mike", &change(current, STRONG), &current_hash, &previous),
+            Err(FlowError::Rejected(PasswordError::MatchesPreviousPassword))
+        );
+        assert_eq!(
+            flow().change_password(
+                "mike",
+                &change(current, "Tr0ub4dor & 3 Horses"),
+                &current_hash,
+                &previous
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_rejects_small_changes() {
+        let current = "Correct Horse Battery Staple";
+        let current_hash = format!("hashed:{}", current);
+        let none = Vec::<String>::new();
+
+        assert_eq!(
+            flow().change_password(
+                "mike",
+                &change(current, "correct horse battery staple!1"),
+                &current_hash,
+                &none
+            ),
+            Err(FlowError::TooSimilarToCurrent)
+        );
+        assert_eq!(
+            flow().min_changes(2).change_password(
+                "mike",
+                &change(current, "correct horse battery staple!1"),
+                &current_hash,
+                &none
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            flow().min_changes(0).change_password(
+                "mike",
+                &change(current, current),
+                &current_hash,
+                &none
+            ),
+            Err(FlowError::Rejected(PasswordError::MatchesPreviousPassword))
+        );
+    }
+
+    #[test]
+    fn it_redacts_passwords() {
+        let set = SetPassword {
+            password: STRONG.to_string(),
+            confirmation: STRONG.to_string(),
+        };
+        let debug = format!("{:?} {:?}", set, change("hunter22", STRONG));
+
+        assert!(!debug.contains(STRONG));
+        assert!(!debug.contains("hunter22"));
+        assert!(debug.contains("current_password"));
+    }
+}
@@ -0,0 +1,236 @@
+//! Counting how often passwords are chosen within one organization, so that its own favorites,
+//! like a seasonal slogan, are blocked once enough users have picked them.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use sha2::{Digest, Sha256};
+
+use {PassablewordResult, PasswordError};
+
+/// How many counters are in each row by default, which is a quarter of a MiB per row.
+const DEFAULT_WIDTH: usize = 1 << 16;
+
+/// How many rows there are by default. A password is only overcounted when it collides with
+/// busier passwords in every row.
+const DEFAULT_DEPTH: usize = 4;
+
+/// The most counters a sketch can have across every row, which is 256 MiB.
+const MAX_COUNTERS: usize = 1 << 26;
+
+/// A count-min sketch of the passwords an organization's users have chosen, for blocking the ones
+/// that have become popular locally even though they aren't in any list of common passwords.
+/// Record each password when it's set, and once `threshold` users have chosen one, `check`
+/// rejects it for everyone else with `PasswordError::TooCommonLocally`. Give one to
+/// `PasswordChecker::with_frequency_sketch` to check it along with the wordlist.
+///
+/// # Privacy
+///
+/// The sketch never stores passwords or hashes of them, only counters which each password bumps
+/// one of in every row. Passwords are hashed with `key` first, so without it there's no way to
+/// tell which counters belong to which password, and even with it, every counter is shared by a
+/// great many passwords. Keep the key as secret as the password hashes themselves, since anyone
+/// holding both it and the counters can ask how often a guess of theirs was chosen, and only
+/// record passwords that were accepted. Counts only ever overestimate, so a rare password may
+/// occasionally be rejected, but a popular one is never let through.
+///
+/// The counters are atomic, so one sketch can be shared between threads and recorded to without
+/// a lock.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{FrequencySketch, PasswordError};
+///
+/// let sketch = FrequencySketch::new(b"a secret only the servers know", 3);
+///
+/// for _ in 0..3 {
+///     assert_eq!(sketch.check("AcmeSummer2024!"), Ok(()));
+///     sketch.record("AcmeSummer2024!");
+/// }
+///
+/// assert_eq!(sketch.estimate("AcmeSummer2024!"), 3);
+/// assert_eq!(
+///     sketch.check("AcmeSummer2024!"),
+///     Err(PasswordError::TooCommonLocally)
+/// );
+/// ```
+pub struct FrequencySketch {
+    key: Vec<u8>,
+    threshold: u32,
+    width: usize,
+    counters: Vec<AtomicU32>,
+}
+
+impl FrequencySketch {
+    /// Create an empty sketch, hashing passwords with `key`, which rejects passwords once
+    /// `threshold` users have chosen them. It holds 4 rows of 65,536 counters, which is 1 MiB.
+    pub fn new(key: &[u8], threshold: u32) -> FrequencySketch {
+        FrequencySketch::with_size(key, threshold, DEFAULT_WIDTH, DEFAULT_DEPTH)
+            .expect("the default size is within the limit")
+    }
+
+    /// Create an empty sketch with `depth` rows of `width` counters each. Wider rows overcount
+    /// less often, and more rows make it less likely that an overcount gets through. Both are at
+    /// least 1, and there are at most 8 rows. `None` is returned if the sketch would need more
+    /// than 2^26 counters, or 256 MiB, in total.
+    pub fn with_size(
+        key: &[u8],
+        threshold: u32,
+        width: usize,
+        depth: usize,
+    ) -> Option<FrequencySketch> {
+        let width = width.max(1);
+        let depth = depth.clamp(1, 8);
+        let len = width
+            .checked_mul(depth)
+            .filter(|&len| len <= MAX_COUNTERS)?;
+
+        Some(FrequencySketch {
+            key: key.to_vec(),
+            threshold: threshold.max(1),
+            width,
+            counters: (0..len).map(|_| AtomicU32::new(0)).collect(),
+        })
+    }
+
+    /// How many users have chosen `password`, or more if it shares its counters with passwords
+    /// that are more popular.
+    pub fn estimate(&self, password: &str) -> u32 {
+        self.slots(password)
+            .map(|slot| self.counters[slot].load(Ordering::Relaxed))
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Count another user choosing `password`.
+    pub fn record(&self, password: &str) {
+        for slot in self.slots(password) {
+            self.counters[slot].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Check that fewer than `threshold` users have chosen `password`, rejecting it with
+    /// `PasswordError::TooCommonLocally` otherwise.
+    pub fn check(&self, password: &str) -> PassablewordResult {
+        if self.estimate(password) >= self.threshold {
+            Err(PasswordError::TooCommonLocally)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// How many users have to choose a password before it's rejected.
+    pub fn threshold(&self) -> u32 {
+        self.threshold
+    }
+
+    /// The counter `password` bumps in each row, found by double hashing a keyed SHA-256 of it.
+    fn slots(&self, password: &str) -> impl Iterator<Item = usize> {
+        let digest = Sha256::new()
+            .chain_update((self.key.len() as u64).to_le_bytes())
+            .chain_update(&self.key)
+            .chain_update(password.as_bytes())
+            .finalize();
+        let mut halves = [0; 8];
+
+        halves.copy_from_slice(&digest[..8]);
+        let first = u64::from_le_bytes(halves);
+        halves.copy_from_slice(&digest[8..16]);
+        let second = u64::from_le_bytes(halves) | 1;
+        let width = self.width;
+
+        (0..self.counters.len() / width).map(move |row| {
+            let column = first.wrapping_add((row as u64).wrapping_mul(second)) % width as u64;
+
+            row * width + column as usize
+        })
+    }
+}
+
+impl fmt::Debug for FrequencySketch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FrequencySketch")
+            .field("threshold", &self.threshold)
+            .field("width", &self.width)
+            .field("depth", &(self.counters.len() / self.width))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrequencySketch;
+    use std::sync::Arc;
+    use std::thread;
+    use PasswordError;
+
+    #[test]
+    fn it_counts_passwords() {
+        let sketch = FrequencySketch::with_size(b"key", 2, 64, 3).unwrap();
+
+        assert_eq!(sketch.estimate("Winter2025!"), 0);
+        sketch.record("Winter2025!");
+        assert_eq!(sketch.check("Winter2025!"), Ok(()));
+        sketch.record("Winter2025!");
+        assert_eq!(
+            sketch.check("Winter2025!"),
+            Err(PasswordError::TooCommonLocally)
+        );
+        assert_eq!(sketch.check("Spring2025!"), Ok(()));
+    }
+
+    #[test]
+    fn it_only_overcounts() {
+        let sketch = FrequencySketch::with_size(b"key", 10, 16, 2).unwrap();
+        let passwords: Vec<String> = (0..100).map(|n| format!("password {}", n)).collect();
+
+        for (n, password) in passwords.iter().enumerate() {
+            for _ in 0..n % 5 {
+                sketch.record(password);
+            }
+        }
+
+        for (n, password) in passwords.iter().enumerate() {
+            assert!(sketch.estimate(password) >= (n % 5) as u32);
+        }
+    }
+
+    #[test]
+    fn it_depends_on_the_key() {
+        let first = FrequencySketch::with_size(b"first", 1, 1 << 20, 1).unwrap();
+        let second = FrequencySketch::with_size(b"second", 1, 1 << 20, 1).unwrap();
+
+        assert!(first.slots("Autumn2025!").ne(second.slots("Autumn2025!")));
+        assert!(!format!("{:?}", first).contains("first"));
+    }
+
+    #[test]
+    fn it_rejects_absurd_sizes() {
+        assert!(FrequencySketch::with_size(b"key", 1, usize::MAX, 2).is_none());
+        assert!(FrequencySketch::with_size(b"key", 1, 1 << 24, 8).is_none());
+        assert!(FrequencySketch::with_size(b"key", 1, 1 << 16, 8).is_some());
+    }
+
+    #[test]
+    fn it_records_from_many_threads() {
+        let sketch = Arc::new(FrequencySketch::new(b"key", 100));
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let sketch = sketch.clone();
+
+                thread::spawn(move || {
+                    for _ in 0..25 {
+                        sketch.record("Summer2025!");
+                    }
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(sketch.estimate("Summer2025!"), 100);
+    }
+}
@@ -0,0 +1,253 @@
+//! A machine-readable copy of a policy's rules, for frontends that want to validate passwords
+//! before they're submitted.
+
+use std::fmt::Write;
+
+/// The rules of a `Policy` in a form that a frontend validator can mirror, created with
+/// `Policy::to_frontend_rules`. Frontends can't check the wordlist or the exact zxcvbn result
+/// that the server will see, so these are hints for giving early feedback rather than a
+/// replacement for checking on the server.
+///
+/// # JSON format
+///
+/// `to_json` (and `serde`, with the `serde` feature) produce an object with these keys, where
+/// optional values are `null` when the rule isn't in use.
+///
+/// - `version` (string or null): the policy's version, from `Policy::with_version`.
+/// - `minLength` (integer): the minimum number of characters.
+/// - `maxBytes` (integer): the most bytes a password can have once it's encoded as UTF-8, which
+///   is what `new TextEncoder().encode(password).length` counts in JavaScript. Longer passwords
+///   are rejected before any other rule is checked.
+/// - `minWords` (integer or null): passwords with at least this many words are accepted even if
+///   they're shorter than `minLength`.
+/// - `requiredWords` (integer or null): the minimum number of words every password needs.
+/// - `minUniqueChars` (integer or null): the minimum number of different characters.
+/// - `rejectsAllDigits` (boolean): whether passwords made of only digits are rejected.
+/// - `rejectsAllLetters` (boolean): whether passwords made of only letters are rejected.
+/// - `minScore` (integer): the lowest zxcvbn score, from 0 to 4, that the policy can accept,
+///   which is lower for policies pinned to `Compat::V1`.
+/// - `rejectsCommonVariants` (boolean): whether common passwords with a few characters added to
+///   either end are rejected.
+/// - `rejectsEmbeddedCommonPasswords` (integer or null): the minimum length of common passwords
+///   that are rejected anywhere inside a password.
+/// - `rejectsWordShells` (boolean): whether a single common word with at most four digits and one
+///   symbol around it is rejected.
+/// - `rejectsKeyboardWalks` (integer or null): the minimum length of keyboard walks, like
+///   `qwerty`, that are rejected.
+/// - `asciiOnly` (boolean): whether passwords can only contain ASCII characters.
+/// - `blockedPatterns` (array): objects with a `name` and a regular expression `pattern` which
+///   passwords can't match.
+///
+/// A word is a run of at least three letters, separated by anything that isn't a letter or by a
+/// lowercase letter followed by an uppercase one. The server also splits runs of letters into the
+/// words of its wordlist, which frontends can't, so they may count fewer words. Patterns use the
+/// syntax of Rust's `regex` crate, which overlaps with JavaScript's for most simple expressions,
+/// but inline flags like `(?i)` need translating.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{CharsetMode, Policy};
+///
+/// let rules = Policy::new()
+///     .min_length_or_words(16, 3)
+///     .charset_mode(CharsetMode::AsciiOnly)
+///     .with_version("v2")
+///     .to_frontend_rules();
+///
+/// assert_eq!(
+///     rules.to_json(),
+///     "{\"version\":\"v2\",\"minLength\":16,\"maxBytes\":1024,\"minWords\":3,\
+///      \"requiredWords\":null,\"minUniqueChars\":null,\"rejectsAllDigits\":false,\
+///      \"rejectsAllLetters\":false,\"minScore\":3,\"rejectsCommonVariants\":false,\
+///      \"rejectsEmbeddedCommonPasswords\":null,\"rejectsWordShells\":false,\
+///      \"rejectsKeyboardWalks\":null,\"asciiOnly\":true,\"blockedPatterns\":[]}"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct FrontendRules {
+    /// The policy's version, if it was given one.
+    pub version: Option<String>,
+    /// The minimum number of characters.
+    pub min_length: usize,
+    /// The most bytes a password can have once it's encoded as UTF-8.
+    pub max_bytes: usize,
+    /// The number of words which is accepted in place of `min_length`, if any.
+    pub min_words: Option<usize>,
+    /// The minimum number of words every password needs, if any.
+    pub required_words: Option<usize>,
+    /// The minimum number of different characters, if any.
+    pub min_unique_chars: Option<usize>,
+    /// Whether passwords made of only digits are rejected.
+    pub rejects_all_digits: bool,
+    /// Whether passwords made of only letters are rejected.
+    pub rejects_all_letters: bool,
+    /// The lowest zxcvbn score the policy can accept.
+    pub min_score: u8,
+    /// Whether common passwords with a few characters added to either end are rejected.
+    pub rejects_common_variants: bool,
+    /// The minimum length of common passwords that are rejected anywhere inside a password, if
+    /// they are.
+    pub rejects_embedded_common_passwords: Option<usize>,
+    /// Whether a single common word with at most four digits and one symbol around it is rejected.
+    pub rejects_word_shells: bool,
+    /// The minimum length of keyboard walks that are rejected, if they are.
+    pub rejects_keyboard_walks: Option<usize>,
+    /// Whether passwords can only contain ASCII characters.
+    pub ascii_only: bool,
+    /// The patterns passwords can't match.
+    pub blocked_patterns: Vec<BlockedPattern>,
+}
+
+/// A named regular expression that passwords can't match.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct BlockedPattern {
+    /// The name of the rule, which is also included in `PasswordError::BannedPattern`.
+    pub name: String,
+    /// The regular expression.
+    pub pattern: String,
+}
+
+impl FrontendRules {
+    /// The rules as a JSON object, in the format described above. This doesn't need the `serde`
+    /// feature.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"version\":");
+
+        match self.version {
+            Some(ref version) => push_json_string(&mut json, version),
+            None => json.push_str("null"),
+        }
+
+        let _ = write!(
+            json,
+            ",\"minLength\":{},\"maxBytes\":{},\"minWords\":",
+            self.min_length, self.max_bytes
+        );
+        push_json_number(&mut json, self.min_words);
+        json.push_str(",\"requiredWords\":");
+        push_json_number(&mut json, self.required_words);
+        json.push_str(",\"minUniqueChars\":");
+        push_json_number(&mut json, self.min_unique_chars);
+        let _ = write!(
+            json,
+            ",\"rejectsAllDigits\":{},\"rejectsAllLetters\":{}",
+            self.rejects_all_digits, self.rejects_all_letters
+        );
+        let _ = write!(
+            json,
+            ",\"minScore\":{},\"rejectsCommonVariants\":{},\"rejectsEmbeddedCommonPasswords\":",
+            self.min_score, self.rejects_common_variants
+        );
+        push_json_number(&mut json, self.rejects_embedded_common_passwords);
+        let _ = write!(json, ",\"rejectsWordShells\":{}", self.rejects_word_shells);
+        json.push_str(",\"rejectsKeyboardWalks\":");
+        push_json_number(&mut json, self.rejects_keyboard_walks);
+        let _ = write!(json, ",\"asciiOnly\":{}", self.ascii_only);
+        json.push_str(",\"blockedPatterns\":[");
+
+        for (index, blocked) in self.blocked_patterns.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"name\":");
+            push_json_string(&mut json, &blocked.name);
+            json.push_str(",\"pattern\":");
+            push_json_string(&mut json, &blocked.pattern);
+            json.push('}');
+        }
+
+        json.push_str("]}");
+        json
+    }
+}
+
+fn push_json_number(json: &mut String, number: Option<usize>) {
+    match number {
+        Some(number) => {
+            let _ = write!(json, "{}", number);
+        }
+        None => json.push_str("null"),
+    }
+}
+
+pub(crate) fn push_json_string(json: &mut String, value: &str) {
+    json.push('"');
+
+    for character in value.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", character as u32);
+            }
+            character => json.push(character),
+        }
+    }
+
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::push_json_string;
+    #[cfg(feature = "serde")]
+    use super::{BlockedPattern, FrontendRules};
+    use compat::Compat;
+    use policy::Policy;
+
+    #[test]
+    fn it_escapes_json_strings() {
+        let mut json = String::new();
+
+        push_json_string(&mut json, "a\"b\\c\nd\u{1}é");
+
+        assert_eq!(json, "\"a\\\"b\\\\c\\nd\\u0001é\"");
+    }
+
+    #[test]
+    fn it_exports_the_effective_min_score() {
+        assert_eq!(Policy::new().to_frontend_rules().min_score, 3);
+        assert_eq!(
+            Policy::new()
+                .pin_compat(Compat::V1)
+                .to_frontend_rules()
+                .min_score,
+            2
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_matches_serde() {
+        let rules = FrontendRules {
+            version: None,
+            min_length: 12,
+            max_bytes: 256,
+            min_words: None,
+            required_words: Some(3),
+            min_unique_chars: Some(5),
+            rejects_all_digits: true,
+            rejects_all_letters: false,
+            min_score: 3,
+            rejects_common_variants: true,
+            rejects_embedded_common_passwords: Some(6),
+            rejects_word_shells: true,
+            rejects_keyboard_walks: Some(5),
+            ascii_only: true,
+            blocked_patterns: vec![BlockedPattern {
+                name: "company \"name\"".to_string(),
+                pattern: r"(?i)acme\d+".to_string(),
+            }],
+        };
+
+        assert_eq!(rules.to_json(), ::serde_json::to_string(&rules).unwrap());
+    }
+}
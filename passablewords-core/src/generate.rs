@@ -0,0 +1,343 @@
+//! Generating passwords that pass a policy, such as stronger suggestions based on a password that
+//! was rejected. Only available with the `generate` feature.
+
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng, RngCore};
+
+use checker::PasswordChecker;
+use passablewords_data;
+use policy::Policy;
+use recovery::{check_recovery_code, RecoveryCodePolicy};
+
+/// The words used to build passphrases, one per line. There are `WORD_COUNT` of them, so each
+/// one adds 8 bits of entropy.
+const WORDS: &str = passablewords_data::GENERATOR_WORDS;
+const WORD_COUNT: usize = 256;
+
+/// The symbols mixed into suggestions.
+const SYMBOLS: &[char] = &['!', '@', '#', '$', '%', '&', '*', '?', '+', '='];
+
+/// The characters random passwords are made of.
+const CHARACTERS: &[u8] =
+    b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%&*?+=";
+
+/// How many candidates are tried for each suggestion before giving up on it.
+const ATTEMPTS: usize = 10;
+
+/// Suggest two or three stronger passwords based on one that was rejected, each of which passes
+/// `policy` and the bundled list of common passwords. See `PasswordChecker::suggest_improvements`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{suggest_improvements, PasswordChecker, Policy};
+///
+/// let policy = Policy::new().min_length(12);
+/// let checker = PasswordChecker::new().with_policy(policy.clone());
+///
+/// for suggestion in suggest_improvements("hunter2", &policy) {
+///     assert!(checker.check_password(&suggestion).is_ok());
+/// }
+/// ```
+pub fn suggest_improvements(password: &str, policy: &Policy) -> Vec<String> {
+    PasswordChecker::new()
+        .with_policy(policy.clone())
+        .suggest_improvements(password)
+}
+
+/// Generate `count` different recovery codes which pass `check_recovery_code` with `policy`,
+/// split into groups with the policy's separator. Only available with the `generate` feature.
+///
+/// Codes that fail, such as ones with too few different characters, are thrown away and another
+/// is tried. Fewer than `count` are returned if the policy can't make enough passing codes, and
+/// none at all if it isn't strong enough.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_recovery_code, generate_recovery_codes, RecoveryCodePolicy};
+///
+/// let policy = RecoveryCodePolicy::new();
+/// let codes = generate_recovery_codes(&policy, 10);
+///
+/// assert_eq!(codes.len(), 10);
+///
+/// for code in codes {
+///     assert_eq!(check_recovery_code(&code, &policy), Ok(()));
+/// }
+/// ```
+pub fn generate_recovery_codes(policy: &RecoveryCodePolicy, count: usize) -> Vec<String> {
+    let mut rng = thread_rng();
+    let mut codes: Vec<String> = Vec::with_capacity(count);
+
+    if policy.characters().is_empty() {
+        return codes;
+    }
+
+    for _ in 0..count {
+        let code = (0..ATTEMPTS)
+            .map(|_| {
+                let code: String = (0..policy.code_length())
+                    .map(|_| policy.characters()[rng.gen_range(0..policy.characters().len())])
+                    .collect();
+
+                policy.format(&code)
+            })
+            .find(|code| !codes.contains(code) && check_recovery_code(code, policy).is_ok());
+
+        match code {
+            Some(code) => codes.push(code),
+            None => break,
+        }
+    }
+
+    codes
+}
+
+impl PasswordChecker {
+    /// Suggest two or three stronger passwords based on one that was rejected, each of which
+    /// passes this checker. Only available with the `generate` feature.
+    ///
+    /// Suggestions keep what the user typed where they can, by adding random words, digits, and
+    /// symbols to it, or by turning the words in it into a longer passphrase. If what they typed
+    /// can never pass, such as when it contains a banned pattern, entirely random passphrases are
+    /// suggested instead. Every suggestion is checked before it's returned, so the list is only
+    /// empty if nothing can pass, for example when the wordlist couldn't be read.
+    pub fn suggest_improvements(&self, password: &str) -> Vec<String> {
+        let mut rng = thread_rng();
+        let strategies: [fn(&str, &mut dyn RngCore) -> String; 4] = [
+            append_words,
+            passphrase_from,
+            insert_symbols,
+            random_passphrase,
+        ];
+        let mut suggestions: Vec<String> = Vec::new();
+
+        for strategy in strategies.iter() {
+            if suggestions.len() == 3 {
+                break;
+            }
+
+            for _ in 0..ATTEMPTS {
+                let suggestion = lengthen(strategy(password, &mut rng), self.policy(), &mut rng);
+
+                if !suggestions.contains(&suggestion) && self.check_password(&suggestion).is_ok() {
+                    suggestions.push(suggestion);
+                    break;
+                }
+            }
+        }
+
+        suggestions
+    }
+
+    /// Generate a random password of `length` characters made of letters, digits, and symbols,
+    /// which passes this checker. Only available with the `generate` feature.
+    ///
+    /// Candidates that fail the checks, such as ones that happen to be in the wordlist, are
+    /// thrown away and another is tried. `None` is returned if none of the first few pass, for
+    /// example when `length` is shorter than the policy allows.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::PasswordChecker;
+    ///
+    /// let password = PasswordChecker::new().generate_password(20).unwrap();
+    ///
+    /// assert_eq!(password.len(), 20);
+    /// ```
+    pub fn generate_password(&self, length: usize) -> Option<String> {
+        let mut rng = thread_rng();
+
+        (0..ATTEMPTS)
+            .map(|_| {
+                (0..length)
+                    .map(|_| char::from(CHARACTERS[rng.gen_range(0..CHARACTERS.len())]))
+                    .collect::<String>()
+            })
+            .find(|password| self.check_password(password).is_ok())
+    }
+
+    /// Generate a passphrase of `words` random words separated by hyphens, with a digit at the
+    /// end, which passes this checker. More words are added if the policy needs it to be longer.
+    /// Only available with the `generate` feature.
+    ///
+    /// The words come from a list of 256, so each adds 8 bits of entropy, and 6 or more are
+    /// recommended for accounts that matter. `None` is returned if none of the first few
+    /// candidates pass.
+    pub fn generate_passphrase(&self, words: usize) -> Option<String> {
+        let mut rng = thread_rng();
+
+        for _ in 0..ATTEMPTS {
+            let chosen: Vec<String> = (0..words).map(|_| random_word(&mut rng, false)).collect();
+            let passphrase = lengthen(
+                format!("{}{}", chosen.join("-"), rng.gen_range(0..10)),
+                self.policy(),
+                &mut rng,
+            );
+
+            if self.check_password(&passphrase).is_ok() {
+                return Some(passphrase);
+            }
+        }
+
+        None
+    }
+}
+
+/// A random word from the generator's list, capitalized if `capitalize` is true.
+fn random_word(rng: &mut dyn RngCore, capitalize: bool) -> String {
+    let word = WORDS
+        .lines()
+        .nth(rng.gen_range(0..WORD_COUNT))
+        .unwrap_or("word");
+
+    if capitalize {
+        let mut characters = word.chars();
+
+        match characters.next() {
+            Some(first) => first.to_uppercase().chain(characters).collect(),
+            None => String::new(),
+        }
+    } else {
+        word.to_string()
+    }
+}
+
+fn random_symbol(rng: &mut dyn RngCore) -> char {
+    *SYMBOLS.choose(rng).unwrap_or(&'!')
+}
+
+/// The password followed by two random words, a digit, and a symbol, like `hunter2-Maple-Orbit7!`.
+fn append_words(password: &str, rng: &mut dyn RngCore) -> String {
+    format!(
+        "{}-{}-{}{}{}",
+        password,
+        random_word(rng, true),
+        random_word(rng, true),
+        rng.gen_range(0..10),
+        random_symbol(rng)
+    )
+}
+
+/// The words in the password, joined with random ones into a passphrase, like
+/// `hunter-maple-orbit-canyon`.
+fn passphrase_from(password: &str, rng: &mut dyn RngCore) -> String {
+    let mut words: Vec<String> = password
+        .split(|character: char| !character.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect();
+
+    for _ in 0..3 {
+        let word = random_word(rng, false);
+        let index = rng.gen_range(0..=words.len());
+
+        words.insert(index, word);
+    }
+
+    words.join("-")
+}
+
+/// The password with a symbol and digits inserted into the middle, followed by a random word,
+/// like `hun#4ter2Maple`.
+fn insert_symbols(password: &str, rng: &mut dyn RngCore) -> String {
+    let boundaries: Vec<usize> = password.char_indices().map(|(index, _)| index).collect();
+    let middle = boundaries
+        .get(boundaries.len() / 2)
+        .map_or(password.len(), |index| *index);
+
+    format!(
+        "{}{}{}{}{}",
+        &password[..middle],
+        random_symbol(rng),
+        rng.gen_range(10..100),
+        &password[middle..],
+        random_word(rng, true)
+    )
+}
+
+/// A passphrase of five random words which has nothing to do with the password, for when the
+/// password can't be salvaged.
+fn random_passphrase(_password: &str, rng: &mut dyn RngCore) -> String {
+    let words: Vec<String> = (0..5).map(|_| random_word(rng, false)).collect();
+
+    format!("{}{}", words.join("-"), rng.gen_range(0..10))
+}
+
+/// Add random words to the end of a suggestion until it's long enough for the policy.
+fn lengthen(mut suggestion: String, policy: &Policy, rng: &mut dyn RngCore) -> String {
+    // Give up eventually so that an absurd minimum length can't loop forever. Anything that's
+    // still too short fails the check afterwards.
+    for _ in 0..64 {
+        if policy.check_length(&suggestion).is_ok() {
+            break;
+        }
+
+        suggestion.push('-');
+        suggestion.push_str(&random_word(rng, false));
+    }
+
+    suggestion
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate_recovery_codes, suggest_improvements, WORDS, WORD_COUNT};
+    use checker::PasswordChecker;
+    use policy::Policy;
+    use recovery::{check_recovery_code, RecoveryCodePolicy};
+
+    #[test]
+    fn it_has_unique_words() {
+        let mut words: Vec<&str> = WORDS.lines().collect();
+
+        words.sort();
+        words.dedup();
+
+        assert_eq!(words.len(), WORD_COUNT);
+    }
+
+    #[test]
+    fn it_generates_passing_passwords() {
+        let checker = PasswordChecker::new().with_policy(Policy::new().min_length(16));
+        let password = checker.generate_password(16).unwrap();
+        let passphrase = checker.generate_passphrase(5).unwrap();
+
+        assert_eq!(password.chars().count(), 16);
+        assert_eq!(checker.check_password(&password), Ok(()));
+        assert!(passphrase.split('-').count() >= 5);
+        assert_eq!(checker.check_password(&passphrase), Ok(()));
+        assert_eq!(checker.generate_password(12), None);
+    }
+
+    #[test]
+    fn it_suggests_passing_passwords() {
+        let policy = Policy::new().min_length(20).min_unique_chars(8);
+        let checker = PasswordChecker::new().with_policy(policy.clone());
+        let suggestions = suggest_improvements("hunter2", &policy);
+
+        assert!(suggestions.len() >= 2);
+
+        for suggestion in suggestions {
+            assert_eq!(checker.check_password(&suggestion), Ok(()));
+        }
+    }
+
+    #[test]
+    fn it_generates_passing_recovery_codes() {
+        let policy = RecoveryCodePolicy::new().length(12).group_size(Some(4));
+        let codes = generate_recovery_codes(&policy, 8);
+
+        assert_eq!(codes.len(), 8);
+
+        for code in &codes {
+            assert_eq!(code.len(), 14);
+            assert_eq!(code.split('-').count(), 3);
+            assert_eq!(check_recovery_code(code, &policy), Ok(()));
+        }
+
+        assert!(generate_recovery_codes(&policy.length(4), 8).is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+//! Hashing a candidate password once and reusing the hash for every list it's looked up in.
+//!
+//! Every hash set wordlist is keyed with the same random SipHash key, chosen once per process, and
+//! stores its entries behind a hasher that passes an already computed hash straight through. A
+//! `HashedCandidate` works out its hash the first time it's looked up in one of them, and every
+//! other list it's looked up in reuses it, rather than hashing the candidate again for each list
+//! like `HashSet::contains` would.
+
+use std::borrow::{Borrow, Cow};
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+use std::sync::OnceLock;
+
+/// The key every hash set wordlist is hashed with.
+static KEY: OnceLock<RandomState> = OnceLock::new();
+
+fn keyed_hash(text: &str) -> u64 {
+    KEY.get_or_init(RandomState::new).hash_one(text)
+}
+
+/// The entries of a hash set wordlist.
+pub(crate) type EntrySet = HashSet<Entry, BuildHasherDefault<PassThrough>>;
+
+/// A password in a hash set wordlist.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Entry(pub(crate) Cow<'static, str>);
+
+impl Hash for Entry {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(keyed_hash(&self.0));
+    }
+}
+
+/// A password, or a variation of one, that's looked up in one or more wordlists.
+pub(crate) struct HashedCandidate<'a> {
+    text: &'a str,
+    hash: Cell<Option<u64>>,
+}
+
+impl<'a> HashedCandidate<'a> {
+    pub(crate) fn new(text: &'a str) -> HashedCandidate<'a> {
+        HashedCandidate {
+            text,
+            hash: Cell::new(None),
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Whether the candidate is in `entries`, hashing it if it hasn't been already.
+    pub(crate) fn is_in(&self, entries: &EntrySet) -> bool {
+        entries.contains(self as &dyn Key)
+    }
+}
+
+/// Something that can be looked up in an `EntrySet`, which is either an entry itself or a
+/// candidate with its hash.
+pub(crate) trait Key {
+    fn key_hash(&self) -> u64;
+    fn key_text(&self) -> &str;
+}
+
+impl Key for Entry {
+    fn key_hash(&self) -> u64 {
+        keyed_hash(&self.0)
+    }
+
+    fn key_text(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> Key for HashedCandidate<'a> {
+    fn key_hash(&self) -> u64 {
+        match self.hash.get() {
+            Some(hash) => hash,
+            None => {
+                let hash = keyed_hash(self.text);
+
+                self.hash.set(Some(hash));
+                hash
+            }
+        }
+    }
+
+    fn key_text(&self) -> &str {
+        self.text
+    }
+}
+
+impl<'a> Borrow<dyn Key + 'a> for Entry {
+    fn borrow(&self) -> &(dyn Key + 'a) {
+        self
+    }
+}
+
+impl<'a> Hash for dyn Key + 'a {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.key_hash());
+    }
+}
+
+impl<'a> PartialEq for dyn Key + 'a {
+    fn eq(&self, other: &(dyn Key + 'a)) -> bool {
+        self.key_text() == other.key_text()
+    }
+}
+
+impl<'a> Eq for dyn Key + 'a {}
+
+/// A hasher for values that have already been hashed, which uses the last `u64` it's given as
+/// the hash.
+#[derive(Default)]
+pub(crate) struct PassThrough(u64);
+
+impl Hasher for PassThrough {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = self.0.rotate_left(8) ^ u64::from(byte);
+        }
+    }
+
+    fn write_u64(&mut self, hash: u64) {
+        self.0 = hash;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, EntrySet, HashedCandidate};
+    use std::borrow::Cow;
+
+    #[test]
+    fn it_reuses_the_hash_across_sets() {
+        let first: EntrySet = vec![Entry(Cow::Borrowed("password"))].into_iter().collect();
+        let second: EntrySet = vec![Entry(Cow::Owned("letmein".to_string()))]
+            .into_iter()
+            .collect();
+        let candidate = HashedCandidate::new("letmein");
+
+        assert!(!candidate.is_in(&first));
+        assert!(candidate.hash.get().is_some());
+        assert!(candidate.is_in(&second));
+        assert!(HashedCandidate::new("password").is_in(&first));
+        assert!(!HashedCandidate::new("passwords").is_in(&first));
+    }
+}
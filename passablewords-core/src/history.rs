@@ -0,0 +1,172 @@
+//! Checks against an account's previous passwords, using the hashes the application already
+//! stores for them.
+
+use {PassablewordResult, PasswordError};
+
+/// Something which can tell whether a password matches a stored hash, so that `check_history` can
+/// work with however an application stores its passwords. Implementations are shared between
+/// threads, so they need to be `Send` and `Sync`.
+///
+/// Closures taking the password and the hash implement it too.
+pub trait PasswordVerifier: Send + Sync {
+    /// Whether `password` is the one `hash` was made from. Hashes which can't be read should
+    /// return `false`.
+    fn verify(&self, password: &str, hash: &str) -> bool;
+}
+
+impl<F: Fn(&str, &str) -> bool + Send + Sync> PasswordVerifier for F {
+    fn verify(&self, password: &str, hash: &str) -> bool {
+        self(password, hash)
+    }
+}
+
+/// Verifies bcrypt hashes, like `$2b$12$...`. Needs the `bcrypt` feature.
+#[cfg(feature = "bcrypt")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BcryptVerifier;
+
+#[cfg(feature = "bcrypt")]
+impl PasswordVerifier for BcryptVerifier {
+    fn verify(&self, password: &str, hash: &str) -> bool {
+        bcrypt::verify(password, hash).unwrap_or(false)
+    }
+}
+
+/// Verifies Argon2 hashes in the PHC string format, like `$argon2id$v=19$...`. The parameters are
+/// read from each hash. Needs the `argon2` feature.
+#[cfg(feature = "argon2")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Argon2Verifier;
+
+#[cfg(feature = "argon2")]
+impl PasswordVerifier for Argon2Verifier {
+    fn verify(&self, password: &str, hash: &str) -> bool {
+        use argon2::password_hash::{PasswordHash, PasswordVerifier as _};
+        use argon2::Argon2;
+
+        PasswordHash::new(hash).is_ok_and(|hash| {
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+}
+
+/// Verifies scrypt hashes in the PHC string format, like `$scrypt$ln=17,r=8,p=1$...`. The
+/// parameters are read from each hash. Needs the `scrypt` feature.
+#[cfg(feature = "scrypt")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScryptVerifier;
+
+#[cfg(feature = "scrypt")]
+impl PasswordVerifier for ScryptVerifier {
+    fn verify(&self, password: &str, hash: &str) -> bool {
+        use scrypt::password_hash::{PasswordHash, PasswordVerifier as _};
+        use scrypt::Scrypt;
+
+        PasswordHash::new(hash).is_ok_and(|hash| {
+            Scrypt
+                .verify_password(password.as_bytes(), &hash)
+                .is_ok()
+        })
+    }
+}
+
+/// Check that a password isn't one of the account's previous passwords, given their stored
+/// hashes. Password hashes are slow to verify on purpose, so each one in `previous` adds to how
+/// long this takes. Keep the history to the last few passwords.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_history, PasswordError};
+///
+/// // A real application would use one of the built-in verifiers, like `BcryptVerifier`
+/// let verifier = |password: &str, hash: &str| password == hash;
+/// let previous = ["Correct Horse Battery 1", "Correct Horse Battery 2"];
+///
+/// assert_eq!(
+///     check_history("Correct Horse Battery 2", &previous, &verifier),
+///     Err(PasswordError::MatchesPreviousPassword)
+/// );
+/// assert_eq!(check_history("Correct Horse Battery 3", &previous, &verifier), Ok(()));
+/// ```
+pub fn check_history<V, I>(password: &str, previous: I, verifier: &V) -> PassablewordResult
+where
+    V: PasswordVerifier + ?Sized,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    if previous
+        .into_iter()
+        .any(|hash| verifier.verify(password, hash.as_ref()))
+    {
+        Err(PasswordError::MatchesPreviousPassword)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_previous_passwords() {
+        let verifier = |password: &str, hash: &str| format!("hashed:{}", password) == hash;
+        let previous = vec!["hashed:first".to_string(), "hashed:second".to_string()];
+
+        assert_eq!(
+            check_history("second", &previous, &verifier),
+            Err(PasswordError::MatchesPreviousPassword)
+        );
+        assert_eq!(check_history("third", &previous, &verifier), Ok(()));
+        assert_eq!(check_history("first", Vec::<String>::new(), &verifier), Ok(()));
+    }
+
+    #[test]
+    #[cfg(feature = "bcrypt")]
+    fn it_verifies_bcrypt_hashes() {
+        let hash = bcrypt::hash("Correct Horse Battery", 4).unwrap();
+
+        assert!(BcryptVerifier.verify("Correct Horse Battery", &hash));
+        assert!(!BcryptVerifier.verify("Correct Horse Battery 2", &hash));
+        assert!(!BcryptVerifier.verify("Correct Horse Battery", "not a hash"));
+    }
+
+    #[test]
+    #[cfg(feature = "argon2")]
+    fn it_verifies_argon2_hashes() {
+        use argon2::password_hash::{PasswordHasher, SaltString};
+        use argon2::Argon2;
+
+        let salt = SaltString::encode_b64(b"sixteen byte salt").unwrap();
+        let hash = Argon2::default()
+            .hash_password(b"Correct Horse Battery", &salt)
+            .unwrap()
+            .to_string();
+
+        assert!(Argon2Verifier.verify("Correct Horse Battery", &hash));
+        assert!(!Argon2Verifier.verify("Correct Horse Battery 2", &hash));
+        assert!(!Argon2Verifier.verify("Correct Horse Battery", "not a hash"));
+    }
+
+    #[test]
+    #[cfg(feature = "scrypt")]
+    fn it_verifies_scrypt_hashes() {
+        use scrypt::password_hash::{PasswordHasher, SaltString};
+        use scrypt::{Params, Scrypt};
+
+        // Cheap parameters, to keep the test fast
+        let params = Params::new(4, 8, 1, Params::RECOMMENDED_LEN).unwrap();
+        let salt = SaltString::encode_b64(b"sixteen byte salt").unwrap();
+        let hash = Scrypt
+            .hash_password_customized(b"Correct Horse Battery", None, None, params, &salt)
+            .unwrap()
+            .to_string();
+
+        assert!(ScryptVerifier.verify("Correct Horse Battery", &hash));
+        assert!(!ScryptVerifier.verify("Correct Horse Battery 2", &hash));
+        assert!(!ScryptVerifier.verify("Correct Horse Battery", "not a hash"));
+    }
+}
@@ -0,0 +1,237 @@
+//! The request and response of a password policy endpoint, for identity providers like Keycloak,
+//! Okta, or Auth0 that can ask an external service whether a new password is allowed. Only
+//! available with the `serde` feature.
+//!
+//! # Contract
+//!
+//! The identity provider `POST`s a JSON object with the new `password`, and whatever it knows
+//! about the account: its `username`, `email`, `firstName`, and `lastName`. Only `password` is
+//! required.
+//!
+//! ```json
+//! { "username": "mike", "email": "mike@example.com", "password": "hunter2" }
+//! ```
+//!
+//! The service always answers with `200 OK`, so that an error status means the service itself
+//! failed, and a JSON object saying whether the password is `valid`. `errors` lists every check it
+//! failed, and `warnings` the checks the policy only warns about, whether or not the password is
+//! valid, each with a `code`, like `TOO_SHORT`, and a `message` to show the user.
+//!
+//! ```json
+//! {
+//!   "valid": false,
+//!   "errors": [{ "code": "TOO_SHORT", "message": "Your password is too short." }],
+//!   "warnings": []
+//! }
+//! ```
+
+use std::fmt;
+
+use checker::PasswordChecker;
+use context::CheckContext;
+use outcome::CheckOutcome;
+use PasswordError;
+
+/// A request to check a new password, as an identity provider sends it. The password is left out
+/// of `Debug` output.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PolicyCheckRequest {
+    /// The account's username. Passwords built from it are rejected.
+    pub username: Option<String>,
+    /// The account's email address.
+    pub email: Option<String>,
+    /// The user's first name.
+    pub first_name: Option<String>,
+    /// The user's last name.
+    pub last_name: Option<String>,
+    /// The new password.
+    pub password: String,
+}
+
+impl fmt::Debug for PolicyCheckRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PolicyCheckRequest")
+            .field("username", &self.username)
+            .field("email", &self.email)
+            .field("first_name", &self.first_name)
+            .field("last_name", &self.last_name)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// The answer to a `PolicyCheckRequest`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyCheckResponse {
+    /// Whether the password is allowed, even if there are warnings.
+    pub valid: bool,
+    /// Every check the password failed, in the order the checks are run.
+    pub errors: Vec<PolicyViolation>,
+    /// The checks the password failed that the policy only warns about. `check_policy_request`
+    /// includes these even when the password isn't valid, but `from_outcome` can't, since
+    /// `CheckOutcome::Fail` leaves them out.
+    pub warnings: Vec<PolicyViolation>,
+}
+
+/// A check a password failed, in a `PolicyCheckResponse`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyViolation {
+    /// The error's code, like `TOO_SHORT`, which is the same as in GraphQL errors and problem
+    /// details.
+    pub code: String,
+    /// The error's explanation, from `PasswordError::explanation`.
+    pub message: String,
+}
+
+impl<'a> From<&'a PasswordError> for PolicyViolation {
+    fn from(error: &'a PasswordError) -> PolicyViolation {
+        PolicyViolation {
+            code: error.code().to_string(),
+            message: error.explanation().to_string(),
+        }
+    }
+}
+
+impl PolicyCheckResponse {
+    /// The response for the outcome of `PasswordChecker::check_password_detailed`. `warnings` is
+    /// empty when the password failed, since the outcome doesn't include them then.
+    pub fn from_outcome(outcome: &CheckOutcome) -> PolicyCheckResponse {
+        let mut response = PolicyCheckResponse {
+            valid: outcome.is_accepted(),
+            errors: Vec::new(),
+            warnings: Vec::new(),
+        };
+
+        match *outcome {
+            CheckOutcome::Pass => {}
+            CheckOutcome::PassWithWarnings(ref warnings) => {
+                response.warnings = warnings
+                    .iter()
+                    .map(|warning| PolicyViolation::from(&warning.error))
+                    .collect();
+            }
+            CheckOutcome::Fail(ref failures) => {
+                response.errors = failures
+                    .iter()
+                    .map(|failure| PolicyViolation::from(&failure.error))
+                    .collect();
+            }
+        }
+        response
+    }
+}
+
+impl PasswordChecker {
+    /// Answer an identity provider's request to check a password. The username is checked like
+    /// `CheckContext::with_username`, and the email address and names are passed to the entropy
+    /// check like `CheckContext::with_user_inputs`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PolicyCheckRequest, Wordlist};
+    ///
+    /// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password"));
+    /// let request: PolicyCheckRequest =
+    ///     serde_json::from_str(r#"{ "username": "mike", "password": "hunter2" }"#).unwrap();
+    /// let response = checker.check_policy_request(&request);
+    ///
+    /// assert!(!response.valid);
+    /// assert_eq!(response.errors[0].code, "TOO_SHORT");
+    /// ```
+    pub fn check_policy_request(&self, request: &PolicyCheckRequest) -> PolicyCheckResponse {
+        let mut context = CheckContext::new();
+
+        if let Some(ref username) = request.username {
+            context = context.with_username(username);
+        }
+
+        let inputs: Vec<&str> = [&request.email, &request.first_name, &request.last_name]
+            .iter()
+            .filter_map(|input| input.as_ref().map(String::as_str))
+            .collect();
+        let context = context.with_user_inputs(&inputs);
+        let (failures, warnings) = self.failures_and_warnings(&request.password, Some(&context));
+
+        PolicyCheckResponse {
+            valid: failures.is_empty(),
+            errors: failures
+                .iter()
+                .map(|failure| PolicyViolation::from(&failure.error))
+                .collect(),
+            warnings: warnings
+                .iter()
+                .map(|warning| PolicyViolation::from(&warning.error))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decision::Check;
+    use policy::Policy;
+    use wordlist::Wordlist;
+
+    #[test]
+    fn it_answers_requests() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+        let request: PolicyCheckRequest = ::serde_json::from_str(
+            r#"{ "username": "mikeengel1984", "firstName": "Mike", "password": "mikeengel1984!" }"#,
+        )
+        .unwrap();
+
+        assert_eq!(request.first_name, Some("Mike".to_string()));
+        assert!(!format!("{:?}", request).contains("mikeengel1984!"));
+        assert_eq!(
+            ::serde_json::to_value(checker.check_policy_request(&request)).unwrap(),
+            ::serde_json::json!({
+                "valid": false,
+                "errors": [{
+                    "code": "MATCHES_USERNAME",
+                    "message": PasswordError::MatchesUsername.explanation(),
+                }],
+                "warnings": [],
+            })
+        );
+
+        let request = PolicyCheckRequest {
+            password: "Th1s iS a Sup3rR4ndom PassW0rd!".to_string(),
+            ..PolicyCheckRequest::default()
+        };
+
+        assert_eq!(
+            checker.check_policy_request(&request),
+            PolicyCheckResponse {
+                valid: true,
+                errors: vec![],
+                warnings: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn it_includes_warnings() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+            .with_policy(Policy::new().min_length(40).warn_only(Check::Length));
+        let response = checker.check_policy_request(&PolicyCheckRequest {
+            password: "Th1s iS a Sup3rR4ndom PassW0rd!".to_string(),
+            ..PolicyCheckRequest::default()
+        });
+
+        assert!(response.valid);
+        assert!(response.errors.is_empty());
+        assert_eq!(response.warnings[0].code, "TOO_SHORT");
+
+        let response = checker.check_policy_request(&PolicyCheckRequest {
+            password: "password123".to_string(),
+            ..PolicyCheckRequest::default()
+        });
+
+        assert!(!response.valid);
+        assert_eq!(response.errors[0].code, "TOO_COMMON");
+        assert_eq!(response.warnings[0].code, "TOO_SHORT");
+    }
+}
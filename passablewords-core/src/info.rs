@@ -0,0 +1,118 @@
+//! What's known about the bundled list of common passwords, for recording exactly which list was
+//! in use when a password was accepted.
+
+use std::fmt::Write;
+use std::sync::OnceLock;
+
+use passablewords_data;
+use sha2::{Digest, Sha256};
+
+use {bundled_passwords, bundled_source};
+
+/// Where the bundled list of common passwords was loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum WordlistSource {
+    /// Read from `src/common-passwords.txt` at runtime, which is the default. See
+    /// `passablewords_data::COMMON_PASSWORDS_PATH` for where it's looked for.
+    File,
+    /// The full list, embedded in the binary with the `embedded-wordlist` feature.
+    Embedded,
+    /// The 100,000 most common passwords, embedded with the `lite` feature.
+    EmbeddedLite,
+    /// An FST built from the list when the crate was compiled, with the `fst` feature.
+    EmbeddedFst,
+    /// The list given in `PASSABLEWORDS_WORDLIST` when the crate was compiled, embedded the same
+    /// way the bundled list would have been.
+    Custom,
+}
+
+impl WordlistSource {
+    /// Where this build of the crate loads the bundled list from.
+    fn current() -> WordlistSource {
+        if passablewords_data::CUSTOM_WORDLIST {
+            WordlistSource::Custom
+        } else if cfg!(feature = "fst") {
+            WordlistSource::EmbeddedFst
+        } else if cfg!(feature = "lite") {
+            WordlistSource::EmbeddedLite
+        } else if cfg!(feature = "embedded-wordlist") {
+            WordlistSource::Embedded
+        } else {
+            WordlistSource::File
+        }
+    }
+}
+
+/// Which build of the bundled list of common passwords is in use. Log it alongside decisions so
+/// that, after an incident, it's clear exactly which list a password was checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct WordlistInfo {
+    /// How many passwords the list holds.
+    pub entries: usize,
+    /// The SHA-256 of the bytes the list was loaded from, in lowercase hex. That's the text of the
+    /// list, or the FST with the `fst` feature.
+    pub sha256: String,
+    /// Where the list was loaded from.
+    pub source: WordlistSource,
+    /// The version of passablewords the list came with.
+    pub version: &'static str,
+}
+
+/// Describe the bundled list of common passwords, or return `None` if it couldn't be read. The
+/// list is hashed the first time this is called, and the answer is reused from then on.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::wordlist_info;
+///
+/// let info = wordlist_info().unwrap();
+///
+/// assert!(info.entries > 0);
+/// assert_eq!(info.sha256.len(), 64);
+/// ```
+pub fn wordlist_info() -> Option<WordlistInfo> {
+    static INFO: OnceLock<Option<WordlistInfo>> = OnceLock::new();
+
+    INFO.get_or_init(|| {
+        let source = bundled_source()?;
+        let passwords = bundled_passwords().ok()?;
+        let mut sha256 = String::with_capacity(64);
+
+        for byte in Sha256::digest(source) {
+            let _ = write!(sha256, "{:02x}", byte);
+        }
+
+        Some(WordlistInfo {
+            entries: passwords.len(),
+            sha256,
+            source: WordlistSource::current(),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    })
+    .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wordlist_info;
+    use wordlist::Wordlist;
+
+    #[test]
+    fn it_describes_the_bundled_list() {
+        let info = wordlist_info().unwrap();
+
+        assert_eq!(info.entries, Wordlist::bundled().unwrap().len());
+        assert!(info
+            .sha256
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || (b'a'..=b'f').contains(&byte)));
+        assert_eq!(info.sha256.len(), 64);
+        assert_eq!(info, wordlist_info().unwrap());
+        #[cfg(not(feature = "embedded-wordlist"))]
+        assert_eq!(info.source, super::WordlistSource::File);
+    }
+}
@@ -0,0 +1,246 @@
+//! Detection of keyboard walks like `qwerty` and `zxcvbn`, using adjacency graphs of keyboard
+//! layouts which can be provided as data.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+#[cfg(feature = "serde")]
+use serde_json;
+
+/// The keys of a keyboard layout and which keys are next to each other. A keyboard walk is a run
+/// of characters where each one is next to the one before it on the same layout, such as `qwerty`,
+/// `asdf`, or `1qaz`. Use `Policy::reject_keyboard_walks` to reject passwords containing them.
+///
+/// QWERTY and Dvorak are built in. Other layouts, like Colemak or Neo2, can be described with
+/// `from_rows` or loaded from JSON with `from_json`, then added to a policy with
+/// `Policy::keyboard_layout`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    name: String,
+    adjacency: HashMap<char, Vec<char>>,
+}
+
+impl KeyboardLayout {
+    /// Create a layout from the characters next to each key. Keys don't have to list each other,
+    /// as two keys are treated as adjacent if either one lists the other.
+    pub fn new<I, S>(name: &str, adjacency: I) -> KeyboardLayout
+    where
+        I: IntoIterator<Item = (char, S)>,
+        S: AsRef<str>,
+    {
+        KeyboardLayout {
+            name: name.to_string(),
+            adjacency: adjacency
+                .into_iter()
+                .map(|(key, neighbours)| (key, neighbours.as_ref().chars().collect()))
+                .collect(),
+        }
+    }
+
+    /// Create a layout from rows of keys, from the top of the keyboard to the bottom, and the same
+    /// rows again with shift held. Each row is offset half a key to the right of the one above,
+    /// as on most keyboards, and can be indented with spaces to line it up. A key is adjacent to
+    /// the keys either side of it, the two above it, and the two below it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::KeyboardLayout;
+    ///
+    /// let colemak = KeyboardLayout::from_rows(
+    ///     "colemak",
+    ///     &["`1234567890-=", " qwfpgjluy;[]\\", " arstdhneio'", " zxcvbkm,./"],
+    ///     &["~!@#$%^&*()_+", " QWFPGJLUY:{}|", " ARSTDHNEIO\"", " ZXCVBKM<>?"],
+    /// );
+    ///
+    /// assert!(colemak.is_walk("arstdhneio", 6));
+    /// ```
+    pub fn from_rows(name: &str, rows: &[&str], shifted_rows: &[&str]) -> KeyboardLayout {
+        let grids: Vec<Vec<Vec<char>>> = [rows, shifted_rows]
+            .iter()
+            .map(|rows| rows.iter().map(|row| row.chars().collect()).collect())
+            .collect();
+        let keys_at = |row: usize, column: usize| {
+            grids
+                .iter()
+                .filter_map(move |grid| grid.get(row).and_then(|keys| keys.get(column)))
+                .cloned()
+                .filter(|&key| key != ' ')
+        };
+        let mut adjacency: HashMap<char, Vec<char>> = HashMap::new();
+
+        for grid in &grids {
+            for (row, keys) in grid.iter().enumerate() {
+                for (column, &key) in keys.iter().enumerate() {
+                    if key == ' ' {
+                        continue;
+                    }
+
+                    // Shifted and unshifted keys share a position, so `qWeR` is still a walk
+                    let mut positions = vec![(row, column + 1), (row + 1, column)];
+
+                    if let Some(left) = column.checked_sub(1) {
+                        positions.extend_from_slice(&[(row, left), (row + 1, left)]);
+                    }
+                    if let Some(up) = row.checked_sub(1) {
+                        positions.extend_from_slice(&[(up, column), (up, column + 1)]);
+                    }
+
+                    let neighbours = adjacency.entry(key).or_default();
+
+                    for (row, column) in positions {
+                        neighbours.extend(keys_at(row, column));
+                    }
+                }
+            }
+        }
+
+        KeyboardLayout {
+            name: name.to_string(),
+            adjacency,
+        }
+    }
+
+    /// Load a layout from JSON, which lets layouts be shipped as data files instead of code. Only
+    /// available with the `serde` feature.
+    ///
+    /// The JSON is an object with the layout's `name` and an `adjacency` object, which maps each
+    /// key to a string of the keys next to it.
+    ///
+    /// ```json
+    /// {
+    ///     "name": "numpad",
+    ///     "adjacency": { "7": "84", "8": "795", "9": "86", "4": "7518" }
+    /// }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<KeyboardLayout, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct LayoutFile {
+            name: String,
+            adjacency: HashMap<char, String>,
+        }
+
+        let file: LayoutFile = serde_json::from_str(json)?;
+
+        Ok(KeyboardLayout::new(&file.name, file.adjacency))
+    }
+
+    /// The US QWERTY layout.
+    pub fn qwerty() -> KeyboardLayout {
+        KeyboardLayout::from_rows(
+            "qwerty",
+            &[
+                "`1234567890-=",
+                " qwertyuiop[]\\",
+                " asdfghjkl;'",
+                " zxcvbnm,./",
+            ],
+            &[
+                "~!@#$%^&*()_+",
+                " QWERTYUIOP{}|",
+                " ASDFGHJKL:\"",
+                " ZXCVBNM<>?",
+            ],
+        )
+    }
+
+    /// The US Dvorak layout.
+    pub fn dvorak() -> KeyboardLayout {
+        KeyboardLayout::from_rows(
+            "dvorak",
+            &[
+                "`1234567890[]",
+                " ',.pyfgcrl/=\\",
+                " aoeuidhtns-",
+                " ;qjkxbmwvz",
+            ],
+            &[
+                "~!@#$%^&*(){}",
+                " \"<>PYFGCRL?+|",
+                " AOEUIDHTNS_",
+                " :QJKXBMWVZ",
+            ],
+        )
+    }
+
+    /// The layout's name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether two keys are next to each other on this layout.
+    pub fn is_adjacent(&self, a: char, b: char) -> bool {
+        let lists = |key: char, other: char| {
+            self.adjacency
+                .get(&key)
+                .is_some_and(|neighbours| neighbours.contains(&other))
+        };
+
+        lists(a, b) || lists(b, a)
+    }
+
+    /// Whether `password` contains a keyboard walk of at least `min_length` characters on this
+    /// layout.
+    pub fn is_walk(&self, password: &str, min_length: usize) -> bool {
+        let mut run = 0;
+        let mut previous: Option<char> = None;
+
+        for character in password.chars() {
+            run = match previous {
+                Some(previous) if self.is_adjacent(previous, character) => run + 1,
+                _ => 1,
+            };
+
+            if run >= min_length.max(2) {
+                return true;
+            }
+
+            previous = Some(character);
+        }
+
+        false
+    }
+}
+
+/// The QWERTY layout, built once and shared by every policy.
+pub(crate) fn qwerty() -> &'static KeyboardLayout {
+    static QWERTY: OnceLock<KeyboardLayout> = OnceLock::new();
+
+    QWERTY.get_or_init(KeyboardLayout::qwerty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KeyboardLayout;
+
+    #[test]
+    fn it_detects_walks() {
+        let qwerty = KeyboardLayout::qwerty();
+
+        assert!(qwerty.is_walk("qwerty", 6));
+        assert!(qwerty.is_walk("xx1qaz2wsx", 4));
+        assert!(qwerty.is_walk("ASDFgh", 6));
+        assert!(qwerty.is_walk("qWeR", 4));
+        assert!(qwerty.is_walk("!@#$", 4));
+        assert!(!qwerty.is_walk("qwe", 4));
+        assert!(!qwerty.is_walk("correct horse", 4));
+        assert!(!qwerty.is_walk("aoeuidhtns", 4));
+        assert!(KeyboardLayout::dvorak().is_walk("aoeuidhtns", 10));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_loads_layouts_from_json() {
+        let numpad = KeyboardLayout::from_json(
+            r#"{ "name": "numpad", "adjacency": { "7": "84", "8": "795", "4": "7518" } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(numpad.name(), "numpad");
+        assert!(numpad.is_adjacent('5', '8'));
+        assert!(numpad.is_walk("7845", 4));
+        assert!(!numpad.is_walk("7895", 4));
+        assert!(KeyboardLayout::from_json(r#"{ "name": "broken" }"#).is_err());
+    }
+}
@@ -0,0 +1,751 @@
+//! The checks behind `passablewords`, without its lists of passwords or its integrations with
+//! other services. Most code should depend on `passablewords` instead, which re-exports all of
+//! this along with the lists and integrations that are enabled.
+//!
+//! The lists live in `passablewords-data`, so that changes here don't recompile the 8MB list of
+//! common passwords when it's embedded.
+
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "argon2")]
+extern crate argon2;
+#[cfg(feature = "bcrypt")]
+extern crate bcrypt;
+#[cfg(feature = "watch")]
+extern crate notify;
+extern crate passablewords_data;
+#[cfg(feature = "generate")]
+extern crate rand;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "fst")]
+extern crate fst;
+#[cfg(feature = "scrypt")]
+extern crate scrypt;
+#[cfg(feature = "secrecy")]
+extern crate secrecy;
+#[cfg(feature = "breach")]
+extern crate sha1;
+extern crate sha2;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(feature = "config")]
+extern crate toml;
+extern crate unicode_segmentation;
+#[cfg(feature = "zeroize")]
+extern crate zeroize;
+#[cfg(feature = "zxcvbn")]
+extern crate zxcvbn;
+
+mod analyzer;
+#[cfg(feature = "tokio")]
+mod async_loading;
+pub mod audit;
+#[cfg(feature = "breach")]
+mod breach;
+mod cancel;
+mod checker;
+mod compat;
+mod composition;
+#[cfg(feature = "config")]
+mod config;
+mod context;
+mod crack_time;
+mod credentials;
+#[cfg(all(unix, feature = "daemon"))]
+mod daemon;
+mod decision;
+mod describe;
+mod encoding;
+mod explain;
+#[cfg(feature = "serde")]
+mod extensions;
+mod feedback;
+mod flow;
+mod frequency;
+mod frontend;
+#[cfg(feature = "generate")]
+mod generate;
+mod hashed;
+mod history;
+#[cfg(feature = "serde")]
+mod idp;
+mod info;
+mod keyboard;
+mod lint;
+mod machine;
+mod normalize;
+mod outcome;
+#[cfg(feature = "regex")]
+mod patterns;
+mod policy;
+pub mod prelude;
+#[cfg(feature = "problem")]
+mod problem;
+mod profile;
+mod quick_entropy;
+mod recovery;
+mod reload;
+#[cfg(feature = "render")]
+mod render;
+mod report;
+mod risk;
+mod rotation;
+mod scrub;
+#[cfg(feature = "secrecy")]
+mod secret;
+mod simulation;
+mod small_string;
+mod temporal;
+mod tenant;
+mod terms;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod variants;
+mod wordlist;
+
+#[cfg(not(feature = "embedded-wordlist"))]
+use std::fs;
+use std::sync::{Arc, OnceLock};
+
+#[cfg(feature = "zxcvbn")]
+use analyzer::check_entropy_analyzed;
+use policy::check_input_size;
+
+pub use analyzer::{Analysis, EntropyAnalyzer};
+#[cfg(feature = "zxcvbn")]
+pub use analyzer::ZxcvbnAnalyzer;
+#[cfg(feature = "tokio")]
+pub use async_loading::{LoadChecker, LoadWordlist};
+#[cfg(feature = "breach")]
+pub use breach::{
+    check_breached, check_breached_with_risk, BreachProvider, CacheStats, CachedBreachProvider,
+    FailMode,
+};
+pub use cancel::{check_entropy_cancellable, CancellationToken};
+pub use checker::PasswordChecker;
+pub use compat::Compat;
+pub use composition::{CharacterClass, Composition, Script};
+#[cfg(feature = "config")]
+pub use config::PolicyConfig;
+pub use context::CheckContext;
+pub use crack_time::HashRate;
+pub use credentials::check_credential_pair;
+#[cfg(all(unix, feature = "daemon"))]
+pub use daemon::{Daemon, DaemonClient};
+pub use decision::{Check, DecisionHook, DecisionRecord, Outcome};
+pub use describe::Locale;
+pub use encoding::{
+    check_encoding, check_password_bytes, check_password_os_str, check_password_utf16,
+    InvalidUtf8,
+};
+pub use explain::{ExplainedCheck, Explanation, Threshold};
+pub use feedback::{Feedback, FeedbackItem};
+pub use flow::{ChangePassword, FlowError, PasswordFlow, SetPassword, DEFAULT_MIN_CHANGES};
+pub use frequency::FrequencySketch;
+pub use frontend::{BlockedPattern, FrontendRules};
+#[cfg(feature = "generate")]
+pub use generate::{generate_recovery_codes, suggest_improvements};
+pub use history::{check_history, PasswordVerifier};
+#[cfg(feature = "argon2")]
+pub use history::Argon2Verifier;
+#[cfg(feature = "bcrypt")]
+pub use history::BcryptVerifier;
+#[cfg(feature = "scrypt")]
+pub use history::ScryptVerifier;
+#[cfg(feature = "serde")]
+pub use idp::{PolicyCheckRequest, PolicyCheckResponse, PolicyViolation};
+pub use info::{wordlist_info, WordlistInfo, WordlistSource};
+pub use keyboard::KeyboardLayout;
+pub use lint::PolicyWarning;
+pub use machine::{check_secret, SecretKind};
+pub use normalize::Normalizer;
+pub use outcome::{CheckOutcome, Failure, Warning};
+pub use policy::{CharsetMode, Policy, PolicyChange, DEFAULT_MAX_INPUT_BYTES};
+#[cfg(feature = "problem")]
+pub use problem::{
+    Problem, ProblemError, PROBLEM_CONTENT_TYPE, REJECTED_PROBLEM_TYPE, UNCHECKED_PROBLEM_TYPE,
+};
+pub use profile::{check_entropy_with, AnalysisProfile, FAST_PROFILE_MAX_LENGTH};
+pub use quick_entropy::{check_quick_entropy, quick_entropy_bits, QUICK_ENTROPY_MIN_BITS};
+pub use recovery::{check_recovery_code, RecoveryCodePolicy};
+pub use reload::ReloadableChecker;
+pub use report::{needs_recheck, PassDetails, StrengthReport};
+pub use risk::RiskLevel;
+pub use rotation::RotationPolicy;
+#[cfg(feature = "zeroize")]
+#[doc(hidden)]
+pub use scrub::set_scrub_hook;
+#[cfg(feature = "secrecy")]
+pub use secret::check_password_secret;
+pub use simulation::{
+    simulate, simulate_diff, RuleRejections, SimulationDiff, SimulationReport, VerdictChange,
+};
+pub use temporal::TemporalTokens;
+pub use tenant::{InMemoryTenantPolicyStore, TenantChecker, TenantPolicyStore};
+pub use terms::{BlockedTerms, OrgProfile};
+pub use wordlist::Wordlist;
+
+#[cfg(not(feature = "embedded-wordlist"))]
+static FILE_CONTENTS: OnceLock<Result<String, PasswordError>> = OnceLock::new();
+static PASSWORDS: OnceLock<Result<Arc<Wordlist>, PasswordError>> = OnceLock::new();
+
+/// The lowest zxcvbn score, out of 4, that passes the entropy check.
+const MIN_ENTROPY_SCORE: u8 = 3;
+
+/// The contents of the bundled list of common passwords, read the first time it's needed. It's
+/// looked for at `src/common-passwords.txt`, relative to the working directory, and then where
+/// `passablewords-data` was built from. If neither could be read, the error says why the second
+/// couldn't.
+#[cfg(not(feature = "embedded-wordlist"))]
+fn bundled_file_contents() -> Result<&'static str, &'static PasswordError> {
+    FILE_CONTENTS
+        .get_or_init(|| {
+            fs::read_to_string("src/common-passwords.txt")
+                .or_else(|_| fs::read_to_string(passablewords_data::COMMON_PASSWORDS_PATH))
+                .map_err(|err| PasswordError::WordlistUnavailable {
+                    path: Some(passablewords_data::COMMON_PASSWORDS_PATH.to_string()),
+                    source: format!(
+                        "{}. Run from a directory containing src/common-passwords.txt, give the \
+                         checker a wordlist of its own, or turn on the embedded-wordlist feature",
+                        err
+                    ),
+                })
+        })
+        .as_ref()
+        .map(String::as_str)
+}
+
+/// The contents of the bundled list of common passwords, embedded in the binary so that it works
+/// without a filesystem, such as in WASI runtimes without a preopened directory.
+#[cfg(all(feature = "embedded-wordlist", not(feature = "fst")))]
+fn bundled_file_contents() -> Result<&'static str, &'static PasswordError> {
+    Ok(passablewords_data::COMMON_PASSWORDS)
+}
+
+/// The bundled list of common passwords, read the first time it's needed, or why it couldn't be
+/// read.
+#[cfg(not(feature = "fst"))]
+fn bundled_passwords() -> Result<&'static Arc<Wordlist>, &'static PasswordError> {
+    PASSWORDS
+        .get_or_init(|| {
+            bundled_file_contents()
+                .map(|file_contents| Arc::new(file_contents.lines().collect()))
+                .map_err(PasswordError::clone)
+        })
+        .as_ref()
+}
+
+/// The bundled list of common passwords, embedded as an FST that `passablewords-data` builds when
+/// the `fst` feature is enabled, or why the FST is somehow invalid.
+#[cfg(feature = "fst")]
+fn bundled_passwords() -> Result<&'static Arc<Wordlist>, &'static PasswordError> {
+    PASSWORDS
+        .get_or_init(|| {
+            Wordlist::from_fst_bytes(passablewords_data::COMMON_PASSWORDS_FST)
+                .map(Arc::new)
+                .map_err(|err| PasswordError::WordlistUnavailable {
+                    path: None,
+                    source: format!(
+                        "the embedded FST is invalid: {}. Rebuild passablewords-data with the \
+                         fst feature",
+                        err
+                    ),
+                })
+        })
+        .as_ref()
+}
+
+/// Why the bundled list couldn't be read, for checkers that were meant to use it but have no
+/// list to check against.
+fn bundled_unavailable() -> PasswordError {
+    match bundled_passwords() {
+        Err(err) => err.clone(),
+        Ok(_) => PasswordError::InternalError,
+    }
+}
+
+/// The bytes the bundled list is loaded from, or `None` if they couldn't be read.
+#[cfg(not(feature = "fst"))]
+fn bundled_source() -> Option<&'static [u8]> {
+    bundled_file_contents().ok().map(str::as_bytes)
+}
+
+/// The bytes the bundled list is loaded from, which is the FST.
+#[cfg(feature = "fst")]
+fn bundled_source() -> Option<&'static [u8]> {
+    Some(passablewords_data::COMMON_PASSWORDS_FST)
+}
+
+/// The suite of possible errors returned from passablewords. These represent the three checks made
+/// for length, uniqueness, and entropy. If something goes wrong during the request, an
+/// `InternalError` error is returned.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PasswordError {
+    /// The password is less than 8 characters, or shorter than the policy allows, and is
+    /// therefore too short.
+    TooShort,
+    /// The password is made up of fewer different characters than the policy requires, such as
+    /// `aaaaaaab`.
+    TooFewUniqueChars,
+    /// The password is made of only digits, or only letters, and the policy rejects those since
+    /// they can be brute-forced over a much smaller set of characters.
+    InsufficientVariety,
+    /// The password is within the list of 1,000,000 most common passwords and should not be used.
+    TooCommon,
+    /// The password is within one of the extra wordlists given to
+    /// `PasswordChecker::with_labelled_wordlist`, such as passwords from a past breach. The
+    /// list's label is included.
+    TooCommonIn(String),
+    /// The password is within the list of common passwords once common prefixes and suffixes,
+    /// like years or a trailing symbol, are removed.
+    TooCommonVariant,
+    /// Enough of the organization's own users have already chosen the password, according to a
+    /// `FrequencySketch`, that it's become one of its common passwords.
+    TooCommonLocally,
+    /// The password has been seen in a data breach, according to a `BreachProvider`.
+    Breached,
+    /// The entropy of the password is too low, which means it could be easily guessable/crackable.
+    /// A more random password should be used instead.
+    TooSimple,
+    /// The password isn't using ascii characters, a requirement that zxcvbn has
+    NonAsciiPassword,
+    /// The password contains a run of keys next to each other on the keyboard, like `qwerty`,
+    /// which is as long as the policy forbids.
+    KeyboardWalk,
+    /// The password is built around the date, like `Winter2025` or `Q3report!`, which the
+    /// policy forbids.
+    TemporalPattern,
+    /// The password matches one of the policy's banned patterns. The name of the rule is included.
+    BannedPattern(String),
+    /// The password is equal to one of the values denied for this particular check, such as a
+    /// recovery code or a temporary password.
+    MatchesDeniedValue,
+    /// The password is one of the account's previous passwords, according to `check_history`.
+    MatchesPreviousPassword,
+    /// The password is the account's username or email address, backwards, or with a few digits
+    /// or a symbol added, according to `check_credential_pair`.
+    MatchesUsername,
+    /// The password is a single dictionary word, in any case or with a few substitutions, without
+    /// the digits or symbols around it that the policy requires. However long the word is, it's
+    /// only one lookup away.
+    SingleDictionaryWord,
+    /// The code doesn't follow the required format, such as a recovery code that's too long or
+    /// has characters outside of its alphabet, according to `check_recovery_code`.
+    InvalidFormat,
+    /// The password was given as bytes that aren't valid UTF-8, as UTF-16 with lone surrogates,
+    /// or with embedded NUL characters, and the caller asked for it to be rejected rather than
+    /// repaired. `check_encoding` also reports passwords damaged by a lossy conversion this way.
+    InvalidEncoding,
+    /// The password is longer than the policy's `input_limit`, which is
+    /// `DEFAULT_MAX_INPUT_BYTES` unless it's been changed, so it was rejected before any check
+    /// looked at it.
+    InputTooLarge,
+    /// The check was cancelled with a `CancellationToken` before it finished, so there's no
+    /// answer either way.
+    Cancelled,
+    /// The list of common passwords couldn't be loaded, so the password couldn't be checked
+    /// against it. This is a problem with the deployment rather than the password, so log it
+    /// and alert on it.
+    WordlistUnavailable {
+        /// The file that couldn't be read, or `None` if the list was embedded in the binary.
+        path: Option<String>,
+        /// What went wrong, and how to fix it, like which feature flag to turn on.
+        source: String,
+    },
+    /// Something went wrong during the password checks and a normal error couldn't be returned.
+    InternalError,
+}
+
+impl PasswordError {
+    /// A short, user-facing explanation of why the password was rejected, for applications that
+    /// don't have their own copy for each error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{check_password, PasswordError};
+    ///
+    /// let err = check_password("short").unwrap_err();
+    ///
+    /// assert_eq!(err, PasswordError::TooShort);
+    /// assert_eq!(err.explanation(), "Your password is too short.");
+    /// ```
+    pub fn explanation(&self) -> &'static str {
+        match *self {
+            PasswordError::TooShort => "Your password is too short.",
+            PasswordError::TooFewUniqueChars => {
+                "Your password repeats the same few characters too many times."
+            }
+            PasswordError::InsufficientVariety => {
+                "Your password can't be made of only numbers or only letters."
+            }
+            PasswordError::TooCommon => "Your password is one of the most commonly used passwords.",
+            PasswordError::TooCommonIn(_) => "Your password is known to have been leaked.",
+            PasswordError::TooCommonVariant => {
+                "Your password is a commonly used password with a few characters added."
+            }
+            PasswordError::TooCommonLocally => {
+                "Your password is one that a lot of other people here already use."
+            }
+            PasswordError::Breached => "Your password has appeared in a data breach.",
+            PasswordError::TooSimple => "Your password would be too easy to guess.",
+            PasswordError::NonAsciiPassword => "Your password can only contain ASCII characters.",
+            PasswordError::KeyboardWalk => "Your password contains a run of neighbouring keys.",
+            PasswordError::TemporalPattern => "Your password is based on the date.",
+            PasswordError::BannedPattern(_) => "Your password contains a term that isn't allowed.",
+            PasswordError::MatchesDeniedValue => "Your password can't be a code we sent you.",
+            PasswordError::MatchesPreviousPassword => {
+                "Your password can't be one you've used before."
+            }
+            PasswordError::MatchesUsername => "Your password can't be your username.",
+            PasswordError::SingleDictionaryWord => {
+                "Your password can't be a single dictionary word."
+            }
+            PasswordError::InvalidFormat => "That code isn't in the right format.",
+            PasswordError::InvalidEncoding => "Your password contains characters we can't read.",
+            PasswordError::InputTooLarge => "Your password is too long.",
+            PasswordError::Cancelled => "Checking your password was cancelled.",
+            PasswordError::WordlistUnavailable { .. } | PasswordError::InternalError => {
+                "We couldn't check your password. Please try again."
+            }
+        }
+    }
+
+    /// The name of the error in the SCREAMING_SNAKE_CASE that GraphQL error codes use, which
+    /// problem details use too.
+    #[cfg(any(feature = "serde", feature = "problem"))]
+    pub(crate) fn code(&self) -> &'static str {
+        match *self {
+            PasswordError::TooShort => "TOO_SHORT",
+            PasswordError::TooFewUniqueChars => "TOO_FEW_UNIQUE_CHARS",
+            PasswordError::InsufficientVariety => "INSUFFICIENT_VARIETY",
+            PasswordError::TooCommon => "TOO_COMMON",
+            PasswordError::TooCommonIn(_) => "TOO_COMMON_IN",
+            PasswordError::TooCommonVariant => "TOO_COMMON_VARIANT",
+            PasswordError::TooCommonLocally => "TOO_COMMON_LOCALLY",
+            PasswordError::Breached => "BREACHED",
+            PasswordError::TooSimple => "TOO_SIMPLE",
+            PasswordError::NonAsciiPassword => "NON_ASCII_PASSWORD",
+            PasswordError::KeyboardWalk => "KEYBOARD_WALK",
+            PasswordError::TemporalPattern => "TEMPORAL_PATTERN",
+            PasswordError::BannedPattern(_) => "BANNED_PATTERN",
+            PasswordError::MatchesDeniedValue => "MATCHES_DENIED_VALUE",
+            PasswordError::MatchesPreviousPassword => "MATCHES_PREVIOUS_PASSWORD",
+            PasswordError::MatchesUsername => "MATCHES_USERNAME",
+            PasswordError::SingleDictionaryWord => "SINGLE_DICTIONARY_WORD",
+            PasswordError::InvalidFormat => "INVALID_FORMAT",
+            PasswordError::InvalidEncoding => "INVALID_ENCODING",
+            PasswordError::InputTooLarge => "INPUT_TOO_LARGE",
+            PasswordError::Cancelled => "CANCELLED",
+            PasswordError::WordlistUnavailable { .. } => "WORDLIST_UNAVAILABLE",
+            PasswordError::InternalError => "INTERNAL_ERROR",
+        }
+    }
+
+    /// A longer explanation with concrete suggestions for picking a better password.
+    pub fn advice(&self) -> &'static str {
+        match *self {
+            PasswordError::TooShort => {
+                "Longer passwords are much harder to crack. Try stringing together a few \
+                 unrelated words to make it longer."
+            }
+            PasswordError::TooFewUniqueChars => {
+                "Passwords made up of one character repeated over and over, with maybe one or two \
+                 others mixed in, are some of the first that attackers try. Use a wider mix of \
+                 letters, numbers, and symbols, or a few random words."
+            }
+            PasswordError::InsufficientVariety => {
+                "A password made of only numbers has just ten possibilities for each character, \
+                 so even a long one can be guessed quickly. Mix in some letters and symbols, or \
+                 some numbers and symbols if it's only letters."
+            }
+            PasswordError::TooCommon => {
+                "Attackers try the most common passwords first, so this one would be guessed \
+                 almost immediately. Avoid well-known passwords, and don't make small tweaks to \
+                 them either. Try a few random words instead."
+            }
+            PasswordError::TooCommonIn(_) => {
+                "This password has turned up in a leak, so attackers may already have it. Don't \
+                 reuse it, or small tweaks to it, anywhere. Try a few random words instead."
+            }
+            PasswordError::TooCommonVariant => {
+                "Adding a year, a few numbers, or a symbol to the start or end of a common \
+                 password is one of the first things attackers try. Instead of adjusting a \
+                 common password, try a few random words."
+            }
+            PasswordError::TooCommonLocally => {
+                "So many people here have picked this password that it's likely to be one of the \
+                 first guesses of anyone targeting us. Try a few random words instead."
+            }
+            PasswordError::Breached => {
+                "This password was exposed in a data breach, which means it's on the lists \
+                 attackers use, even if nobody knows it goes with your account. Pick a password \
+                 you've never used anywhere before."
+            }
+            PasswordError::TooSimple => {
+                "Your password follows a predictable pattern, like a dictionary word, a date, a \
+                 keyboard row, or repeated characters. Add a few more uncommon words, or mix in \
+                 numbers and symbols somewhere other than the start or end."
+            }
+            PasswordError::NonAsciiPassword => {
+                "Accented letters, emoji, and other characters outside of ASCII can't be checked \
+                 yet. Try using only unaccented letters, numbers, and common symbols."
+            }
+            PasswordError::KeyboardWalk => {
+                "Runs of keys next to each other, like qwerty or 1qaz2wsx, are easy to type but \
+                 are among the first patterns attackers try. Try a few random words instead."
+            }
+            PasswordError::TemporalPattern => {
+                "Seasons, months, and years are what most people reach for when they have to \
+                 change their password, so attackers guess them first. Try a few random words \
+                 that have nothing to do with when you picked them."
+            }
+            PasswordError::BannedPattern(_) => {
+                "Your password contains a name or term that attackers targeting us would guess \
+                 early on, like our company or product names. Try a password that isn't related \
+                 to us at all."
+            }
+            PasswordError::MatchesDeniedValue => {
+                "Your new password can't be the same as a temporary password or recovery code \
+                 we gave you, since those may have been seen by someone else. Pick something new \
+                 that only you know."
+            }
+            PasswordError::MatchesPreviousPassword => {
+                "Going back to an old password undoes the point of changing it, since anyone who \
+                 learned the old one could get in again. Pick something you haven't used here \
+                 before."
+            }
+            PasswordError::MatchesUsername => {
+                "Your username or email address is the first thing anyone trying to get into \
+                 your account will guess, however it's spelled. Pick something that has nothing \
+                 to do with it."
+            }
+            PasswordError::SingleDictionaryWord => {
+                "Even a long word is only one guess for an attacker working through a dictionary, \
+                 however it's capitalized or spelled with numbers and symbols. Add another \
+                 unrelated word or two, or some numbers and symbols."
+            }
+            PasswordError::InvalidFormat => {
+                "Check that you've copied the whole code exactly as it was given to you. Spaces \
+                 and dashes don't matter."
+            }
+            PasswordError::InvalidEncoding => {
+                "Your password contains invalid or corrupted characters. Try typing it again, or \
+                 use only letters, numbers, and common symbols."
+            }
+            PasswordError::InputTooLarge => {
+                "Your password is far longer than it needs to be. Check that you've pasted only \
+                 the password, or pick a shorter passphrase of a few random words."
+            }
+            PasswordError::Cancelled => {
+                "We stopped checking your password before we finished, usually because it \
+                 changed in the meantime. Check the latest version of it instead."
+            }
+            PasswordError::WordlistUnavailable { .. } | PasswordError::InternalError => {
+                "Something went wrong on our end while checking your password. It may be fine, \
+                 so please try submitting it again."
+            }
+        }
+    }
+}
+
+/// The result type that will be returned from all public functions. It's simply a `Result` type
+/// that either returns `Ok` or a `PasswordError`.
+pub type PassablewordResult = Result<(), PasswordError>;
+
+/// Check a password to make sure it's at least 8 characters long. While this shouldn't be used as
+/// the only password check, it's a good baseline to start from. The length is counted in bytes of
+/// UTF-8, so a character outside ASCII counts as more than one. `Policy::check_length` counts
+/// characters instead.
+///
+/// # Example (using rocket.rs)
+///
+/// ```
+/// match check_length(password) {
+///     Ok() => status::Ok
+///     Err(err) => match(err) {
+///         PassablewordResult::TooShort => status::BadRequest("Your password should be longer than 8 characters")
+///     }
+/// }
+/// ```
+pub fn check_length(password: &str) -> PassablewordResult {
+    if password.len() >= 8 {
+        Ok(())
+    } else {
+        Err(PasswordError::TooShort)
+    }
+}
+
+/// Check a password to make sure it's not within the top million most common passwords. See the
+/// `Wordlist` documentation for how much this can leak through timing.
+///
+/// # Example (using rocket.rs)
+///
+/// ```
+/// match check_uniqueness(password) {
+///     Ok() => status::Ok
+///     Err(err) => match(err) {
+///         PassablewordResult::TooCommon => status::BadRequest("Your should be more unique")
+///     }
+/// }
+/// ```
+pub fn check_uniqueness(password: &str) -> PassablewordResult {
+    match bundled_passwords() {
+        Ok(passwords) if passwords.contains(password) => Err(PasswordError::TooCommon),
+        Ok(_) => Ok(()),
+        Err(err) => Err(err.clone()),
+    }
+}
+
+/// Check a password to make sure random enough that it would take a lot of effort to crack/guess.
+/// This uses the awesome zxcvbn library behind the scenes. If the `zxcvbn` feature is turned off,
+/// `check_quick_entropy` is used instead.
+///
+/// # Example (using rocket.rs)
+///
+/// ```
+/// match check_entropy(password) {
+///     Ok() => status::Ok
+///     Err(err) => match(err) {
+///         PassablewordResult::TooSimple => status::BadRequest("Your should be more random")
+///     }
+/// }
+/// ```
+#[cfg(feature = "zxcvbn")]
+pub fn check_entropy(password: &str) -> PassablewordResult {
+    check_input_size(password, DEFAULT_MAX_INPUT_BYTES)?;
+    check_entropy_analyzed(password, &[], Compat::Latest, 0, None)
+}
+
+/// Check a password to make sure it's random enough, using `check_quick_entropy` since zxcvbn was
+/// left out of this build.
+#[cfg(not(feature = "zxcvbn"))]
+pub fn check_entropy(password: &str) -> PassablewordResult {
+    check_input_size(password, DEFAULT_MAX_INPUT_BYTES)?;
+    check_quick_entropy(password)
+}
+
+/// Check a password's length, uniqueness, and entropy all in a single call. This is a convenience
+/// method and simply calls `check_length`, `check_uniqueness`, and `check_entropy` with the
+/// password supplied.
+///
+/// # Example (using rocket.rs)
+///
+/// ```
+/// match check_password(password) {
+///     Ok() => status::Ok
+///     Err(err) => match(err) {
+///         PassablewordResult::TooShort => status::BadRequest("Your password should be longer than 8 characters"),
+///         PassablewordResult::TooCommon => status::BadRequest("Your should be more unique"),
+///         PassablewordResult::TooSimple => status::BadRequest("Your should be more random"),
+///         PassablewordResult::InternalError => status::InternalServerError
+///     }
+/// }
+/// ```
+pub fn check_password(password: &str) -> PassablewordResult {
+    check_input_size(password, DEFAULT_MAX_INPUT_BYTES)?;
+    check_length(password)
+        .and(check_uniqueness(password))
+        .and(check_entropy(password))
+}
+
+/// Check a password like `check_password`, and against the stricter thresholds for `risk` too.
+/// See `RiskLevel` for what each level asks for.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_password_with_risk, PasswordError, RiskLevel};
+///
+/// assert_eq!(
+///     check_password_with_risk("Qd7#vL2p!x", RiskLevel::High),
+///     Err(PasswordError::TooShort)
+/// );
+/// ```
+pub fn check_password_with_risk(password: &str, risk: RiskLevel) -> PassablewordResult {
+    PasswordChecker::new().check_password_with_risk(password, risk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_entropy, check_length, check_password, check_uniqueness, PasswordError};
+
+    #[test]
+    fn it_validates_length() {
+        let too_short = check_length("short");
+        let long_enough = check_length("this is a long password");
+
+        assert_eq!(too_short, Err(PasswordError::TooShort));
+        assert_eq!(long_enough, Ok(()));
+    }
+
+    #[test]
+    fn it_validates_uniqueness() {
+        let too_common = check_uniqueness("password");
+        let unique_enough = check_uniqueness("this is a unique password");
+
+        assert_eq!(too_common, Err(PasswordError::TooCommon));
+        assert_eq!(unique_enough, Ok(()));
+    }
+
+    #[cfg(feature = "zxcvbn")]
+    #[test]
+    fn it_validates_entropy() {
+        let too_simple = check_entropy("NotTooRandom");
+        let random_enough = check_entropy("Th1s iS a Sup3rR4ndom PassW0rd!");
+
+        assert_eq!(too_simple, Err(PasswordError::TooSimple));
+        assert_eq!(random_enough, Ok(()));
+    }
+
+    #[test]
+    fn it_does_not_panic_on_unusual_input() {
+        let huge = "ü".repeat(1_000_000);
+        let unusual = [
+            "",
+            "\0\0\0\0\0\0\0\0",
+            "\u{feff}\u{200d}\u{202e}\u{fffd}\u{10ffff}",
+            "e\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}\u{301}",
+            "👩‍👩‍👧‍👦👩‍👩‍👧‍👦",
+            &huge,
+        ];
+
+        for password in unusual.iter() {
+            let _ = check_password(password);
+            let _ = check_entropy(password);
+        }
+    }
+
+    #[test]
+    fn it_explains_errors() {
+        assert_eq!(
+            PasswordError::TooCommon.explanation(),
+            "Your password is one of the most commonly used passwords."
+        );
+        assert!(PasswordError::TooSimple
+            .advice()
+            .starts_with("Your password follows"));
+    }
+
+    #[test]
+    fn it_validates_a_password() {
+        let too_short = check_password("short");
+        let too_common = check_password("password");
+        #[cfg(feature = "zxcvbn")]
+        let too_simple = check_password("NotTooRandom");
+        let ok_password = check_password("Th1s iS a Sup3rR4ndom PassW0rd!");
+
+        assert_eq!(too_short, Err(PasswordError::TooShort));
+        assert_eq!(too_common, Err(PasswordError::TooCommon));
+        #[cfg(feature = "zxcvbn")]
+        assert_eq!(too_simple, Err(PasswordError::TooSimple));
+        assert_eq!(ok_password, Ok(()));
+    }
+}
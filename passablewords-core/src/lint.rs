@@ -0,0 +1,81 @@
+//! Warnings about policies that are weaker than recommended, so that mistakes in a custom policy
+//! are caught when it's configured rather than after weak passwords have been accepted.
+
+use std::time::Duration;
+
+use decision::Check;
+
+/// The shortest minimum length that doesn't produce a warning, following NIST SP 800-63B.
+pub(crate) const RECOMMENDED_MIN_LENGTH: usize = 8;
+
+/// The fewest words that doesn't produce a warning when passphrases are accepted in place of
+/// length.
+pub(crate) const RECOMMENDED_MIN_WORDS: usize = 3;
+
+/// The smallest input limit that doesn't produce a warning, which leaves room for a 64 character
+/// passphrase even if every character takes 4 bytes.
+pub(crate) const RECOMMENDED_MIN_INPUT_BYTES: usize = 256;
+
+/// The checks that stop the weakest passwords, which produce a warning when they only warn.
+pub(crate) const ESSENTIAL_CHECKS: [Check; 3] = [Check::Length, Check::Uniqueness, Check::Entropy];
+
+/// The shortest rotation period that doesn't produce a warning.
+pub(crate) const RECOMMENDED_MIN_ROTATION: Duration = Duration::from_secs(90 * 24 * 60 * 60);
+
+/// Something about a policy that makes it weaker than recommended, returned by `Policy::lint`
+/// and `RotationPolicy::lint`. None of these stop the policy from working.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{Policy, PolicyWarning};
+///
+/// let warnings = Policy::new().min_length(6).lint();
+///
+/// assert_eq!(warnings, vec![PolicyWarning::ShortMinLength(6)]);
+/// println!("{}", warnings[0].message());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyWarning {
+    /// The minimum length, which is included, is less than 8 characters.
+    ShortMinLength(usize),
+    /// Passphrases with fewer than 3 words, with the number included, are accepted in place of
+    /// the minimum length.
+    FewMinWords(usize),
+    /// A check that stops the weakest passwords, which is included, only produces a warning. This
+    /// is the length, uniqueness, or entropy check.
+    WarnOnly(Check),
+    /// The most bytes a password can have, which is included, is less than 256, so long
+    /// passphrases are rejected.
+    SmallInputLimit(usize),
+    /// Passwords expire more often than every 90 days. The rotation period is included.
+    ShortRotation(Duration),
+}
+
+impl PolicyWarning {
+    /// A short description of the problem and how to fix it, for logging when a policy is loaded.
+    pub fn message(&self) -> &'static str {
+        match *self {
+            PolicyWarning::ShortMinLength(_) => {
+                "Passwords shorter than 8 characters can be cracked quickly. Require at least 8, \
+                 or more for accounts that matter."
+            }
+            PolicyWarning::FewMinWords(_) => {
+                "One or two words are easy to guess from a dictionary. Require at least 3 words \
+                 if passphrases are accepted in place of length."
+            }
+            PolicyWarning::WarnOnly(_) => {
+                "Passwords that are too short, too common, or too easy to guess are accepted with \
+                 only a warning. Only relax these checks while rolling out a stricter policy."
+            }
+            PolicyWarning::SmallInputLimit(_) => {
+                "A small input limit rejects long passphrases, which are among the strongest \
+                 passwords. Allow at least 256 bytes."
+            }
+            PolicyWarning::ShortRotation(_) => {
+                "Changing passwords often leads users to pick predictable variations of their old \
+                 ones. Avoid forced rotation, or rotate no more often than every 90 days."
+            }
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Undoing the character substitutions people make to dress up common passwords, like `p@ssw0rd`
+//! for `password`.
+
+#[cfg(feature = "simd")]
+use std::convert::TryFrom;
+
+use scrub::Scrubbed;
+
+/// The substitutions a `Normalizer` undoes by default. Digits and symbols that stand in for more
+/// than one letter, like `1` for `i` or `l`, are mapped to the one that's used most often.
+const DEFAULT_SUBSTITUTIONS: [(&str, char); 17] = [
+    ("4", 'a'),
+    ("@", 'a'),
+    ("8", 'b'),
+    ("(", 'c'),
+    ("3", 'e'),
+    ("6", 'g'),
+    ("9", 'g'),
+    ("#", 'h'),
+    ("1", 'i'),
+    ("!", 'i'),
+    ("|", 'l'),
+    ("0", 'o'),
+    ("$", 's'),
+    ("5", 's'),
+    ("7", 't'),
+    ("+", 't'),
+    ("2", 'z'),
+];
+
+/// Turns a password back into the word it was probably based on, by lowercasing it and undoing
+/// common substitutions, so that it can be looked up in a wordlist. Use it with
+/// `Policy::with_normalizer`, or on its own with `PasswordChecker::check_uniqueness_any`.
+///
+/// The default table covers the usual l33t substitutions, like `@` for `a` and `0` for `o`.
+/// Attackers tune their rules to the people they're targeting, so organizations can add their
+/// own with `with_substitutions`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::Normalizer;
+///
+/// let normalizer = Normalizer::new().with_substitutions(vec![("()", 'o'), ("\\/", 'v')]);
+///
+/// assert_eq!(normalizer.normalize("P@$$W0RD"), "password");
+/// assert_eq!(normalizer.normalize("L()\\/E"), "love");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normalizer {
+    // Longest first, so that `()` is matched before `(`
+    substitutions: Vec<(String, char)>,
+    // Rebuilt whenever the substitutions change
+    ascii: Option<AsciiTable>,
+}
+
+/// What every ASCII character normalizes to, for normalizers whose substitutions are all a single
+/// ASCII character standing in for another. ASCII passwords are then normalized a byte at a time
+/// with a lookup each, rather than trying every substitution at every character.
+#[derive(Debug, Clone, PartialEq)]
+struct AsciiTable {
+    bytes: [u8; 128],
+    // Whether letters are only ever lowercased, so runs of them can be lowercased in bulk
+    #[cfg_attr(not(feature = "simd"), allow(dead_code))]
+    letters_lowercased: bool,
+}
+
+impl AsciiTable {
+    fn new(substitutions: &[(String, char)]) -> Option<AsciiTable> {
+        let mut replacements = [None; 128];
+
+        for &(ref from, to) in substitutions {
+            if from.len() != 1 || !from.is_ascii() || !to.is_ascii() {
+                return None;
+            }
+
+            replacements[from.as_bytes()[0] as usize] = Some(to as u8);
+        }
+
+        let mut bytes = [0; 128];
+        let mut letters_lowercased = true;
+
+        for (byte, normalized) in bytes.iter_mut().enumerate() {
+            let lowercase = (byte as u8).to_ascii_lowercase();
+
+            *normalized = replacements[lowercase as usize].unwrap_or(lowercase);
+
+            if lowercase.is_ascii_alphabetic() && *normalized != lowercase {
+                letters_lowercased = false;
+            }
+        }
+
+        Some(AsciiTable {
+            bytes,
+            letters_lowercased,
+        })
+    }
+
+    /// Normalize a password that's entirely ASCII.
+    #[cfg(not(feature = "simd"))]
+    fn normalize(&self, password: &[u8]) -> String {
+        password
+            .iter()
+            .map(|&byte| self.bytes[byte as usize] as char)
+            .collect()
+    }
+
+    /// Normalize a password that's entirely ASCII, eight bytes at a time where they're all
+    /// letters that only need lowercasing, which is most of a typical password.
+    #[cfg(feature = "simd")]
+    fn normalize(&self, password: &[u8]) -> String {
+        let mut normalized = Vec::with_capacity(password.len());
+        let mut chunks = password.chunks_exact(8);
+
+        for chunk in &mut chunks {
+            let word = u64::from_le_bytes(<[u8; 8]>::try_from(chunk).unwrap_or_default());
+
+            if self.letters_lowercased && all_letters(word) {
+                normalized.extend_from_slice(&(word | (LANES * 0x20)).to_le_bytes());
+            } else {
+                normalized.extend(chunk.iter().map(|&byte| self.bytes[byte as usize]));
+            }
+        }
+
+        normalized.extend(
+            chunks
+                .remainder()
+                .iter()
+                .map(|&byte| self.bytes[byte as usize]),
+        );
+
+        String::from_utf8(normalized).expect("only ASCII is written")
+    }
+}
+
+/// A 1 in each of the eight bytes of a `u64`, for working on all of them at once.
+#[cfg(feature = "simd")]
+const LANES: u64 = 0x0101_0101_0101_0101;
+
+/// Whether all eight bytes of `word`, which are all ASCII, are letters. Setting the case bit
+/// folds uppercase letters onto lowercase ones, and then adding to every byte sets its top bit
+/// where it's at least `a` but not where it's at most `z`. ASCII bytes never carry into the next
+/// byte along.
+#[cfg(feature = "simd")]
+fn all_letters(word: u64) -> bool {
+    let lowercase = word | (LANES * 0x20);
+    let at_least_a = lowercase + LANES * (0x80 - u64::from(b'a'));
+    let at_most_z = !(lowercase + LANES * (0x80 - u64::from(b'z') - 1));
+
+    at_least_a & at_most_z & (LANES * 0x80) == LANES * 0x80
+}
+
+impl Normalizer {
+    /// Create a normalizer with the default substitutions.
+    pub fn new() -> Normalizer {
+        Normalizer::empty().with_substitutions(DEFAULT_SUBSTITUTIONS.iter().cloned())
+    }
+
+    /// Create a normalizer without any substitutions, which only lowercases passwords.
+    pub fn empty() -> Normalizer {
+        Normalizer {
+            substitutions: Vec::new(),
+            ascii: AsciiTable::new(&[]),
+        }
+    }
+
+    /// Also replace each string in `substitutions` with the letter it stands for. A string that's
+    /// already in the table is given the new letter, and empty strings are ignored. Where
+    /// substitutions overlap, the longest one that matches is used. Passwords are lowercased
+    /// before any substitutions are made, so strings containing uppercase letters never match.
+    pub fn with_substitutions<I, S>(mut self, substitutions: I) -> Normalizer
+    where
+        I: IntoIterator<Item = (S, char)>,
+        S: Into<String>,
+    {
+        for (from, to) in substitutions {
+            let from = from.into();
+
+            if from.is_empty() {
+                continue;
+            }
+
+            match self.substitutions.iter_mut().find(|entry| entry.0 == from) {
+                Some(entry) => entry.1 = to,
+                None => self.substitutions.push((from, to)),
+            }
+        }
+
+        self.substitutions
+            .sort_by(|a, b| b.0.len().cmp(&a.0.len()).then_with(|| a.0.cmp(&b.0)));
+        self.ascii = AsciiTable::new(&self.substitutions);
+        self
+    }
+
+    /// The substitutions this normalizer makes, as the strings that are replaced and the letters
+    /// they're replaced with.
+    pub fn substitutions(&self) -> &[(String, char)] {
+        &self.substitutions
+    }
+
+    /// Lowercase `password` and undo every substitution in it, working from left to right.
+    ///
+    /// ASCII passwords are normalized with a lookup table when every substitution is a single
+    /// ASCII character, like the default ones are. With the `simd` feature, runs of eight letters
+    /// are lowercased at once.
+    pub fn normalize(&self, password: &str) -> String {
+        match self.ascii {
+            Some(ref table) if password.is_ascii() => table.normalize(password.as_bytes()),
+            _ => self.normalize_chars(password),
+        }
+    }
+
+    /// Normalize `password` one character at a time, trying every substitution at each one.
+    fn normalize_chars(&self, password: &str) -> String {
+        let lowercase = Scrubbed::from(password.to_lowercase());
+        let mut normalized = String::with_capacity(lowercase.len());
+        let mut rest = &*lowercase;
+
+        'outer: while let Some(next) = rest.chars().next() {
+            for &(ref from, to) in &self.substitutions {
+                if rest.starts_with(from.as_str()) {
+                    normalized.push(to);
+                    rest = &rest[from.len()..];
+                    continue 'outer;
+                }
+            }
+
+            normalized.push(next);
+            rest = &rest[next.len_utf8()..];
+        }
+
+        normalized
+    }
+}
+
+impl Default for Normalizer {
+    fn default() -> Normalizer {
+        Normalizer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Normalizer;
+
+    #[test]
+    fn it_normalizes_passwords() {
+        let normalizer = Normalizer::new();
+
+        assert_eq!(normalizer.normalize("P4$$w0rd"), "password");
+        assert_eq!(normalizer.normalize("dr@60n"), "dragon");
+        assert_eq!(normalizer.normalize("Émile"), "émile");
+        assert_eq!(Normalizer::empty().normalize("P4$$w0rd"), "p4$$w0rd");
+    }
+
+    #[test]
+    fn it_extends_substitutions() {
+        let normalizer = Normalizer::new().with_substitutions(vec![
+            ("()", 'o'),
+            ("|<", 'k'),
+            ("1", 'l'),
+            ("", 'x'),
+        ]);
+
+        assert_eq!(normalizer.normalize("m()n|<ey"), "monkey");
+        assert_eq!(normalizer.normalize("1ove(at"), "lovecat");
+        assert_eq!(normalizer.substitutions()[0].0, "()");
+        assert_eq!(
+            normalizer.substitutions().len(),
+            Normalizer::new().substitutions().len() + 2
+        );
+    }
+
+    #[test]
+    fn it_normalizes_ascii_like_any_other_password() {
+        let passwords = [
+            "",
+            "P4$$w0rd",
+            "CorrectHorseBatteryStaple",
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`abcdefghijklmnopqrstuvwxyz{|}~",
+            "Tr0ub4dor&3 tr0ub4dor&3",
+            "\u{1}\t@Z`{ 1!|",
+        ];
+        let normalizers = [
+            Normalizer::new(),
+            Normalizer::empty(),
+            Normalizer::new().with_substitutions(vec![("q", 'k'), ("Z", 's'), ("x", 'Y')]),
+        ];
+
+        for normalizer in &normalizers {
+            assert!(normalizer.ascii.is_some());
+
+            for password in &passwords {
+                assert_eq!(
+                    normalizer.normalize(password),
+                    normalizer.normalize_chars(password)
+                );
+            }
+        }
+
+        assert!(Normalizer::new()
+            .with_substitutions(vec![("()", 'o')])
+            .ascii
+            .is_none());
+        assert!(Normalizer::new()
+            .with_substitutions(vec![("€", 'e')])
+            .ascii
+            .is_none());
+    }
+}
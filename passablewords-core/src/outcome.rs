@@ -0,0 +1,205 @@
+//! A detailed result which reports every check that failed, and separates the failures that
+//! reject a password from the ones the policy only warns about.
+
+use checker::{PasswordChecker, CHECKS};
+use context::CheckContext;
+use decision::{Check, Outcome};
+use PasswordError;
+
+/// The result of `PasswordChecker::check_password_detailed`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum CheckOutcome {
+    /// The password passed every check.
+    Pass,
+    /// The password only failed checks that the policy warns about, set with
+    /// `Policy::warn_only`, so it should be accepted while nagging the user to change it.
+    PassWithWarnings(Vec<Warning>),
+    /// The password failed at least one check and should be rejected. Every failure is
+    /// included, in the order the checks are run.
+    Fail(Vec<Failure>),
+}
+
+/// A check that failed, but that the policy only warns about.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Warning {
+    /// The check that failed.
+    pub check: Check,
+    /// Why it failed.
+    pub error: PasswordError,
+}
+
+/// A check that failed and rejects the password.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Failure {
+    /// The check that failed.
+    pub check: Check,
+    /// Why it failed.
+    pub error: PasswordError,
+}
+
+impl CheckOutcome {
+    /// Whether or not the password should be accepted, with or without warnings.
+    pub fn is_accepted(&self) -> bool {
+        match *self {
+            CheckOutcome::Pass | CheckOutcome::PassWithWarnings(_) => true,
+            CheckOutcome::Fail(_) => false,
+        }
+    }
+}
+
+impl PasswordChecker {
+    /// Check a password like `check_password` does, but run every check rather than stopping at
+    /// the first failure, and report failures of checks the policy only warns about separately.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Check, CheckOutcome, PasswordChecker, PasswordError};
+    ///
+    /// let checker = PasswordChecker::new();
+    ///
+    /// assert_eq!(
+    ///     checker.check_password_detailed("Th1s iS a Sup3rR4ndom PassW0rd!"),
+    ///     CheckOutcome::Pass
+    /// );
+    /// match checker.check_password_detailed("qwerty") {
+    ///     CheckOutcome::Fail(failures) => {
+    ///         assert_eq!(failures[0].check, Check::Length);
+    ///         assert_eq!(failures[1].error, PasswordError::TooCommon);
+    ///     }
+    ///     outcome => panic!("unexpected outcome {:?}", outcome),
+    /// }
+    /// ```
+    pub fn check_password_detailed(&self, password: &str) -> CheckOutcome {
+        self.detailed(password, None)
+    }
+
+    /// Check a password like `check_password_detailed` does, while also taking the information in
+    /// `context` into account.
+    pub fn check_password_detailed_with_context(
+        &self,
+        password: &str,
+        context: &CheckContext,
+    ) -> CheckOutcome {
+        self.detailed(password, Some(context))
+    }
+
+    fn detailed(&self, password: &str, context: Option<&CheckContext>) -> CheckOutcome {
+        let (failures, warnings) = self.failures_and_warnings(password, context);
+
+        if !failures.is_empty() {
+            CheckOutcome::Fail(failures)
+        } else if !warnings.is_empty() {
+            CheckOutcome::PassWithWarnings(warnings)
+        } else {
+            CheckOutcome::Pass
+        }
+    }
+
+    /// Run every check, telling the decision hook what happened, and split the ones that failed
+    /// into failures and warnings. Unlike `CheckOutcome`, the warnings are kept even when there
+    /// are failures.
+    pub(crate) fn failures_and_warnings(
+        &self,
+        password: &str,
+        context: Option<&CheckContext>,
+    ) -> (Vec<Failure>, Vec<Warning>) {
+        // Too large to check at all, which is put down to the length check
+        if let Err(error) = self.policy().check_input_size(password) {
+            self.record_decision(context, Vec::new(), Outcome::Rejected(error.clone()));
+
+            let failure = Failure {
+                check: Check::Length,
+                error,
+            };
+
+            return (vec![failure], Vec::new());
+        }
+
+        let mut checks = Vec::new();
+        let mut warnings = Vec::new();
+        let mut failures = Vec::new();
+
+        for &check in CHECKS.iter() {
+            let result = match self.run_check(check, password, context) {
+                Some(result) => result,
+                None => continue,
+            };
+
+            if self.has_decision_hook() {
+                checks.push(check);
+            }
+
+            if let Err(error) = result {
+                if self.policy().is_warning_only(check) {
+                    warnings.push(Warning { check, error });
+                } else {
+                    failures.push(Failure { check, error });
+                }
+            }
+        }
+
+        let outcome = match failures.first() {
+            Some(failure) => Outcome::Rejected(failure.error.clone()),
+            None => Outcome::Accepted,
+        };
+
+        self.record_decision(context, checks, outcome);
+        (failures, warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CheckOutcome, Failure, Warning};
+    use checker::PasswordChecker;
+    use context::CheckContext;
+    use decision::Check;
+    use policy::Policy;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    #[test]
+    fn it_separates_warnings_from_failures() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"))
+            .with_policy(Policy::new().min_length(16).warn_only(Check::Uniqueness));
+        let context = CheckContext::new().deny_exact(&["x7#Kq9!zLm2$vB4&wP"]);
+
+        assert_eq!(
+            checker.check_password_detailed("x7#Kq9!zLm2$"),
+            CheckOutcome::Fail(vec![Failure {
+                check: Check::Length,
+                error: PasswordError::TooShort,
+            }])
+        );
+        assert_eq!(
+            checker.check_password_detailed("x7#Kq9!zLm2$vB4&wP"),
+            CheckOutcome::Pass
+        );
+        assert_eq!(
+            checker.check_password_detailed_with_context("x7#Kq9!zLm2$vB4&wP", &context),
+            CheckOutcome::Fail(vec![Failure {
+                check: Check::DeniedValues,
+                error: PasswordError::MatchesDeniedValue,
+            }])
+        );
+
+        let nagging = PasswordChecker::with_wordlist(Wordlist::parse("x7#Kq9!zLm2$"))
+            .with_policy(Policy::new().warn_only(Check::Uniqueness));
+
+        assert_eq!(
+            nagging.check_password_detailed("x7#Kq9!zLm2$"),
+            CheckOutcome::PassWithWarnings(vec![Warning {
+                check: Check::Uniqueness,
+                error: PasswordError::TooCommon,
+            }])
+        );
+        assert!(nagging
+            .check_password_detailed("x7#Kq9!zLm2$")
+            .is_accepted());
+        assert!(nagging.check_password("x7#Kq9!zLm2$").is_ok());
+    }
+}
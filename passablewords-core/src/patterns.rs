@@ -0,0 +1,95 @@
+//! Rules that reject passwords matching regular expressions, such as a company name followed by a
+//! few digits. Only available with the `regex` feature.
+
+use regex::{Error, RegexSet};
+use {PassablewordResult, PasswordError};
+
+/// A named set of regular expressions, compiled into a single `RegexSet` so that checking a
+/// password against all of them only takes one pass.
+#[derive(Debug, Clone)]
+pub struct BannedPatterns {
+    names: Vec<String>,
+    set: RegexSet,
+}
+
+impl BannedPatterns {
+    pub fn new() -> BannedPatterns {
+        BannedPatterns {
+            names: Vec::new(),
+            set: RegexSet::empty(),
+        }
+    }
+
+    /// Add a pattern, recompiling the whole set. Patterns are only added while configuring a
+    /// policy, so the cost of compiling them isn't paid while checking passwords.
+    pub fn add(&self, name: &str, pattern: &str) -> Result<BannedPatterns, Error> {
+        let mut patterns = self.set.patterns().to_vec();
+
+        patterns.push(pattern.to_string());
+
+        let set = RegexSet::new(&patterns)?;
+        let mut names = self.names.clone();
+
+        names.push(name.to_string());
+
+        Ok(BannedPatterns { names, set })
+    }
+
+    /// The name and pattern of every rule, in the order they were added.
+    pub fn rules<'a>(&'a self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        self.names
+            .iter()
+            .map(String::as_str)
+            .zip(self.set.patterns().iter().map(String::as_str))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Check a password against every pattern, reporting the name of the first one, in the order
+    /// they were added, which matches.
+    pub fn check(&self, password: &str) -> PassablewordResult {
+        match self.set.matches(password).iter().next() {
+            Some(index) => Err(PasswordError::BannedPattern(self.names[index].clone())),
+            None => Ok(()),
+        }
+    }
+}
+
+impl PartialEq for BannedPatterns {
+    fn eq(&self, other: &BannedPatterns) -> bool {
+        self.names == other.names && self.set.patterns() == other.set.patterns()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BannedPatterns;
+    use PasswordError;
+
+    #[test]
+    fn it_validates_banned_patterns() {
+        let patterns = BannedPatterns::new()
+            .add("company name", r"(?i)acme[0-9]{2,4}")
+            .unwrap()
+            .add("product name", r"(?i)roadrunner")
+            .unwrap();
+
+        assert_eq!(
+            patterns.check("MyAcme2024Password"),
+            Err(PasswordError::BannedPattern("company name".to_string()))
+        );
+        assert_eq!(
+            patterns.check("acme99-roadrunner"),
+            Err(PasswordError::BannedPattern("company name".to_string()))
+        );
+        assert_eq!(
+            patterns.check("RoadRunner!"),
+            Err(PasswordError::BannedPattern("product name".to_string()))
+        );
+        assert_eq!(patterns.check("acme-corp"), Ok(()));
+        assert_eq!(BannedPatterns::new().check("acme2024"), Ok(()));
+        assert!(BannedPatterns::new().add("broken", "(").is_err());
+    }
+}
@@ -0,0 +1,1312 @@
+//! Configurable rules for what makes a password acceptable, for applications that need something
+//! other than the defaults used by the free functions.
+
+use checker::CHECKS;
+use compat::Compat;
+use crack_time::HashRate;
+use decision::Check;
+use frontend::FrontendRules;
+use keyboard::{self, KeyboardLayout};
+use lint::{
+    PolicyWarning, ESSENTIAL_CHECKS, RECOMMENDED_MIN_INPUT_BYTES, RECOMMENDED_MIN_LENGTH,
+    RECOMMENDED_MIN_WORDS,
+};
+use normalize::Normalizer;
+#[cfg(feature = "regex")]
+use frontend::BlockedPattern;
+#[cfg(feature = "regex")]
+use patterns::BannedPatterns;
+#[cfg(feature = "regex")]
+use regex;
+use scrub::Scrubbed;
+use temporal::TemporalTokens;
+use wordlist::Wordlist;
+use {PassablewordResult, PasswordError};
+
+/// The most bytes a password can be under the default policy, and for the free functions, before
+/// it's rejected as `PasswordError::InputTooLarge`. No real password comes close, and turning
+/// away anything longer keeps a giant input from tying up normalization or zxcvbn.
+pub const DEFAULT_MAX_INPUT_BYTES: usize = 1024;
+
+/// The fewest letters in a word.
+const MIN_WORD_LETTERS: usize = 3;
+
+/// The most letters in a word of the dictionary when splitting runs of letters, which keeps the
+/// search linear in the length of the password.
+const MAX_WORD_LETTERS: usize = 24;
+
+/// The rules a password has to follow. The default policy has the same rules as the free functions
+/// in the crate root, so a password must be at least 8 characters long, with one difference: the
+/// policy counts characters, while the free `check_length` counts bytes of UTF-8, as it always has.
+/// A password like `"üüüü"` is long enough for `check_length` but too short for `Policy::new()`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{PasswordChecker, Policy};
+///
+/// let checker = PasswordChecker::new().with_policy(Policy::new().min_length_or_words(16, 3));
+///
+/// assert!(checker.check_password("correct-horse-battery-staple").is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+    length: LengthRule,
+    min_words: Option<usize>,
+    min_unique_chars: Option<usize>,
+    reject_all_digits: bool,
+    reject_all_letters: bool,
+    strip_common_affixes: bool,
+    normalizer: Option<Normalizer>,
+    embedded_min_length: Option<usize>,
+    reject_word_shells: bool,
+    single_word_extras: Option<(usize, usize)>,
+    keyboard_walk_length: Option<usize>,
+    keyboard_layouts: Vec<KeyboardLayout>,
+    temporal_tokens: Option<TemporalTokens>,
+    warning_only: Vec<Check>,
+    charset_mode: CharsetMode,
+    compat: Compat,
+    score_floor: u8,
+    hash_rate: HashRate,
+    #[cfg(feature = "regex")]
+    banned_patterns: BannedPatterns,
+    max_input_bytes: usize,
+    version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LengthRule {
+    Characters(usize),
+    CharactersOrWords(usize, usize),
+}
+
+impl LengthRule {
+    /// The minimum number of characters, and the number of words accepted in their place.
+    fn parts(self) -> (usize, Option<usize>) {
+        match self {
+            LengthRule::Characters(length) => (length, None),
+            LengthRule::CharactersOrWords(length, words) => (length, Some(words)),
+        }
+    }
+}
+
+/// Which characters a policy allows passwords to contain.
+///
+/// zxcvbn can only analyse ASCII passwords, so by default they're all a policy allows when it's
+/// enabled. Without the `zxcvbn` feature, the default is `Unicode`, since `check_quick_entropy`
+/// copes with any character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CharsetMode {
+    /// Reject passwords containing anything other than ASCII with
+    /// `PasswordError::NonAsciiPassword`, before any other check runs, so users aren't told to
+    /// make a password longer or less common only for it to be rejected anyway.
+    AsciiOnly,
+    /// Accept any Unicode password and run every check on it. Since zxcvbn can't analyse
+    /// passwords containing anything other than ASCII, their entropy is checked with
+    /// `check_quick_entropy` instead, which is stricter about length.
+    Unicode,
+}
+
+impl Default for CharsetMode {
+    #[cfg(feature = "zxcvbn")]
+    fn default() -> CharsetMode {
+        CharsetMode::AsciiOnly
+    }
+
+    #[cfg(not(feature = "zxcvbn"))]
+    fn default() -> CharsetMode {
+        CharsetMode::Unicode
+    }
+}
+
+/// One way two policies differ, returned by `Policy::diff`. Rules with a value carry the old value
+/// and then the new one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolicyChange {
+    /// The policy's version label changed.
+    Version(Option<String>, Option<String>),
+    /// The minimum number of characters changed.
+    MinLength(usize, usize),
+    /// The most bytes a password can be changed.
+    MaxInputBytes(usize, usize),
+    /// The number of words accepted in place of the minimum length changed, or passphrases
+    /// started or stopped being accepted.
+    MinWords(Option<usize>, Option<usize>),
+    /// The number of words every password needs changed.
+    RequiredWords(Option<usize>, Option<usize>),
+    /// The minimum number of different characters changed.
+    MinUniqueChars(Option<usize>, Option<usize>),
+    /// Passwords made of only digits started or stopped being rejected.
+    RejectsAllDigits(bool, bool),
+    /// Passwords made of only letters started or stopped being rejected.
+    RejectsAllLetters(bool, bool),
+    /// Common passwords with affixes started or stopped being rejected.
+    StripsCommonAffixes(bool, bool),
+    /// The normalizer was added, removed, or configured differently.
+    Normalizer,
+    /// The minimum length of common passwords searched for inside passwords changed.
+    EmbeddedCommonPasswords(Option<usize>, Option<usize>),
+    /// Single words with a few digits and a symbol around them started or stopped being
+    /// rejected.
+    RejectsWordShells(bool, bool),
+    /// The digits and symbols a single dictionary word needs around it changed, or single
+    /// dictionary words started or stopped being rejected.
+    SingleDictionaryWords(Option<(usize, usize)>, Option<(usize, usize)>),
+    /// The minimum length of rejected keyboard walks changed.
+    KeyboardWalks(Option<usize>, Option<usize>),
+    /// Keyboard layouts were added or removed.
+    KeyboardLayouts,
+    /// Passwords built around the date started or stopped being rejected, or the tokens that date
+    /// them changed.
+    TemporalPatterns,
+    /// Failures of the check are only warnings under the new policy.
+    WarnOnly(Check),
+    /// Failures of the check were only warnings under the old policy, and now reject passwords.
+    Enforced(Check),
+    /// Which characters passwords can contain changed.
+    Charset(CharsetMode, CharsetMode),
+    /// The version of passablewords the entropy check is pinned to changed.
+    Compat(Compat, Compat),
+    /// The rate of guessing crack times are estimated at changed.
+    HashRate(HashRate, HashRate),
+    /// The new policy bans a pattern, by name, that the old one didn't. A rule whose pattern
+    /// changed is reported as both unbanned and banned.
+    PatternBanned(String),
+    /// The old policy banned a pattern, by name, that the new one doesn't.
+    PatternUnbanned(String),
+}
+
+impl Policy {
+    /// Create a policy with the default rules.
+    pub fn new() -> Policy {
+        Policy {
+            length: LengthRule::Characters(8),
+            min_words: None,
+            min_unique_chars: None,
+            reject_all_digits: false,
+            reject_all_letters: false,
+            strip_common_affixes: false,
+            normalizer: None,
+            embedded_min_length: None,
+            reject_word_shells: false,
+            single_word_extras: None,
+            keyboard_walk_length: None,
+            keyboard_layouts: Vec::new(),
+            temporal_tokens: None,
+            warning_only: Vec::new(),
+            charset_mode: CharsetMode::default(),
+            compat: Compat::default(),
+            score_floor: 0,
+            hash_rate: HashRate::default(),
+            #[cfg(feature = "regex")]
+            banned_patterns: BannedPatterns::new(),
+            max_input_bytes: DEFAULT_MAX_INPUT_BYTES,
+            version: None,
+        }
+    }
+
+    /// Label this policy with a version of your choosing, such as `2024-06` or `v3`, so that
+    /// decisions can be traced back to the rules that were in place when they were made. Bump it
+    /// whenever the rules change, and `needs_recheck` will flag any `StrengthReport` made under
+    /// an earlier version.
+    pub fn with_version(mut self, version: &str) -> Policy {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// The version this policy was labelled with, if any.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Require passwords to be at least `length` characters long.
+    pub fn min_length(mut self, length: usize) -> Policy {
+        self.length = LengthRule::Characters(length);
+        self
+    }
+
+    /// Require passwords to be at least `length` characters long, or to be made up of at least
+    /// `words` words, whichever is easier for the password to meet. This favors passphrases over
+    /// short passwords that are only long enough, following current guidance from OWASP and
+    /// NIST.
+    ///
+    /// A word is a run of at least three letters. Words are separated by anything that isn't a
+    /// letter, such as spaces, hyphens, or numbers, and by a lowercase letter followed by an
+    /// uppercase one, so `correct horse battery` and `CorrectHorseBattery` are both three words.
+    /// A `PasswordChecker` also splits runs of letters into the words of its wordlist, so
+    /// `correcthorsebattery` is three words too. The uniqueness and entropy checks still apply
+    /// either way.
+    pub fn min_length_or_words(mut self, length: usize, words: usize) -> Policy {
+        self.length = LengthRule::CharactersOrWords(length, words);
+        self
+    }
+
+    /// Require every password to be made up of at least `words` words, as well as meeting the
+    /// length rule, for organizations following "three random words" guidance. Passwords with
+    /// fewer are reported as `PasswordError::TooShort`.
+    ///
+    /// Words are counted like they are for `min_length_or_words`. Runs of letters are split into
+    /// the fewest words from the checker's wordlist that make them up, and a run that can't be
+    /// split counts as one word. `Policy::check_length` has no wordlist, so it only splits on
+    /// spaces, hyphens, and the like.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Policy, Wordlist};
+    ///
+    /// let wordlist = Wordlist::parse("correct\nhorse\nbattery\nstaple");
+    /// let checker = PasswordChecker::with_wordlist(wordlist).with_policy(Policy::new().min_words(3));
+    ///
+    /// assert_eq!(checker.check_length("correct horse battery"), Ok(()));
+    /// assert_eq!(checker.check_length("correcthorsebattery"), Ok(()));
+    /// assert_eq!(checker.check_length("correcthorse"), Err(PasswordError::TooShort));
+    /// ```
+    pub fn min_words(mut self, words: usize) -> Policy {
+        self.min_words = Some(words);
+        self
+    }
+
+    /// Require passwords to contain at least `count` different characters, reporting passwords
+    /// with fewer as `PasswordError::TooFewUniqueChars`. This is a cheap way to reject lazy
+    /// padding like `aaaaaaab`, which is long enough and unlikely to be in the wordlist.
+    pub fn min_unique_chars(mut self, count: usize) -> Policy {
+        self.min_unique_chars = Some(count);
+        self
+    }
+
+    /// Reject passwords made of nothing but digits, like `7382910465`, reporting them as
+    /// `PasswordError::InsufficientVariety`. They're long enough and rarely in the wordlist, but
+    /// attackers brute-force numeric passwords separately since there are so few of them.
+    pub fn reject_all_digits(mut self, reject: bool) -> Policy {
+        self.reject_all_digits = reject;
+        self
+    }
+
+    /// Whether or not passwords made of only digits are rejected.
+    pub fn rejects_all_digits(&self) -> bool {
+        self.reject_all_digits
+    }
+
+    /// Reject passwords made of nothing but letters, in any case and any script, reporting them
+    /// as `PasswordError::InsufficientVariety`.
+    pub fn reject_all_letters(mut self, reject: bool) -> Policy {
+        self.reject_all_letters = reject;
+        self
+    }
+
+    /// Whether or not passwords made of only letters are rejected.
+    pub fn rejects_all_letters(&self) -> bool {
+        self.reject_all_letters
+    }
+
+    /// Also reject passwords that are only in the wordlist once common prefixes and suffixes are
+    /// removed, such as `password2024!`. Years and other runs of digits, single symbols, and
+    /// combinations of the two are stripped from either end, and whatever's left is looked up
+    /// again. A match is reported as `PasswordError::TooCommonVariant`.
+    pub fn strip_common_affixes(mut self, strip: bool) -> Policy {
+        self.strip_common_affixes = strip;
+        self
+    }
+
+    /// Whether or not passwords are looked up again with common affixes removed.
+    pub fn strips_common_affixes(&self) -> bool {
+        self.strip_common_affixes
+    }
+
+    /// Also reject passwords that are in the wordlist once `normalizer` has lowercased them and
+    /// undone its substitutions, such as `P@ssw0rd`. If common affixes are stripped too, the
+    /// stripped forms are normalized and looked up as well. A match is reported as
+    /// `PasswordError::TooCommonVariant`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Normalizer, PasswordChecker, PasswordError, Policy};
+    ///
+    /// let normalizer = Normalizer::new().with_substitutions(vec![("()", 'o')]);
+    /// let checker = PasswordChecker::new().with_policy(Policy::new().with_normalizer(normalizer));
+    ///
+    /// assert_eq!(
+    ///     checker.check_uniqueness("P@SSW()RD"),
+    ///     Err(PasswordError::TooCommonVariant)
+    /// );
+    /// ```
+    pub fn with_normalizer(mut self, normalizer: Normalizer) -> Policy {
+        self.normalizer = Some(normalizer);
+        self
+    }
+
+    /// The normalizer passwords are looked up with, if there is one.
+    pub fn normalizer(&self) -> Option<&Normalizer> {
+        self.normalizer.as_ref()
+    }
+
+    /// Reject passwords made of a single word from the wordlist, in any mix of upper and lower
+    /// case, with no more than four digits and one symbol around it, such as `Summer2024!` or
+    /// `!DRAGON1`. This is the most common way people try to meet composition rules, and
+    /// attackers know it, so these are rejected even when zxcvbn would accept them. A match is
+    /// reported as `PasswordError::TooCommonVariant`. To warn about them instead, also call
+    /// `warn_only(Check::WordShells)`.
+    pub fn reject_word_shells(mut self, reject: bool) -> Policy {
+        self.reject_word_shells = reject;
+        self
+    }
+
+    /// Whether or not single words with a few digits and a symbol around them are rejected.
+    pub fn rejects_word_shells(&self) -> bool {
+        self.reject_word_shells
+    }
+
+    /// Reject passwords made of a single dictionary word, however long it is, unless there's a
+    /// run of at least `min_digits` digits or at least `min_symbols` symbols around it. A limit
+    /// of 0 means digits, or symbols, are never enough on their own. A second word is always
+    /// enough.
+    ///
+    /// Dictionary words are the entries of the wordlist, and of the checker's dictionary if it
+    /// has one, matched in any case and with substitutions undone by the policy's normalizer, or
+    /// the default one. `antidisestablishmentarianism` is long, but it's still only one lookup
+    /// away. A match is reported as `PasswordError::SingleDictionaryWord`. To warn about them
+    /// instead, also call `warn_only(Check::SingleDictionaryWord)`.
+    pub fn reject_single_dictionary_words(
+        mut self,
+        min_digits: usize,
+        min_symbols: usize,
+    ) -> Policy {
+        self.single_word_extras = Some((min_digits, min_symbols));
+        self
+    }
+
+    /// The digits and symbols a single dictionary word needs around it, if single dictionary
+    /// words are rejected.
+    pub fn single_dictionary_word_extras(&self) -> Option<(usize, usize)> {
+        self.single_word_extras
+    }
+
+    /// Also reject passwords that contain a common password of at least `min_length` characters
+    /// anywhere inside them, such as `xXpasswordXx`. A match is reported as
+    /// `PasswordError::TooCommonVariant`. Very short entries would match far too many passwords,
+    /// so a `min_length` of 6 or more is a good place to start.
+    ///
+    /// Searching inside passwords is only possible with wordlists backed by an FST, which are the
+    /// bundled list with the `fst` feature and lists created with `Wordlist::parse_fst`. Other
+    /// wordlists skip this check.
+    pub fn reject_embedded_common_passwords(mut self, min_length: usize) -> Policy {
+        self.embedded_min_length = Some(min_length);
+        self
+    }
+
+    /// The minimum length of common passwords that are searched for inside passwords, or `None`
+    /// if they aren't.
+    pub fn embedded_common_password_length(&self) -> Option<usize> {
+        self.embedded_min_length
+    }
+
+    /// Let passwords that fail `check` through, reporting the failure as a warning from
+    /// `PasswordChecker::check_password_detailed` instead. This is useful for "allow but nag"
+    /// flows, like accepting a password that's a little too simple while asking the user to
+    /// change it soon. `check_password` ignores these failures entirely.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Check, CheckOutcome, PasswordChecker, Policy};
+    ///
+    /// let checker = PasswordChecker::new()
+    ///     .with_policy(Policy::new().min_length(20).warn_only(Check::Length));
+    ///
+    /// assert!(checker.check_password("x7#Kq9!zLm2$vB").is_ok());
+    /// match checker.check_password_detailed("x7#Kq9!zLm2$vB") {
+    ///     CheckOutcome::PassWithWarnings(warnings) => assert_eq!(warnings[0].check, Check::Length),
+    ///     outcome => panic!("unexpected outcome {:?}", outcome),
+    /// }
+    /// ```
+    pub fn warn_only(mut self, check: Check) -> Policy {
+        if !self.warning_only.contains(&check) {
+            self.warning_only.push(check);
+        }
+        self
+    }
+
+    /// Whether failures of `check` are only warnings under this policy.
+    pub fn is_warning_only(&self, check: Check) -> bool {
+        self.warning_only.contains(&check)
+    }
+
+    /// Choose which characters passwords can contain. See `CharsetMode` for the default.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{CharsetMode, PasswordChecker, PasswordError, Policy};
+    ///
+    /// let checker = PasswordChecker::new()
+    ///     .with_policy(Policy::new().charset_mode(CharsetMode::AsciiOnly));
+    ///
+    /// assert_eq!(
+    ///     checker.check_password("pässwort"),
+    ///     Err(PasswordError::NonAsciiPassword)
+    /// );
+    /// ```
+    pub fn charset_mode(mut self, mode: CharsetMode) -> Policy {
+        self.charset_mode = mode;
+        self
+    }
+
+    /// Which characters passwords can contain under this policy.
+    pub fn charset(&self) -> CharsetMode {
+        self.charset_mode
+    }
+
+    /// Reject passwords longer than `bytes` as `PasswordError::InputTooLarge` before any check
+    /// looks at them, instead of the default `DEFAULT_MAX_INPUT_BYTES`. The limit is in bytes of
+    /// UTF-8 rather than characters, so it can be enforced without reading the whole input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, Policy};
+    ///
+    /// let checker = PasswordChecker::new().with_policy(Policy::new().max_input_bytes(64));
+    ///
+    /// assert_eq!(
+    ///     checker.check_password(&"correct horse battery staple ".repeat(3)),
+    ///     Err(PasswordError::InputTooLarge)
+    /// );
+    /// ```
+    pub fn max_input_bytes(mut self, bytes: usize) -> Policy {
+        self.max_input_bytes = bytes;
+        self
+    }
+
+    /// The most bytes a password can be under this policy.
+    pub fn input_limit(&self) -> usize {
+        self.max_input_bytes
+    }
+
+    /// Pin how the entropy check decides whether a password passes to a major version of
+    /// passablewords, so that upgrading zxcvbn doesn't change the answer. See `Compat` for what
+    /// each version means.
+    pub fn pin_compat(mut self, compat: Compat) -> Policy {
+        self.compat = compat;
+        self
+    }
+
+    /// How the entropy check decides whether a password passes under this policy.
+    pub fn compat(&self) -> Compat {
+        self.compat
+    }
+
+    /// The lowest zxcvbn score a password that passes can have, which is `compat`'s unless the
+    /// policy was raised for a risk level.
+    pub(crate) fn min_score(&self) -> u8 {
+        self.compat.min_score().max(self.score_floor)
+    }
+
+    /// The score a password has to reach on top of what `compat` asks for, or 0 if there isn't
+    /// one.
+    pub(crate) fn score_floor(&self) -> u8 {
+        self.score_floor
+    }
+
+    /// This policy, made to require at least `min_length` characters and a zxcvbn score of at
+    /// least `min_score`, for `check_password_with_risk`. A longer minimum length of its own is
+    /// kept, along with any number of words it accepts in place of length, but otherwise every
+    /// password needs `min_length` characters.
+    pub(crate) fn raise(mut self, min_length: usize, min_score: u8) -> Policy {
+        let (length, _) = self.length.parts();
+
+        if length < min_length {
+            self.length = LengthRule::Characters(min_length);
+        }
+        self.score_floor = self.score_floor.max(min_score);
+        self
+    }
+
+    /// Estimate crack times, with `PasswordChecker::estimate_crack_time`, as if the attacker
+    /// guessed at `rate`, such as `HashRate::bcrypt(12).gpus(8)` for a rig of eight GPUs against
+    /// the bcrypt hashes the organization stores. It doesn't change which passwords are
+    /// accepted. The default is `HashRate::OFFLINE_SLOW_HASHING`, one of zxcvbn's scenarios.
+    pub fn assume_hash_rate(mut self, rate: HashRate) -> Policy {
+        self.hash_rate = rate;
+        self
+    }
+
+    /// The rate of guessing crack times are estimated at under this policy.
+    pub fn hash_rate(&self) -> HashRate {
+        self.hash_rate
+    }
+
+    /// Reject passwords containing a keyboard walk of at least `min_length` characters, such as
+    /// `qwerty` or `1qaz2wsx`, reporting them as `PasswordError::KeyboardWalk`. Walks are looked
+    /// for on the QWERTY layout and any layouts added with `keyboard_layout`. A `min_length` of 5
+    /// or more avoids rejecting short, accidental runs like `we` or `as` in ordinary words.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{KeyboardLayout, PasswordError, Policy};
+    ///
+    /// let policy = Policy::new()
+    ///     .reject_keyboard_walks(5)
+    ///     .keyboard_layout(KeyboardLayout::dvorak());
+    ///
+    /// assert_eq!(
+    ///     policy.check_keyboard_walks("my qwerty pass"),
+    ///     Err(PasswordError::KeyboardWalk)
+    /// );
+    /// assert_eq!(
+    ///     policy.check_keyboard_walks("aoeui is the dvorak home row"),
+    ///     Err(PasswordError::KeyboardWalk)
+    /// );
+    /// assert_eq!(policy.check_keyboard_walks("correct horse"), Ok(()));
+    /// ```
+    pub fn reject_keyboard_walks(mut self, min_length: usize) -> Policy {
+        self.keyboard_walk_length = Some(min_length);
+        self
+    }
+
+    /// Also look for keyboard walks on `layout`, for users with layouts other than QWERTY. This
+    /// has no effect unless `reject_keyboard_walks` is used too.
+    pub fn keyboard_layout(mut self, layout: KeyboardLayout) -> Policy {
+        self.keyboard_layouts.push(layout);
+        self
+    }
+
+    /// The minimum length of keyboard walks that are rejected, or `None` if they aren't.
+    pub fn keyboard_walk_length(&self) -> Option<usize> {
+        self.keyboard_walk_length
+    }
+
+    /// Reject passwords built around the date, like `Winter2025` or `Q3report!`, reporting them
+    /// as `PasswordError::TemporalPattern`. These are what people pick when they're made to
+    /// change their password, so they're the first thing attackers try after a forced reset.
+    /// See `TemporalTokens` for what counts, and for adding tokens of your own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordError, Policy, TemporalTokens};
+    ///
+    /// let policy = Policy::new()
+    ///     .reject_temporal_patterns(TemporalTokens::new().with_names(vec!["fy"]));
+    ///
+    /// assert_eq!(
+    ///     policy.check_temporal_patterns("FY-Q3-report!"),
+    ///     Err(PasswordError::TemporalPattern)
+    /// );
+    /// assert_eq!(policy.check_temporal_patterns("correct horse battery"), Ok(()));
+    /// ```
+    pub fn reject_temporal_patterns(mut self, tokens: TemporalTokens) -> Policy {
+        self.temporal_tokens = Some(tokens);
+        self
+    }
+
+    /// The tokens that date passwords, or `None` if passwords built around the date aren't
+    /// rejected.
+    pub fn temporal_tokens(&self) -> Option<&TemporalTokens> {
+        self.temporal_tokens.as_ref()
+    }
+
+    /// Reject passwords matching the regular expression `pattern`, reporting the match as
+    /// `PasswordError::BannedPattern` with the given rule name. This is useful for terms which
+    /// attackers will guess first when targeting your users in particular, like your company's
+    /// name followed by a year. Only available with the `regex` feature.
+    ///
+    /// Patterns match anywhere within the password unless they're anchored, and are compiled into
+    /// a single `RegexSet` so that every rule is checked in one pass. An error is returned if the
+    /// pattern isn't a valid regular expression.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate passablewords_core;
+    /// # extern crate regex;
+    /// use passablewords_core::{PasswordError, Policy};
+    ///
+    /// # fn main() -> Result<(), regex::Error> {
+    /// let policy = Policy::new().ban_pattern("company name", r"(?i)acme[0-9]{2,4}")?;
+    ///
+    /// assert_eq!(
+    ///     policy.check_banned_patterns("Acme2024!"),
+    ///     Err(PasswordError::BannedPattern("company name".to_string()))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn ban_pattern(mut self, name: &str, pattern: &str) -> Result<Policy, regex::Error> {
+        self.banned_patterns = self.banned_patterns.add(name, pattern)?;
+        Ok(self)
+    }
+
+    /// Look for rules that make this policy weaker than recommended, such as a minimum length
+    /// below 8 characters, or the length, uniqueness, or entropy check only producing a warning.
+    /// The default policy has no warnings. Rotation is configured separately, so check it with
+    /// `RotationPolicy::lint`.
+    ///
+    /// Composition rules like `reject_all_digits` are off by default and turning them off never
+    /// makes a policy weaker, so they don't produce warnings. Neither does `pin_compat`, since
+    /// every version of the entropy check draws the line at about 10^8 guesses.
+    pub fn lint(&self) -> Vec<PolicyWarning> {
+        let mut warnings = Vec::new();
+        let (length, words) = self.length.parts();
+
+        if length < RECOMMENDED_MIN_LENGTH {
+            warnings.push(PolicyWarning::ShortMinLength(length));
+        }
+        match words {
+            Some(words) if words.max(self.min_words.unwrap_or(0)) < RECOMMENDED_MIN_WORDS => {
+                warnings.push(PolicyWarning::FewMinWords(words));
+            }
+            _ => {}
+        }
+        for &check in ESSENTIAL_CHECKS.iter() {
+            if self.is_warning_only(check) {
+                warnings.push(PolicyWarning::WarnOnly(check));
+            }
+        }
+        if self.max_input_bytes < RECOMMENDED_MIN_INPUT_BYTES {
+            warnings.push(PolicyWarning::SmallInputLimit(self.max_input_bytes));
+        }
+
+        warnings
+    }
+
+    /// List every rule that differs between `old` and `new`, in the order the rules are checked,
+    /// for writing release notes when a policy changes. Use `simulate_diff` to see which
+    /// passwords the changes affect.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{Check, Policy, PolicyChange};
+    ///
+    /// let old = Policy::new().min_length(8).warn_only(Check::Uniqueness);
+    /// let new = Policy::new().min_length(12);
+    ///
+    /// assert_eq!(
+    ///     Policy::diff(&old, &new),
+    ///     vec![PolicyChange::MinLength(8, 12), PolicyChange::Enforced(Check::Uniqueness)]
+    /// );
+    /// ```
+    pub fn diff(old: &Policy, new: &Policy) -> Vec<PolicyChange> {
+        let mut changes = Vec::new();
+        let mut compare = |changed: bool, change: PolicyChange| {
+            if changed {
+                changes.push(change);
+            }
+        };
+        let (old_length, old_words) = old.length.parts();
+        let (new_length, new_words) = new.length.parts();
+
+        compare(
+            old.version != new.version,
+            PolicyChange::Version(old.version.clone(), new.version.clone()),
+        );
+        compare(
+            old.charset_mode != new.charset_mode,
+            PolicyChange::Charset(old.charset_mode, new.charset_mode),
+        );
+        compare(
+            old_length != new_length,
+            PolicyChange::MinLength(old_length, new_length),
+        );
+        compare(
+            old.max_input_bytes != new.max_input_bytes,
+            PolicyChange::MaxInputBytes(old.max_input_bytes, new.max_input_bytes),
+        );
+        compare(
+            old_words != new_words,
+            PolicyChange::MinWords(old_words, new_words),
+        );
+        compare(
+            old.min_words != new.min_words,
+            PolicyChange::RequiredWords(old.min_words, new.min_words),
+        );
+        compare(
+            old.min_unique_chars != new.min_unique_chars,
+            PolicyChange::MinUniqueChars(old.min_unique_chars, new.min_unique_chars),
+        );
+        compare(
+            old.reject_all_digits != new.reject_all_digits,
+            PolicyChange::RejectsAllDigits(old.reject_all_digits, new.reject_all_digits),
+        );
+        compare(
+            old.reject_all_letters != new.reject_all_letters,
+            PolicyChange::RejectsAllLetters(old.reject_all_letters, new.reject_all_letters),
+        );
+        #[cfg(feature = "regex")]
+        {
+            let old_rules: Vec<_> = old.banned_patterns.rules().collect();
+            let new_rules: Vec<_> = new.banned_patterns.rules().collect();
+
+            for &(name, pattern) in &old_rules {
+                compare(
+                    !new_rules.contains(&(name, pattern)),
+                    PolicyChange::PatternUnbanned(name.to_string()),
+                );
+            }
+            for &(name, pattern) in &new_rules {
+                compare(
+                    !old_rules.contains(&(name, pattern)),
+                    PolicyChange::PatternBanned(name.to_string()),
+                );
+            }
+        }
+        compare(
+            old.keyboard_walk_length != new.keyboard_walk_length,
+            PolicyChange::KeyboardWalks(old.keyboard_walk_length, new.keyboard_walk_length),
+        );
+        compare(
+            old.keyboard_layouts != new.keyboard_layouts,
+            PolicyChange::KeyboardLayouts,
+        );
+        compare(
+            old.temporal_tokens != new.temporal_tokens,
+            PolicyChange::TemporalPatterns,
+        );
+        compare(
+            old.strip_common_affixes != new.strip_common_affixes,
+            PolicyChange::StripsCommonAffixes(old.strip_common_affixes, new.strip_common_affixes),
+        );
+        compare(old.normalizer != new.normalizer, PolicyChange::Normalizer);
+        compare(
+            old.embedded_min_length != new.embedded_min_length,
+            PolicyChange::EmbeddedCommonPasswords(old.embedded_min_length, new.embedded_min_length),
+        );
+        compare(
+            old.reject_word_shells != new.reject_word_shells,
+            PolicyChange::RejectsWordShells(old.reject_word_shells, new.reject_word_shells),
+        );
+        compare(
+            old.single_word_extras != new.single_word_extras,
+            PolicyChange::SingleDictionaryWords(old.single_word_extras, new.single_word_extras),
+        );
+        compare(
+            old.compat != new.compat,
+            PolicyChange::Compat(old.compat, new.compat),
+        );
+        compare(
+            old.hash_rate != new.hash_rate,
+            PolicyChange::HashRate(old.hash_rate, new.hash_rate),
+        );
+
+        for &check in CHECKS.iter() {
+            let (was, is) = (old.is_warning_only(check), new.is_warning_only(check));
+
+            compare(!was && is, PolicyChange::WarnOnly(check));
+            compare(was && !is, PolicyChange::Enforced(check));
+        }
+
+        changes
+    }
+
+    /// Export this policy's rules so that a frontend can mirror them. See `FrontendRules` for the
+    /// JSON format.
+    pub fn to_frontend_rules(&self) -> FrontendRules {
+        let (min_length, min_words) = self.length.parts();
+        #[cfg(feature = "regex")]
+        let blocked_patterns = self
+            .banned_patterns
+            .rules()
+            .map(|(name, pattern)| BlockedPattern {
+                name: name.to_string(),
+                pattern: pattern.to_string(),
+            })
+            .collect();
+        #[cfg(not(feature = "regex"))]
+        let blocked_patterns = Vec::new();
+
+        FrontendRules {
+            version: self.version.clone(),
+            min_length,
+            max_bytes: self.max_input_bytes,
+            min_words,
+            required_words: self.min_words,
+            min_unique_chars: self.min_unique_chars,
+            rejects_all_digits: self.reject_all_digits,
+            rejects_all_letters: self.reject_all_letters,
+            min_score: self.min_score(),
+            rejects_common_variants: self.strip_common_affixes,
+            rejects_embedded_common_passwords: self.embedded_min_length,
+            rejects_word_shells: self.reject_word_shells,
+            rejects_keyboard_walks: self.keyboard_walk_length,
+            ascii_only: self.charset_mode == CharsetMode::AsciiOnly,
+            blocked_patterns,
+        }
+    }
+
+    /// The fewest characters this policy accepts, not counting passwords accepted for their words.
+    pub(crate) fn required_length(&self) -> usize {
+        self.length.parts().0
+    }
+
+    /// Check that a password is no longer than this policy's `input_limit`.
+    pub fn check_input_size(&self, password: &str) -> PassablewordResult {
+        check_input_size(password, self.max_input_bytes)
+    }
+
+    /// Check that a password only contains the characters this policy's `CharsetMode` allows.
+    pub fn check_charset(&self, password: &str) -> PassablewordResult {
+        match self.charset_mode {
+            CharsetMode::AsciiOnly if !password.is_ascii() => Err(PasswordError::NonAsciiPassword),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a password against this policy's banned patterns. Without the `regex` feature there
+    /// can't be any, so every password passes.
+    pub fn check_banned_patterns(&self, password: &str) -> PassablewordResult {
+        #[cfg(feature = "regex")]
+        {
+            if !self.banned_patterns.is_empty() {
+                return self.banned_patterns.check(password);
+            }
+        }
+
+        let _ = password;
+
+        Ok(())
+    }
+
+    /// Check a password for keyboard walks, if this policy rejects them.
+    pub fn check_keyboard_walks(&self, password: &str) -> PassablewordResult {
+        let min_length = match self.keyboard_walk_length {
+            Some(min_length) => min_length,
+            None => return Ok(()),
+        };
+        let walks = ::std::iter::once(keyboard::qwerty())
+            .chain(self.keyboard_layouts.iter())
+            .any(|layout| layout.is_walk(password, min_length));
+
+        if walks {
+            Err(PasswordError::KeyboardWalk)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check that a password isn't built around the date, if this policy rejects those.
+    pub fn check_temporal_patterns(&self, password: &str) -> PassablewordResult {
+        match self.temporal_tokens {
+            Some(ref tokens) if tokens.is_temporal(password) => Err(PasswordError::TemporalPattern),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a password against this policy's minimum number of unique characters, if it has
+    /// one. Counting stops as soon as there are enough, and only passwords containing characters
+    /// outside of ASCII need to allocate.
+    pub fn check_unique_chars(&self, password: &str) -> PassablewordResult {
+        match self.min_unique_chars {
+            Some(count) if !has_unique_chars(password, count) => {
+                Err(PasswordError::TooFewUniqueChars)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that a password isn't made of only digits or only letters, if this policy rejects
+    /// them. Empty passwords are left to the length rule.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordError, Policy};
+    ///
+    /// let policy = Policy::new().reject_all_digits(true);
+    ///
+    /// assert_eq!(
+    ///     policy.check_variety("7382910465"),
+    ///     Err(PasswordError::InsufficientVariety)
+    /// );
+    /// assert_eq!(policy.check_variety("7382910465a"), Ok(()));
+    /// ```
+    pub fn check_variety(&self, password: &str) -> PassablewordResult {
+        if password.is_empty() {
+            return Ok(());
+        }
+
+        let only = |class: fn(char) -> bool| password.chars().all(class);
+
+        if (self.reject_all_digits && only(char::is_numeric))
+            || (self.reject_all_letters && only(char::is_alphabetic))
+        {
+            Err(PasswordError::InsufficientVariety)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check a password against this policy's length rule, and the number of words it requires,
+    /// if any. Without a wordlist, runs of letters aren't split into words. Lengths are counted
+    /// in characters, unlike the free `check_length`, which counts bytes.
+    pub fn check_length(&self, password: &str) -> PassablewordResult {
+        self.check_length_in(password, None)
+    }
+
+    /// Check a password's length like `check_length`, splitting runs of letters into the words
+    /// of `dictionary`.
+    pub(crate) fn check_length_in(
+        &self,
+        password: &str,
+        dictionary: Option<&Wordlist>,
+    ) -> PassablewordResult {
+        let long_enough = match self.length {
+            LengthRule::Characters(length) => password.chars().count() >= length,
+            LengthRule::CharactersOrWords(length, words) => {
+                password.chars().count() >= length || count_words(password, dictionary) >= words
+            }
+        };
+        let enough_words = match self.min_words {
+            Some(words) => count_words(password, dictionary) >= words,
+            None => true,
+        };
+
+        if long_enough && enough_words {
+            Ok(())
+        } else {
+            Err(PasswordError::TooShort)
+        }
+    }
+}
+
+/// Check that a password is at most `max_bytes` long, which only looks at its length.
+pub(crate) fn check_input_size(password: &str, max_bytes: usize) -> PassablewordResult {
+    if password.len() > max_bytes {
+        Err(PasswordError::InputTooLarge)
+    } else {
+        Ok(())
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::new()
+    }
+}
+
+/// Whether `password` contains at least `count` different characters.
+fn has_unique_chars(password: &str, count: usize) -> bool {
+    let mut ascii: u128 = 0;
+    let mut other: Vec<char> = Vec::new();
+    let mut unique = 0;
+
+    for character in password.chars() {
+        if unique >= count {
+            break;
+        }
+
+        if character.is_ascii() {
+            let bit = 1u128 << (character as u32);
+
+            if ascii & bit == 0 {
+                ascii |= bit;
+                unique += 1;
+            }
+        } else if let Err(index) = other.binary_search(&character) {
+            other.insert(index, character);
+            unique += 1;
+        }
+    }
+
+    unique >= count
+}
+
+/// The number of runs of at least three letters, split on anything that isn't a letter and on a
+/// change from lowercase to uppercase.
+fn count_words(password: &str, dictionary: Option<&Wordlist>) -> usize {
+    let mut words = 0;
+    let mut start: Option<usize> = None;
+    let mut previous_lowercase = false;
+
+    for (index, character) in password.char_indices() {
+        let starts_word = character.is_uppercase() && previous_lowercase;
+
+        if !character.is_alphabetic() || starts_word {
+            if let Some(start) = start.take() {
+                words += run_words(&password[start..index], dictionary);
+            }
+        }
+        if character.is_alphabetic() && start.is_none() {
+            start = Some(index);
+        }
+
+        previous_lowercase = character.is_lowercase();
+    }
+
+    if let Some(start) = start {
+        words += run_words(&password[start..], dictionary);
+    }
+
+    words
+}
+
+/// How many words a run of letters counts as: none if it's too short to be a word, the fewest
+/// words of `dictionary` it can be split into, or one if it can't be split.
+fn run_words(run: &str, dictionary: Option<&Wordlist>) -> usize {
+    if run.chars().count() < MIN_WORD_LETTERS {
+        return 0;
+    }
+
+    dictionary
+        .and_then(|dictionary| segment(run, dictionary))
+        .unwrap_or(1)
+}
+
+/// The fewest words of `dictionary`, each at least `MIN_WORD_LETTERS` and at most
+/// `MAX_WORD_LETTERS` letters long, that `run` can be split into, or `None` if it can't be.
+fn segment(run: &str, dictionary: &Wordlist) -> Option<usize> {
+    let lowercase = Scrubbed::from(run.to_lowercase());
+    let mut bounds: Vec<usize> = lowercase.char_indices().map(|(index, _)| index).collect();
+
+    bounds.push(lowercase.len());
+
+    // fewest[end] is the fewest words that the first `end` letters can be split into
+    let letters = bounds.len() - 1;
+    let mut fewest: Vec<Option<usize>> = vec![None; letters + 1];
+
+    fewest[0] = Some(0);
+
+    for end in MIN_WORD_LETTERS..=letters {
+        for start in end.saturating_sub(MAX_WORD_LETTERS)..=end - MIN_WORD_LETTERS {
+            let words = match fewest[start] {
+                Some(words) => words + 1,
+                None => continue,
+            };
+
+            if fewest[end].is_some_and(|fewest| fewest <= words) {
+                continue;
+            }
+            if dictionary.contains(&lowercase[bounds[start]..bounds[end]]) {
+                fewest[end] = Some(words);
+            }
+        }
+    }
+
+    fewest[letters]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_words, has_unique_chars, CharsetMode, Policy, PolicyChange};
+    use crack_time::HashRate;
+    use decision::Check;
+    use lint::PolicyWarning;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    #[test]
+    fn it_counts_words() {
+        assert_eq!(count_words("correct horse battery", None), 3);
+        assert_eq!(count_words("CorrectHorseBattery", None), 3);
+        assert_eq!(count_words("correct-horse-battery-staple", None), 4);
+        assert_eq!(count_words("a b c de fgh", None), 1);
+        assert_eq!(count_words("PASSWORD123abc", None), 2);
+        assert_eq!(count_words("", None), 0);
+    }
+
+    #[test]
+    fn it_splits_words_with_a_dictionary() {
+        let dictionary =
+            Wordlist::parse("correct\nhorse\nbattery\nstaple\nhorsebattery\nbat\ntery");
+        let dictionary = Some(&dictionary);
+
+        assert_eq!(count_words("correcthorsebatterystaple", None), 1);
+        assert_eq!(count_words("correcthorsebatterystaple", dictionary), 3);
+        assert_eq!(count_words("CORRECTstaple", dictionary), 2);
+        assert_eq!(count_words("correcthorsezqx", dictionary), 1);
+        assert_eq!(count_words("ba-tt-tery-correcthorse", dictionary), 3);
+        assert_eq!(count_words("Ünïcode-horsestaple", dictionary), 3);
+    }
+
+    #[test]
+    fn it_validates_required_words() {
+        let policy = Policy::new().min_words(3);
+
+        assert_eq!(policy.check_length("red fox jumps"), Ok(()));
+        assert_eq!(
+            policy.check_length("redfoxjumps"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            policy.check_length("fox jumps"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            Policy::new()
+                .min_length(20)
+                .min_words(3)
+                .check_length("red fox jumps"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            policy.check_length_in("redfoxjumps", Some(&Wordlist::parse("red\nfox\njumps"))),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_validates_unique_chars() {
+        let policy = Policy::new().min_unique_chars(4);
+
+        assert_eq!(
+            policy.check_unique_chars("aaaaaaab"),
+            Err(PasswordError::TooFewUniqueChars)
+        );
+        assert_eq!(policy.check_unique_chars("aabbccdd"), Ok(()));
+        assert_eq!(Policy::new().check_unique_chars("aaaaaaaa"), Ok(()));
+        assert!(has_unique_chars("ééüüaa", 3));
+        assert!(!has_unique_chars("ééüüaa", 4));
+        assert!(has_unique_chars("anything", 0));
+    }
+
+    #[test]
+    fn it_validates_variety() {
+        let policy = Policy::new()
+            .reject_all_digits(true)
+            .reject_all_letters(true);
+
+        assert_eq!(
+            policy.check_variety("7382910465"),
+            Err(PasswordError::InsufficientVariety)
+        );
+        assert_eq!(
+            policy.check_variety("CorrectHorseBattery"),
+            Err(PasswordError::InsufficientVariety)
+        );
+        assert_eq!(
+            policy.check_variety("пароль"),
+            Err(PasswordError::InsufficientVariety)
+        );
+        assert_eq!(policy.check_variety("correct horse"), Ok(()));
+        assert_eq!(policy.check_variety("x7382910465"), Ok(()));
+        assert_eq!(Policy::new().check_variety("7382910465"), Ok(()));
+        assert_eq!(policy.check_variety(""), Ok(()));
+    }
+
+    #[test]
+    fn it_validates_charset() {
+        let ascii = Policy::new().charset_mode(CharsetMode::AsciiOnly);
+        let unicode = Policy::new().charset_mode(CharsetMode::Unicode);
+
+        assert_eq!(
+            ascii.check_charset("contraseña"),
+            Err(PasswordError::NonAsciiPassword)
+        );
+        assert_eq!(ascii.check_charset("contrasena"), Ok(()));
+        assert_eq!(unicode.check_charset("contraseña"), Ok(()));
+        assert!(ascii.to_frontend_rules().ascii_only);
+        assert!(!unicode.to_frontend_rules().ascii_only);
+    }
+
+    #[test]
+    fn it_validates_length() {
+        let policy = Policy::new().min_length(12);
+
+        assert_eq!(
+            policy.check_length("elevenchars"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(policy.check_length("twelve chars"), Ok(()));
+        assert_eq!(
+            Policy::default().check_length("short"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            Policy::default().check_length("üüüü"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(::check_length("üüüü"), Ok(()));
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn it_exports_frontend_rules() {
+        let rules = Policy::new()
+            .min_length(12)
+            .strip_common_affixes(true)
+            .ban_pattern("company name", r"(?i)acme")
+            .unwrap()
+            .to_frontend_rules();
+
+        assert_eq!(rules.min_length, 12);
+        assert_eq!(rules.min_words, None);
+        assert!(rules.rejects_common_variants);
+        assert_eq!(rules.blocked_patterns[0].name, "company name");
+        assert_eq!(rules.blocked_patterns[0].pattern, "(?i)acme");
+    }
+
+    #[test]
+    fn it_lints_weak_policies() {
+        assert_eq!(Policy::new().lint(), vec![]);
+        assert_eq!(Policy::new().min_length_or_words(16, 3).lint(), vec![]);
+        assert_eq!(
+            Policy::new().min_length_or_words(6, 2).lint(),
+            vec![
+                PolicyWarning::ShortMinLength(6),
+                PolicyWarning::FewMinWords(2)
+            ]
+        );
+        assert_eq!(
+            Policy::new()
+                .warn_only(Check::Entropy)
+                .warn_only(Check::Variety)
+                .max_input_bytes(64)
+                .lint(),
+            vec![
+                PolicyWarning::WarnOnly(Check::Entropy),
+                PolicyWarning::SmallInputLimit(64)
+            ]
+        );
+    }
+
+    #[test]
+    fn it_validates_length_or_words() {
+        let policy = Policy::new().min_length_or_words(16, 3);
+
+        assert_eq!(policy.check_length("red fox jumps"), Ok(()));
+        assert_eq!(policy.check_length("a long passphrase"), Ok(()));
+        assert_eq!(
+            policy.check_length("x7#Kq9!zLm2$"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            policy.check_length("ab cd ef gh"),
+            Err(PasswordError::TooShort)
+        );
+    }
+
+    #[test]
+    fn it_diffs_policies() {
+        let old = Policy::new()
+            .with_version("v1")
+            .min_length_or_words(12, 3)
+            .warn_only(Check::Entropy);
+        let new = Policy::new()
+            .with_version("v2")
+            .min_length(12)
+            .min_words(3)
+            .reject_all_digits(true)
+            .assume_hash_rate(HashRate::md5())
+            .warn_only(Check::Length);
+
+        assert_eq!(Policy::diff(&old, &old.clone()), vec![]);
+        assert_eq!(
+            Policy::diff(&old, &new),
+            vec![
+                PolicyChange::Version(Some("v1".to_string()), Some("v2".to_string())),
+                PolicyChange::MinWords(Some(3), None),
+                PolicyChange::RequiredWords(None, Some(3)),
+                PolicyChange::RejectsAllDigits(false, true),
+                PolicyChange::HashRate(HashRate::OFFLINE_SLOW_HASHING, HashRate::md5()),
+                PolicyChange::WarnOnly(Check::Length),
+                PolicyChange::Enforced(Check::Entropy),
+            ]
+        );
+    }
+}
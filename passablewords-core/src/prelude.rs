@@ -0,0 +1,98 @@
+//! Everything a typical integration needs, in a single import:
+//!
+//! ```
+//! use passablewords_core::prelude::*;
+//!
+//! let checker = PasswordChecker::new().with_policy(Policy::new().min_length(12));
+//!
+//! assert_eq!(checker.check_password("password"), Err(PasswordError::TooShort));
+//! assert!("Th1s iS a Sup3rR4ndom PassW0rd!".is_passable());
+//! ```
+//!
+//! That's the checker, its policy and wordlist, the results and reports it returns, the traits
+//! for plugging into it, and `PasswordCheckExt` for calling checks on a `&str` directly. Things
+//! fewer integrations need, like auditing and simulations, stay in the crate root. Nothing here is
+//! brought into scope unless the prelude is imported, and everything in it is also exported from
+//! the crate root.
+
+#[cfg(feature = "breach")]
+pub use breach::BreachProvider;
+pub use checker::PasswordChecker;
+pub use context::CheckContext;
+pub use decision::{Check, DecisionHook, Outcome};
+pub use feedback::Feedback;
+pub use history::PasswordVerifier;
+pub use outcome::CheckOutcome;
+pub use policy::{CharsetMode, Policy};
+pub use report::StrengthReport;
+pub use risk::RiskLevel;
+pub use wordlist::Wordlist;
+pub use {check_password, EntropyAnalyzer, PassablewordResult, PasswordError};
+
+/// Checks for strings, using the bundled list of common passwords. `PasswordCheckExt` is sealed,
+/// so it can only be used on `str`, and `String` through it, and new methods can be added to it
+/// without a major release.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::prelude::*;
+///
+/// assert!("Th1s iS a Sup3rR4ndom PassW0rd!".is_passable());
+/// assert!(!"password".is_passable());
+///
+/// let report = "Th1s iS a Sup3rR4ndom".passable_report(&Policy::new().min_length(24));
+///
+/// assert_eq!(report.outcome, Outcome::Rejected(PasswordError::TooShort));
+/// ```
+pub trait PasswordCheckExt: private::Sealed {
+    /// Whether this password passes `check_password`.
+    fn is_passable(&self) -> bool;
+
+    /// Check this password against `policy`, returning a report like `PasswordChecker::report`.
+    fn passable_report(&self, policy: &Policy) -> StrengthReport;
+}
+
+impl PasswordCheckExt for str {
+    fn is_passable(&self) -> bool {
+        check_password(self).is_ok()
+    }
+
+    fn passable_report(&self, policy: &Policy) -> StrengthReport {
+        PasswordChecker::new()
+            .with_policy(policy.clone())
+            .report(self)
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for str {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PasswordCheckExt;
+    use decision::Outcome;
+    use policy::Policy;
+    use PasswordError;
+
+    #[test]
+    fn it_checks_strings() {
+        let password = String::from("Th1s iS a Sup3rR4ndom PassW0rd!");
+
+        assert!(password.is_passable());
+        assert!(!"letmein1".is_passable());
+        assert_eq!(
+            password.passable_report(&Policy::new()).outcome,
+            Outcome::Accepted
+        );
+        assert_eq!(
+            password
+                .passable_report(&Policy::new().with_version("v2").min_length(40))
+                .outcome,
+            Outcome::Rejected(PasswordError::TooShort)
+        );
+    }
+}
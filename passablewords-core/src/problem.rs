@@ -0,0 +1,260 @@
+//! Rejected passwords as RFC 7807 problem details, the `application/problem+json` bodies that
+//! REST services return for errors. Only available with the `problem` feature.
+
+use std::fmt::Write;
+
+use frontend::push_json_string;
+use outcome::CheckOutcome;
+use PasswordError;
+
+/// The media type to send `Problem::to_json` with.
+pub const PROBLEM_CONTENT_TYPE: &str = "application/problem+json";
+
+/// The `type` of problems about passwords that were rejected, unless `Problem::with_type` says
+/// otherwise.
+pub const REJECTED_PROBLEM_TYPE: &str = "urn:passablewords:password-rejected";
+
+/// The `type` of problems about passwords that couldn't be checked, like when the list of common
+/// passwords is missing.
+pub const UNCHECKED_PROBLEM_TYPE: &str = "urn:passablewords:password-unchecked";
+
+impl PasswordError {
+    /// The HTTP status to respond with: `413 Payload Too Large` for `InputTooLarge`, `500
+    /// Internal Server Error` when the password couldn't be checked, and `422 Unprocessable
+    /// Entity` when it was rejected.
+    pub fn http_status(&self) -> u16 {
+        match *self {
+            PasswordError::InputTooLarge => 413,
+            PasswordError::Cancelled
+            | PasswordError::WordlistUnavailable { .. }
+            | PasswordError::InternalError => 500,
+            _ => 422,
+        }
+    }
+}
+
+/// An RFC 7807 problem detail for a password that was rejected, or couldn't be checked, built
+/// from a `PasswordError` or a failed `CheckOutcome`. Every failure is listed in `errors`, so a
+/// form can show all of them at once.
+///
+/// # JSON format
+///
+/// `to_json` (and `serde`, with the `serde` feature) produce an object with these keys.
+///
+/// | Key | Type | Meaning |
+/// |-----|------|---------|
+/// | `type` | string | A URI for the kind of problem, `REJECTED_PROBLEM_TYPE` or `UNCHECKED_PROBLEM_TYPE` by default |
+/// | `title` | string | A short summary of the kind of problem, which doesn't change between occurrences |
+/// | `status` | integer | The HTTP status, from `PasswordError::http_status` |
+/// | `detail` | string | The explanation of the first failure |
+/// | `errors` | array | Every failure, as an object with the `code` that `PasswordError::to_extension_map` uses and the failure's `detail` |
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{PasswordChecker, Problem, PROBLEM_CONTENT_TYPE};
+///
+/// let checker = PasswordChecker::new();
+/// let problem = Problem::from_outcome(&checker.check_password_detailed("qwerty")).unwrap();
+///
+/// assert_eq!(problem.status, 422);
+/// assert_eq!(problem.errors[0].code, "TOO_SHORT");
+/// assert_eq!(PROBLEM_CONTENT_TYPE, "application/problem+json");
+/// assert!(problem.to_json().starts_with(
+///     "{\"type\":\"urn:passablewords:password-rejected\",\"title\":\"Your password was rejected.\""
+/// ));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct Problem {
+    /// A URI for the kind of problem.
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub problem_type: String,
+    /// A short summary of the kind of problem.
+    pub title: String,
+    /// The HTTP status.
+    pub status: u16,
+    /// The explanation of the first failure.
+    pub detail: String,
+    /// Every failure, in the order the checks are run.
+    pub errors: Vec<ProblemError>,
+}
+
+/// A single failure in a `Problem`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ProblemError {
+    /// The error's code, like `TOO_SHORT`.
+    pub code: String,
+    /// The error's explanation, from `PasswordError::explanation`.
+    pub detail: String,
+}
+
+impl Problem {
+    /// The problem for a single error.
+    pub fn from_error(error: &PasswordError) -> Problem {
+        Problem::from_errors(&[error])
+    }
+
+    /// The problem for a password that failed the checks, or `None` if it was accepted, with or
+    /// without warnings. The status is the highest of the failures', so a check that couldn't run
+    /// isn't reported as the password's fault.
+    pub fn from_outcome(outcome: &CheckOutcome) -> Option<Problem> {
+        match *outcome {
+            CheckOutcome::Fail(ref failures) if !failures.is_empty() => {
+                let errors: Vec<&PasswordError> =
+                    failures.iter().map(|failure| &failure.error).collect();
+
+                Some(Problem::from_errors(&errors))
+            }
+            _ => None,
+        }
+    }
+
+    /// Use `problem_type` as the problem's `type`, like a link to the application's own
+    /// documentation for it.
+    pub fn with_type(mut self, problem_type: &str) -> Problem {
+        self.problem_type = problem_type.to_string();
+        self
+    }
+
+    /// The problem as a JSON object, in the format described above, to send with
+    /// `PROBLEM_CONTENT_TYPE`. This doesn't need the `serde` feature.
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"type\":");
+
+        push_json_string(&mut json, &self.problem_type);
+        json.push_str(",\"title\":");
+        push_json_string(&mut json, &self.title);
+        let _ = write!(json, ",\"status\":{},\"detail\":", self.status);
+        push_json_string(&mut json, &self.detail);
+        json.push_str(",\"errors\":[");
+
+        for (index, error) in self.errors.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            json.push_str("{\"code\":");
+            push_json_string(&mut json, &error.code);
+            json.push_str(",\"detail\":");
+            push_json_string(&mut json, &error.detail);
+            json.push('}');
+        }
+
+        json.push_str("]}");
+        json
+    }
+
+    /// The problem for `errors`, which can't be empty.
+    fn from_errors(errors: &[&PasswordError]) -> Problem {
+        let status = errors
+            .iter()
+            .map(|error| error.http_status())
+            .max()
+            .unwrap_or(422);
+        let (problem_type, title) = if status >= 500 {
+            (UNCHECKED_PROBLEM_TYPE, "Your password couldn't be checked.")
+        } else {
+            (REJECTED_PROBLEM_TYPE, "Your password was rejected.")
+        };
+
+        Problem {
+            problem_type: problem_type.to_string(),
+            title: title.to_string(),
+            status,
+            detail: errors
+                .first()
+                .map_or("", |error| error.explanation())
+                .to_string(),
+            errors: errors
+                .iter()
+                .map(|error| ProblemError {
+                    code: error.code().to_string(),
+                    detail: error.explanation().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<PasswordError> for Problem {
+    fn from(error: PasswordError) -> Problem {
+        Problem::from_error(&error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use decision::Check;
+    use outcome::{Failure, Warning};
+
+    #[test]
+    fn it_describes_failures() {
+        let outcome = CheckOutcome::Fail(vec![
+            Failure {
+                check: Check::Length,
+                error: PasswordError::TooShort,
+            },
+            Failure {
+                check: Check::Uniqueness,
+                error: PasswordError::TooCommon,
+            },
+        ]);
+        let problem = Problem::from_outcome(&outcome)
+            .unwrap()
+            .with_type("https://example.com/problems/weak-password");
+
+        assert_eq!(
+            problem.to_json(),
+            "{\"type\":\"https://example.com/problems/weak-password\",\
+             \"title\":\"Your password was rejected.\",\"status\":422,\
+             \"detail\":\"Your password is too short.\",\"errors\":[\
+             {\"code\":\"TOO_SHORT\",\"detail\":\"Your password is too short.\"},\
+             {\"code\":\"TOO_COMMON\",\"detail\":\"Your password is one of the most commonly \
+             used passwords.\"}]}"
+        );
+        assert_eq!(Problem::from_outcome(&CheckOutcome::Pass), None);
+        assert_eq!(
+            Problem::from_outcome(&CheckOutcome::PassWithWarnings(vec![Warning {
+                check: Check::Length,
+                error: PasswordError::TooShort,
+            }])),
+            None
+        );
+    }
+
+    #[test]
+    fn it_picks_the_highest_status() {
+        let outcome = CheckOutcome::Fail(vec![
+            Failure {
+                check: Check::Length,
+                error: PasswordError::TooShort,
+            },
+            Failure {
+                check: Check::Uniqueness,
+                error: PasswordError::WordlistUnavailable {
+                    path: None,
+                    source: "missing".to_string(),
+                },
+            },
+        ]);
+        let problem = Problem::from_outcome(&outcome).unwrap();
+
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.problem_type, UNCHECKED_PROBLEM_TYPE);
+        assert_eq!(problem.detail, "Your password is too short.");
+        assert_eq!(Problem::from(PasswordError::InputTooLarge).status, 413);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn it_serializes_like_to_json() {
+        let problem = Problem::from(PasswordError::KeyboardWalk);
+        let serialized: ::serde_json::Value = ::serde_json::to_value(&problem).unwrap();
+        let handwritten: ::serde_json::Value = ::serde_json::from_str(&problem.to_json()).unwrap();
+
+        assert_eq!(serialized, handwritten);
+    }
+}
@@ -0,0 +1,141 @@
+//! How thoroughly a password's entropy is analyzed, for trading accuracy for speed in batch jobs
+//! like audits.
+
+use analyzer::{check_entropy_analyzed, EntropyAnalyzer};
+use compat::Compat;
+use policy::{check_input_size, DEFAULT_MAX_INPUT_BYTES};
+use quick_entropy::quick_entropy_bits;
+use {PassablewordResult, PasswordError};
+
+/// The most characters the `Fast` profile hands to zxcvbn. zxcvbn looks for dictionary words in
+/// every substring, so its cost grows much faster than the password's length.
+pub const FAST_PROFILE_MAX_LENGTH: usize = 32;
+
+/// Passwords which `quick_entropy_bits` rates below this are rejected by the `Fast` profile
+/// without running zxcvbn. It's just under the `10^8` guesses zxcvbn needs before it accepts a
+/// password, and the quick estimate can't see words or dates, so it rarely rates a password
+/// higher than zxcvbn does.
+const FAST_PROFILE_REJECT_BITS: f64 = 26.0;
+
+/// How thoroughly `check_entropy_with` analyzes a password.
+///
+/// - `Thorough` runs zxcvbn on the whole password, so it's exactly as accurate as
+///   `check_entropy`, but slows down sharply on long passwords.
+/// - `Fast` rejects passwords that `quick_entropy_bits` already finds weak without running
+///   zxcvbn, and only analyzes the first `FAST_PROFILE_MAX_LENGTH` characters of the rest. Its
+///   time per password is bounded, which suits batch jobs like audits. It can reject a long
+///   password whose first 32 characters are weak, even if the rest is random, and very rarely a
+///   short password which zxcvbn would have accepted. A password whose first 32 characters are
+///   strong is strong however it goes on, so the two profiles accept the same passwords
+///   otherwise.
+///
+/// The same goes for a custom `EntropyAnalyzer`, with it in place of zxcvbn. Without the `zxcvbn`
+/// feature or a custom analyzer, both profiles use `check_quick_entropy`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_entropy_with, AnalysisProfile, PasswordError};
+///
+/// assert_eq!(
+///     check_entropy_with("aaaaaaaaaaaaaaab", AnalysisProfile::Fast),
+///     Err(PasswordError::TooSimple)
+/// );
+/// assert_eq!(
+///     check_entropy_with("Th1s iS a Sup3rR4ndom PassW0rd!", AnalysisProfile::Fast),
+///     Ok(())
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum AnalysisProfile {
+    /// Run zxcvbn's full analysis on the whole password. This is the default.
+    #[default]
+    Thorough,
+    /// Reject passwords the quick estimate already finds weak without running zxcvbn, and only
+    /// run zxcvbn on the first `FAST_PROFILE_MAX_LENGTH` characters of the rest.
+    Fast,
+}
+
+/// Check a password's entropy like `check_entropy` does, as thoroughly as `profile` asks for.
+pub fn check_entropy_with(password: &str, profile: AnalysisProfile) -> PassablewordResult {
+    check_input_size(password, DEFAULT_MAX_INPUT_BYTES)?;
+    check_entropy_profiled(password, &[], profile, Compat::Latest, 0, None)
+}
+
+/// Check a password's entropy with `analyzer`, or the default analysis if there isn't one, as
+/// thoroughly as `profile` asks for, deciding whether it passes as `compat` and `score_floor`
+/// say to.
+pub(crate) fn check_entropy_profiled(
+    password: &str,
+    inputs: &[&str],
+    profile: AnalysisProfile,
+    compat: Compat,
+    score_floor: u8,
+    analyzer: Option<&dyn EntropyAnalyzer>,
+) -> PassablewordResult {
+    match profile {
+        // check_quick_entropy is already fast, so there's nothing to skip without an analyzer
+        AnalysisProfile::Fast if cfg!(feature = "zxcvbn") || analyzer.is_some() => {
+            if quick_entropy_bits(password) < FAST_PROFILE_REJECT_BITS {
+                return Err(PasswordError::TooSimple);
+            }
+
+            let end = password
+                .char_indices()
+                .nth(FAST_PROFILE_MAX_LENGTH)
+                .map_or(password.len(), |(index, _)| index);
+
+            check_entropy_analyzed(&password[..end], inputs, compat, score_floor, analyzer)
+        }
+        AnalysisProfile::Thorough | AnalysisProfile::Fast => {
+            check_entropy_analyzed(password, inputs, compat, score_floor, analyzer)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_entropy_with, AnalysisProfile};
+    use PasswordError;
+
+    #[test]
+    fn it_rejects_obviously_weak_passwords_quickly() {
+        for password in ["aaaaaaaaaaaaaaab", "abcdefghijklmnop", "1234512345123451"].iter() {
+            assert_eq!(
+                check_entropy_with(password, AnalysisProfile::Fast),
+                Err(PasswordError::TooSimple)
+            );
+        }
+    }
+
+    #[test]
+    fn it_agrees_with_the_thorough_profile() {
+        let passwords = [
+            "password1",
+            "Th1s iS a Sup3rR4ndom PassW0rd!",
+            "correct-horse-battery-staple",
+            "q8#Lm2$vX9!tR4&w",
+        ];
+
+        for password in passwords.iter() {
+            assert_eq!(
+                check_entropy_with(password, AnalysisProfile::Fast),
+                check_entropy_with(password, AnalysisProfile::Thorough),
+                "{}",
+                password
+            );
+        }
+    }
+
+    #[test]
+    fn it_bounds_long_passwords() {
+        let long = "Th1s iS a Sup3rR4ndom PassW0rd!".repeat(30);
+        let too_long = "Th1s iS a Sup3rR4ndom PassW0rd!".repeat(10_000);
+
+        assert_eq!(check_entropy_with(&long, AnalysisProfile::Fast), Ok(()));
+        assert_eq!(
+            check_entropy_with(&too_long, AnalysisProfile::Fast),
+            Err(PasswordError::InputTooLarge)
+        );
+    }
+}
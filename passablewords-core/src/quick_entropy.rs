@@ -0,0 +1,148 @@
+//! A rough entropy estimate based on the kinds of characters in a password, for builds where
+//! zxcvbn is too large.
+
+use std::collections::HashSet;
+
+use {PassablewordResult, PasswordError};
+
+/// The fewest bits of estimated entropy that pass `check_quick_entropy`. This is much higher than
+/// the entropy of a password zxcvbn would accept, because the estimate can't see dictionary words
+/// and assumes every other character was picked at random.
+pub const QUICK_ENTROPY_MIN_BITS: f64 = 50.0;
+
+/// Estimate a password's entropy in bits from the size of the character set it uses and its
+/// length. Characters are drawn from lowercase letters (26), uppercase letters (26), digits
+/// (10), ASCII symbols and spaces (33), and everything else (100), and each one adds
+/// `log2(character set size)` bits, with a few penalties for predictable patterns.
+///
+/// - A character repeating the one before it, as in `aaaa`, adds nothing.
+/// - A character following on from the one before it, as in `abcd` or `4321`, adds nothing.
+/// - A character that already appeared earlier in the password adds half as much.
+///
+/// This is far cruder than zxcvbn, which knows about words, dates, names, and keyboard patterns,
+/// but it's tiny and fast.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::quick_entropy_bits;
+///
+/// assert!(quick_entropy_bits("aaaaaaab") < 10.0);
+/// assert!(quick_entropy_bits("Th1s iS a Sup3rR4ndom PassW0rd!") > 100.0);
+/// ```
+pub fn quick_entropy_bits(password: &str) -> f64 {
+    let mut lowercase = false;
+    let mut uppercase = false;
+    let mut digits = false;
+    let mut symbols = false;
+    let mut other = false;
+
+    for character in password.chars() {
+        match character {
+            'a'..='z' => lowercase = true,
+            'A'..='Z' => uppercase = true,
+            '0'..='9' => digits = true,
+            ' '..='~' => symbols = true,
+            _ => other = true,
+        }
+    }
+
+    let pool = [
+        (lowercase, 26),
+        (uppercase, 26),
+        (digits, 10),
+        (symbols, 33),
+        (other, 100),
+    ]
+    .iter()
+    .filter(|&&(present, _)| present)
+    .map(|&(_, size)| size)
+    .sum::<u32>();
+
+    if pool == 0 {
+        return 0.0;
+    }
+
+    let bits_per_character = f64::from(pool).log2();
+    let mut bits = 0.0;
+    let mut previous: Option<char> = None;
+    let mut seen_ascii: u128 = 0;
+    let mut seen_other = HashSet::new();
+
+    for character in password.chars() {
+        let predictable = previous.is_some_and(|previous| {
+            (i64::from(u32::from(character)) - i64::from(u32::from(previous))).abs() <= 1
+        });
+        let seen = if character.is_ascii() {
+            let bit = 1u128 << u32::from(character);
+            let seen = seen_ascii & bit != 0;
+
+            seen_ascii |= bit;
+            seen
+        } else {
+            !seen_other.insert(character)
+        };
+
+        // Repeats and sequences add nothing
+        if !predictable {
+            bits += if seen {
+                bits_per_character / 2.0
+            } else {
+                bits_per_character
+            };
+        }
+
+        previous = Some(character);
+    }
+
+    bits
+}
+
+/// Check a password to make sure its `quick_entropy_bits` are at least
+/// `QUICK_ENTROPY_MIN_BITS`. `check_entropy` falls back to this when the `zxcvbn` feature is
+/// turned off.
+pub fn check_quick_entropy(password: &str) -> PassablewordResult {
+    check_quick_entropy_bits(password, QUICK_ENTROPY_MIN_BITS)
+}
+
+/// Check a password to make sure its `quick_entropy_bits` are at least `min_bits`.
+pub(crate) fn check_quick_entropy_bits(password: &str, min_bits: f64) -> PassablewordResult {
+    if quick_entropy_bits(password) >= min_bits {
+        Ok(())
+    } else {
+        Err(PasswordError::TooSimple)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_quick_entropy, quick_entropy_bits};
+    use PasswordError;
+
+    #[test]
+    fn it_estimates_entropy() {
+        let lowercase = 26f64.log2();
+
+        assert_eq!(quick_entropy_bits(""), 0.0);
+        assert_eq!(quick_entropy_bits("aaaaaaaa"), lowercase);
+        assert_eq!(quick_entropy_bits("abcdefgh"), lowercase);
+        assert!((quick_entropy_bits("acegaceg") - lowercase * 6.0).abs() < 1e-9);
+        assert!(quick_entropy_bits("Tr0ub4dor&3") > quick_entropy_bits("troubadour"));
+    }
+
+    #[test]
+    fn it_validates_quick_entropy() {
+        assert_eq!(
+            check_quick_entropy("aaaaaaaaaaaaaaaaaaaab"),
+            Err(PasswordError::TooSimple)
+        );
+        assert_eq!(
+            check_quick_entropy("12345678901234567890"),
+            Err(PasswordError::TooSimple)
+        );
+        assert_eq!(
+            check_quick_entropy("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Checking the format and strength of recovery codes, the one-time codes users keep in case they
+//! lose their second factor. With the `generate` feature, `generate_recovery_codes` makes codes
+//! that pass.
+
+use policy::{check_input_size, DEFAULT_MAX_INPUT_BYTES};
+use {PassablewordResult, PasswordError};
+
+/// Crockford's base 32, which leaves out `I`, `L`, `O`, and `U` so codes can't be misread.
+const CROCKFORD_BASE32: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// The format recovery codes have to follow: which characters they're made of, how many of them,
+/// and how they're grouped for display. The default is 10 characters of Crockford's base 32 in
+/// two groups of 5, like `7KQ2M-X9D4T`, which is 50 bits.
+///
+/// Separators and whitespace are ignored when checking a code, and so is case unless
+/// `case_sensitive` is set, since users copy codes in all sorts of ways.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{check_recovery_code, PasswordError, RecoveryCodePolicy};
+///
+/// let policy = RecoveryCodePolicy::new();
+///
+/// assert_eq!(check_recovery_code("7KQ2M-X9D4T", &policy), Ok(()));
+/// assert_eq!(check_recovery_code("7kq2m x9d4t", &policy), Ok(()));
+/// assert_eq!(check_recovery_code("7KQ2M", &policy), Err(PasswordError::TooShort));
+/// assert_eq!(
+///     check_recovery_code("7KQ2M-X9D4U", &policy),
+///     Err(PasswordError::InvalidFormat)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryCodePolicy {
+    alphabet: Vec<char>,
+    length: usize,
+    group_size: Option<usize>,
+    separator: char,
+    case_sensitive: bool,
+    min_entropy_bits: f64,
+    min_unique_chars: usize,
+}
+
+impl RecoveryCodePolicy {
+    /// Create a policy with the default format.
+    pub fn new() -> RecoveryCodePolicy {
+        RecoveryCodePolicy {
+            alphabet: CROCKFORD_BASE32.chars().collect(),
+            length: 10,
+            group_size: Some(5),
+            separator: '-',
+            case_sensitive: false,
+            min_entropy_bits: 40.0,
+            min_unique_chars: 4,
+        }
+    }
+
+    /// Make codes out of the characters in `alphabet`. Repeated characters are only counted once.
+    pub fn alphabet(mut self, alphabet: &str) -> RecoveryCodePolicy {
+        self.alphabet = Vec::new();
+
+        for character in alphabet.chars() {
+            if !self.alphabet.contains(&character) {
+                self.alphabet.push(character);
+            }
+        }
+
+        self
+    }
+
+    /// Make codes exactly `length` characters long, not counting separators.
+    pub fn length(mut self, length: usize) -> RecoveryCodePolicy {
+        self.length = length;
+        self
+    }
+
+    /// Split generated codes into groups of `size` characters with the separator between them,
+    /// or don't split them at all with `None`.
+    pub fn group_size(mut self, size: Option<usize>) -> RecoveryCodePolicy {
+        self.group_size = size.filter(|&size| size > 0);
+        self
+    }
+
+    /// Put `separator` between groups of a generated code. It's ignored wherever it appears in a
+    /// code that's being checked.
+    pub fn separator(mut self, separator: char) -> RecoveryCodePolicy {
+        self.separator = separator;
+        self
+    }
+
+    /// Only accept codes typed in the same case as the alphabet, rather than in either case. Only
+    /// turn this on for alphabets with both cases, which are harder to read out and type.
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> RecoveryCodePolicy {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Require the format to allow at least `bits` of entropy, which is the length times the
+    /// bits per character of the alphabet. Every code is rejected as `PasswordError::TooSimple`
+    /// when the format can't reach it, so a weakened policy is caught straight away.
+    pub fn min_entropy_bits(mut self, bits: f64) -> RecoveryCodePolicy {
+        self.min_entropy_bits = bits;
+        self
+    }
+
+    /// Require codes to contain at least `count` different characters, so that ones like
+    /// `AAAAA-AAAAA` are rejected as `PasswordError::TooFewUniqueChars`.
+    pub fn min_unique_chars(mut self, count: usize) -> RecoveryCodePolicy {
+        self.min_unique_chars = count;
+        self
+    }
+
+    /// The characters codes are made of.
+    pub fn characters(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    /// How many characters codes have, not counting separators.
+    pub fn code_length(&self) -> usize {
+        self.length
+    }
+
+    /// How many bits of entropy a random code in this format has.
+    pub fn entropy_bits(&self) -> f64 {
+        if self.alphabet.len() < 2 {
+            return 0.0;
+        }
+
+        self.length as f64 * (self.alphabet.len() as f64).log2()
+    }
+
+    /// Split `code` into groups with the separator between them, for displaying a code that was
+    /// generated elsewhere.
+    pub fn format(&self, code: &str) -> String {
+        let size = match self.group_size {
+            Some(size) => size,
+            None => return code.to_string(),
+        };
+        let mut formatted = String::with_capacity(code.len() + code.len() / size);
+
+        for (index, character) in code.chars().enumerate() {
+            if index > 0 && index % size == 0 {
+                formatted.push(self.separator);
+            }
+            formatted.push(character);
+        }
+
+        formatted
+    }
+
+    fn contains(&self, character: char) -> bool {
+        if self.case_sensitive {
+            self.alphabet.contains(&character)
+        } else {
+            self.alphabet
+                .iter()
+                .any(|allowed| allowed.to_lowercase().eq(character.to_lowercase()))
+        }
+    }
+}
+
+impl Default for RecoveryCodePolicy {
+    fn default() -> RecoveryCodePolicy {
+        RecoveryCodePolicy::new()
+    }
+}
+
+/// Check that a recovery code follows `policy`, and that the policy itself makes strong enough
+/// codes. Separators and whitespace are ignored, and so is case unless the policy is case
+/// sensitive.
+///
+/// A code with too few characters is `PasswordError::TooShort`, while one with too many or with
+/// characters outside of the alphabet is `PasswordError::InvalidFormat`. Codes over
+/// `DEFAULT_MAX_INPUT_BYTES` are `PasswordError::InputTooLarge`, without being looked at.
+pub fn check_recovery_code(code: &str, policy: &RecoveryCodePolicy) -> PassablewordResult {
+    check_input_size(code, DEFAULT_MAX_INPUT_BYTES)?;
+
+    if policy.entropy_bits() < policy.min_entropy_bits {
+        return Err(PasswordError::TooSimple);
+    }
+
+    let characters: Vec<char> = code
+        .chars()
+        .filter(|&character| character != policy.separator && !character.is_whitespace())
+        .collect();
+
+    if characters.len() < policy.length {
+        return Err(PasswordError::TooShort);
+    }
+    if characters.len() > policy.length
+        || !characters
+            .iter()
+            .all(|&character| policy.contains(character))
+    {
+        return Err(PasswordError::InvalidFormat);
+    }
+
+    let mut unique: Vec<char> = Vec::new();
+
+    for character in characters {
+        let character = if policy.case_sensitive {
+            character
+        } else {
+            character.to_lowercase().next().unwrap_or(character)
+        };
+
+        if !unique.contains(&character) {
+            unique.push(character);
+        }
+    }
+
+    if unique.len() < policy.min_unique_chars {
+        return Err(PasswordError::TooFewUniqueChars);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_recovery_code, RecoveryCodePolicy};
+    use PasswordError;
+
+    #[test]
+    fn it_checks_recovery_codes() {
+        let policy = RecoveryCodePolicy::new();
+
+        assert_eq!(check_recovery_code("7KQ2M-X9D4T", &policy), Ok(()));
+        assert_eq!(check_recovery_code(" 7kq2mx9d4t\n", &policy), Ok(()));
+        assert_eq!(
+            check_recovery_code("7KQ2M-X9D4", &policy),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            check_recovery_code("7KQ2M-X9D4T-A", &policy),
+            Err(PasswordError::InvalidFormat)
+        );
+        assert_eq!(
+            check_recovery_code("7KQ2M-X9D4O", &policy),
+            Err(PasswordError::InvalidFormat)
+        );
+        assert_eq!(
+            check_recovery_code("AAAAA-AAAAB", &policy),
+            Err(PasswordError::TooFewUniqueChars)
+        );
+        assert_eq!(
+            check_recovery_code(&"-".repeat(2048), &policy),
+            Err(PasswordError::InputTooLarge)
+        );
+    }
+
+    #[test]
+    fn it_checks_the_policy_is_strong_enough() {
+        let digits = RecoveryCodePolicy::new().alphabet("0123456789").length(8);
+
+        assert!((digits.entropy_bits() - 26.575).abs() < 0.001);
+        assert_eq!(
+            check_recovery_code("8302-9175", &digits),
+            Err(PasswordError::TooSimple)
+        );
+        assert_eq!(
+            check_recovery_code("8302-9175", &digits.min_entropy_bits(20.0)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_respects_case_sensitivity() {
+        let policy = RecoveryCodePolicy::new()
+            .alphabet("abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789")
+            .case_sensitive(true);
+
+        assert_eq!(check_recovery_code("aB3dE-fG4hJ", &policy), Ok(()));
+        assert_eq!(
+            check_recovery_code("aB3dE-fG4hl", &policy),
+            Err(PasswordError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn it_formats_codes_in_groups() {
+        let policy = RecoveryCodePolicy::new();
+
+        assert_eq!(policy.format("7KQ2MX9D4T"), "7KQ2M-X9D4T");
+        assert_eq!(
+            policy
+                .group_size(Some(4))
+                .separator(' ')
+                .format("7KQ2MX9D4T"),
+            "7KQ2 MX9D 4T"
+        );
+        assert_eq!(
+            RecoveryCodePolicy::new()
+                .group_size(None)
+                .format("7KQ2MX9D4T"),
+            "7KQ2MX9D4T"
+        );
+    }
+}
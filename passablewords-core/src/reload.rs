@@ -0,0 +1,343 @@
+//! A checker whose wordlist and policy can be refreshed while the program is running, for
+//! long-lived services that pick up new blocklists without a restart.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+#[cfg(feature = "watch")]
+use std::time::Duration;
+
+use checker::PasswordChecker;
+use policy::Policy;
+use wordlist::Wordlist;
+use PassablewordResult;
+
+/// How long the file has to stay unchanged before `watch` reloads it, so that a file written in
+/// several steps is only read once it's complete.
+#[cfg(feature = "watch")]
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// A checker backed by a wordlist file which can be reloaded at any time. Reloading reads and
+/// parses the whole file before atomically swapping it in, so checks running at the same time
+/// always see either the old or the new list in full. The policy can be swapped the same way with
+/// `set_policy`.
+///
+/// Cloning a `ReloadableChecker` is cheap and every clone shares the same list and policy, so a
+/// reload through any of them is visible to all of them.
+///
+/// # Example
+///
+/// ```no_run
+/// use passablewords_core::{Policy, ReloadableChecker};
+///
+/// let checker = ReloadableChecker::from_file("/etc/passablewords/blocklist.txt").unwrap();
+///
+/// // Later, after the file has been updated
+/// checker.reload().unwrap();
+/// checker.set_policy(Policy::new().min_length(12));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ReloadableChecker {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    path: PathBuf,
+    wordlist: RwLock<Arc<Wordlist>>,
+    policy: RwLock<Arc<Policy>>,
+}
+
+impl Inner {
+    fn wordlist(&self) -> Arc<Wordlist> {
+        match self.wordlist.read() {
+            Ok(wordlist) => wordlist.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    fn reload(&self, force: bool) -> io::Result<()> {
+        let wordlist = Wordlist::from_file(&self.path)?;
+        let current = self.wordlist().len();
+
+        // A file that's been truncated, or is still being written, would let every password
+        // through until the next reload
+        if !force && (wordlist.is_empty() || wordlist.len().saturating_mul(2) < current) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "{} has {} passwords, less than half of the {} in use",
+                    self.path.display(),
+                    wordlist.len(),
+                    current
+                ),
+            ));
+        }
+
+        let wordlist = Arc::new(wordlist);
+
+        match self.wordlist.write() {
+            Ok(mut current) => *current = wordlist,
+            Err(poisoned) => *poisoned.into_inner() = wordlist,
+        }
+
+        Ok(())
+    }
+}
+
+impl ReloadableChecker {
+    /// Create a checker from a wordlist file with one password per line, using the default
+    /// policy. The file is read right away and then again every time `reload` is called.
+    pub fn from_file<P: Into<PathBuf>>(path: P) -> io::Result<ReloadableChecker> {
+        let path = path.into();
+        let wordlist = Wordlist::from_file(&path)?;
+
+        Ok(ReloadableChecker {
+            inner: Arc::new(Inner {
+                path,
+                wordlist: RwLock::new(Arc::new(wordlist)),
+                policy: RwLock::new(Arc::new(Policy::new())),
+            }),
+        })
+    }
+
+    /// The path of the wordlist file this checker reads from.
+    pub fn path(&self) -> &Path {
+        &self.inner.path
+    }
+
+    /// Read the wordlist file again and swap it in. If the file can't be read, is empty, or has
+    /// less than half as many passwords as the list in use, which usually means it's only been
+    /// partly written, an error is returned and the previous list stays in place. Use
+    /// `force_reload` to swap in a list that's meant to be that much smaller.
+    pub fn reload(&self) -> io::Result<()> {
+        self.inner.reload(false)
+    }
+
+    /// Read the wordlist file again and swap it in, however small it is. If the file can't be
+    /// read, the error is returned and the previous list stays in place.
+    pub fn force_reload(&self) -> io::Result<()> {
+        self.inner.reload(true)
+    }
+
+    /// The wordlist that's currently in use. The returned list won't change, even if the checker
+    /// is reloaded while it's being held onto.
+    pub fn wordlist(&self) -> Arc<Wordlist> {
+        self.inner.wordlist()
+    }
+
+    /// The policy that's currently in use.
+    pub fn policy(&self) -> Arc<Policy> {
+        match self.inner.policy.read() {
+            Ok(policy) => policy.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Swap in a new policy, such as one read again with `PolicyConfig`. Checks running at the
+    /// same time see either the old or the new policy in full.
+    pub fn set_policy(&self, policy: Policy) {
+        let policy = Arc::new(policy);
+
+        match self.inner.policy.write() {
+            Ok(mut current) => *current = policy,
+            Err(poisoned) => *poisoned.into_inner() = policy,
+        }
+    }
+
+    /// A `PasswordChecker` using the wordlist and policy that are currently in use. Like
+    /// `wordlist`, the returned checker keeps using them even if this one is reloaded.
+    pub fn checker(&self) -> PasswordChecker {
+        PasswordChecker::with_wordlist(self.wordlist()).with_shared_policy(self.policy())
+    }
+
+    /// Check a password to make sure it's not within the currently loaded wordlist.
+    pub fn check_uniqueness(&self, password: &str) -> PassablewordResult {
+        self.checker().check_uniqueness(password)
+    }
+
+    /// Check a password's length, uniqueness against the currently loaded wordlist, and entropy
+    /// all in a single call, with the current policy.
+    pub fn check_password(&self, password: &str) -> PassablewordResult {
+        self.checker().check_password(password)
+    }
+
+    /// Watch the wordlist file and reload it whenever it changes. Watching stops once the
+    /// returned watcher is dropped.
+    ///
+    /// The directory holding the file is watched rather than the file itself, so replacing the
+    /// file by renaming another one over it is picked up, and the file is only reloaded once it
+    /// has stopped changing for half a second. A change that leaves the file unreadable, empty,
+    /// or much smaller is ignored like it is by `reload`, and the previous list stays in place
+    /// until the next successful reload. Only the wordlist is watched; swap in a new policy with
+    /// `set_policy`.
+    #[cfg(feature = "watch")]
+    pub fn watch(&self) -> ::notify::Result<::notify::RecommendedWatcher> {
+        use notify::{recommended_watcher, Event, EventKind, RecursiveMode, Watcher};
+        use std::sync::mpsc;
+        use std::thread;
+
+        let path = &self.inner.path;
+        let file_name = path.file_name().map(|name| name.to_os_string());
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let (sender, receiver) = mpsc::channel();
+
+        let mut watcher = recommended_watcher(move |event: ::notify::Result<Event>| {
+            let event = match event {
+                Ok(event) => event,
+                Err(_) => return,
+            };
+            let changed = match event.kind {
+                EventKind::Create(_) | EventKind::Modify(_) => event
+                    .paths
+                    .iter()
+                    .any(|path| path.file_name() == file_name.as_deref()),
+                _ => false,
+            };
+
+            if changed {
+                let _ = sender.send(());
+            }
+        })?;
+
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        // The sender is dropped along with the watcher, which ends the thread
+        let inner = self.inner.clone();
+        thread::spawn(move || {
+            while receiver.recv().is_ok() {
+                while receiver.recv_timeout(RELOAD_DEBOUNCE).is_ok() {}
+                let _ = inner.reload(false);
+            }
+        });
+
+        Ok(watcher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReloadableChecker;
+    use policy::Policy;
+    use std::env;
+    use std::fs::{self, File};
+    use std::io::{ErrorKind, Write};
+    use PasswordError;
+
+    #[test]
+    fn it_reloads_the_wordlist() {
+        let path = env::temp_dir().join("passablewords-reload-test.txt");
+
+        File::create(&path).unwrap().write_all(b"hunter22\n").unwrap();
+
+        let checker = ReloadableChecker::from_file(&path).unwrap();
+        let before = checker.check_uniqueness("correcthorse");
+        let held = checker.wordlist();
+
+        File::create(&path).unwrap().write_all(b"hunter22\ncorrecthorse\n").unwrap();
+        checker.reload().unwrap();
+
+        let after = checker.check_uniqueness("correcthorse");
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(before, Ok(()));
+        assert_eq!(after, Err(PasswordError::TooCommon));
+        assert!(!held.contains("correcthorse"));
+        assert!(checker.reload().is_err());
+        assert_eq!(checker.check_uniqueness("correcthorse"), Err(PasswordError::TooCommon));
+    }
+
+    #[test]
+    fn it_keeps_the_list_when_the_file_shrinks() {
+        let path = env::temp_dir().join("passablewords-reload-shrink-test.txt");
+
+        fs::write(&path, "hunter22\ncorrecthorse\nletmein\n").unwrap();
+
+        let checker = ReloadableChecker::from_file(&path).unwrap();
+
+        fs::write(&path, "").unwrap();
+        let empty = checker.reload();
+
+        fs::write(&path, "hunter22\n").unwrap();
+        let shrunk = checker.reload();
+        let kept = checker.check_uniqueness("letmein");
+        let forced = checker.force_reload();
+
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(empty.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(shrunk.unwrap_err().kind(), ErrorKind::InvalidData);
+        assert_eq!(kept, Err(PasswordError::TooCommon));
+        assert!(forced.is_ok());
+        assert_eq!(checker.check_uniqueness("letmein"), Ok(()));
+    }
+
+    #[test]
+    fn it_swaps_the_policy() {
+        let path = env::temp_dir().join("passablewords-reload-policy-test.txt");
+
+        fs::write(&path, "hunter22\n").unwrap();
+
+        let checker = ReloadableChecker::from_file(&path).unwrap();
+        let held = checker.checker();
+
+        fs::remove_file(&path).unwrap();
+        checker.set_policy(Policy::new().min_length(40));
+
+        assert_eq!(
+            checker.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            held.check_password("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Ok(())
+        );
+    }
+
+    #[cfg(feature = "watch")]
+    #[test]
+    fn it_watches_files_renamed_into_place() {
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        let directory = env::temp_dir().join("passablewords-reload-watch-test");
+        let path = directory.join("blocklist.txt");
+        let staged = directory.join("blocklist.txt.tmp");
+
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(&path, "hunter22\n").unwrap();
+
+        let checker = ReloadableChecker::from_file(&path).unwrap();
+        let watcher = checker.watch().unwrap();
+        let reloaded = |password: &str| {
+            let deadline = Instant::now() + Duration::from_secs(10);
+
+            while Instant::now() < deadline {
+                if checker.wordlist().contains(password) {
+                    return true;
+                }
+                thread::sleep(Duration::from_millis(50));
+            }
+            false
+        };
+
+        fs::write(&staged, "hunter22\ncorrecthorse\n").unwrap();
+        fs::rename(&staged, &path).unwrap();
+        let first = reloaded("correcthorse");
+
+        fs::write(&staged, "hunter22\ncorrecthorse\nletmein\n").unwrap();
+        fs::rename(&staged, &path).unwrap();
+        let second = reloaded("letmein");
+
+        drop(watcher);
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(first);
+        assert!(second);
+    }
+}
@@ -0,0 +1,291 @@
+//! Rendering reports as Markdown or HTML, for embedding in admin tools, audit results, and emails
+//! without a templating library. Only available with the `render` feature.
+
+use std::fmt::Write;
+
+use composition::CharacterClass;
+use decision::Outcome;
+use report::StrengthReport;
+
+/// The highest score on zxcvbn's scale.
+const MAX_SCORE: u8 = 4;
+
+/// The checklist of what a password is made of, with the classes that tick each item off.
+const CHECKLIST: [(&str, &[CharacterClass]); 4] = [
+    ("Lowercase letters", &[CharacterClass::Lowercase]),
+    ("Uppercase letters", &[CharacterClass::Uppercase]),
+    ("Numbers", &[CharacterClass::Digit]),
+    (
+        "Symbols or spaces",
+        &[CharacterClass::Symbol, CharacterClass::Space],
+    ),
+];
+
+impl StrengthReport {
+    /// The report as Markdown: whether the password was accepted and why not, a checklist of
+    /// what it's made of, a meter for its score, and a suggestion for doing better. Anything that
+    /// came from outside the library, like the policy version, is escaped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, Wordlist};
+    ///
+    /// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+    /// let markdown = checker.report("password123").to_markdown();
+    ///
+    /// assert!(markdown.starts_with("## Password rejected\n\nYour password is one of the most"));
+    /// assert!(markdown.contains("- [x] Numbers\n"));
+    /// assert!(markdown.contains("- [ ] Uppercase letters\n"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("## {}\n\n", self.title());
+
+        if let Outcome::Rejected(ref error) = self.outcome {
+            let _ = write!(markdown, "{}\n\n", error.explanation());
+        }
+
+        for &(label, classes) in CHECKLIST.iter() {
+            let mark = if self.has_any(classes) { "x" } else { " " };
+            let _ = writeln!(markdown, "- [{}] {}", mark, label);
+        }
+        let _ = write!(
+            markdown,
+            "- Length: {} characters\n\n",
+            self.composition.chars
+        );
+
+        match self.score {
+            Some(score) => {
+                let _ = write!(
+                    markdown,
+                    "**Strength:** `{}{}` {} of {}\n\n",
+                    "█".repeat(usize::from(score.min(MAX_SCORE))),
+                    "░".repeat(usize::from(MAX_SCORE - score.min(MAX_SCORE))),
+                    score,
+                    MAX_SCORE
+                );
+            }
+            None => markdown.push_str("**Strength:** not estimated\n\n"),
+        }
+
+        if let Outcome::Rejected(ref error) = self.outcome {
+            let _ = write!(markdown, "### Suggestions\n\n- {}\n\n", error.advice());
+        }
+
+        if let Some(ref version) = self.policy_version {
+            let _ = write!(
+                markdown,
+                "Checked under policy {}\n\n",
+                escape_markdown(version)
+            );
+        }
+
+        markdown.truncate(markdown.trim_end().len());
+        markdown.push('\n');
+        markdown
+    }
+
+    /// The report as a fragment of HTML with the same content as `to_markdown`, wrapped in a
+    /// `<section class="passablewords-report">` for styling. The score is a `<meter>` element,
+    /// and the checklist items have a `present` or `missing` class. Everything is escaped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, Wordlist};
+    ///
+    /// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+    /// let html = checker.report("password123").to_html();
+    ///
+    /// assert!(html.starts_with("<section class=\"passablewords-report passablewords-rejected\">"));
+    /// assert!(html.contains("<li class=\"present\">Numbers</li>"));
+    /// ```
+    pub fn to_html(&self) -> String {
+        let status = if self.is_accepted() {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        let mut html = format!(
+            "<section class=\"passablewords-report passablewords-{}\">\n<h2>{}</h2>\n",
+            status,
+            self.title()
+        );
+
+        if let Outcome::Rejected(ref error) = self.outcome {
+            let _ = writeln!(html, "<p>{}</p>", escape_html(error.explanation()));
+        }
+
+        html.push_str("<ul class=\"checklist\">\n");
+        for &(label, classes) in CHECKLIST.iter() {
+            let class = if self.has_any(classes) {
+                "present"
+            } else {
+                "missing"
+            };
+            let _ = writeln!(html, "<li class=\"{}\">{}</li>", class, label);
+        }
+        let _ = writeln!(
+            html,
+            "<li class=\"length\">Length: {} characters</li>\n</ul>",
+            self.composition.chars
+        );
+
+        match self.score {
+            Some(score) => {
+                let _ = writeln!(
+                    html,
+                    "<p>Strength: <meter min=\"0\" max=\"{max}\" low=\"2\" high=\"3\" \
+                     optimum=\"{max}\" value=\"{score}\">{score} of {max}</meter></p>",
+                    max = MAX_SCORE,
+                    score = score
+                );
+            }
+            None => html.push_str("<p>Strength: not estimated</p>\n"),
+        }
+
+        if let Outcome::Rejected(ref error) = self.outcome {
+            let _ = writeln!(
+                html,
+                "<h3>Suggestions</h3>\n<ul class=\"suggestions\">\n<li>{}</li>\n</ul>",
+                escape_html(error.advice())
+            );
+        }
+
+        if let Some(ref version) = self.policy_version {
+            let _ = writeln!(
+                html,
+                "<p class=\"policy\">Checked under policy {}</p>",
+                escape_html(version)
+            );
+        }
+
+        html.push_str("</section>\n");
+        html
+    }
+
+    fn title(&self) -> &'static str {
+        if self.is_accepted() {
+            "Password accepted"
+        } else {
+            "Password rejected"
+        }
+    }
+
+    fn has_any(&self, classes: &[CharacterClass]) -> bool {
+        classes
+            .iter()
+            .any(|class| self.composition.classes.contains(class))
+    }
+}
+
+/// `text` with every character that means something inline in Markdown escaped with a backslash,
+/// and line breaks turned into spaces so it can't start a heading or any other block of its own.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for (index, character) in text.chars().enumerate() {
+        match character {
+            '\n' | '\r' => escaped.push(' '),
+            '#' if index == 0 => escaped.push_str("\\#"),
+            character => {
+                if "\\`*_[]<>|!".contains(character) {
+                    escaped.push('\\');
+                }
+                escaped.push(character);
+            }
+        }
+    }
+    escaped
+}
+
+/// `text` with every character that means something in HTML replaced with an entity.
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_html, escape_markdown};
+    use analyzer::Analysis;
+    use checker::PasswordChecker;
+    use policy::Policy;
+    use wordlist::Wordlist;
+
+    fn checker() -> PasswordChecker {
+        PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+            .with_policy(Policy::new().with_version("<v2>"))
+            .with_analyzer(|password: &str, _: &[&str]| {
+                Ok(Analysis {
+                    score: if password.len() > 12 { 4 } else { 1 },
+                    guesses_log10: 0.0,
+                })
+            })
+    }
+
+    #[test]
+    fn it_renders_markdown() {
+        assert_eq!(
+            checker().report("password123").to_markdown(),
+            "## Password rejected\n\n\
+             Your password is one of the most commonly used passwords.\n\n\
+             - [x] Lowercase letters\n\
+             - [ ] Uppercase letters\n\
+             - [x] Numbers\n\
+             - [ ] Symbols or spaces\n\
+             - Length: 11 characters\n\n\
+             **Strength:** `█░░░` 1 of 4\n\n\
+             ### Suggestions\n\n\
+             - Attackers try the most common passwords first, so this one would be guessed almost \
+             immediately. Avoid well-known passwords, and don't make small tweaks to them either. \
+             Try a few random words instead.\n\n\
+             Checked under policy \\<v2\\>\n"
+        );
+    }
+
+    #[test]
+    fn it_renders_html() {
+        let html = checker()
+            .report("Th1s iS a Sup3rR4ndom PassW0rd!")
+            .to_html();
+
+        assert!(html.starts_with(
+            "<section class=\"passablewords-report passablewords-accepted\">\n\
+             <h2>Password accepted</h2>\n<ul class=\"checklist\">\n"
+        ));
+        assert!(html.contains("<li class=\"present\">Symbols or spaces</li>"));
+        assert!(html.contains("value=\"4\">4 of 4</meter>"));
+        assert!(html.contains("Checked under policy &lt;v2&gt;"));
+        assert!(!html.contains("Suggestions"));
+        assert!(html.ends_with("</section>\n"));
+    }
+
+    #[test]
+    fn it_escapes() {
+        assert_eq!(
+            escape_markdown("*a* [b](c) v1.2"),
+            "\\*a\\* \\[b\\](c) v1.2"
+        );
+        assert_eq!(
+            escape_markdown("# v2\n\n![x](y) #3"),
+            "\\# v2  \\!\\[x\\](y) #3"
+        );
+        assert_eq!(
+            escape_html("<a href=\"x\">'&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+}
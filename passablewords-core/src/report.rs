@@ -0,0 +1,262 @@
+//! Reports which record the outcome of a check along with the policy it was made under, so that
+//! passwords can be checked again once the policy changes.
+
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use composition::Composition;
+use decision::Outcome;
+use policy::Policy;
+
+/// The outcome of checking a password, stamped with the version of the policy that was enforced.
+/// Store it alongside the account when a password is set, and use `needs_recheck` to find the
+/// passwords that were only accepted under older rules. It never contains the password.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{needs_recheck, PasswordChecker, Policy};
+///
+/// let checker = PasswordChecker::new().with_policy(Policy::new().with_version("2023"));
+/// let report = checker.report("Th1s iS a Sup3rR4ndom PassW0rd!");
+///
+/// assert!(report.is_accepted());
+/// assert!(!needs_recheck(&report, checker.policy()));
+/// assert!(needs_recheck(&report, &Policy::new().min_length(12).with_version("2024")));
+/// ```
+///
+/// # Stability
+///
+/// Reports are meant to be stored for years, so their format only ever grows. New fields may be
+/// added in minor releases, but existing ones are never removed, renamed, or given a different
+/// meaning without a new major release and a new `schema_version`. With the `serde` feature, new
+/// fields are always optional when deserializing and unknown fields are ignored, so reports
+/// written by an older release can be read by a newer one, and the other way around. The struct
+/// is `#[non_exhaustive]` so that adding fields isn't a breaking change either. Create reports
+/// with `PasswordChecker::report`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct StrengthReport {
+    /// The version of this format the report was written with, which is
+    /// `StrengthReport::SCHEMA_VERSION` for reports created by this release. Reports stored before
+    /// it was added are read as version 1.
+    #[cfg_attr(feature = "serde", serde(default = "first_schema_version"))]
+    pub schema_version: u32,
+    /// When the password was checked.
+    pub checked_at: SystemTime,
+    /// The version of the policy the password was checked against, if it was given one.
+    pub policy_version: Option<String>,
+    /// Whether the password was accepted, and if not, why.
+    pub outcome: Outcome,
+    /// What the password is made of, such as its scripts and its length. This is enough to give
+    /// targeted advice, but keep in mind that it narrows down what the password could be.
+    pub composition: Composition,
+    /// How strong the password is on zxcvbn's scale from 0 to 4, from the checker's
+    /// `EntropyAnalyzer`, or `None` if it couldn't be estimated, such as for passwords zxcvbn
+    /// can't analyze, builds without the `zxcvbn` feature, and reports stored before it was added.
+    pub score: Option<u8>,
+    /// How long the checks took, including loading the wordlist if this was the first check to
+    /// need it, and any lists or breach lookups the checker waits on. Estimating the score isn't
+    /// included. `None` for reports stored before it was added.
+    pub check_duration: Option<Duration>,
+    /// When the password was checked on a monotonic clock, as the time since the first report
+    /// created by this process. Unlike `checked_at`, it never goes backwards when the system clock
+    /// is adjusted, so it orders the reports of one process and the time between them exactly. It
+    /// can't be compared between processes. `None` for reports stored before it was added.
+    pub monotonic_time: Option<Duration>,
+    /// Why the password was accepted, or `None` if it was rejected, and for reports stored before
+    /// it was added.
+    pub pass_details: Option<PassDetails>,
+}
+
+/// What a password did well, so that the message for an accepted password can be as specific as
+/// the ones for rejected passwords, like "12 characters more than needed, and not in any of the 3
+/// lists of common passwords". Part of a `StrengthReport`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{PasswordChecker, Policy, Wordlist};
+///
+/// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password"))
+///     .with_policy(Policy::new().min_length(12));
+/// let report = checker.report("Th1s iS a Sup3rR4ndom PassW0rd!");
+/// let details = report.pass_details.unwrap();
+///
+/// assert_eq!(details.length_margin, 19);
+/// assert_eq!(details.lists_checked, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[non_exhaustive]
+pub struct PassDetails {
+    /// How many characters longer than the policy's minimum length the password is. It's 0 for
+    /// passwords accepted for their number of words, or while the length check only warns.
+    pub length_margin: usize,
+    /// How far the password's score is above the lowest score that's accepted, or `None` if it
+    /// couldn't be estimated. The score itself is the report's `score`.
+    pub score_margin: Option<u8>,
+    /// How many lists of common passwords the password isn't in, counting the checker's wordlist
+    /// and every list added with `with_labelled_wordlist`.
+    pub lists_checked: usize,
+}
+
+impl StrengthReport {
+    /// The version of the format reports are created with. It only changes along with a major
+    /// release, when the meaning of an existing field changes.
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Whether the password passed every check.
+    pub fn is_accepted(&self) -> bool {
+        self.outcome == Outcome::Accepted
+    }
+}
+
+/// The time since the first report created by this process, which `monotonic_time` counts from.
+pub(crate) fn monotonic_time(now: Instant) -> Duration {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+    now.saturating_duration_since(*EPOCH.get_or_init(|| now))
+}
+
+/// The version of reports that were stored before they had one.
+#[cfg(feature = "serde")]
+fn first_schema_version() -> u32 {
+    1
+}
+
+/// Whether a password should be checked again because the policy it was checked against isn't
+/// `current_policy`. Policies are compared by their version, so a report is only considered
+/// current when both it and the policy have the same version. Unversioned policies always need a
+/// recheck, since there's no way to tell whether the rules have changed.
+pub fn needs_recheck(report: &StrengthReport, current_policy: &Policy) -> bool {
+    match (report.policy_version.as_ref(), current_policy.version()) {
+        (Some(checked), Some(current)) => checked != current,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{needs_recheck, PassDetails, StrengthReport};
+    use analyzer::Analysis;
+    use checker::PasswordChecker;
+    use compat::Compat;
+    use composition::Composition;
+    use decision::Outcome;
+    use policy::Policy;
+    use std::time::{Duration, SystemTime};
+    use wordlist::Wordlist;
+
+    fn report(policy_version: Option<&str>) -> StrengthReport {
+        StrengthReport {
+            schema_version: StrengthReport::SCHEMA_VERSION,
+            checked_at: SystemTime::now(),
+            policy_version: policy_version.map(str::to_string),
+            outcome: Outcome::Accepted,
+            composition: Composition::of("Th1s iS a Sup3rR4ndom PassW0rd!"),
+            score: Some(4),
+            check_duration: Some(Duration::from_micros(250)),
+            monotonic_time: Some(Duration::from_secs(1)),
+            pass_details: Some(PassDetails {
+                length_margin: 23,
+                score_margin: Some(1),
+                lists_checked: 1,
+            }),
+        }
+    }
+
+    #[test]
+    fn it_compares_policy_versions() {
+        let current = Policy::new().with_version("v2");
+
+        assert!(!needs_recheck(&report(Some("v2")), &current));
+        assert!(needs_recheck(&report(Some("v1")), &current));
+        assert!(needs_recheck(&report(None), &current));
+        assert!(needs_recheck(&report(None), &Policy::new()));
+        assert!(needs_recheck(&report(Some("v2")), &Policy::new()));
+    }
+
+    #[test]
+    fn it_times_checks() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+        let first = checker.report("password123");
+        let second = checker.report("Th1s iS a Sup3rR4ndom PassW0rd!");
+
+        assert!(first.check_duration.is_some());
+        assert!(second.check_duration.unwrap() > Duration::from_secs(0));
+        assert!(second.monotonic_time.unwrap() >= first.monotonic_time.unwrap());
+    }
+
+    #[test]
+    fn it_explains_why_passwords_passed() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+            .with_labelled_wordlist("company", Wordlist::parse("Acme2020!"))
+            .with_policy(Policy::new().min_length(10));
+        let report = checker.report("Th1s iS a Sup3rR4ndom PassW0rd!");
+        let details = report.pass_details.unwrap();
+
+        assert_eq!(details.length_margin, 21);
+        assert_eq!(details.lists_checked, 2);
+        assert_eq!(
+            details.score_margin,
+            report.score.map(|score| score.saturating_sub(3))
+        );
+        assert_eq!(checker.report("password123").pass_details, None);
+    }
+
+    #[test]
+    fn it_measures_the_score_against_the_policy() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123")).with_analyzer(
+            |_: &str, _: &[&str]| {
+                Ok(Analysis {
+                    score: 3,
+                    guesses_log10: 9.0,
+                })
+            },
+        );
+        let margin = |checker: &PasswordChecker| {
+            checker
+                .report("Th1s iS a Sup3rR4ndom PassW0rd!")
+                .pass_details
+                .unwrap()
+                .score_margin
+        };
+
+        assert_eq!(margin(&checker), Some(0));
+        assert_eq!(
+            margin(&checker.with_policy(Policy::new().pin_compat(Compat::V1))),
+            Some(1)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_reads_reports_from_other_releases() {
+        let stored = ::serde_json::to_value(report(Some("v2"))).unwrap();
+        let mut older = stored.clone();
+        let mut newer = stored.clone();
+
+        older.as_object_mut().unwrap().remove("schema_version");
+        older.as_object_mut().unwrap().remove("score");
+        older.as_object_mut().unwrap().remove("check_duration");
+        older.as_object_mut().unwrap().remove("monotonic_time");
+        older.as_object_mut().unwrap().remove("pass_details");
+        newer
+            .as_object_mut()
+            .unwrap()
+            .insert("added_later".to_string(), ::serde_json::Value::Bool(true));
+
+        let older: StrengthReport = ::serde_json::from_value(older).unwrap();
+        let newer: StrengthReport = ::serde_json::from_value(newer).unwrap();
+
+        assert_eq!(stored["schema_version"], 1);
+        assert_eq!(older.schema_version, 1);
+        assert_eq!(older.score, None);
+        assert_eq!(older.monotonic_time, None);
+        assert_eq!(older.pass_details, None);
+        assert_eq!(newer.check_duration, Some(Duration::from_micros(250)));
+        assert_eq!(newer.policy_version, Some("v2".to_string()));
+    }
+}
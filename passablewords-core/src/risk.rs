@@ -0,0 +1,200 @@
+//! Built-in thresholds that get stricter with how much is at stake for an account, so applications
+//! can pass how risky an account is instead of maintaining a policy for each kind.
+
+use std::fmt;
+
+use checker::PasswordChecker;
+use {PassablewordResult, MIN_ENTROPY_SCORE};
+
+/// How much is at stake if an account's password is guessed, which decides how strict the checks
+/// are. Each level raises the bar on top of the checker's policy and never lowers it.
+///
+/// | Level | Minimum length | Minimum score | Most breach sightings |
+/// |-------|----------------|---------------|-----------------------|
+/// | `Low` | 8 characters | 3 | 100 |
+/// | `Medium` | 12 characters | 3 | 10 |
+/// | `High` | 16 characters | 4 | 0 |
+///
+/// The thresholds only change in a new major version.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RiskLevel {
+    /// Accounts that hold little of value, like a forum profile.
+    Low,
+    /// Most accounts. This is the default.
+    #[default]
+    Medium,
+    /// Accounts that can do a lot of damage, like administrators, or ones with payment details.
+    High,
+}
+
+impl RiskLevel {
+    /// The fewest characters a password can have.
+    pub fn min_length(self) -> usize {
+        match self {
+            RiskLevel::Low => 8,
+            RiskLevel::Medium => 12,
+            RiskLevel::High => 16,
+        }
+    }
+
+    /// The lowest score on zxcvbn's scale from 0 to 4 that a password can have.
+    pub fn min_score(self) -> u8 {
+        match self {
+            RiskLevel::Low | RiskLevel::Medium => MIN_ENTROPY_SCORE,
+            RiskLevel::High => 4,
+        }
+    }
+
+    /// The most times a password can have been seen in breaches, for `check_breached_with_risk`.
+    pub fn max_breach_count(self) -> u64 {
+        match self {
+            RiskLevel::Low => 100,
+            RiskLevel::Medium => 10,
+            RiskLevel::High => 0,
+        }
+    }
+}
+
+impl fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        })
+    }
+}
+
+impl PasswordChecker {
+    /// Check a password like `check_password` does, with the policy raised to the thresholds for
+    /// `risk`. Passwords shorter than the level's minimum length are rejected as `TooShort`, and
+    /// ones that score below its minimum as `TooSimple`. The score comes from the checker's
+    /// analyzer, or zxcvbn, so without either only the length is raised.
+    ///
+    /// The raised thresholds are part of the length and entropy checks, so checks the policy only
+    /// warns about stay that way, and the decision hook is told the outcome of this check. A policy
+    /// that accepts a number of words in place of length keeps doing so if its own minimum length
+    /// is longer than the level's.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordChecker, PasswordError, RiskLevel, Wordlist};
+    ///
+    /// let checker = PasswordChecker::with_wordlist(Wordlist::parse("password"));
+    ///
+    /// assert_eq!(checker.check_password_with_risk("Qd7#vL2p!x", RiskLevel::Low), Ok(()));
+    /// assert_eq!(
+    ///     checker.check_password_with_risk("Qd7#vL2p!x", RiskLevel::Medium),
+    ///     Err(PasswordError::TooShort)
+    /// );
+    /// ```
+    pub fn check_password_with_risk(&self, password: &str, risk: RiskLevel) -> PassablewordResult {
+        let policy = self
+            .policy()
+            .clone()
+            .raise(risk.min_length(), risk.min_score());
+
+        self.clone().with_policy(policy).check_password(password)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use analyzer::Analysis;
+    use decision::{Check, DecisionRecord, Outcome};
+    use policy::Policy;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    fn checker() -> PasswordChecker {
+        PasswordChecker::with_wordlist(Wordlist::parse("password123"))
+    }
+
+    #[test]
+    fn it_raises_the_length() {
+        let password = "Qd7#vL2p!xW4";
+
+        assert_eq!(
+            checker().check_password_with_risk(password, RiskLevel::Low),
+            Ok(())
+        );
+        assert_eq!(
+            checker().check_password_with_risk(password, RiskLevel::Medium),
+            Ok(())
+        );
+        assert_eq!(
+            checker().check_password_with_risk(password, RiskLevel::High),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            checker().check_password_with_risk("password123", RiskLevel::Low),
+            Err(PasswordError::TooCommon)
+        );
+    }
+
+    #[test]
+    fn it_decides_once_with_the_raised_policy() {
+        let analyses = Arc::new(AtomicUsize::new(0));
+        let counted = analyses.clone();
+        let outcomes = Arc::new(Mutex::new(Vec::new()));
+        let recorded = outcomes.clone();
+        let checker = checker()
+            .with_analyzer(move |_: &str, _: &[&str]| {
+                counted.fetch_add(1, Ordering::SeqCst);
+
+                Ok(Analysis {
+                    score: 3,
+                    guesses_log10: 12.0,
+                })
+            })
+            .with_decision_hook(move |record: &DecisionRecord| {
+                let error = match record.outcome {
+                    Outcome::Accepted => None,
+                    Outcome::Rejected(ref error) => Some(error.clone()),
+                };
+
+                recorded.lock().unwrap().push(error);
+            });
+
+        assert_eq!(
+            checker.check_password_with_risk("Qd7#vL2p!xW4pQ7z", RiskLevel::High),
+            Err(PasswordError::TooSimple)
+        );
+        assert_eq!(analyses.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *outcomes.lock().unwrap(),
+            vec![Some(PasswordError::TooSimple)]
+        );
+
+        let lenient = checker.with_policy(
+            Policy::new()
+                .warn_only(Check::Length)
+                .warn_only(Check::Entropy),
+        );
+
+        assert_eq!(
+            lenient.check_password_with_risk("Qd7#vL2p!x", RiskLevel::High),
+            Ok(())
+        );
+        assert_eq!(outcomes.lock().unwrap()[1], None);
+    }
+
+    #[test]
+    fn it_orders_the_levels() {
+        let levels = [RiskLevel::Low, RiskLevel::Medium, RiskLevel::High];
+
+        for pair in levels.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].min_length() < pair[1].min_length());
+            assert!(pair[0].min_score() <= pair[1].min_score());
+            assert!(pair[0].max_breach_count() > pair[1].max_breach_count());
+        }
+        assert_eq!(RiskLevel::default(), RiskLevel::Medium);
+        assert_eq!(RiskLevel::High.to_string(), "high");
+    }
+}
@@ -0,0 +1,147 @@
+//! Helpers for applications that still require passwords to be changed periodically.
+
+use std::time::{Duration, SystemTime};
+
+use lint::{PolicyWarning, RECOMMENDED_MIN_ROTATION};
+
+/// How often passwords have to be changed, and how long before that users should be reminded.
+/// Rotation is opt-in, and the default policy never expires passwords.
+///
+/// Current NIST guidance (SP 800-63B) recommends against forcing periodic changes, since users
+/// tend to respond with predictable tweaks like bumping a number at the end. Only expire
+/// passwords if you're required to, or when there's evidence that one was compromised.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use passablewords_core::RotationPolicy;
+///
+/// let day = Duration::from_secs(24 * 60 * 60);
+/// let rotation = RotationPolicy::new(day * 90).warn_before(day * 14);
+/// let set_at = SystemTime::now() - day * 80;
+///
+/// assert!(!rotation.is_expired(set_at));
+/// assert!(rotation.should_warn(set_at));
+/// assert!(!RotationPolicy::nist().is_expired(set_at));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RotationPolicy {
+    max_age: Option<Duration>,
+    warn_before: Duration,
+}
+
+impl RotationPolicy {
+    /// Expire passwords once they're older than `max_age`.
+    pub fn new(max_age: Duration) -> RotationPolicy {
+        RotationPolicy {
+            max_age: Some(max_age),
+            warn_before: Duration::from_secs(0),
+        }
+    }
+
+    /// Never expire passwords, following NIST SP 800-63B. This is the same as the default.
+    pub fn nist() -> RotationPolicy {
+        RotationPolicy::default()
+    }
+
+    /// Start warning users this long before their password expires.
+    pub fn warn_before(mut self, warn_before: Duration) -> RotationPolicy {
+        self.warn_before = warn_before;
+        self
+    }
+
+    /// How old a password can get before it expires, or `None` if passwords never expire.
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+
+    /// How long until a password set at `set_at` expires. This is zero if it's already expired,
+    /// and `None` if passwords never expire. Times in the future are treated as now, so clock
+    /// skew between servers can't make a password last longer than `max_age`.
+    pub fn expires_in(&self, set_at: SystemTime) -> Option<Duration> {
+        let age = SystemTime::now()
+            .duration_since(set_at)
+            .unwrap_or_else(|_| Duration::from_secs(0));
+
+        self.max_age.map(|max_age| {
+            max_age
+                .checked_sub(age)
+                .unwrap_or_else(|| Duration::from_secs(0))
+        })
+    }
+
+    /// Whether a password set at `set_at` has expired.
+    pub fn is_expired(&self, set_at: SystemTime) -> bool {
+        self.expires_in(set_at) == Some(Duration::from_secs(0))
+    }
+
+    /// Warn if passwords expire more often than every 90 days. Never expiring them is fine.
+    pub fn lint(&self) -> Vec<PolicyWarning> {
+        match self.max_age {
+            Some(max_age) if max_age < RECOMMENDED_MIN_ROTATION => {
+                vec![PolicyWarning::ShortRotation(max_age)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a password set at `set_at` expires soon enough that the user should be reminded to
+    /// change it. This is also true once it's expired.
+    pub fn should_warn(&self, set_at: SystemTime) -> bool {
+        match self.expires_in(set_at) {
+            Some(expires_in) => expires_in <= self.warn_before,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RotationPolicy;
+    use lint::PolicyWarning;
+    use std::time::{Duration, SystemTime};
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn it_expires_old_passwords() {
+        let rotation = RotationPolicy::new(Duration::from_secs(DAY * 90))
+            .warn_before(Duration::from_secs(DAY * 7));
+        let now = SystemTime::now();
+        let fresh = now - Duration::from_secs(DAY);
+        let expiring = now - Duration::from_secs(DAY * 85);
+        let expired = now - Duration::from_secs(DAY * 91);
+
+        assert!(!rotation.is_expired(fresh));
+        assert!(!rotation.should_warn(fresh));
+        assert!(rotation.expires_in(fresh).unwrap() > Duration::from_secs(DAY * 88));
+        assert!(!rotation.is_expired(expiring));
+        assert!(rotation.should_warn(expiring));
+        assert!(rotation.is_expired(expired));
+        assert!(rotation.should_warn(expired));
+        assert_eq!(rotation.expires_in(expired), Some(Duration::from_secs(0)));
+        assert!(!rotation.is_expired(now + Duration::from_secs(DAY)));
+    }
+
+    #[test]
+    fn it_never_expires_without_a_max_age() {
+        let ancient = SystemTime::now() - Duration::from_secs(DAY * 10_000);
+
+        assert_eq!(RotationPolicy::nist().expires_in(ancient), None);
+        assert!(!RotationPolicy::nist().is_expired(ancient));
+        assert!(!RotationPolicy::default().should_warn(ancient));
+    }
+
+    #[test]
+    fn it_lints_short_rotations() {
+        let month = Duration::from_secs(DAY * 30);
+
+        assert_eq!(
+            RotationPolicy::new(month).lint(),
+            vec![PolicyWarning::ShortRotation(month)]
+        );
+        assert_eq!(RotationPolicy::new(month * 3).lint(), vec![]);
+        assert_eq!(RotationPolicy::nist().lint(), vec![]);
+    }
+}
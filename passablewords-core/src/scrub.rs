@@ -0,0 +1,126 @@
+//! Wiping the copies of a password that checks make along the way, like its lowercased or
+//! normalized form, so they don't linger in freed memory. Nothing is wiped without the `zeroize`
+//! feature.
+//!
+//! No check writes a password, or anything derived from it, to disk or to a log, and none keeps
+//! one after it returns. `DecisionRecord`s only say which checks ran, and `audit` only keeps keyed
+//! hashes. zxcvbn makes copies of its own which are outside of our control, so callers who need
+//! every copy wiped should use `EntropyAnalyzer` with an estimator that wipes its own, or
+//! `check_quick_entropy`.
+
+use std::borrow::Cow;
+#[cfg(feature = "zeroize")]
+use std::cell::Cell;
+use std::ops::Deref;
+
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+/// Called with the bytes of each buffer once it's been wiped.
+#[cfg(feature = "zeroize")]
+type ScrubHook = fn(&[u8]);
+
+#[cfg(feature = "zeroize")]
+thread_local! {
+    static HOOK: Cell<Option<ScrubHook>> = const { Cell::new(None) };
+}
+
+/// Call `hook` with the bytes of every buffer wiped on this thread, right after it's been wiped
+/// and before it's freed, or stop calling it with `None`. This is only meant for tests proving
+/// that buffers are wiped, so it isn't part of the public API. Only available with the `zeroize`
+/// feature.
+#[cfg(feature = "zeroize")]
+#[doc(hidden)]
+pub fn set_scrub_hook(hook: Option<ScrubHook>) {
+    HOOK.with(|current| current.set(hook));
+}
+
+/// Wipe `bytes` and tell the hook, if there is one.
+#[cfg(feature = "zeroize")]
+pub fn scrub_bytes(bytes: &mut [u8]) {
+    bytes.zeroize();
+
+    if let Some(hook) = HOOK.with(Cell::get) {
+        hook(bytes);
+    }
+}
+
+/// Wipe a string's bytes, including any spare capacity, leaving it empty.
+#[cfg(feature = "zeroize")]
+pub fn scrub_string(string: &mut String) {
+    let mut bytes = ::std::mem::take(string).into_bytes();
+
+    scrub_bytes(&mut bytes);
+    bytes.zeroize();
+}
+
+/// A string derived from a password, which is wiped when it's dropped if it was allocated for
+/// the check rather than borrowed from the caller.
+#[derive(Debug)]
+pub struct Scrubbed<'a>(Cow<'a, str>);
+
+impl<'a> Deref for Scrubbed<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> AsRef<str> for Scrubbed<'a> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for Scrubbed<'a> {
+    fn from(string: Cow<'a, str>) -> Scrubbed<'a> {
+        Scrubbed(string)
+    }
+}
+
+impl From<String> for Scrubbed<'static> {
+    fn from(string: String) -> Scrubbed<'static> {
+        Scrubbed(Cow::Owned(string))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<'a> Drop for Scrubbed<'a> {
+    fn drop(&mut self) {
+        if let Cow::Owned(ref mut string) = self.0 {
+            scrub_string(string);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "zeroize"))]
+mod tests {
+    use super::{set_scrub_hook, Scrubbed};
+    use std::borrow::Cow;
+    use std::cell::Cell;
+
+    thread_local! {
+        static WIPED: Cell<(usize, bool)> = const { Cell::new((0, true)) };
+    }
+
+    fn record(bytes: &[u8]) {
+        WIPED.with(|wiped| {
+            let (count, zeroed) = wiped.get();
+
+            wiped.set((count + 1, zeroed && bytes.iter().all(|&byte| byte == 0)));
+        });
+    }
+
+    #[test]
+    fn it_wipes_owned_strings_only() {
+        set_scrub_hook(Some(record));
+
+        drop(Scrubbed::from("hunter2".to_lowercase()));
+        drop(Scrubbed::from(Cow::Borrowed("hunter2")));
+
+        set_scrub_hook(None);
+
+        assert_eq!(WIPED.with(Cell::get), (1, true));
+    }
+}
@@ -0,0 +1,87 @@
+//! Checks for passwords wrapped in `secrecy` types, so that callers don't have to expose them
+//! at every call site. Only available with the `secrecy` feature.
+
+use secrecy::ExposeSecret;
+
+use checker::PasswordChecker;
+use context::CheckContext;
+use {check_password, PassablewordResult};
+
+/// Check a password held in a `secrecy` type, such as `SecretString`, like `check_password`
+/// does. The password is only exposed for the duration of the check.
+///
+/// # Example
+///
+/// ```
+/// # extern crate passablewords_core;
+/// # extern crate secrecy;
+/// use passablewords_core::{check_password_secret, PasswordError};
+/// use secrecy::SecretString;
+///
+/// # fn main() {
+/// let password = SecretString::from("password");
+///
+/// assert_eq!(check_password_secret(&password), Err(PasswordError::TooCommon));
+/// # }
+/// ```
+pub fn check_password_secret<S, T>(password: &S) -> PassablewordResult
+where
+    S: ExposeSecret<T>,
+    T: AsRef<str> + ?Sized,
+{
+    check_password(password.expose_secret().as_ref())
+}
+
+impl PasswordChecker {
+    /// Check a password held in a `secrecy` type, like `check_password` does. Only available
+    /// with the `secrecy` feature.
+    pub fn check_password_secret<S, T>(&self, password: &S) -> PassablewordResult
+    where
+        S: ExposeSecret<T>,
+        T: AsRef<str> + ?Sized,
+    {
+        self.check_password(password.expose_secret().as_ref())
+    }
+
+    /// Check a password held in a `secrecy` type, like `check_password_with_context` does. Only
+    /// available with the `secrecy` feature.
+    pub fn check_password_secret_with_context<S, T>(
+        &self,
+        password: &S,
+        context: &CheckContext,
+    ) -> PassablewordResult
+    where
+        S: ExposeSecret<T>,
+        T: AsRef<str> + ?Sized,
+    {
+        self.check_password_with_context(password.expose_secret().as_ref(), context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_password_secret;
+    use checker::PasswordChecker;
+    use context::CheckContext;
+    use secrecy::{SecretBox, SecretString};
+    use PasswordError;
+
+    #[test]
+    fn it_checks_secret_passwords() {
+        let checker = PasswordChecker::new();
+        let context = CheckContext::new().deny_exact(&["Th1s iS a Sup3rR4ndom PassW0rd!"]);
+        let secret = SecretString::from("Th1s iS a Sup3rR4ndom PassW0rd!");
+        let boxed = SecretBox::new(Box::new("short".to_string()));
+
+        assert_eq!(check_password_secret(&secret), Ok(()));
+        assert_eq!(checker.check_password_secret(&secret), Ok(()));
+        assert_eq!(
+            checker.check_password_secret_with_context(&secret, &context),
+            Err(PasswordError::MatchesDeniedValue)
+        );
+        assert_eq!(
+            checker.check_password_secret(&boxed),
+            Err(PasswordError::TooShort)
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Running a sample of passwords through a policy before it's rolled out, to predict how many
+//! users a change would turn away and which rules would be responsible.
+
+use checker::{PasswordChecker, CHECKS};
+use decision::Check;
+use policy::Policy;
+use PassablewordResult;
+
+/// How often one check rejected the passwords in a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct RuleRejections {
+    /// The check.
+    pub check: Check,
+    /// How many passwords the check rejects on its own, whether or not an earlier check would
+    /// have rejected them first.
+    pub rejected: usize,
+    /// How many passwords the check was the first to reject, which is the error those users would
+    /// see. Always 0 for checks the policy only warns about.
+    pub decided: usize,
+}
+
+/// How a policy treated a sample of passwords, returned by `simulate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SimulationReport {
+    /// How many passwords were in the sample.
+    pub samples: usize,
+    /// How many passwords were rejected, ignoring checks the policy only warns about.
+    pub rejected: usize,
+    /// How often each check rejected passwords, in the order the checks are run. Checks which
+    /// need a `CheckContext`, like `Check::DeniedValues`, are left out.
+    pub rules: Vec<RuleRejections>,
+}
+
+impl SimulationReport {
+    /// The fraction of the sample that was rejected, from 0 to 1. This is 0 for an empty sample.
+    pub fn rejection_rate(&self) -> f64 {
+        rate(self.rejected, self.samples)
+    }
+
+    /// The fraction of the sample that `check` rejects on its own, from 0 to 1.
+    pub fn rule_rejection_rate(&self, check: Check) -> f64 {
+        self.rules
+            .iter()
+            .find(|rule| rule.check == check)
+            .map_or(0.0, |rule| rate(rule.rejected, self.samples))
+    }
+}
+
+/// A password that one policy accepts and another rejects, or that the two reject for different
+/// reasons.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct VerdictChange {
+    /// The password from the sample.
+    pub password: String,
+    /// What the old policy made of the password.
+    pub old: PassablewordResult,
+    /// What the new policy makes of the password.
+    pub new: PassablewordResult,
+}
+
+/// How the verdicts on a sample of passwords change between two policies, returned by
+/// `simulate_diff`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct SimulationDiff {
+    /// How many passwords were in the sample.
+    pub samples: usize,
+    /// Passwords the old policy accepted and the new one rejects, in the order they were given.
+    pub newly_rejected: Vec<VerdictChange>,
+    /// Passwords the old policy rejected and the new one accepts, in the order they were given.
+    pub newly_accepted: Vec<VerdictChange>,
+    /// Passwords both policies reject, but with different errors, in the order they were given.
+    pub reason_changed: Vec<VerdictChange>,
+}
+
+impl SimulationDiff {
+    /// Whether every password in the sample gets the same verdict under both policies.
+    pub fn is_unchanged(&self) -> bool {
+        self.newly_rejected.is_empty()
+            && self.newly_accepted.is_empty()
+            && self.reason_changed.is_empty()
+    }
+}
+
+fn rate(count: usize, samples: usize) -> f64 {
+    if samples == 0 {
+        0.0
+    } else {
+        count as f64 / samples as f64
+    }
+}
+
+/// Run a sample of passwords through `policy` with the bundled list of common passwords, and
+/// report how many would be rejected and by which rules. Use `PasswordChecker::simulate` to
+/// simulate with another wordlist or entropy analyzer.
+///
+/// A useful sample mixes passwords the policy should reject, like the top of the bundled list,
+/// with ones it shouldn't, like generated passphrases or passwords from your own users' test
+/// accounts. Every check is run on every password, including zxcvbn, which takes around a
+/// millisecond each, so a few thousand passwords is usually plenty.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{simulate, Check, Policy};
+///
+/// let samples = ["password", "letmein", "12345678901234", "Th1s iS a Sup3rR4ndom PassW0rd!"];
+/// let report = simulate(&Policy::new().min_length(12), samples.iter());
+///
+/// assert_eq!(report.rejected, 3);
+/// assert_eq!(report.rule_rejection_rate(Check::Length), 0.5);
+/// ```
+pub fn simulate<I>(policy: &Policy, samples: I) -> SimulationReport
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    PasswordChecker::new()
+        .with_policy(policy.clone())
+        .simulate(samples)
+}
+
+/// Run a sample of passwords through both `old` and `new` with the bundled list of common
+/// passwords, and report which ones the change would reject, accept, or reject for a different
+/// reason. Use `PasswordChecker::simulate_diff` to compare with another wordlist or entropy
+/// analyzer, and `Policy::diff` to list the rules that changed.
+///
+/// The sample passwords are copied into the report, so use examples rather than real users'
+/// passwords.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{simulate_diff, PasswordError, Policy};
+///
+/// let samples = ["x7#Kq9!zLm2$", "Th1s iS a Sup3rR4ndom PassW0rd!"];
+/// let diff = simulate_diff(&Policy::new(), &Policy::new().min_length(16), samples.iter());
+///
+/// assert_eq!(diff.newly_rejected.len(), 1);
+/// assert_eq!(diff.newly_rejected[0].password, "x7#Kq9!zLm2$");
+/// assert_eq!(diff.newly_rejected[0].new, Err(PasswordError::TooShort));
+/// ```
+pub fn simulate_diff<I>(old: &Policy, new: &Policy, samples: I) -> SimulationDiff
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    PasswordChecker::new().simulate_diff(old, new, samples)
+}
+
+impl PasswordChecker {
+    /// Run a sample of passwords through this checker, and report how many would be rejected
+    /// and by which rules, like `simulate`. The decision hook isn't called.
+    pub fn simulate<I>(&self, samples: I) -> SimulationReport
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut report = SimulationReport {
+            samples: 0,
+            rejected: 0,
+            rules: CHECKS
+                .iter()
+                .filter(|&&check| check != Check::DeniedValues)
+                .map(|&check| RuleRejections {
+                    check,
+                    rejected: 0,
+                    decided: 0,
+                })
+                .collect(),
+        };
+
+        for password in samples {
+            let password = password.as_ref();
+            let mut rejected = false;
+
+            for rule in &mut report.rules {
+                if let Some(Err(_)) = self.run_check(rule.check, password, None) {
+                    rule.rejected += 1;
+
+                    if !rejected && !self.policy().is_warning_only(rule.check) {
+                        rule.decided += 1;
+                        rejected = true;
+                    }
+                }
+            }
+
+            report.samples += 1;
+            report.rejected += rejected as usize;
+        }
+
+        report
+    }
+
+    /// Run a sample of passwords through this checker under both `old` and `new`, and report
+    /// which verdicts change, like `simulate_diff`. This checker's own policy isn't used, and the
+    /// decision hook isn't called.
+    pub fn simulate_diff<I>(&self, old: &Policy, new: &Policy, samples: I) -> SimulationDiff
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let old_checker = self.clone().with_policy(old.clone());
+        let new_checker = self.clone().with_policy(new.clone());
+        let mut diff = SimulationDiff {
+            samples: 0,
+            newly_rejected: Vec::new(),
+            newly_accepted: Vec::new(),
+            reason_changed: Vec::new(),
+        };
+
+        for password in samples {
+            let password = password.as_ref();
+            let change = VerdictChange {
+                password: password.to_string(),
+                old: old_checker.verdict(password),
+                new: new_checker.verdict(password),
+            };
+
+            diff.samples += 1;
+
+            match (&change.old, &change.new) {
+                (Ok(()), Err(_)) => diff.newly_rejected.push(change),
+                (Err(_), Ok(())) => diff.newly_accepted.push(change),
+                (Err(old), Err(new)) if old != new => diff.reason_changed.push(change),
+                _ => {}
+            }
+        }
+
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{simulate, simulate_diff, SimulationReport};
+    use checker::PasswordChecker;
+    use decision::Check;
+    use policy::Policy;
+    use wordlist::Wordlist;
+    use PasswordError;
+
+    const SAMPLES: [&str; 5] = [
+        "password",
+        "letmein",
+        "aaaaaaaaaaaaaaaa",
+        "Th1s iS a Sup3rR4ndom PassW0rd!",
+        "correct-horse-battery-staple-92",
+    ];
+
+    fn rule(report: &SimulationReport, check: Check) -> (usize, usize) {
+        let rule = report
+            .rules
+            .iter()
+            .find(|rule| rule.check == check)
+            .unwrap();
+
+        (rule.rejected, rule.decided)
+    }
+
+    #[test]
+    fn it_counts_rejections_per_rule() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+            .with_policy(Policy::new().min_length(12));
+        let report = checker.simulate(SAMPLES.iter());
+
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.rejected, 3);
+        assert_eq!(rule(&report, Check::Length), (2, 2));
+        assert_eq!(rule(&report, Check::Uniqueness), (2, 0));
+        assert_eq!(report.rejection_rate(), 0.6);
+        assert_eq!(report.rule_rejection_rate(Check::Length), 0.4);
+    }
+
+    #[test]
+    fn it_leaves_warnings_out_of_the_verdict() {
+        let policy = Policy::new().min_length(12).warn_only(Check::Length);
+        let checker =
+            PasswordChecker::with_wordlist(Wordlist::parse("password")).with_policy(policy);
+        let report = checker.simulate(SAMPLES.iter());
+
+        assert_eq!(rule(&report, Check::Length), (2, 0));
+        assert_eq!(rule(&report, Check::Uniqueness), (1, 1));
+    }
+
+    #[test]
+    fn it_leaves_out_checks_that_need_a_context() {
+        let report = simulate(&Policy::new(), SAMPLES.iter());
+
+        assert!(report
+            .rules
+            .iter()
+            .all(|rule| rule.check != Check::DeniedValues));
+    }
+
+    #[test]
+    fn it_handles_an_empty_sample() {
+        let report = simulate(&Policy::new(), Vec::<String>::new());
+
+        assert_eq!(report.samples, 0);
+        assert_eq!(report.rejection_rate(), 0.0);
+        assert_eq!(report.rule_rejection_rate(Check::Length), 0.0);
+    }
+
+    #[test]
+    fn it_diffs_verdicts_between_policies() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"));
+        let old = Policy::new().warn_only(Check::Uniqueness);
+        let new = Policy::new().min_length(16).warn_only(Check::Entropy);
+        let samples = [
+            "x7#Kq9!zLm2$",
+            "aaaaaaaaaaaaaaaa",
+            "password",
+            "Th1s iS a Sup3rR4ndom PassW0rd!",
+        ];
+        let diff = checker.simulate_diff(&old, &new, samples.iter());
+
+        assert_eq!(diff.samples, 4);
+        assert_eq!(diff.newly_rejected.len(), 1);
+        assert_eq!(diff.newly_rejected[0].password, "x7#Kq9!zLm2$");
+        assert_eq!(diff.newly_rejected[0].new, Err(PasswordError::TooShort));
+        assert_eq!(diff.newly_accepted.len(), 1);
+        assert_eq!(diff.newly_accepted[0].password, "aaaaaaaaaaaaaaaa");
+        assert_eq!(diff.newly_accepted[0].old, Err(PasswordError::TooSimple));
+        assert_eq!(diff.reason_changed.len(), 1);
+        assert_eq!(diff.reason_changed[0].password, "password");
+        assert!(!diff.is_unchanged());
+    }
+
+    #[test]
+    fn it_finds_no_changes_between_equal_policies() {
+        let diff = simulate_diff(&Policy::new(), &Policy::new(), SAMPLES.iter());
+
+        assert_eq!(diff.samples, 5);
+        assert!(diff.is_unchanged());
+    }
+}
@@ -0,0 +1,105 @@
+//! A string that stays on the stack while it's short, so that normalizing a typical password
+//! doesn't allocate.
+
+use std::str;
+
+#[cfg(feature = "zeroize")]
+use scrub::{scrub_bytes, scrub_string};
+
+/// How many bytes fit before the string moves to the heap. Nearly every real password fits.
+pub const INLINE_CAPACITY: usize = 64;
+
+/// A string built one character at a time, kept in a fixed buffer until it outgrows
+/// `INLINE_CAPACITY` bytes.
+#[derive(Debug, Clone)]
+pub enum SmallString {
+    Inline([u8; INLINE_CAPACITY], usize),
+    Heap(String),
+}
+
+impl SmallString {
+    pub fn new() -> SmallString {
+        SmallString::Inline([0; INLINE_CAPACITY], 0)
+    }
+
+    pub fn push(&mut self, character: char) {
+        let spilled = match *self {
+            SmallString::Inline(ref mut bytes, ref mut len) => {
+                let width = character.len_utf8();
+
+                if *len + width <= INLINE_CAPACITY {
+                    character.encode_utf8(&mut bytes[*len..*len + width]);
+                    *len += width;
+                    return;
+                }
+
+                let mut heap = String::with_capacity(INLINE_CAPACITY * 2);
+
+                heap.push_str(
+                    str::from_utf8(&bytes[..*len]).expect("only whole characters are written"),
+                );
+                heap.push(character);
+                heap
+            }
+            SmallString::Heap(ref mut heap) => {
+                // Grow into a new buffer by hand, so the old one can be wiped before it's freed
+                if heap.len() + character.len_utf8() > heap.capacity() {
+                    let mut grown = String::with_capacity(heap.capacity() * 2);
+
+                    grown.push_str(heap);
+                    #[cfg(feature = "zeroize")]
+                    scrub_string(heap);
+                    *heap = grown;
+                }
+
+                heap.push(character);
+                return;
+            }
+        };
+
+        *self = SmallString::Heap(spilled);
+    }
+
+    pub fn as_str(&self) -> &str {
+        match *self {
+            SmallString::Inline(ref bytes, len) => {
+                str::from_utf8(&bytes[..len]).expect("only whole characters are written")
+            }
+            SmallString::Heap(ref heap) => heap,
+        }
+    }
+}
+
+/// Normalized passwords are kept in these, so they're wiped when dropped with the `zeroize`
+/// feature, wherever they were stored.
+#[cfg(feature = "zeroize")]
+impl Drop for SmallString {
+    fn drop(&mut self) {
+        match *self {
+            SmallString::Inline(ref mut bytes, _) => scrub_bytes(bytes),
+            SmallString::Heap(ref mut heap) => scrub_string(heap),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SmallString, INLINE_CAPACITY};
+
+    #[test]
+    fn it_spills_to_the_heap() {
+        let mut string = SmallString::new();
+
+        for _ in 0..INLINE_CAPACITY - 1 {
+            string.push('a');
+        }
+        string.push('é');
+
+        match string {
+            SmallString::Heap(_) => {}
+            SmallString::Inline(..) => panic!("Expected the string to move to the heap"),
+        }
+        assert_eq!(string.as_str().len(), INLINE_CAPACITY + 1);
+        assert!(string.as_str().ends_with("aé"));
+    }
+}
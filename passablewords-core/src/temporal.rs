@@ -0,0 +1,315 @@
+//! Detection of passwords built around the date, like `Winter2025` or `Q3report!`, which are what
+//! people reach for when they're forced to pick a new password, and so what attackers try first.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use scrub::Scrubbed;
+
+/// The names that are tokens by default: months, their abbreviations, seasons, and Christmas.
+const DEFAULT_NAMES: [&str; 31] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+    "jan",
+    "feb",
+    "mar",
+    "apr",
+    "jun",
+    "jul",
+    "aug",
+    "sep",
+    "sept",
+    "oct",
+    "nov",
+    "dec",
+    "spring",
+    "summer",
+    "autumn",
+    "fall",
+    "winter",
+    "xmas",
+    "christmas",
+];
+
+/// How many years before the current one count as nearby by default.
+pub(crate) const DEFAULT_YEARS_BEFORE: i32 = 5;
+
+/// How many years after the current one count as nearby by default.
+pub(crate) const DEFAULT_YEARS_AFTER: i32 = 1;
+
+/// The tokens that date a password: names like months, seasons, and holidays, quarter tags like
+/// `Q3`, and years near the current one. Use it with `Policy::reject_temporal_patterns`.
+///
+/// A password is rejected when it has at least one of these tokens and, apart from them, at most
+/// one word, so `Winter2025`, `Q3report!`, `January!`, and `Acme2025` are all rejected, while
+/// `correct horse battery 2025` isn't. Words are runs of letters, split where a lowercase letter
+/// is followed by an uppercase one. Only years written out in full count, since two digits on
+/// their own are as likely to be anything else, but a name like `Winter` in `Winter25` is enough.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::TemporalTokens;
+///
+/// let tokens = TemporalTokens::new()
+///     .with_years(2020, 2026)
+///     .with_names(vec!["fy", "ramadan"]);
+///
+/// assert!(tokens.is_temporal("Winter2025"));
+/// assert!(tokens.is_temporal("Q3report!"));
+/// assert!(tokens.is_temporal("Ramadan-Kareem"));
+/// assert!(!tokens.is_temporal("correct horse battery 2025"));
+/// assert!(!tokens.is_temporal("Dragon1987"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct TemporalTokens {
+    names: Vec<String>,
+    first_year: i32,
+    last_year: i32,
+}
+
+impl TemporalTokens {
+    /// The months, their abbreviations, the seasons, Christmas, and the years from five before
+    /// the current one to the one after it.
+    pub fn new() -> TemporalTokens {
+        let year = current_year();
+
+        TemporalTokens::empty()
+            .with_names(DEFAULT_NAMES.iter().cloned())
+            .with_years(year - DEFAULT_YEARS_BEFORE, year + DEFAULT_YEARS_AFTER)
+    }
+
+    /// No names and no years, only quarter tags, for building a list from scratch.
+    pub fn empty() -> TemporalTokens {
+        TemporalTokens {
+            names: Vec::new(),
+            first_year: 1,
+            last_year: 0,
+        }
+    }
+
+    /// Also treat each of `names` as a token, in any case, such as a fiscal year prefix or a
+    /// holiday that matters to the organization's users. Empty names are ignored.
+    pub fn with_names<I, S>(mut self, names: I) -> TemporalTokens
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for name in names {
+            let name = name.as_ref().to_lowercase();
+
+            if !name.is_empty() && !self.names.contains(&name) {
+                self.names.push(name);
+            }
+        }
+        self
+    }
+
+    /// Treat the years from `first` to `last`, inclusive, as tokens, replacing the years there
+    /// were before.
+    pub fn with_years(mut self, first: i32, last: i32) -> TemporalTokens {
+        self.first_year = first;
+        self.last_year = last;
+        self
+    }
+
+    /// The names that are tokens, lowercased.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The first and last years that are tokens.
+    pub fn years(&self) -> (i32, i32) {
+        (self.first_year, self.last_year)
+    }
+
+    /// Whether `password` is built around these tokens, with at most one other word.
+    pub fn is_temporal(&self, password: &str) -> bool {
+        let runs = runs(password);
+        let mut named = false;
+        let mut dated = false;
+        let mut words = 0;
+        let mut index = 0;
+
+        while index < runs.len() {
+            match runs[index] {
+                Run::Letters(ref letters) => {
+                    let quarter = match runs.get(index + 1) {
+                        Some(&Run::Digits(digits)) => {
+                            &**letters == "q" && matches!(digits, "1" | "2" | "3" | "4")
+                        }
+                        _ => false,
+                    };
+
+                    if quarter {
+                        named = true;
+                        index += 1;
+                    } else if self.names.iter().any(|name| **name == **letters) {
+                        named = true;
+                    } else {
+                        words += 1;
+                    }
+                }
+                Run::Digits(digits) if digits.len() == 4 => dated |= self.is_year(digits),
+                Run::Digits(_) => (),
+                Run::Other => (),
+            }
+
+            index += 1;
+        }
+
+        (named || dated) && words <= 1
+    }
+
+    /// Whether `digits` is one of the years.
+    fn is_year(&self, digits: &str) -> bool {
+        digits
+            .parse::<i32>()
+            .map(|year| (self.first_year..=self.last_year).contains(&year))
+            .unwrap_or(false)
+    }
+}
+
+impl Default for TemporalTokens {
+    fn default() -> TemporalTokens {
+        TemporalTokens::new()
+    }
+}
+
+/// A run of a password's characters of the same kind.
+enum Run<'a> {
+    /// Letters, lowercased.
+    Letters(Scrubbed<'a>),
+    Digits(&'a str),
+    Other,
+}
+
+/// Split `password` into runs of letters, runs of ASCII digits, and everything else. Runs of
+/// letters are also split where a lowercase letter is followed by an uppercase one.
+fn runs(password: &str) -> Vec<Run<'_>> {
+    let mut runs = Vec::new();
+    let mut chars = password.char_indices().peekable();
+
+    while let Some((start, first)) = chars.next() {
+        if first.is_alphabetic() {
+            let mut letters: String = first.to_lowercase().collect();
+            let mut previous = first;
+
+            while let Some(&(_, next)) = chars.peek() {
+                if !next.is_alphabetic() || (previous.is_lowercase() && next.is_uppercase()) {
+                    break;
+                }
+
+                letters.extend(next.to_lowercase());
+                previous = next;
+                chars.next();
+            }
+
+            runs.push(Run::Letters(Scrubbed::from(letters)));
+        } else if first.is_ascii_digit() {
+            let mut end = start + 1;
+
+            while let Some(&(index, next)) = chars.peek() {
+                if !next.is_ascii_digit() {
+                    break;
+                }
+
+                end = index + 1;
+                chars.next();
+            }
+
+            runs.push(Run::Digits(&password[start..end]));
+        } else if !matches!(runs.last(), Some(&Run::Other)) {
+            runs.push(Run::Other);
+        }
+    }
+
+    runs
+}
+
+/// The current year in UTC, or 1970 if the clock is set before then.
+pub(crate) fn current_year() -> i32 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+
+    // Howard Hinnant's days_from_civil, run backwards
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+
+    (year_of_era + era * 400 + i64::from(month >= 10)) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{current_year, TemporalTokens};
+
+    #[test]
+    fn it_detects_temporal_passwords() {
+        let tokens = TemporalTokens::new().with_years(2020, 2026);
+
+        for password in [
+            "Winter2025",
+            "winter2025!",
+            "Summer25",
+            "Q3report!",
+            "q4-2024",
+            "January!",
+            "Acme2025",
+            "2024",
+            "Spring Fall",
+        ]
+        .iter()
+        {
+            assert!(tokens.is_temporal(password), "{}", password);
+        }
+
+        for password in [
+            "correct horse battery 2025",
+            "Dragon1987",
+            "Acme25",
+            "Marvelous!",
+            "WinterIsComing2025",
+            "Q5report",
+            "Th1s iS a Sup3rR4ndom PassW0rd!",
+            "",
+        ]
+        .iter()
+        {
+            assert!(!tokens.is_temporal(password), "{}", password);
+        }
+    }
+
+    #[test]
+    fn it_uses_configured_tokens() {
+        let tokens = TemporalTokens::empty().with_names(vec!["FY", "", "fy"]);
+
+        assert_eq!(tokens.names(), &["fy".to_string()]);
+        assert!(tokens.is_temporal("FY25"));
+        assert!(!tokens.is_temporal("Winter2025"));
+        assert!(tokens.is_temporal("Q1!"));
+    }
+
+    #[test]
+    fn it_knows_the_current_year() {
+        let (first, last) = TemporalTokens::new().years();
+
+        assert!(current_year() >= 2024);
+        assert_eq!(last - first, 6);
+    }
+}
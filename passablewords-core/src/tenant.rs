@@ -0,0 +1,405 @@
+//! Policies which differ from one tenant to the next, for multi-tenant applications where every
+//! customer sets their own password requirements.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::RwLock;
+
+use checker::PasswordChecker;
+use policy::Policy;
+use {PassablewordResult, PasswordError};
+
+/// How many tenants' checkers a `TenantChecker` keeps by default.
+const DEFAULT_CACHE_CAPACITY: usize = 1000;
+
+/// Somewhere to look up each tenant's policy, such as a database table or a configuration service.
+pub trait TenantPolicyStore {
+    /// The policy for the given tenant, or `None` if the tenant should use the default policy. An
+    /// error means the store couldn't be asked, and the tenant's passwords are rejected rather
+    /// than checked against the default policy.
+    fn policy(&self, tenant_id: &str) -> io::Result<Option<Policy>>;
+}
+
+/// A `TenantPolicyStore` which keeps every policy in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryTenantPolicyStore {
+    policies: RwLock<HashMap<String, Policy>>,
+}
+
+impl InMemoryTenantPolicyStore {
+    /// Create a store without any policies, so every tenant uses the default.
+    pub fn new() -> InMemoryTenantPolicyStore {
+        InMemoryTenantPolicyStore::default()
+    }
+
+    /// Set the policy for a tenant, replacing any previous one.
+    pub fn insert(&self, tenant_id: &str, policy: Policy) {
+        match self.policies.write() {
+            Ok(mut policies) => policies.insert(tenant_id.to_string(), policy),
+            Err(poisoned) => poisoned.into_inner().insert(tenant_id.to_string(), policy),
+        };
+    }
+
+    /// Remove the policy for a tenant, so it goes back to using the default.
+    pub fn remove(&self, tenant_id: &str) {
+        match self.policies.write() {
+            Ok(mut policies) => policies.remove(tenant_id),
+            Err(poisoned) => poisoned.into_inner().remove(tenant_id),
+        };
+    }
+}
+
+impl TenantPolicyStore for InMemoryTenantPolicyStore {
+    fn policy(&self, tenant_id: &str) -> io::Result<Option<Policy>> {
+        Ok(match self.policies.read() {
+            Ok(policies) => policies.get(tenant_id).cloned(),
+            Err(poisoned) => poisoned.into_inner().get(tenant_id).cloned(),
+        })
+    }
+}
+
+/// Checks passwords against the policy of whichever tenant they belong to. Each tenant's policy is
+/// looked up in the store the first time one of their passwords is checked and cached from then
+/// on, so call `invalidate` after changing a tenant's policy. Tenants without a policy of their
+/// own use the policy of the default checker, and aren't cached, so they pick up a policy added
+/// to the store right away. Once the cache holds 1000 tenants, or the capacity given with
+/// `with_cache_capacity`, the tenant that was cached first is evicted to make room.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{InMemoryTenantPolicyStore, PasswordError, Policy, TenantChecker};
+///
+/// let store = InMemoryTenantPolicyStore::new();
+///
+/// store.insert("strict-corp", Policy::new().min_length(40));
+///
+/// let checker = TenantChecker::new(store);
+/// let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+///
+/// assert_eq!(checker.check_password_for_tenant("relaxed-inc", password), Ok(()));
+/// assert_eq!(
+///     checker.check_password_for_tenant("strict-corp", password),
+///     Err(PasswordError::TooShort)
+/// );
+/// ```
+#[derive(Debug)]
+pub struct TenantChecker<S> {
+    store: S,
+    default: PasswordChecker,
+    cache: RwLock<TenantCache>,
+}
+
+/// The checkers of the tenants with policies of their own, and the order they were cached in.
+/// The generation changes whenever a tenant is invalidated, so a policy read from the store before
+/// that isn't cached after it.
+#[derive(Debug)]
+struct TenantCache {
+    capacity: usize,
+    checkers: HashMap<String, PasswordChecker>,
+    order: VecDeque<String>,
+    generation: u64,
+}
+
+impl TenantCache {
+    fn new(capacity: usize) -> TenantCache {
+        TenantCache {
+            capacity,
+            checkers: HashMap::new(),
+            order: VecDeque::new(),
+            generation: 0,
+        }
+    }
+
+    /// Cache a tenant's checker, unless the cache has been invalidated since `generation`.
+    fn insert(&mut self, generation: u64, tenant_id: &str, checker: PasswordChecker) {
+        if self.capacity == 0 || self.generation != generation {
+            return;
+        }
+
+        if self
+            .checkers
+            .insert(tenant_id.to_string(), checker)
+            .is_none()
+        {
+            self.order.push_back(tenant_id.to_string());
+        }
+
+        while self.checkers.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => self.checkers.remove(&oldest),
+                None => break,
+            };
+        }
+    }
+
+    fn remove(&mut self, tenant_id: &str) {
+        self.generation = self.generation.wrapping_add(1);
+
+        if self.checkers.remove(tenant_id).is_some() {
+            self.order.retain(|cached| cached != tenant_id);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.checkers.clear();
+        self.order.clear();
+    }
+}
+
+impl<S: TenantPolicyStore> TenantChecker<S> {
+    /// Create a checker which falls back to the default policy and the bundled list of common
+    /// passwords.
+    pub fn new(store: S) -> TenantChecker<S> {
+        TenantChecker::with_default(store, PasswordChecker::new())
+    }
+
+    /// Create a checker which falls back to the given checker. Every tenant also shares that
+    /// checker's wordlist.
+    pub fn with_default(store: S, default: PasswordChecker) -> TenantChecker<S> {
+        TenantChecker {
+            store,
+            default,
+            cache: RwLock::new(TenantCache::new(DEFAULT_CACHE_CAPACITY)),
+        }
+    }
+
+    /// Keep the checkers of at most `capacity` tenants, rather than 1000. A capacity of 0 looks
+    /// every tenant's policy up in the store for every check.
+    pub fn with_cache_capacity(self, capacity: usize) -> TenantChecker<S> {
+        TenantChecker {
+            cache: RwLock::new(TenantCache::new(capacity)),
+            ..self
+        }
+    }
+
+    /// The store policies are looked up in.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The checker used for a particular tenant, or the store's error if it couldn't be asked for
+    /// the tenant's policy.
+    pub fn checker_for_tenant(&self, tenant_id: &str) -> io::Result<PasswordChecker> {
+        let (generation, cached) = match self.cache.read() {
+            Ok(cache) => (cache.generation, cache.checkers.get(tenant_id).cloned()),
+            Err(poisoned) => {
+                let cache = poisoned.into_inner();
+
+                (cache.generation, cache.checkers.get(tenant_id).cloned())
+            }
+        };
+
+        if let Some(checker) = cached {
+            return Ok(checker);
+        }
+
+        let checker = match self.store.policy(tenant_id)? {
+            Some(policy) => self.default.clone().with_policy(policy),
+            None => return Ok(self.default.clone()),
+        };
+
+        match self.cache.write() {
+            Ok(mut cache) => cache.insert(generation, tenant_id, checker.clone()),
+            Err(poisoned) => poisoned
+                .into_inner()
+                .insert(generation, tenant_id, checker.clone()),
+        };
+
+        Ok(checker)
+    }
+
+    /// Check a password against the policy of the given tenant. If the store can't be asked for
+    /// the tenant's policy, the password is rejected with `PasswordError::InternalError`.
+    pub fn check_password_for_tenant(&self, tenant_id: &str, password: &str) -> PassablewordResult {
+        self.checker_for_tenant(tenant_id)
+            .map_err(|_| PasswordError::InternalError)?
+            .check_password(password)
+    }
+
+    /// Forget the cached policy for a tenant, so that it's looked up in the store again the next
+    /// time it's needed. A policy that was being read from the store at the same time isn't
+    /// cached either.
+    pub fn invalidate(&self, tenant_id: &str) {
+        match self.cache.write() {
+            Ok(mut cache) => cache.remove(tenant_id),
+            Err(poisoned) => poisoned.into_inner().remove(tenant_id),
+        };
+    }
+
+    /// Forget every cached policy.
+    pub fn invalidate_all(&self) {
+        match self.cache.write() {
+            Ok(mut cache) => cache.clear(),
+            Err(poisoned) => poisoned.into_inner().clear(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InMemoryTenantPolicyStore, TenantChecker, TenantPolicyStore};
+    use policy::Policy;
+    use std::io;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::sync::Mutex;
+    use std::thread;
+    use PasswordError;
+
+    #[test]
+    fn it_checks_passwords_per_tenant() {
+        let store = InMemoryTenantPolicyStore::new();
+        let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+        store.insert("strict", Policy::new().min_length(40));
+
+        let checker = TenantChecker::new(store);
+
+        assert_eq!(checker.check_password_for_tenant("other", password), Ok(()));
+        assert_eq!(
+            checker.check_password_for_tenant("strict", password),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            checker.check_password_for_tenant("other", "short"),
+            Err(PasswordError::TooShort)
+        );
+    }
+
+    #[test]
+    fn it_caches_policies_until_invalidated() {
+        let checker = TenantChecker::new(InMemoryTenantPolicyStore::new());
+        let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Ok(())
+        );
+
+        checker
+            .store()
+            .insert("tenant", Policy::new().min_length(40));
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Err(PasswordError::TooShort)
+        );
+
+        checker.store().remove("tenant");
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Err(PasswordError::TooShort)
+        );
+
+        checker.invalidate("tenant");
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Ok(())
+        );
+
+        checker
+            .store()
+            .insert("tenant", Policy::new().min_length(40));
+        checker.check_password_for_tenant("tenant", password).ok();
+        checker.store().remove("tenant");
+        checker.invalidate_all();
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_does_not_cache_a_policy_invalidated_while_reading_it() {
+        // Hands out a strict policy once, waiting to be released after it's been asked, and then
+        // no policy at all, as if the tenant's policy was removed in the meantime
+        struct Blocking {
+            calls: AtomicUsize,
+            entered: Mutex<Sender<()>>,
+            release: Mutex<Receiver<()>>,
+        }
+
+        impl TenantPolicyStore for Blocking {
+            fn policy(&self, _tenant_id: &str) -> io::Result<Option<Policy>> {
+                if self.calls.fetch_add(1, Ordering::SeqCst) > 0 {
+                    return Ok(None);
+                }
+
+                self.entered.lock().unwrap().send(()).unwrap();
+                self.release.lock().unwrap().recv().unwrap();
+
+                Ok(Some(Policy::new().min_length(40)))
+            }
+        }
+
+        let (entered, on_entered) = mpsc::channel();
+        let (release, on_release) = mpsc::channel();
+        let checker = TenantChecker::new(Blocking {
+            calls: AtomicUsize::new(0),
+            entered: Mutex::new(entered),
+            release: Mutex::new(on_release),
+        });
+        let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+        thread::scope(|scope| {
+            let reading = scope.spawn(|| checker.check_password_for_tenant("tenant", password));
+
+            on_entered.recv().unwrap();
+            checker.invalidate("tenant");
+            release.send(()).unwrap();
+
+            assert_eq!(reading.join().unwrap(), Err(PasswordError::TooShort));
+        });
+
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", password),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_tenant() {
+        let store = InMemoryTenantPolicyStore::new();
+        let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+        store.insert("first", Policy::new().min_length(40));
+        store.insert("second", Policy::new().min_length(40));
+
+        let checker = TenantChecker::new(store).with_cache_capacity(1);
+
+        checker.check_password_for_tenant("first", password).ok();
+        checker.check_password_for_tenant("second", password).ok();
+        checker.store().remove("first");
+        checker.store().remove("second");
+
+        assert_eq!(checker.check_password_for_tenant("first", password), Ok(()));
+        assert_eq!(
+            checker.check_password_for_tenant("second", password),
+            Err(PasswordError::TooShort)
+        );
+    }
+
+    #[test]
+    fn it_rejects_passwords_when_the_store_fails() {
+        struct Unreachable;
+
+        impl TenantPolicyStore for Unreachable {
+            fn policy(&self, _tenant_id: &str) -> io::Result<Option<Policy>> {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "timed out"))
+            }
+        }
+
+        let checker = TenantChecker::new(Unreachable);
+
+        assert!(checker.checker_for_tenant("tenant").is_err());
+        assert_eq!(
+            checker.check_password_for_tenant("tenant", "Th1s iS a Sup3rR4ndom PassW0rd!"),
+            Err(PasswordError::InternalError)
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Generating the passwords an organization's users are likely to build from its own name, like
+//! `Acme2025!` or `@cm3c0rp`, so that admins don't have to list every variant by hand.
+
+use std::collections::BTreeSet;
+
+use temporal::{current_year, DEFAULT_YEARS_AFTER, DEFAULT_YEARS_BEFORE};
+use wordlist::Wordlist;
+
+/// Words that are part of a company's legal name but not of what anyone calls it.
+const LEGAL_SUFFIXES: [&str; 12] = [
+    "inc",
+    "llc",
+    "ltd",
+    "corp",
+    "co",
+    "gmbh",
+    "plc",
+    "ag",
+    "sa",
+    "company",
+    "corporation",
+    "group",
+];
+
+/// What's added to the end of a term, apart from years.
+const SUFFIXES: [&str; 5] = ["", "!", "1", "123", "1!"];
+
+/// The l33t substitutions that use `@` and `$`, like `@cm3`.
+const AT_SIGN_SUBSTITUTIONS: [(char, char); 5] =
+    [('a', '@'), ('e', '3'), ('i', '1'), ('o', '0'), ('s', '$')];
+
+/// The l33t substitutions that only use digits, like `4cm3`.
+const DIGIT_SUBSTITUTIONS: [(char, char); 6] = [
+    ('a', '4'),
+    ('e', '3'),
+    ('i', '1'),
+    ('o', '0'),
+    ('s', '5'),
+    ('t', '7'),
+];
+
+/// The shortest word of a longer name that's blocked on its own.
+const MIN_WORD_LENGTH: usize = 4;
+
+/// What an organization calls itself, for generating `BlockedTerms` from. Only the company name is
+/// required.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::OrgProfile;
+///
+/// let profile = OrgProfile::new("Acme Corp")
+///     .with_domain("acme-widgets.com")
+///     .with_products(vec!["RoadRunner", "Anvil Pro"])
+///     .with_years(2020, 2026);
+///
+/// assert_eq!(profile.company_name(), "Acme Corp");
+/// assert_eq!(profile.products(), &["RoadRunner".to_string(), "Anvil Pro".to_string()]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrgProfile {
+    company_name: String,
+    domain: Option<String>,
+    products: Vec<String>,
+    first_year: i32,
+    last_year: i32,
+}
+
+impl OrgProfile {
+    /// A profile of the company called `company_name`, which combines its terms with the years
+    /// from five before the current one to the one after it, like `TemporalTokens::new` does.
+    pub fn new(company_name: &str) -> OrgProfile {
+        let year = current_year();
+
+        OrgProfile {
+            company_name: company_name.to_string(),
+            domain: None,
+            products: Vec::new(),
+            first_year: year - DEFAULT_YEARS_BEFORE,
+            last_year: year + DEFAULT_YEARS_AFTER,
+        }
+    }
+
+    /// Also block terms from the company's domain, like `acme-widgets.com` or
+    /// `https://www.acme.com`. Only the first label after any `www` is used.
+    pub fn with_domain(mut self, domain: &str) -> OrgProfile {
+        self.domain = Some(domain.to_string());
+        self
+    }
+
+    /// Also block terms from the names of the company's products. Empty names are ignored.
+    pub fn with_products<I, S>(mut self, products: I) -> OrgProfile
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.products.extend(
+            products
+                .into_iter()
+                .map(|product| product.as_ref().trim().to_string())
+                .filter(|product| !product.is_empty()),
+        );
+        self
+    }
+
+    /// Combine terms with the years from `first` to `last`, inclusive, instead.
+    pub fn with_years(mut self, first: i32, last: i32) -> OrgProfile {
+        self.first_year = first;
+        self.last_year = last;
+        self
+    }
+
+    /// The company's name.
+    pub fn company_name(&self) -> &str {
+        &self.company_name
+    }
+
+    /// The company's domain, if it was given.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// The names of the company's products.
+    pub fn products(&self) -> &[String] {
+        &self.products
+    }
+
+    /// The first and last years that are combined with terms.
+    pub fn years(&self) -> (i32, i32) {
+        (self.first_year, self.last_year)
+    }
+}
+
+/// The passwords that are built from an organization's own name, generated from an `OrgProfile`.
+/// Give them to `PasswordChecker::with_labelled_wordlist` with `to_wordlist` to reject them.
+///
+/// Each name is split into words, ignoring legal suffixes like `Inc` and `Corp`, and the terms
+/// are the whole name run together, each word of at least four letters, and the initials of
+/// names with more than one word. Each term is then written in lowercase, capitalized, with each
+/// word capitalized, and in uppercase, and with two common sets of l33t substitutions, like `@cm3`
+/// and `4cm3`. Every one of those is blocked on its own, with a `!`, `1`, `123`, or `1!` after it, and with each of the
+/// profile's years, in full or as two digits, with or without a `!`.
+///
+/// # Example
+///
+/// ```
+/// use passablewords_core::{BlockedTerms, OrgProfile, PasswordChecker, PasswordError};
+///
+/// let profile = OrgProfile::new("Acme Corp")
+///     .with_products(vec!["RoadRunner"])
+///     .with_years(2020, 2026);
+/// let terms = BlockedTerms::from_profile(&profile);
+///
+/// assert!(terms.contains("Acme2025!"));
+/// assert!(terms.contains("@cm3c0rp"));
+/// assert!(terms.contains("R04DRUNN3R1!"));
+///
+/// let checker = PasswordChecker::new().with_labelled_wordlist("organization", terms.to_wordlist());
+///
+/// assert_eq!(
+///     checker.check_uniqueness("AcmeCorp24"),
+///     Err(PasswordError::TooCommonIn("organization".to_string()))
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockedTerms {
+    terms: BTreeSet<String>,
+}
+
+impl BlockedTerms {
+    /// Generate every variant of the names in `profile`.
+    pub fn from_profile(profile: &OrgProfile) -> BlockedTerms {
+        let mut bases = BTreeSet::new();
+
+        add_name(&mut bases, &profile.company_name);
+        if let Some(label) = profile
+            .domain
+            .as_ref()
+            .and_then(|domain| domain_label(domain))
+        {
+            add_name(&mut bases, label);
+        }
+        for product in &profile.products {
+            add_name(&mut bases, product);
+        }
+
+        let mut suffixes: Vec<String> = SUFFIXES.iter().map(|suffix| suffix.to_string()).collect();
+
+        for year in profile.first_year..=profile.last_year {
+            let short = format!("{:02}", year.rem_euclid(100));
+
+            suffixes.push(year.to_string());
+            suffixes.push(format!("{}!", year));
+            suffixes.push(short.clone());
+            suffixes.push(format!("{}!", short));
+        }
+
+        let terms = bases
+            .iter()
+            .flat_map(|words| spellings(words))
+            .flat_map(|spelling| {
+                suffixes
+                    .iter()
+                    .map(move |suffix| format!("{}{}", spelling, suffix))
+            })
+            .collect();
+
+        BlockedTerms { terms }
+    }
+
+    /// Whether `password` is one of the terms, exactly.
+    pub fn contains(&self, password: &str) -> bool {
+        self.terms.contains(password)
+    }
+
+    /// The terms, in order.
+    pub fn iter<'a>(&'a self) -> impl Iterator<Item = &'a str> + 'a {
+        self.terms.iter().map(String::as_str)
+    }
+
+    /// How many terms there are.
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// Whether there are no terms at all.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// A wordlist of the terms, for `PasswordChecker::with_labelled_wordlist` or
+    /// `Wordlist::merge`.
+    pub fn to_wordlist(&self) -> Wordlist {
+        self.terms.iter().cloned().collect()
+    }
+}
+
+/// Add the terms a name is split into to `bases`, as lowercase words.
+fn add_name(bases: &mut BTreeSet<Vec<String>>, name: &str) {
+    let words: Vec<String> = name
+        .split(|character: char| !character.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let core: Vec<String> = words
+        .iter()
+        .filter(|word| !LEGAL_SUFFIXES.contains(&word.as_str()))
+        .cloned()
+        .collect();
+
+    if words.is_empty() {
+        return;
+    }
+
+    if core.len() > 1 {
+        bases.insert(vec![core
+            .iter()
+            .filter_map(|word| word.chars().next())
+            .collect()]);
+        bases.extend(
+            core.iter()
+                .filter(|word| word.chars().count() >= MIN_WORD_LENGTH)
+                .map(|word| vec![word.clone()]),
+        );
+    }
+    if !core.is_empty() {
+        bases.insert(core);
+    }
+    bases.insert(words);
+}
+
+/// The part of a domain that names the organization, like `acme` in `https://www.acme.com/`.
+fn domain_label(domain: &str) -> Option<&str> {
+    let host = domain
+        .split("://")
+        .last()
+        .unwrap_or(domain)
+        .split(&['/', ':'][..])
+        .next()
+        .unwrap_or("");
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    host.split('.').next().filter(|label| !label.is_empty())
+}
+
+/// The ways the lowercase `words` are likely to be written when they're run together.
+fn spellings(words: &[String]) -> BTreeSet<String> {
+    let lowercase = words.concat();
+    let capitalized = capitalize(&lowercase);
+    let camel_case: String = words.iter().map(|word| capitalize(word)).collect();
+    let uppercase = lowercase.to_uppercase();
+    let mut spellings = BTreeSet::new();
+
+    for spelling in &[lowercase, capitalized, camel_case] {
+        spellings.insert(substitute(spelling, &AT_SIGN_SUBSTITUTIONS));
+        spellings.insert(substitute(spelling, &DIGIT_SUBSTITUTIONS));
+        spellings.insert(spelling.clone());
+    }
+    spellings.insert(substitute(&uppercase.to_lowercase(), &DIGIT_SUBSTITUTIONS).to_uppercase());
+    spellings.insert(uppercase);
+
+    spellings
+}
+
+/// `word` with its first letter in uppercase.
+fn capitalize(word: &str) -> String {
+    let mut characters = word.chars();
+
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+/// `word` with each character replaced by the one it's paired with in `substitutions`, if any.
+fn substitute(word: &str, substitutions: &[(char, char)]) -> String {
+    word.chars()
+        .map(|character| {
+            substitutions
+                .iter()
+                .find(|&&(from, _)| from == character)
+                .map_or(character, |&(_, to)| to)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{domain_label, BlockedTerms, OrgProfile};
+
+    #[test]
+    fn it_generates_terms_from_a_profile() {
+        let profile = OrgProfile::new("Acme Widgets, Inc.")
+            .with_domain("https://www.acme-widgets.com/login")
+            .with_products(vec!["Anvil Pro", ""])
+            .with_years(2024, 2025);
+        let terms = BlockedTerms::from_profile(&profile);
+
+        for term in [
+            "acmewidgets",
+            "AcmeWidgets!",
+            "ACMEWIDGETS2025",
+            "Widgets24!",
+            "@cm3w1dg3t$",
+            "4cm3",
+            "aw2024",
+            "AnvilPro123",
+            "4NV1LPR0",
+            "acmewidgetsinc",
+        ]
+        .iter()
+        {
+            assert!(terms.contains(term), "{}", term);
+        }
+
+        for term in ["inc", "Acme2023", "pro", "acme widgets", ""].iter() {
+            assert!(!terms.contains(term), "{}", term);
+        }
+
+        assert!(terms.len() < 2_000);
+        assert!(terms.to_wordlist().contains("Acme2024!"));
+    }
+
+    #[test]
+    fn it_finds_the_domain_label() {
+        assert_eq!(domain_label("acme.com"), Some("acme"));
+        assert_eq!(domain_label("https://www.acme.co.uk/"), Some("acme"));
+        assert_eq!(domain_label("acme.com:8080"), Some("acme"));
+        assert_eq!(domain_label("https://"), None);
+    }
+
+    #[test]
+    fn it_ignores_empty_names() {
+        let terms = BlockedTerms::from_profile(&OrgProfile::new(" - "));
+
+        assert!(terms.is_empty());
+    }
+}
@@ -0,0 +1,193 @@
+//! Helpers for property testing code that relies on passablewords, such as signup and password
+//! change flows. Every password these helpers return has been run through the crate's own checks,
+//! so a "weak" password is one that passablewords rejects and a "passing" one is one that it
+//! accepts, rather than an approximation of either.
+//!
+//! The generators are deterministic for a given seed and don't depend on any particular property
+//! testing crate, so the seed can come from proptest, quickcheck, or a plain loop.
+//!
+//! This module is only available with the `testing` feature, which is meant to be enabled in
+//! `dev-dependencies`.
+//!
+//! # Example
+//!
+//! ```
+//! use passablewords_core::testing::{arbitrary_passing_password, arbitrary_weak_password};
+//! use passablewords_core::{check_password, PasswordChecker};
+//!
+//! let checker = PasswordChecker::new();
+//!
+//! for seed in 0..10 {
+//!     assert!(check_password(&arbitrary_weak_password(seed)).is_err());
+//!     assert!(check_password(&arbitrary_passing_password(&checker, seed)).is_ok());
+//! }
+//! ```
+
+use check_password;
+use checker::PasswordChecker;
+
+/// A handful of passwords which are near the top of the bundled list.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "12345678",
+    "123456789",
+    "qwertyuiop",
+    "iloveyou",
+    "sunshine",
+    "football",
+    "baseball",
+    "princess",
+    "superman",
+    "trustno1",
+    "1234567890",
+    "welcome1",
+    "password1",
+];
+
+/// Runs of characters with next to no entropy.
+const SIMPLE_RUNS: &[&str] = &["a", "ab", "abc", "0", "12", "123", "xyz", "qwe"];
+
+const PASSING_CHARACTERS: &[u8] =
+    b"abcdefghijkmnopqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ23456789!@#$%^&*-_=+?";
+
+/// How many candidates a generator tries before giving up. Candidates are built to fail or pass
+/// on the first try, so this is only reached if the checks are configured in a way the
+/// generator can't satisfy.
+const MAX_ATTEMPTS: usize = 1000;
+
+/// What seeds are mixed with before they become the generator's state.
+const SEED_MIX: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// A deterministic generator of weak and passing passwords.
+#[derive(Debug, Clone)]
+pub struct PasswordGenerator {
+    state: u64,
+}
+
+impl PasswordGenerator {
+    /// Create a generator. The same seed always produces the same passwords.
+    pub fn new(seed: u64) -> PasswordGenerator {
+        // xorshift gets stuck on a state of 0, so mix the seed into a fixed odd constant, and
+        // use the constant itself for the one seed that mixes to 0
+        let state = match seed ^ SEED_MIX {
+            0 => SEED_MIX,
+            state => state,
+        };
+
+        PasswordGenerator { state }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn pick<'a>(&mut self, choices: &[&'a str]) -> &'a str {
+        choices[self.below(choices.len())]
+    }
+
+    /// A password which is too short, too common, or too simple. Each of the three is equally
+    /// likely.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator can't find a password that `check_password` rejects.
+    pub fn weak_password(&mut self) -> String {
+        for _ in 0..MAX_ATTEMPTS {
+            let candidate = match self.below(3) {
+                0 => {
+                    let length = 1 + self.below(7);
+
+                    (0..length)
+                        .map(|_| PASSING_CHARACTERS[self.below(PASSING_CHARACTERS.len())] as char)
+                        .collect()
+                }
+                1 => self.pick(COMMON_PASSWORDS).to_string(),
+                _ => self.pick(SIMPLE_RUNS).repeat(8 + self.below(8)),
+            };
+
+            if check_password(&candidate).is_err() {
+                return candidate;
+            }
+        }
+
+        panic!("Couldn't generate a password that passablewords rejects")
+    }
+
+    /// A password which the checker accepts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator can't find a password that `checker` accepts.
+    pub fn passing_password(&mut self, checker: &PasswordChecker) -> String {
+        for _ in 0..MAX_ATTEMPTS {
+            let length = 16 + self.below(16);
+            let candidate: String = (0..length)
+                .map(|_| PASSING_CHARACTERS[self.below(PASSING_CHARACTERS.len())] as char)
+                .collect();
+
+            if checker.check_password(&candidate).is_ok() {
+                return candidate;
+            }
+        }
+
+        panic!("Couldn't generate a password that the checker accepts")
+    }
+}
+
+/// A password which `check_password` rejects, generated from `seed`.
+pub fn arbitrary_weak_password(seed: u64) -> String {
+    PasswordGenerator::new(seed).weak_password()
+}
+
+/// A password which `checker` accepts, generated from `seed`.
+pub fn arbitrary_passing_password(checker: &PasswordChecker, seed: u64) -> String {
+    PasswordGenerator::new(seed).passing_password(checker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{arbitrary_passing_password, arbitrary_weak_password, PasswordGenerator};
+    use {check_password, PasswordChecker};
+
+    #[test]
+    fn it_generates_weak_passwords() {
+        let mut generator = PasswordGenerator::new(42);
+
+        for _ in 0..50 {
+            assert!(check_password(&generator.weak_password()).is_err());
+        }
+        assert_eq!(arbitrary_weak_password(7), arbitrary_weak_password(7));
+    }
+
+    #[test]
+    fn it_never_starts_stuck() {
+        let mut generator = PasswordGenerator::new(0x9e37_79b9_7f4a_7c15);
+
+        assert_ne!(generator.next(), 0);
+        assert_ne!(generator.weak_password(), generator.weak_password());
+    }
+
+    #[test]
+    fn it_generates_passing_passwords() {
+        let checker = PasswordChecker::new();
+        let mut generator = PasswordGenerator::new(42);
+
+        for _ in 0..50 {
+            assert_eq!(
+                checker.check_password(&generator.passing_password(&checker)),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            arbitrary_passing_password(&checker, 7),
+            arbitrary_passing_password(&checker, 7)
+        );
+    }
+}
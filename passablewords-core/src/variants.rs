@@ -0,0 +1,299 @@
+//! Variants of a password that attackers commonly try on top of the passwords in their lists.
+
+/// The shortest core that's still checked after stripping, to avoid flagging passwords only
+/// because a few of their letters happen to be in a list.
+const MIN_CORE_LENGTH: usize = 4;
+
+fn is_symbol(character: char) -> bool {
+    character.is_ascii_punctuation()
+}
+
+/// The ways the end of a password can be stripped: nothing, a single symbol, a run of digits, or
+/// a run of digits followed by a single symbol. Returns the number of bytes removed by each, and
+/// how many of the three slots are used.
+fn suffix_lengths(password: &str) -> ([usize; 3], usize) {
+    let mut lengths = [0; 3];
+    let mut count = 1;
+    let mut rest = password;
+
+    if let Some(last) = rest.chars().next_back() {
+        if is_symbol(last) {
+            rest = &rest[..rest.len() - 1];
+            lengths[count] = 1;
+            count += 1;
+        }
+    }
+
+    let digits = rest.len() - rest.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+
+    if digits > 0 {
+        lengths[count] = password.len() - rest.len() + digits;
+        count += 1;
+    }
+
+    (lengths, count)
+}
+
+/// The same as `suffix_lengths`, but for the start of a password, where symbols come first.
+fn prefix_lengths(password: &str) -> ([usize; 3], usize) {
+    let mut lengths = [0; 3];
+    let mut count = 1;
+    let mut rest = password;
+
+    if let Some(first) = rest.chars().next() {
+        if is_symbol(first) {
+            rest = &rest[1..];
+            lengths[count] = 1;
+            count += 1;
+        }
+    }
+
+    let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+
+    if digits > 0 {
+        lengths[count] = password.len() - rest.len() + digits;
+        count += 1;
+    }
+
+    (lengths, count)
+}
+
+/// The variants of a password with its affixes stripped. They're kept in a fixed-size array
+/// rather than a `Vec` since there can be at most eight of them, which keeps uniqueness checks
+/// free of allocations.
+#[derive(Debug, Clone)]
+pub struct AffixStripped<'a> {
+    variants: [&'a str; 8],
+    len: usize,
+    next: usize,
+}
+
+impl<'a> Iterator for AffixStripped<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.next < self.len {
+            self.next += 1;
+            Some(self.variants[self.next - 1])
+        } else {
+            None
+        }
+    }
+}
+
+/// Every version of the password with common prefixes and suffixes removed, such as years,
+/// trailing digits, and single symbols. `password2024!` gives `password2024` and `password`,
+/// for example. The password itself isn't included.
+pub fn affix_stripped<'a>(password: &'a str) -> AffixStripped<'a> {
+    let (prefixes, prefix_count) = prefix_lengths(password);
+    let (suffixes, suffix_count) = suffix_lengths(password);
+    let mut stripped = AffixStripped {
+        variants: [""; 8],
+        len: 0,
+        next: 0,
+    };
+
+    for &prefix in &prefixes[..prefix_count] {
+        for &suffix in &suffixes[..suffix_count] {
+            if prefix + suffix == 0 || prefix + suffix + MIN_CORE_LENGTH > password.len() {
+                continue;
+            }
+
+            let core = &password[prefix..password.len() - suffix];
+
+            if !stripped.variants[..stripped.len].contains(&core) {
+                stripped.variants[stripped.len] = core;
+                stripped.len += 1;
+            }
+        }
+    }
+
+    stripped
+}
+
+/// The most digits and symbols that can surround a word for `word_shell` to match.
+pub(crate) const MAX_SHELL_DIGITS: usize = 4;
+pub(crate) const MAX_SHELL_SYMBOLS: usize = 1;
+
+/// The word at the heart of passwords shaped like `Summer2024!`: a single run of letters, in any
+/// case, with at most four digits and one symbol around it. The word is returned in lowercase,
+/// and `None` is returned for passwords of any other shape.
+pub fn word_shell(password: &str) -> Option<String> {
+    let mut digits = 0;
+    let mut symbols = 0;
+    let mut word: Option<(usize, usize)> = None;
+
+    for (index, character) in password.char_indices() {
+        if character.is_alphabetic() {
+            word = match word {
+                None => Some((index, index + character.len_utf8())),
+                // A second run of letters means there's more than one word
+                Some((start, end)) if end == index => Some((start, index + character.len_utf8())),
+                Some(_) => return None,
+            };
+        } else if character.is_ascii_digit() {
+            digits += 1;
+        } else {
+            symbols += 1;
+        }
+    }
+
+    match word {
+        Some((start, end))
+            if digits <= MAX_SHELL_DIGITS
+                && symbols <= MAX_SHELL_SYMBOLS
+                && password[start..end].chars().count() >= MIN_CORE_LENGTH =>
+        {
+            Some(password[start..end].to_lowercase())
+        }
+        _ => None,
+    }
+}
+
+/// How many characters next to the first and last letters of a password are also tried as part
+/// of the word by `single_words`, for substitutions like the `$` in `$unshine`.
+const MAX_EDGE_SUBSTITUTIONS: usize = 2;
+
+/// A password split into a run of characters that may be a dressed-up dictionary word, and what's
+/// around it, returned by `single_words`.
+#[derive(Debug, PartialEq)]
+pub struct SingleWord<'a> {
+    /// The possible word, as it was typed.
+    pub core: &'a str,
+    /// The longest run of digits before or after the core.
+    pub digits: usize,
+    /// How many characters before or after the core are neither letters nor digits.
+    pub symbols: usize,
+}
+
+/// The ways a password can be split around a single word, for checking whether it's a dictionary
+/// word with only digits and symbols around it. The core runs from the first letter to the last,
+/// and then takes in up to `MAX_EDGE_SUBSTITUTIONS` more characters on either side, in case
+/// they're substituted letters. Nothing is returned if there are fewer than `MIN_CORE_LENGTH`
+/// characters from the first letter to the last.
+pub fn single_words<'a>(password: &'a str) -> Vec<SingleWord<'a>> {
+    let mut words = Vec::new();
+    let start = match password.find(char::is_alphabetic) {
+        Some(start) => start,
+        None => return words,
+    };
+    let end = password
+        .char_indices()
+        .rev()
+        .find(|&(_, character)| character.is_alphabetic())
+        .map_or(start, |(index, character)| index + character.len_utf8());
+
+    if password[start..end].chars().count() < MIN_CORE_LENGTH {
+        return words;
+    }
+
+    let starts = password[..start]
+        .char_indices()
+        .rev()
+        .map(|(index, _)| index)
+        .take(MAX_EDGE_SUBSTITUTIONS);
+    let ends: Vec<usize> = password[end..]
+        .char_indices()
+        .map(|(index, character)| end + index + character.len_utf8())
+        .take(MAX_EDGE_SUBSTITUTIONS)
+        .collect();
+
+    for start in ::std::iter::once(start).chain(starts) {
+        for &end in ::std::iter::once(&end).chain(ends.iter()) {
+            let (digits, symbols) = affix_counts(&password[..start], &password[end..]);
+
+            words.push(SingleWord {
+                core: &password[start..end],
+                digits,
+                symbols,
+            });
+        }
+    }
+
+    words
+}
+
+/// The longest run of digits in either affix, and the number of other characters in both.
+fn affix_counts(prefix: &str, suffix: &str) -> (usize, usize) {
+    let mut digits = 0;
+    let mut symbols = 0;
+
+    for affix in [prefix, suffix].iter() {
+        let mut run = 0;
+
+        for character in affix.chars() {
+            if character.is_numeric() {
+                run += 1;
+                digits = digits.max(run);
+            } else {
+                run = 0;
+                symbols += 1;
+            }
+        }
+    }
+
+    (digits, symbols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{affix_stripped, single_words, word_shell, SingleWord};
+
+    #[test]
+    fn it_strips_common_affixes() {
+        let stripped = |password| affix_stripped(password).collect::<Vec<_>>();
+
+        assert_eq!(stripped("password2024!"), vec!["password2024", "password"]);
+        assert_eq!(stripped("!1password"), vec!["1password", "password"]);
+        assert_eq!(stripped("1dragon1"), vec!["1dragon", "dragon1", "dragon"]);
+        assert_eq!(stripped("!12dragon34!").len(), 8);
+        assert!(stripped("abc1").is_empty());
+        assert!(stripped("12345678").is_empty());
+        assert!(stripped("correcthorse").is_empty());
+        assert!(stripped("").is_empty());
+    }
+
+    #[test]
+    fn it_finds_words_in_shells() {
+        assert_eq!(word_shell("Summer2024!"), Some("summer".to_string()));
+        assert_eq!(word_shell("!DRAGON1"), Some("dragon".to_string()));
+        assert_eq!(word_shell("12monKey34"), Some("monkey".to_string()));
+        assert_eq!(word_shell("password"), Some("password".to_string()));
+        assert_eq!(word_shell("Summer20245!"), None);
+        assert_eq!(word_shell("Summer2024!!"), None);
+        assert_eq!(word_shell("correct1horse"), None);
+        assert_eq!(word_shell("cat123!"), None);
+        assert_eq!(word_shell("12345678"), None);
+    }
+
+    #[test]
+    fn it_splits_passwords_around_their_letters() {
+        let words = single_words("!!Antidisestablishmentarianism2024");
+
+        assert_eq!(words.len(), 9);
+        assert_eq!(
+            words[0],
+            SingleWord {
+                core: "Antidisestablishmentarianism",
+                digits: 4,
+                symbols: 2,
+            }
+        );
+        assert_eq!(
+            single_words("12#P@ssw0rd!345")[0],
+            SingleWord {
+                core: "P@ssw0rd",
+                digits: 3,
+                symbols: 2,
+            }
+        );
+        assert!(single_words("$unsh1ne!99").contains(&SingleWord {
+            core: "$unsh1ne",
+            digits: 2,
+            symbols: 1,
+        }));
+        assert_eq!(single_words("correct horse").len(), 1);
+        assert!(single_words("12cat!").is_empty());
+        assert!(single_words("12345678").is_empty());
+    }
+}
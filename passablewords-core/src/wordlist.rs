@@ -0,0 +1,663 @@
+//! Lists of passwords that should never be accepted. The bundled list of 1,000,000 passwords is
+//! used by the free functions in the crate root, while a `Wordlist` lets you bring your own.
+
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::fs::File;
+use std::hash::BuildHasher;
+use std::io::{self, Read};
+use std::iter::FromIterator;
+use std::path::Path;
+#[cfg(feature = "fst")]
+use std::str;
+use std::sync::Arc;
+
+#[cfg(feature = "fst")]
+use fst;
+#[cfg(feature = "fst")]
+use fst::automaton::{Automaton, Str};
+#[cfg(feature = "fst")]
+use fst::{IntoStreamer, Set, Streamer};
+
+use {bundled_passwords, PasswordError};
+use hashed::{Entry, EntrySet, HashedCandidate};
+
+/// A set of passwords to check candidates against, with one password per line in its source.
+/// Blank lines are ignored.
+///
+/// # Timing
+///
+/// A wordlist can look passwords up in one of a few ways, and they leak different amounts of
+/// information to someone who can precisely time the checks.
+///
+/// - **Hash set** (the default, and what the free functions use). Passwords are hashed with a
+///   randomly keyed SipHash, so an attacker can't predict which bucket a password lands in. The
+///   key is shared by every hash set list in the process, so a checker with several lists only
+///   hashes a password once. A password that shares a bucket with an entry is then compared byte
+///   by byte, and that comparison stops at the first difference. A password within the list
+///   therefore does slightly more work than one that isn't, which is enough to learn membership
+///   from a large number of carefully timed samples.
+/// - **Keyed hashes** (`Wordlist::constant_time`). Every entry is reduced to a 64-bit keyed hash
+///   up front and the plain text is thrown away. A lookup hashes the whole candidate, walks the
+///   sorted hashes with the same number of steps no matter what it finds, and finishes with a
+///   single integer comparison. The time taken depends on the length of the candidate and the
+///   size of the list, not on whether the candidate is in it. The memory touched depends on the
+///   secret hash key, so cache timing doesn't reveal anything useful either. This is best effort
+///   rather than a cryptographic guarantee since the compiler is free to reintroduce branches.
+/// - **FST** (`Wordlist::parse_fst`, and the bundled list with the `fst` feature). Lookups walk a
+///   finite state transducer one byte at a time and stop as soon as the candidate leaves it, so
+///   the time taken reveals roughly how much of the candidate is a prefix of some entry.
+///
+/// Checking a password is usually followed by slower, very much not constant-time work such as
+/// the entropy check or hashing the password for storage, so the keyed mode only matters if the
+/// result of the uniqueness check can be timed on its own.
+///
+/// # Equality
+///
+/// Two lists are equal when they look passwords up the same way and hold the same passwords.
+/// Keyed hash lists no longer know their passwords, so they're only equal to lists made from the
+/// same one, like their clones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Wordlist {
+    lookup: Lookup,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Lookup {
+    HashSet(EntrySet),
+    Keyed(KeyedHashes),
+    #[cfg(feature = "fst")]
+    Fst(FstSet),
+    Union(Box<Wordlist>, Box<Wordlist>),
+}
+
+/// An FST holding every entry in a list, which is either embedded in the crate or built at
+/// runtime.
+#[cfg(feature = "fst")]
+#[derive(Clone)]
+struct FstSet(Set<Cow<'static, [u8]>>);
+
+#[cfg(feature = "fst")]
+impl FstSet {
+    /// Whether any entry of at least `min_length` characters is a prefix of `text`.
+    fn has_prefix_of(&self, text: &str, min_length: usize) -> bool {
+        let fst = self.0.as_fst();
+        let mut node = fst.root();
+        let mut length = 0;
+
+        for &byte in text.as_bytes() {
+            node = match node.find_input(byte) {
+                Some(index) => fst.node(node.transition_addr(index)),
+                None => return false,
+            };
+
+            // Only count the first byte of each UTF-8 sequence
+            if byte & 0xc0 != 0x80 {
+                length += 1;
+            }
+
+            if node.is_final() && length >= min_length {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+#[cfg(feature = "fst")]
+impl fmt::Debug for FstSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("FstSet").field("len", &self.0.len()).finish()
+    }
+}
+
+#[cfg(feature = "fst")]
+impl PartialEq for FstSet {
+    fn eq(&self, other: &FstSet) -> bool {
+        let mut ours = self.0.stream();
+        let mut theirs = other.0.stream();
+
+        loop {
+            match (ours.next(), theirs.next()) {
+                (Some(our), Some(their)) if our == their => {}
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// The sorted, keyed hashes of every entry in a list, along with the key used to create them.
+#[derive(Clone)]
+struct KeyedHashes {
+    key: RandomState,
+    hashes: Vec<u64>,
+}
+
+impl KeyedHashes {
+    fn hash(&self, password: &str) -> u64 {
+        self.key.hash_one(password)
+    }
+
+    /// A binary search which always takes the same number of steps for a given list length,
+    /// rather than returning as soon as the hash is found.
+    fn contains(&self, password: &str) -> bool {
+        let needle = self.hash(password);
+        let mut base = 0;
+        let mut size = self.hashes.len();
+
+        if size == 0 {
+            return false;
+        }
+
+        while size > 1 {
+            let half = size / 2;
+            let middle = base + half;
+
+            base = if self.hashes[middle] <= needle {
+                middle
+            } else {
+                base
+            };
+            size -= half;
+        }
+
+        self.hashes[base] == needle
+    }
+}
+
+impl PartialEq for KeyedHashes {
+    /// The keys can't be compared directly, so they're taken to be the same if they hash a probe
+    /// the same way.
+    fn eq(&self, other: &KeyedHashes) -> bool {
+        self.hashes == other.hashes && self.hash("") == other.hash("")
+    }
+}
+
+impl fmt::Debug for KeyedHashes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyedHashes")
+            .field("len", &self.hashes.len())
+            .finish()
+    }
+}
+
+impl Wordlist {
+    /// Read a wordlist from a file containing one password per line.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> io::Result<Wordlist> {
+        let mut file = File::open(path)?;
+        let mut contents = String::new();
+
+        file.read_to_string(&mut contents)?;
+
+        Ok(Wordlist::parse(&contents))
+    }
+
+    /// Build a wordlist from a string containing one password per line.
+    pub fn parse(contents: &str) -> Wordlist {
+        contents
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Build a wordlist backed by an FST from a string containing one password per line. This
+    /// takes longer to build than `parse` and lookups are a little slower, but it takes up far
+    /// less memory and supports prefix queries. Only available with the `fst` feature.
+    #[cfg(feature = "fst")]
+    pub fn parse_fst(contents: &str) -> Result<Wordlist, fst::Error> {
+        let mut passwords: Vec<&str> = contents.lines().filter(|line| !line.is_empty()).collect();
+
+        passwords.sort_unstable();
+        passwords.dedup();
+
+        let bytes = Set::from_iter(passwords)?.into_fst().into_inner();
+
+        Wordlist::from_fst_bytes(bytes)
+    }
+
+    /// Load a wordlist from an FST that was built ahead of time, such as one written out by the
+    /// `fst` crate's `SetBuilder`. The bytes can be borrowed from a `static`, so an FST that's
+    /// embedded with `include_bytes!` doesn't have to be copied. Only available with the `fst`
+    /// feature.
+    #[cfg(feature = "fst")]
+    pub fn from_fst_bytes<B: Into<Cow<'static, [u8]>>>(bytes: B) -> Result<Wordlist, fst::Error> {
+        Ok(Wordlist {
+            lookup: Lookup::Fst(FstSet(Set::new(bytes.into())?)),
+        })
+    }
+
+    /// Whether any password in the list starts with `prefix`. Only lists backed by an FST can
+    /// answer this efficiently, so `None` is returned for every other kind of list.
+    pub fn has_prefix(&self, prefix: &str) -> Option<bool> {
+        match self.lookup {
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => {
+                let matcher = Str::new(prefix).starts_with();
+
+                Some(set.0.search(matcher).into_stream().next().is_some())
+            }
+            Lookup::Union(ref first, ref second) => {
+                either(first.has_prefix(prefix), second.has_prefix(prefix))
+            }
+            _ => {
+                let _ = prefix;
+
+                None
+            }
+        }
+    }
+
+    /// Whether any password in the list that's at least `min_length` characters long appears
+    /// anywhere within `password`, such as `password` within `xXpasswordXx`. Only lists backed by
+    /// an FST can answer this efficiently, so `None` is returned for every other kind of list.
+    ///
+    /// Each position in `password` is followed through the FST until it leaves it, so this takes
+    /// at most the length of `password` times the length of the longest entry, and doesn't
+    /// allocate.
+    pub fn contains_embedded(&self, password: &str, min_length: usize) -> Option<bool> {
+        match self.lookup {
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => Some(
+                password
+                    .char_indices()
+                    .any(|(start, _)| set.has_prefix_of(&password[start..], min_length)),
+            ),
+            Lookup::Union(ref first, ref second) => either(
+                first.contains_embedded(password, min_length),
+                second.contains_embedded(password, min_length),
+            ),
+            _ => {
+                let _ = (password, min_length);
+
+                None
+            }
+        }
+    }
+
+    /// The bundled list of the 1,000,000 most common passwords, or `None` if it couldn't be read.
+    /// It's read the first time it's needed and shared from then on, so calling this is cheap.
+    /// With the `lite` feature, only the 100,000 most common passwords are included. With the
+    /// `fst` feature, the list is embedded as an FST built when the crate is compiled.
+    pub fn bundled() -> Option<Arc<Wordlist>> {
+        bundled_passwords().ok().cloned()
+    }
+
+    /// The bundled list, like `bundled`, or `PasswordError::WordlistUnavailable` saying which file
+    /// couldn't be read, or which feature flag is missing, if it couldn't be. Call it when the
+    /// application starts to fail fast rather than on the first password.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::{PasswordError, Wordlist};
+    ///
+    /// match Wordlist::load_bundled() {
+    ///     Ok(wordlist) => assert!(wordlist.contains("password")),
+    ///     Err(PasswordError::WordlistUnavailable { path, source }) => {
+    ///         panic!("couldn't read {:?}: {}", path, source)
+    ///     }
+    ///     Err(err) => panic!("{:?}", err),
+    /// }
+    /// ```
+    pub fn load_bundled() -> Result<Arc<Wordlist>, PasswordError> {
+        bundled_passwords().cloned().map_err(PasswordError::clone)
+    }
+
+    /// A copy of this list which looks passwords up in roughly constant time, so that timing a
+    /// check doesn't reveal whether the password was in the list. See the `Wordlist` documentation
+    /// for the threat model. The copy only stores keyed hashes of the entries, not the entries
+    /// themselves.
+    pub fn constant_time(&self) -> Wordlist {
+        let key = RandomState::new();
+        let mut keyed = KeyedHashes { key, hashes: Vec::with_capacity(self.len()) };
+
+        match self.lookup {
+            Lookup::HashSet(ref passwords) => {
+                keyed.hashes = passwords
+                    .iter()
+                    .map(|password| keyed.hash(&password.0))
+                    .collect();
+                keyed.hashes.sort_unstable();
+                keyed.hashes.dedup();
+            }
+            Lookup::Keyed(ref existing) => keyed = existing.clone(),
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => {
+                let mut stream = set.0.stream();
+
+                while let Some(password) = stream.next() {
+                    if let Ok(password) = str::from_utf8(password) {
+                        keyed.hashes.push(keyed.hash(password));
+                    }
+                }
+                keyed.hashes.sort_unstable();
+                keyed.hashes.dedup();
+            }
+            Lookup::Union(ref first, ref second) => {
+                return Wordlist {
+                    lookup: Lookup::Union(
+                        Box::new(first.constant_time()),
+                        Box::new(second.constant_time()),
+                    ),
+                };
+            }
+        }
+
+        Wordlist { lookup: Lookup::Keyed(keyed) }
+    }
+
+    /// Whether or not this list was created with `constant_time`.
+    pub fn is_constant_time(&self) -> bool {
+        match self.lookup {
+            Lookup::HashSet(_) => false,
+            Lookup::Keyed(_) => true,
+            #[cfg(feature = "fst")]
+            Lookup::Fst(_) => false,
+            Lookup::Union(ref first, ref second) => {
+                first.is_constant_time() && second.is_constant_time()
+            }
+        }
+    }
+
+    /// Whether or not the password is within this list.
+    pub fn contains(&self, password: &str) -> bool {
+        match self.lookup {
+            Lookup::HashSet(ref passwords) => HashedCandidate::new(password).is_in(passwords),
+            Lookup::Keyed(ref keyed) => keyed.contains(password),
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => set.0.contains(password),
+            // Both lists are always searched so that constant-time lists stay that way
+            Lookup::Union(ref first, ref second) => {
+                first.contains(password) | second.contains(password)
+            }
+        }
+    }
+
+    /// Whether a candidate that's already been hashed is within this list. Hash set lists reuse
+    /// the candidate's hash, so looking it up in several of them only hashes it once.
+    pub(crate) fn contains_hashed(&self, candidate: &HashedCandidate) -> bool {
+        match self.lookup {
+            Lookup::HashSet(ref passwords) => candidate.is_in(passwords),
+            Lookup::Keyed(ref keyed) => keyed.contains(candidate.as_str()),
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => set.0.contains(candidate.as_str()),
+            Lookup::Union(ref first, ref second) => {
+                first.contains_hashed(candidate) | second.contains_hashed(candidate)
+            }
+        }
+    }
+
+    /// Whether any of `candidates` is within this list, such as the lowercased, de-leeted, and
+    /// suffix-stripped forms of a password. The kind of list is only worked out once rather than
+    /// for every candidate, and candidates are checked in order until one matches.
+    pub fn contains_any<I>(&self, candidates: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut candidates = candidates.into_iter();
+
+        match self.lookup {
+            Lookup::HashSet(ref passwords) => {
+                candidates.any(|candidate| HashedCandidate::new(candidate.as_ref()).is_in(passwords))
+            }
+            Lookup::Keyed(ref keyed) => candidates.any(|candidate| keyed.contains(candidate.as_ref())),
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => candidates.any(|candidate| set.0.contains(candidate.as_ref())),
+            Lookup::Union(..) => candidates.any(|candidate| self.contains(candidate.as_ref())),
+        }
+    }
+
+    /// The number of passwords in the list. For lists created with `merge` which kept both lists
+    /// separate, passwords in both are counted twice.
+    pub fn len(&self) -> usize {
+        match self.lookup {
+            Lookup::HashSet(ref passwords) => passwords.len(),
+            Lookup::Keyed(ref keyed) => keyed.hashes.len(),
+            #[cfg(feature = "fst")]
+            Lookup::Fst(ref set) => set.0.len(),
+            Lookup::Union(ref first, ref second) => first.len() + second.len(),
+        }
+    }
+
+    /// Combine this list with `other`, such as an internal list of passwords from past incidents
+    /// with the bundled list, so that both can be checked with a single lookup.
+    ///
+    /// Two lists created with `parse`, `from_file`, or `collect` are combined into a single hash
+    /// set. Any other kinds of list keep their own way of looking passwords up and are searched
+    /// one after the other, so merging two constant-time lists gives a constant-time list. Use
+    /// `PasswordChecker::with_labelled_wordlist` instead if you need to know which list a
+    /// password was found in.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use passablewords_core::Wordlist;
+    ///
+    /// let incidents = Wordlist::parse("Acme2019!\nWinter2020!");
+    /// let wordlist = Wordlist::parse("password\nletmein").merge(incidents);
+    ///
+    /// assert!(wordlist.contains("password"));
+    /// assert!(wordlist.contains("Winter2020!"));
+    /// ```
+    pub fn merge(self, other: Wordlist) -> Wordlist {
+        match (self.lookup, other.lookup) {
+            (Lookup::HashSet(mut passwords), Lookup::HashSet(others)) => {
+                passwords.extend(others);
+
+                Wordlist { lookup: Lookup::HashSet(passwords) }
+            }
+            (first, second) => Wordlist {
+                lookup: Lookup::Union(
+                    Box::new(Wordlist { lookup: first }),
+                    Box::new(Wordlist { lookup: second }),
+                ),
+            },
+        }
+    }
+
+    /// Whether or not the list has no passwords at all.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget the passwords that `keep` returns `false` for. Only lists created with `parse`,
+    /// `from_file`, or `collect` hold passwords they can forget, so any other list is left as it
+    /// is.
+    pub(crate) fn retain<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        if let Lookup::HashSet(ref mut passwords) = self.lookup {
+            passwords.retain(|entry| keep(&entry.0));
+        }
+    }
+}
+
+/// Combine the answers to a query from two lists, where `None` means a list can't answer it.
+fn either(first: Option<bool>, second: Option<bool>) -> Option<bool> {
+    match (first, second) {
+        (Some(true), _) | (_, Some(true)) => Some(true),
+        (Some(false), Some(false)) => Some(false),
+        _ => None,
+    }
+}
+
+impl Default for Wordlist {
+    fn default() -> Wordlist {
+        Wordlist {
+            lookup: Lookup::HashSet(EntrySet::default()),
+        }
+    }
+}
+
+impl FromIterator<&'static str> for Wordlist {
+    fn from_iter<I: IntoIterator<Item = &'static str>>(iter: I) -> Wordlist {
+        Wordlist {
+            lookup: Lookup::HashSet(
+                iter.into_iter()
+                    .map(|password| Entry(Cow::Borrowed(password)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl FromIterator<String> for Wordlist {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Wordlist {
+        Wordlist {
+            lookup: Lookup::HashSet(
+                iter.into_iter()
+                    .map(|password| Entry(Cow::Owned(password)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Wordlist;
+    use passablewords_data;
+    use std::borrow::Cow;
+
+    #[test]
+    fn it_parses_a_wordlist() {
+        let wordlist = Wordlist::parse("password\r\n\nletmein\n");
+
+        assert_eq!(wordlist.len(), 2);
+        assert!(wordlist.contains("password"));
+        assert!(wordlist.contains("letmein"));
+        assert!(!wordlist.contains(""));
+    }
+
+    #[test]
+    fn it_reads_a_wordlist_file() {
+        let wordlist = Wordlist::from_file(passablewords_data::COMMON_PASSWORDS_PATH).unwrap();
+
+        assert!(wordlist.contains("password"));
+        assert!(Wordlist::from_file("src/missing-passwords.txt").is_err());
+    }
+
+    #[cfg(feature = "lite")]
+    #[test]
+    fn it_bundles_the_most_common_passwords_when_lite() {
+        let wordlist = Wordlist::bundled().unwrap();
+
+        assert_eq!(wordlist.len(), 100_000);
+        assert!(wordlist.contains("password"));
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn it_looks_up_passwords_in_an_fst() {
+        let wordlist = Wordlist::parse_fst("password\nletmein\n\npassword").unwrap();
+
+        assert_eq!(wordlist.len(), 2);
+        assert!(wordlist.contains("password"));
+        assert!(!wordlist.contains("pass"));
+        assert_eq!(wordlist.has_prefix("pass"), Some(true));
+        assert_eq!(wordlist.has_prefix("qwerty"), Some(false));
+        assert!(wordlist.constant_time().contains("letmein"));
+        assert!(Wordlist::bundled().unwrap().has_prefix("passw").unwrap());
+        assert_eq!(Wordlist::parse("password").has_prefix("pass"), None);
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn it_finds_embedded_passwords() {
+        let wordlist = Wordlist::parse_fst("password\nabc\nlöwe").unwrap();
+
+        assert_eq!(wordlist.contains_embedded("xXpasswordXx", 6), Some(true));
+        assert_eq!(wordlist.contains_embedded("password", 6), Some(true));
+        assert_eq!(wordlist.contains_embedded("xxpasswor", 6), Some(false));
+        assert_eq!(wordlist.contains_embedded("xxabcxx", 6), Some(false));
+        assert_eq!(wordlist.contains_embedded("xxabcxx", 3), Some(true));
+        assert_eq!(wordlist.contains_embedded("ÜlöweÜ", 4), Some(true));
+        assert_eq!(wordlist.contains_embedded("ÜlöweÜ", 5), Some(false));
+        assert_eq!(Wordlist::parse("password").contains_embedded("xpassword", 6), None);
+    }
+
+    #[test]
+    fn it_looks_up_many_candidates() {
+        let wordlist = Wordlist::parse("password\nletmein");
+        let candidates = vec![Cow::Borrowed("P4ssword"), Cow::Owned("password".to_string())];
+
+        assert!(wordlist.contains_any(&candidates));
+        assert!(wordlist.constant_time().contains_any(candidates));
+        assert!(!wordlist.contains_any(&["P4ssword", "passw0rd"]));
+        assert!(!wordlist.contains_any(Vec::<String>::new()));
+    }
+
+    #[test]
+    fn it_merges_wordlists() {
+        let merged = Wordlist::parse("password\nletmein").merge(Wordlist::parse("letmein\nqwerty"));
+
+        assert_eq!(merged.len(), 3);
+        assert!(merged.contains("password"));
+        assert!(merged.contains("qwerty"));
+
+        let constant_time = Wordlist::parse("password")
+            .constant_time()
+            .merge(Wordlist::parse("qwerty").constant_time());
+
+        assert!(constant_time.is_constant_time());
+        assert!(constant_time.contains("password"));
+        assert!(constant_time.contains_any(&["letmein", "qwerty"]));
+        assert!(!constant_time.contains("letmein"));
+        assert_eq!(constant_time.len(), 2);
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn it_merges_wordlists_of_different_kinds() {
+        let merged = Wordlist::parse_fst("password").unwrap().merge(Wordlist::parse("qwerty"));
+
+        assert!(merged.contains("qwerty"));
+        assert_eq!(merged.has_prefix("pass"), Some(true));
+        assert_eq!(merged.has_prefix("qwe"), None);
+        assert_eq!(merged.contains_embedded("xXpasswordXx", 6), Some(true));
+        assert!(merged.constant_time().contains("password"));
+    }
+
+    #[test]
+    fn it_looks_up_passwords_in_constant_time() {
+        let wordlist = Wordlist::parse("password\nletmein\nqwerty\nletmein").constant_time();
+
+        assert!(wordlist.is_constant_time());
+        assert_eq!(wordlist.len(), 3);
+        assert!(wordlist.contains("password"));
+        assert!(wordlist.contains("letmein"));
+        assert!(wordlist.contains("qwerty"));
+        assert!(!wordlist.contains("correcthorse"));
+        assert!(!Wordlist::default().constant_time().contains("password"));
+    }
+
+    #[test]
+    fn it_compares_wordlists() {
+        let keyed = Wordlist::parse("password\nletmein").constant_time();
+
+        assert_eq!(
+            Wordlist::parse("password\nletmein"),
+            Wordlist::parse("letmein\npassword")
+        );
+        assert_ne!(Wordlist::parse("password"), Wordlist::parse("letmein"));
+        assert_eq!(keyed.clone(), keyed);
+        assert_ne!(keyed, Wordlist::parse("password\nletmein").constant_time());
+        assert_ne!(keyed, Wordlist::parse("password\nletmein"));
+    }
+
+    #[cfg(feature = "fst")]
+    #[test]
+    fn it_compares_fst_wordlists() {
+        assert_eq!(
+            Wordlist::parse_fst("password\nletmein").unwrap(),
+            Wordlist::parse_fst("letmein\npassword").unwrap()
+        );
+        assert_ne!(
+            Wordlist::parse_fst("password").unwrap(),
+            Wordlist::parse_fst("password\nletmein").unwrap()
+        );
+    }
+}
@@ -0,0 +1,97 @@
+//! Prepares the copies of the bundled wordlist that get embedded in the crate: the trimmed-down
+//! list used by the `lite` feature, and the FST used by the `fst` feature.
+//!
+//! Setting `PASSABLEWORDS_WORDLIST` to the absolute path of another list, with one password per
+//! line from most to least common, embeds that list instead.
+
+#[cfg(feature = "fst")]
+extern crate fst;
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// How many of the most common passwords the `lite` feature keeps.
+const LITE_LENGTH: usize = 100_000;
+
+/// The environment variable that replaces the embedded list.
+const CUSTOM_WORDLIST: &str = "PASSABLEWORDS_WORDLIST";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed={}", CUSTOM_WORDLIST);
+    println!("cargo:rustc-check-cfg=cfg(passablewords_custom_wordlist)");
+
+    let lite = env::var_os("CARGO_FEATURE_LITE").is_some();
+    let fst = env::var_os("CARGO_FEATURE_FST").is_some();
+    let embedded = env::var_os("CARGO_FEATURE_EMBEDDED_WORDLIST").is_some();
+    let custom = env::var_os(CUSTOM_WORDLIST).filter(|path| !path.is_empty());
+    let source = match custom {
+        Some(ref path) if embedded => {
+            println!("cargo:rustc-cfg=passablewords_custom_wordlist");
+            Path::new(path)
+        }
+        Some(_) => {
+            println!(
+                "cargo:warning={} is only used with the embedded-wordlist, lite, or fst features",
+                CUSTOM_WORDLIST
+            );
+            Path::new("src/common-passwords.txt")
+        }
+        None => Path::new("src/common-passwords.txt"),
+    };
+
+    println!("cargo:rerun-if-changed={}", source.display());
+
+    let custom = custom.is_some() && embedded;
+
+    if !lite && !fst && !custom {
+        return;
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let contents = fs::read_to_string(source)
+        .unwrap_or_else(|err| panic!("{} should be readable: {}", source.display(), err));
+    // The list is ordered from most to least common, so the top of it is what attackers try first
+    let passwords: Vec<&str> = contents
+        .lines()
+        .filter(|line| !line.is_empty())
+        .take(if lite { LITE_LENGTH } else { usize::MAX })
+        .collect();
+
+    if custom && !lite && !fst {
+        fs::write(Path::new(&out_dir).join("common-passwords.txt"), &contents)
+            .expect("the custom wordlist should be writable");
+    }
+
+    if lite {
+        let mut lite = String::new();
+
+        for password in &passwords {
+            lite.push_str(password);
+            lite.push('\n');
+        }
+
+        fs::write(Path::new(&out_dir).join("common-passwords-lite.txt"), lite)
+            .expect("the lite wordlist should be writable");
+    }
+
+    #[cfg(feature = "fst")]
+    write_fst(&out_dir, passwords);
+}
+
+/// Write the passwords out as an FST, which shares both prefixes and suffixes between entries and
+/// takes up much less space than the plain text.
+#[cfg(feature = "fst")]
+fn write_fst(out_dir: &str, mut passwords: Vec<&str>) {
+    passwords.sort_unstable();
+    passwords.dedup();
+
+    let set = fst::Set::from_iter(passwords).expect("sorted passwords should build an FST");
+
+    fs::write(
+        Path::new(out_dir).join("common-passwords.fst"),
+        set.as_fst().as_bytes(),
+    )
+    .expect("the FST wordlist should be writable");
+}
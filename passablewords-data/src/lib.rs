@@ -0,0 +1,51 @@
+//! The lists that `passablewords` is built with: a million of the most common passwords, and the
+//! words used to generate passphrases. They're kept in their own crate so that changes to the rest
+//! of passablewords don't recompile the 8MB list when it's embedded.
+//!
+//! By default the list of common passwords isn't embedded at all, and `passablewords` reads it
+//! from `COMMON_PASSWORDS_PATH` at runtime. The `embedded-wordlist`, `lite`, and `fst` features
+//! embed it in one of three forms, and `PASSABLEWORDS_WORDLIST` replaces it with another list
+//! when the crate is compiled. See the `passablewords` README for how they compare.
+
+#![forbid(unsafe_code)]
+
+/// Where the list of common passwords was when this crate was compiled, with one password per
+/// line from most to least common. It's only there as long as the crate's source is, so it's no
+/// use to a binary that's been copied to another machine.
+pub const COMMON_PASSWORDS_PATH: &str =
+    concat!(env!("CARGO_MANIFEST_DIR"), "/src/common-passwords.txt");
+
+/// Whether the embedded list of common passwords is one given in `PASSABLEWORDS_WORDLIST` rather
+/// than the bundled one.
+pub const CUSTOM_WORDLIST: bool = cfg!(passablewords_custom_wordlist);
+
+/// The words used to generate passphrases, one per line. There are 256 of them, so each one adds
+/// 8 bits of entropy.
+pub const GENERATOR_WORDS: &str = include_str!("generator-words.txt");
+
+/// The list of common passwords, embedded with the `embedded-wordlist` feature.
+#[cfg(all(
+    feature = "embedded-wordlist",
+    not(any(feature = "lite", feature = "fst", passablewords_custom_wordlist))
+))]
+pub static COMMON_PASSWORDS: &str = include_str!("common-passwords.txt");
+
+/// The list of common passwords given in `PASSABLEWORDS_WORDLIST`, which the build script copies
+/// so it can be embedded in place of the bundled one.
+#[cfg(all(
+    passablewords_custom_wordlist,
+    not(any(feature = "lite", feature = "fst"))
+))]
+pub static COMMON_PASSWORDS: &str = include_str!(concat!(env!("OUT_DIR"), "/common-passwords.txt"));
+
+/// The 100,000 most common passwords, embedded with the `lite` feature. The build script writes
+/// them out.
+#[cfg(all(feature = "lite", not(feature = "fst")))]
+pub static COMMON_PASSWORDS: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/common-passwords-lite.txt"));
+
+/// The list of common passwords as an FST, which the build script creates with the `fst` feature.
+/// It's the 100,000 most common passwords with `lite` as well.
+#[cfg(feature = "fst")]
+pub static COMMON_PASSWORDS_FST: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/common-passwords.fst"));
@@ -0,0 +1,280 @@
+//! A Windows password filter which rejects weak passwords when they're set or changed in Active
+//! Directory, with the same checks as `passablewords`, so domain accounts are held to the same
+//! blocklist as the web apps. It builds as `passablewords_filter.dll`, which is copied to
+//! `System32` on every domain controller and added to the `Notification Packages` registry value
+//! under `HKLM\SYSTEM\CurrentControlSet\Control\Lsa`. LSA loads it when the controller starts.
+//!
+//! The DLL exports the three functions LSA expects of a password filter:
+//!
+//! - `InitializeChangeNotify` loads the policy and the list of common passwords.
+//! - `PasswordFilter` checks a new password, rejecting passwords built from the account name, and
+//!   words from the user's full name count against it in the entropy check.
+//! - `PasswordChangeNotify` is told about changes that went through, and does nothing.
+//!
+//! The policy is read from `passablewords\passablewords.toml` in `%ProgramData%`, in the format
+//! described by `PolicyConfig`. Changes take effect when the controller restarts. If the policy or
+//! the list can't be read, every password is rejected, so a broken install doesn't let weak
+//! passwords through, and they're read again with the next password until they load. A panic
+//! never reaches LSA, since that would take down the controller: the password is rejected instead.
+
+extern crate passablewords_core;
+
+use std::env;
+use std::panic;
+use std::path::PathBuf;
+use std::ptr;
+use std::slice;
+use std::sync::OnceLock;
+
+use passablewords_core::{
+    CheckContext, PassablewordResult, PasswordChecker, PasswordError, PolicyConfig,
+};
+
+/// Where the policy is read from when `%ProgramData%` isn't set.
+pub const DEFAULT_CONFIG_PATH: &str = r"C:\ProgramData\passablewords\passablewords.toml";
+
+/// A Windows `BOOLEAN`.
+pub type Boolean = u8;
+
+/// A Windows `NTSTATUS`.
+pub type NtStatus = i32;
+
+const TRUE: Boolean = 1;
+const FALSE: Boolean = 0;
+const STATUS_SUCCESS: NtStatus = 0;
+
+/// A Windows `UNICODE_STRING`, the UTF-16 strings LSA passes to the filter. They aren't
+/// NUL-terminated.
+#[repr(C)]
+#[derive(Debug)]
+pub struct UnicodeString {
+    /// The length of the string in bytes, not characters.
+    pub length: u16,
+    /// The size of the buffer in bytes.
+    pub maximum_length: u16,
+    /// The string's UTF-16 code units.
+    pub buffer: *mut u16,
+}
+
+static CHECKER: OnceLock<PasswordChecker> = OnceLock::new();
+
+/// Where the policy is read from.
+fn config_path() -> PathBuf {
+    match env::var_os("ProgramData") {
+        Some(dir) => PathBuf::from(dir)
+            .join("passablewords")
+            .join("passablewords.toml"),
+        None => PathBuf::from(DEFAULT_CONFIG_PATH),
+    }
+}
+
+/// The checker, loading it first if it hasn't been. `None` means it couldn't be loaded, and
+/// loading is tried again next time, so fixing the policy or the list doesn't need a restart.
+fn checker() -> Option<&'static PasswordChecker> {
+    cached(&CHECKER, || {
+        PolicyConfig::load(config_path())
+            .map_err(|_| PasswordError::InternalError)
+            .and_then(|config| config.checker())
+    })
+}
+
+/// The checker in `cell`, or the one `load` returns, which is kept in `cell` if it loaded.
+fn cached<F>(cell: &OnceLock<PasswordChecker>, load: F) -> Option<&PasswordChecker>
+where
+    F: FnOnce() -> Result<PasswordChecker, PasswordError>,
+{
+    if let Some(checker) = cell.get() {
+        return Some(checker);
+    }
+
+    let checker = load().ok()?;
+
+    Some(cell.get_or_init(|| checker))
+}
+
+/// The contents of `string`, or `None` if it's null or isn't valid UTF-16.
+unsafe fn decode(string: *const UnicodeString) -> Option<String> {
+    if string.is_null() {
+        return None;
+    }
+
+    let string = &*string;
+
+    if string.buffer.is_null() || string.length == 0 {
+        return Some(String::new());
+    }
+
+    String::from_utf16(slice::from_raw_parts(
+        string.buffer,
+        string.length as usize / 2,
+    ))
+    .ok()
+}
+
+/// Overwrite the copy of a password before it's freed, since LSA's memory is worth attacking.
+fn wipe(password: String) {
+    let mut bytes = password.into_bytes();
+
+    for byte in bytes.iter_mut() {
+        unsafe { ptr::write_volatile(byte, 0) };
+    }
+}
+
+/// Check a new password for `account`, whose full name is `full_name`.
+fn check(
+    checker: &PasswordChecker,
+    account: &str,
+    full_name: &str,
+    password: &str,
+) -> PassablewordResult {
+    let names: Vec<&str> = full_name.split_whitespace().collect();
+    let context = CheckContext::new()
+        .with_username(account)
+        .with_user_inputs(&names);
+
+    checker.check_password_with_context(password, &context)
+}
+
+/// Called by LSA when the filter is loaded. This loads the policy and the list of common
+/// passwords, so that the first password change isn't slow. It always succeeds, since failing
+/// would unload the filter and let every password through.
+#[no_mangle]
+#[allow(non_snake_case)]
+pub extern "system" fn InitializeChangeNotify() -> Boolean {
+    // a panic can't unwind into LSA, and the checker is loaded again with the next password
+    let _ = panic::catch_unwind(|| {
+        checker();
+    });
+    TRUE
+}
+
+/// Called by LSA before a password is set or changed. Returns `TRUE` to accept the password and
+/// `FALSE` to reject it. Passwords set by an administrator are checked like any other, and a panic
+/// while checking rejects the password.
+///
+/// # Safety
+///
+/// This must only be called by LSA, with valid `UNICODE_STRING`s or null pointers.
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "system" fn PasswordFilter(
+    AccountName: *const UnicodeString,
+    FullName: *const UnicodeString,
+    Password: *const UnicodeString,
+    _SetOperation: Boolean,
+) -> Boolean {
+    // unwinding into LSA would abort lsass.exe, so a panic rejects the password instead
+    panic::catch_unwind(|| filter_password(AccountName, FullName, Password)).unwrap_or(FALSE)
+}
+
+/// `PasswordFilter`, which may panic.
+unsafe fn filter_password(
+    account: *const UnicodeString,
+    full_name: *const UnicodeString,
+    password: *const UnicodeString,
+) -> Boolean {
+    let checker = match checker() {
+        Some(checker) => checker,
+        None => return FALSE,
+    };
+    let password = match decode(password) {
+        Some(password) => password,
+        None => return FALSE,
+    };
+    let account = decode(account).unwrap_or_default();
+    let full_name = decode(full_name).unwrap_or_default();
+    let result = check(checker, &account, &full_name, &password);
+
+    wipe(password);
+
+    if result.is_ok() {
+        TRUE
+    } else {
+        FALSE
+    }
+}
+
+/// Called by LSA after a password has been changed. There's nothing to do.
+///
+/// # Safety
+///
+/// This must only be called by LSA.
+#[no_mangle]
+#[allow(non_snake_case)]
+pub unsafe extern "system" fn PasswordChangeNotify(
+    _UserName: *const UnicodeString,
+    _RelativeId: u32,
+    _NewPassword: *const UnicodeString,
+) -> NtStatus {
+    STATUS_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use passablewords_core::Wordlist;
+
+    fn unicode(value: &str) -> (Vec<u16>, UnicodeString) {
+        let mut units: Vec<u16> = value.encode_utf16().collect();
+        let string = UnicodeString {
+            length: (units.len() * 2) as u16,
+            maximum_length: (units.len() * 2) as u16,
+            buffer: units.as_mut_ptr(),
+        };
+
+        (units, string)
+    }
+
+    fn filter(account: &str, full_name: &str, password: &str) -> Boolean {
+        let (_account, account) = unicode(account);
+        let (_full_name, full_name) = unicode(full_name);
+        let (_password, password) = unicode(password);
+
+        unsafe { PasswordFilter(&account, &full_name, &password, FALSE) }
+    }
+
+    #[test]
+    fn it_filters_passwords() {
+        assert_eq!(InitializeChangeNotify(), TRUE);
+        assert_eq!(filter("mike", "Mike Engel", "password"), FALSE);
+        assert_eq!(
+            filter("mikeengel1984", "Mike Engel", "mikeengel1984!"),
+            FALSE
+        );
+        assert_eq!(
+            filter("mike", "Mike Engel", "Th1s iS a Sup3rR4ndom PassW0rd!"),
+            TRUE
+        );
+        assert_eq!(
+            unsafe { PasswordFilter(ptr::null(), ptr::null(), ptr::null(), TRUE) },
+            FALSE
+        );
+    }
+
+    #[test]
+    fn it_retries_loading_the_checker() {
+        let cell = OnceLock::new();
+        let loaded = || Ok(PasswordChecker::with_wordlist(Wordlist::parse("password")));
+
+        assert!(cached(&cell, || Err(PasswordError::InternalError)).is_none());
+        assert!(cached(&cell, loaded).is_some());
+        assert!(cached(&cell, || Err(PasswordError::InternalError)).is_some());
+    }
+
+    #[test]
+    fn it_decodes_strings() {
+        let (_units, string) = unicode("çorrect horse");
+        let mut unpaired = [0xd800u16];
+        let invalid = UnicodeString {
+            length: 2,
+            maximum_length: 2,
+            buffer: unpaired.as_mut_ptr(),
+        };
+
+        unsafe {
+            assert_eq!(decode(&string), Some("çorrect horse".to_string()));
+            assert_eq!(decode(&invalid), None);
+            assert_eq!(decode(ptr::null()), None);
+        }
+    }
+}
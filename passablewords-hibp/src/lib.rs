@@ -0,0 +1,16 @@
+//! Checks passwords against [Have I Been Pwned](https://haveibeenpwned.com/Passwords)'s Pwned
+//! Passwords service, or a self-hosted mirror of it, for `passablewords`. It's usually used
+//! through `passablewords` with the `hibp` feature, which re-exports `HibpProvider`.
+
+#![forbid(unsafe_code)]
+
+extern crate passablewords_core;
+extern crate rustls;
+extern crate rustls_pemfile;
+extern crate sha1;
+extern crate ureq;
+extern crate webpki_roots;
+
+mod hibp;
+
+pub use hibp::HibpProvider;
@@ -0,0 +1,49 @@
+/*
+ * The C ABI of passablewords-mobile. See src/lib.rs for the full documentation of each function.
+ *
+ * Passwords are NUL-terminated UTF-8. Every function is safe to call from any thread.
+ */
+
+#ifndef PASSABLEWORDS_H
+#define PASSABLEWORDS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define PASSABLEWORDS_OK 0
+#define PASSABLEWORDS_TOO_SHORT 1
+#define PASSABLEWORDS_TOO_FEW_UNIQUE_CHARS 2
+#define PASSABLEWORDS_INSUFFICIENT_VARIETY 3
+#define PASSABLEWORDS_TOO_COMMON 4
+#define PASSABLEWORDS_TOO_SIMPLE 5
+#define PASSABLEWORDS_NON_ASCII 6
+#define PASSABLEWORDS_INVALID_ENCODING 7
+#define PASSABLEWORDS_INTERNAL_ERROR 8
+#define PASSABLEWORDS_NULL_PASSWORD -1
+
+typedef struct PasswordAnalysis {
+    /* One of the PASSABLEWORDS_* codes. */
+    int code;
+    /* Why the password was rejected, or NULL if it was accepted. */
+    char *explanation;
+    /* How to pick a better password, or NULL if it was accepted. */
+    char *advice;
+    /* A rough estimate of the password's entropy, for a strength meter. */
+    double entropy_bits;
+} PasswordAnalysis;
+
+/* Check a password, returning PASSABLEWORDS_OK or the reason it was rejected. */
+int passablewords_check(const char *password);
+
+/* Check a password and explain the result. Returns NULL only if password is NULL. */
+PasswordAnalysis *passablewords_analyze(const char *password);
+
+/* Free a result of passablewords_analyze. NULL is ignored. */
+void passablewords_free_analysis(PasswordAnalysis *analysis);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
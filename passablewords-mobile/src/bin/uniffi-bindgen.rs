@@ -0,0 +1,8 @@
+//! Generates the Kotlin and Swift bindings from a build of this crate. See the README for how to
+//! run it.
+
+extern crate uniffi;
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
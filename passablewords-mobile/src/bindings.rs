@@ -0,0 +1,328 @@
+//! Typed Kotlin and Swift bindings generated by UniFFI, as an alternative to the C ABI, with the
+//! same errors and the same policies as the backend. Only available with the `uniffi` feature.
+//!
+//! The checks only match the backend's when the crate is built with the same features. With the
+//! defaults, passwords are checked against the `lite` list of the 100,000 most common passwords
+//! instead of 1,000,000, and entropy is estimated with `quick_entropy_bits` instead of zxcvbn, so
+//! some passwords the backend rejects as `TooCommon` or `TooSimple` are accepted here. Build with
+//! `--no-default-features --features uniffi,embedded-wordlist,zxcvbn` to check them the same way.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use passablewords_core::{self, quick_entropy_bits, PassablewordResult, PasswordChecker};
+
+/// Why a password was rejected. These mirror `passablewords_core::PasswordError`, with names for
+/// the values some of them carry, which UniFFI needs.
+#[derive(Debug, Clone, PartialEq, uniffi::Error)]
+pub enum PasswordError {
+    TooShort,
+    TooFewUniqueChars,
+    InsufficientVariety,
+    TooCommon,
+    TooCommonIn { label: String },
+    TooCommonVariant,
+    TooCommonLocally,
+    Breached,
+    TooSimple,
+    NonAsciiPassword,
+    KeyboardWalk,
+    TemporalPattern,
+    BannedPattern { rule: String },
+    MatchesDeniedValue,
+    MatchesPreviousPassword,
+    MatchesUsername,
+    SingleDictionaryWord,
+    InvalidFormat,
+    InvalidEncoding,
+    InputTooLarge,
+    Cancelled,
+    WordlistUnavailable { path: Option<String>, source: String },
+    InternalError,
+}
+
+impl From<passablewords_core::PasswordError> for PasswordError {
+    fn from(err: passablewords_core::PasswordError) -> PasswordError {
+        use passablewords_core::PasswordError as Core;
+
+        match err {
+            Core::TooShort => PasswordError::TooShort,
+            Core::TooFewUniqueChars => PasswordError::TooFewUniqueChars,
+            Core::InsufficientVariety => PasswordError::InsufficientVariety,
+            Core::TooCommon => PasswordError::TooCommon,
+            Core::TooCommonIn(label) => PasswordError::TooCommonIn { label },
+            Core::TooCommonVariant => PasswordError::TooCommonVariant,
+            Core::TooCommonLocally => PasswordError::TooCommonLocally,
+            Core::Breached => PasswordError::Breached,
+            Core::TooSimple => PasswordError::TooSimple,
+            Core::NonAsciiPassword => PasswordError::NonAsciiPassword,
+            Core::KeyboardWalk => PasswordError::KeyboardWalk,
+            Core::TemporalPattern => PasswordError::TemporalPattern,
+            Core::BannedPattern(rule) => PasswordError::BannedPattern { rule },
+            Core::MatchesDeniedValue => PasswordError::MatchesDeniedValue,
+            Core::MatchesPreviousPassword => PasswordError::MatchesPreviousPassword,
+            Core::MatchesUsername => PasswordError::MatchesUsername,
+            Core::SingleDictionaryWord => PasswordError::SingleDictionaryWord,
+            Core::InvalidFormat => PasswordError::InvalidFormat,
+            Core::InvalidEncoding => PasswordError::InvalidEncoding,
+            Core::InputTooLarge => PasswordError::InputTooLarge,
+            Core::Cancelled => PasswordError::Cancelled,
+            Core::WordlistUnavailable { path, source } => {
+                PasswordError::WordlistUnavailable { path, source }
+            }
+            Core::InternalError => PasswordError::InternalError,
+        }
+    }
+}
+
+impl From<PasswordError> for passablewords_core::PasswordError {
+    fn from(err: PasswordError) -> passablewords_core::PasswordError {
+        use passablewords_core::PasswordError as Core;
+
+        match err {
+            PasswordError::TooShort => Core::TooShort,
+            PasswordError::TooFewUniqueChars => Core::TooFewUniqueChars,
+            PasswordError::InsufficientVariety => Core::InsufficientVariety,
+            PasswordError::TooCommon => Core::TooCommon,
+            PasswordError::TooCommonIn { label } => Core::TooCommonIn(label),
+            PasswordError::TooCommonVariant => Core::TooCommonVariant,
+            PasswordError::TooCommonLocally => Core::TooCommonLocally,
+            PasswordError::Breached => Core::Breached,
+            PasswordError::TooSimple => Core::TooSimple,
+            PasswordError::NonAsciiPassword => Core::NonAsciiPassword,
+            PasswordError::KeyboardWalk => Core::KeyboardWalk,
+            PasswordError::TemporalPattern => Core::TemporalPattern,
+            PasswordError::BannedPattern { rule } => Core::BannedPattern(rule),
+            PasswordError::MatchesDeniedValue => Core::MatchesDeniedValue,
+            PasswordError::MatchesPreviousPassword => Core::MatchesPreviousPassword,
+            PasswordError::MatchesUsername => Core::MatchesUsername,
+            PasswordError::SingleDictionaryWord => Core::SingleDictionaryWord,
+            PasswordError::InvalidFormat => Core::InvalidFormat,
+            PasswordError::InvalidEncoding => Core::InvalidEncoding,
+            PasswordError::InputTooLarge => Core::InputTooLarge,
+            PasswordError::Cancelled => Core::Cancelled,
+            PasswordError::WordlistUnavailable { path, source } => {
+                Core::WordlistUnavailable { path, source }
+            }
+            PasswordError::InternalError => Core::InternalError,
+        }
+    }
+}
+
+impl fmt::Display for PasswordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(passablewords_core::PasswordError::from(self.clone()).explanation())
+    }
+}
+
+impl Error for PasswordError {}
+
+/// What `analyze` made of a password.
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct Analysis {
+    /// Whether the password was accepted. Use `check_password` to find out why it wasn't.
+    pub accepted: bool,
+    /// A short explanation of why the password was rejected, or `None` if it was accepted.
+    pub explanation: Option<String>,
+    /// Longer advice on picking a better password, or `None` if it was accepted.
+    pub advice: Option<String>,
+    /// A rough estimate of the password's entropy in bits, for driving a strength meter.
+    pub entropy_bits: f64,
+}
+
+fn analysis(password: &str, result: PassablewordResult) -> Analysis {
+    let entropy_bits = quick_entropy_bits(password);
+
+    match result {
+        Ok(()) => Analysis {
+            accepted: true,
+            explanation: None,
+            advice: None,
+            entropy_bits,
+        },
+        Err(err) => Analysis {
+            accepted: false,
+            explanation: Some(err.explanation().to_string()),
+            advice: Some(err.advice().to_string()),
+            entropy_bits,
+        },
+    }
+}
+
+/// Check a password with the default rules and the embedded wordlist, like `check_password`.
+#[uniffi::export]
+pub fn check_password(password: String) -> Result<(), PasswordError> {
+    passablewords_core::check_password(&password).map_err(PasswordError::from)
+}
+
+/// Check a password with the default rules, and explain the result.
+#[uniffi::export]
+pub fn analyze(password: String) -> Analysis {
+    analysis(&password, passablewords_core::check_password(&password))
+}
+
+/// The rules a password has to follow, like `passablewords_core::Policy`. Each rule returns a
+/// new policy, so they can be chained the same way.
+#[derive(Debug, uniffi::Object)]
+pub struct Policy {
+    checker: PasswordChecker,
+}
+
+impl Policy {
+    fn with(
+        &self,
+        change: impl FnOnce(passablewords_core::Policy) -> passablewords_core::Policy,
+    ) -> Arc<Policy> {
+        let policy = change(self.checker.policy().clone());
+
+        Arc::new(Policy {
+            checker: self.checker.clone().with_policy(policy),
+        })
+    }
+}
+
+#[uniffi::export]
+impl Policy {
+    /// Create a policy with the default rules.
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Policy> {
+        Arc::new(Policy {
+            checker: PasswordChecker::new(),
+        })
+    }
+
+    /// See `passablewords_core::Policy::with_version`.
+    pub fn with_version(&self, version: String) -> Arc<Policy> {
+        self.with(|policy| policy.with_version(&version))
+    }
+
+    /// See `passablewords_core::Policy::min_length`.
+    pub fn min_length(&self, length: u32) -> Arc<Policy> {
+        self.with(|policy| policy.min_length(length as usize))
+    }
+
+    /// See `passablewords_core::Policy::min_length_or_words`.
+    pub fn min_length_or_words(&self, length: u32, words: u32) -> Arc<Policy> {
+        self.with(|policy| policy.min_length_or_words(length as usize, words as usize))
+    }
+
+    /// See `passablewords_core::Policy::min_words`.
+    pub fn min_words(&self, words: u32) -> Arc<Policy> {
+        self.with(|policy| policy.min_words(words as usize))
+    }
+
+    /// See `passablewords_core::Policy::min_unique_chars`.
+    pub fn min_unique_chars(&self, count: u32) -> Arc<Policy> {
+        self.with(|policy| policy.min_unique_chars(count as usize))
+    }
+
+    /// See `passablewords_core::Policy::reject_all_digits`.
+    pub fn reject_all_digits(&self, reject: bool) -> Arc<Policy> {
+        self.with(|policy| policy.reject_all_digits(reject))
+    }
+
+    /// See `passablewords_core::Policy::reject_all_letters`.
+    pub fn reject_all_letters(&self, reject: bool) -> Arc<Policy> {
+        self.with(|policy| policy.reject_all_letters(reject))
+    }
+
+    /// See `passablewords_core::Policy::strip_common_affixes`.
+    pub fn strip_common_affixes(&self, strip: bool) -> Arc<Policy> {
+        self.with(|policy| policy.strip_common_affixes(strip))
+    }
+
+    /// See `passablewords_core::Policy::reject_word_shells`.
+    pub fn reject_word_shells(&self, reject: bool) -> Arc<Policy> {
+        self.with(|policy| policy.reject_word_shells(reject))
+    }
+
+    /// See `passablewords_core::Policy::max_input_bytes`.
+    pub fn max_input_bytes(&self, bytes: u32) -> Arc<Policy> {
+        self.with(|policy| policy.max_input_bytes(bytes as usize))
+    }
+
+    /// See `passablewords_core::Policy::reject_keyboard_walks`.
+    pub fn reject_keyboard_walks(&self, min_length: u32) -> Arc<Policy> {
+        self.with(|policy| policy.reject_keyboard_walks(min_length as usize))
+    }
+
+    /// See `passablewords_core::Policy::reject_temporal_patterns`. Passwords are checked against
+    /// the default `TemporalTokens`, along with any of `extra_names`.
+    pub fn reject_temporal_patterns(&self, extra_names: Vec<String>) -> Arc<Policy> {
+        self.with(|policy| {
+            policy.reject_temporal_patterns(
+                passablewords_core::TemporalTokens::new().with_names(extra_names),
+            )
+        })
+    }
+
+    /// See `passablewords_core::Policy::reject_single_dictionary_words`.
+    pub fn reject_single_dictionary_words(&self, min_digits: u32, min_symbols: u32) -> Arc<Policy> {
+        self.with(|policy| {
+            policy.reject_single_dictionary_words(min_digits as usize, min_symbols as usize)
+        })
+    }
+
+    /// The version this policy was labelled with, if any.
+    pub fn version(&self) -> Option<String> {
+        self.checker.policy().version().map(str::to_string)
+    }
+
+    /// See `passablewords_core::Policy::describe`. `language` is a tag like `de` or `fr-CA`, and
+    /// languages that aren't supported get English.
+    pub fn describe(&self, language: String) -> Vec<String> {
+        let locale = passablewords_core::Locale::from_tag(&language).unwrap_or_default();
+
+        self.checker.policy().describe(locale)
+    }
+
+    /// Check a password against this policy, like `PasswordChecker::check_password`.
+    pub fn check_password(&self, password: String) -> Result<(), PasswordError> {
+        self.checker
+            .check_password(&password)
+            .map_err(PasswordError::from)
+    }
+
+    /// Check a password against this policy, and explain the result.
+    pub fn analyze(&self, password: String) -> Analysis {
+        analysis(&password, self.checker.check_password(&password))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze, check_password, PasswordError, Policy};
+
+    #[test]
+    fn it_checks_passwords() {
+        assert_eq!(
+            check_password("Th1s iS a Sup3rR4ndom PassW0rd!".to_string()),
+            Ok(())
+        );
+        assert_eq!(
+            check_password("password".to_string()),
+            Err(PasswordError::TooCommon)
+        );
+        assert_eq!(
+            analyze("password".to_string()).explanation.as_deref(),
+            Some("Your password is one of the most commonly used passwords.")
+        );
+        assert!(!analyze("password".to_string()).accepted);
+    }
+
+    #[test]
+    fn it_checks_passwords_against_a_policy() {
+        let policy = Policy::new().min_length(40).with_version("v2".to_string());
+
+        assert_eq!(policy.version().as_deref(), Some("v2"));
+        assert_eq!(
+            policy.check_password("Th1s iS a Sup3rR4ndom PassW0rd!".to_string()),
+            Err(PasswordError::TooShort)
+        );
+        assert_eq!(
+            Policy::new().check_password("Th1s iS a Sup3rR4ndom PassW0rd!".to_string()),
+            Ok(())
+        );
+    }
+}
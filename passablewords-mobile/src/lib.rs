@@ -0,0 +1,255 @@
+//! A small C ABI over `passablewords-core`, for checking passwords on the device in Android and
+//! iOS apps. It builds as a static library for iOS and a shared library for Android, with the
+//! `lite` wordlist embedded by default so there are no files to ship alongside it.
+//!
+//! The whole surface is three functions, declared in `include/passablewords.h`:
+//!
+//! - `passablewords_check` checks a password, returning one of the `PASSABLEWORDS_*` codes.
+//! - `passablewords_analyze` checks a password and explains the result, returning a
+//!   `PasswordAnalysis` that the caller owns.
+//! - `passablewords_free_analysis` frees a `PasswordAnalysis`.
+//!
+//! Passwords are NUL-terminated UTF-8, which is what Kotlin's JNA and Swift's `withCString` hand
+//! over. Anything that isn't valid UTF-8 is rejected with `PASSABLEWORDS_INVALID_ENCODING`. All
+//! three functions are safe to call from any thread, and a panic while checking is reported as
+//! `PASSABLEWORDS_INTERNAL_ERROR` instead of taking down the app.
+//!
+//! With the `uniffi` feature, `bindings` adds typed Kotlin and Swift bindings generated by UniFFI,
+//! for `check_password`, `analyze`, and `Policy`.
+
+extern crate passablewords_core;
+#[cfg(feature = "uniffi")]
+extern crate uniffi;
+
+#[cfg(feature = "uniffi")]
+pub mod bindings;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, UnwindSafe};
+use std::ptr;
+
+use passablewords_core::{
+    check_password_bytes, quick_entropy_bits, InvalidUtf8, PassablewordResult, PasswordError,
+};
+
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!("passablewords");
+
+/// The password was accepted.
+pub const PASSABLEWORDS_OK: c_int = 0;
+/// The password is too short.
+pub const PASSABLEWORDS_TOO_SHORT: c_int = 1;
+/// The password is made up of too few different characters.
+pub const PASSABLEWORDS_TOO_FEW_UNIQUE_CHARS: c_int = 2;
+/// The password is made of only digits, or only letters.
+pub const PASSABLEWORDS_INSUFFICIENT_VARIETY: c_int = 3;
+/// The password is one of the most common passwords, or a close variant of one.
+pub const PASSABLEWORDS_TOO_COMMON: c_int = 4;
+/// The password's entropy is too low.
+pub const PASSABLEWORDS_TOO_SIMPLE: c_int = 5;
+/// The password contains characters that aren't ASCII, and the entropy check needs ASCII.
+pub const PASSABLEWORDS_NON_ASCII: c_int = 6;
+/// The password isn't valid UTF-8.
+pub const PASSABLEWORDS_INVALID_ENCODING: c_int = 7;
+/// The password was rejected for a reason that the checks run here never give, or something went
+/// wrong while checking it.
+pub const PASSABLEWORDS_INTERNAL_ERROR: c_int = 8;
+/// The password was a null pointer.
+pub const PASSABLEWORDS_NULL_PASSWORD: c_int = -1;
+
+/// The result of `passablewords_analyze`. Free it with `passablewords_free_analysis`, which also
+/// frees its strings.
+#[repr(C)]
+#[derive(Debug)]
+pub struct PasswordAnalysis {
+    /// One of the `PASSABLEWORDS_*` codes, as `passablewords_check` would return.
+    pub code: c_int,
+    /// A short explanation of why the password was rejected, suitable for showing next to the
+    /// field, or null if it was accepted.
+    pub explanation: *mut c_char,
+    /// Longer advice on picking a better password, or null if it was accepted.
+    pub advice: *mut c_char,
+    /// A rough estimate of the password's entropy in bits, from `quick_entropy_bits`, for
+    /// driving a strength meter. This is 0 when the password isn't valid UTF-8.
+    pub entropy_bits: f64,
+}
+
+fn code(result: &PassablewordResult) -> c_int {
+    match *result {
+        Ok(()) => PASSABLEWORDS_OK,
+        Err(PasswordError::TooShort) => PASSABLEWORDS_TOO_SHORT,
+        Err(PasswordError::TooFewUniqueChars) => PASSABLEWORDS_TOO_FEW_UNIQUE_CHARS,
+        Err(PasswordError::InsufficientVariety) => PASSABLEWORDS_INSUFFICIENT_VARIETY,
+        Err(PasswordError::TooCommon)
+        | Err(PasswordError::TooCommonIn(_))
+        | Err(PasswordError::TooCommonVariant) => PASSABLEWORDS_TOO_COMMON,
+        Err(PasswordError::TooSimple) => PASSABLEWORDS_TOO_SIMPLE,
+        Err(PasswordError::NonAsciiPassword) => PASSABLEWORDS_NON_ASCII,
+        Err(PasswordError::InvalidEncoding) => PASSABLEWORDS_INVALID_ENCODING,
+        Err(_) => PASSABLEWORDS_INTERNAL_ERROR,
+    }
+}
+
+/// Run `check`, turning a panic into `PasswordError::InternalError`, since unwinding out of an
+/// `extern "C"` function aborts the app.
+fn guarded<F>(check: F) -> PassablewordResult
+where
+    F: FnOnce() -> PassablewordResult + UnwindSafe,
+{
+    panic::catch_unwind(check).unwrap_or(Err(PasswordError::InternalError))
+}
+
+/// Read a NUL-terminated password, or `None` for a null pointer.
+///
+/// # Safety
+///
+/// `password` must be null or point to a NUL-terminated string that stays valid for `'a`.
+unsafe fn password_bytes<'a>(password: *const c_char) -> Option<&'a [u8]> {
+    if password.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(password).to_bytes())
+    }
+}
+
+/// Copy a message into a string the caller can free with `passablewords_free_analysis`. The
+/// messages are all static text without NULs, so this only fails if that changes.
+fn message(text: &str) -> *mut c_char {
+    CString::new(text).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Check a NUL-terminated UTF-8 password with the default rules and the embedded wordlist,
+/// returning `PASSABLEWORDS_OK` or the code for why it was rejected.
+///
+/// # Safety
+///
+/// `password` must be null or point to a NUL-terminated string, which is only read during the
+/// call.
+#[no_mangle]
+pub unsafe extern "C" fn passablewords_check(password: *const c_char) -> c_int {
+    match password_bytes(password) {
+        Some(password) => code(&guarded(|| {
+            check_password_bytes(password, InvalidUtf8::Reject)
+        })),
+        None => PASSABLEWORDS_NULL_PASSWORD,
+    }
+}
+
+/// Check a password like `passablewords_check`, and explain the result. Returns null only if
+/// `password` is null. The result must be freed with `passablewords_free_analysis`.
+///
+/// # Safety
+///
+/// `password` must be null or point to a NUL-terminated string, which is only read during the
+/// call.
+#[no_mangle]
+pub unsafe extern "C" fn passablewords_analyze(password: *const c_char) -> *mut PasswordAnalysis {
+    let password = match password_bytes(password) {
+        Some(password) => password,
+        None => return ptr::null_mut(),
+    };
+    let result = guarded(|| check_password_bytes(password, InvalidUtf8::Reject));
+    let (explanation, advice) = match result {
+        Ok(()) => (ptr::null_mut(), ptr::null_mut()),
+        Err(ref err) => (message(err.explanation()), message(err.advice())),
+    };
+
+    Box::into_raw(Box::new(PasswordAnalysis {
+        code: code(&result),
+        explanation,
+        advice,
+        entropy_bits: panic::catch_unwind(|| {
+            ::std::str::from_utf8(password).map_or(0.0, quick_entropy_bits)
+        })
+        .unwrap_or(0.0),
+    }))
+}
+
+/// Free a `PasswordAnalysis` returned by `passablewords_analyze`, along with its strings. Null
+/// is ignored.
+///
+/// # Safety
+///
+/// `analysis` must be null or a pointer returned by `passablewords_analyze` that hasn't been
+/// freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn passablewords_free_analysis(analysis: *mut PasswordAnalysis) {
+    if analysis.is_null() {
+        return;
+    }
+
+    let analysis = Box::from_raw(analysis);
+
+    for text in [analysis.explanation, analysis.advice].iter() {
+        if !text.is_null() {
+            drop(CString::from_raw(*text));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        code, guarded, passablewords_analyze, passablewords_check, passablewords_free_analysis,
+        PASSABLEWORDS_INTERNAL_ERROR, PASSABLEWORDS_INVALID_ENCODING, PASSABLEWORDS_NULL_PASSWORD,
+        PASSABLEWORDS_OK, PASSABLEWORDS_TOO_COMMON, PASSABLEWORDS_TOO_SHORT,
+    };
+    use std::ffi::{CStr, CString};
+    use std::ptr;
+
+    fn check(password: &[u8]) -> i32 {
+        let password = CString::new(password).unwrap();
+
+        unsafe { passablewords_check(password.as_ptr()) }
+    }
+
+    #[test]
+    fn it_checks_passwords() {
+        assert_eq!(check(b"Th1s iS a Sup3rR4ndom PassW0rd!"), PASSABLEWORDS_OK);
+        assert_eq!(check(b"short"), PASSABLEWORDS_TOO_SHORT);
+        assert_eq!(check(b"password"), PASSABLEWORDS_TOO_COMMON);
+        assert_eq!(check(b"pass\xffword"), PASSABLEWORDS_INVALID_ENCODING);
+        assert_eq!(
+            unsafe { passablewords_check(ptr::null()) },
+            PASSABLEWORDS_NULL_PASSWORD
+        );
+    }
+
+    #[test]
+    fn it_reports_panics_as_internal_errors() {
+        assert_eq!(
+            code(&guarded(|| panic!("checking panicked"))),
+            PASSABLEWORDS_INTERNAL_ERROR
+        );
+        assert_eq!(code(&guarded(|| Ok(()))), PASSABLEWORDS_OK);
+    }
+
+    #[test]
+    fn it_analyzes_passwords() {
+        let rejected = CString::new("password").unwrap();
+        let accepted = CString::new("Th1s iS a Sup3rR4ndom PassW0rd!").unwrap();
+
+        unsafe {
+            let analysis = passablewords_analyze(rejected.as_ptr());
+
+            assert_eq!((*analysis).code, PASSABLEWORDS_TOO_COMMON);
+            assert_eq!(
+                CStr::from_ptr((*analysis).explanation).to_str(),
+                Ok("Your password is one of the most commonly used passwords.")
+            );
+            assert!(!(*analysis).advice.is_null());
+            passablewords_free_analysis(analysis);
+
+            let analysis = passablewords_analyze(accepted.as_ptr());
+
+            assert_eq!((*analysis).code, PASSABLEWORDS_OK);
+            assert!((*analysis).explanation.is_null());
+            assert!((*analysis).entropy_bits > 50.0);
+            passablewords_free_analysis(analysis);
+
+            assert!(passablewords_analyze(ptr::null()).is_null());
+            passablewords_free_analysis(ptr::null_mut());
+        }
+    }
+}
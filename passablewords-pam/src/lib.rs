@@ -0,0 +1,274 @@
+//! A PAM module which rejects weak passwords when they're changed, such as with `passwd`, using
+//! the same checks as `passablewords`. It builds as `libpam_passablewords.so`, which is installed
+//! as `pam_passablewords.so` and stacked in front of `pam_unix` in the `password` group:
+//!
+//! ```text
+//! password  requisite  pam_passablewords.so
+//! password  required   pam_unix.so use_authtok yescrypt
+//! ```
+//!
+//! The module prompts for the new password, twice, and checks it with the policy in
+//! `/etc/passablewords.toml`, or the file given with a `config=` argument, in the format described
+//! by `PolicyConfig`. Passwords built from the username are rejected too. Rejected passwords are
+//! explained to the user, who's asked again up to three times, or as many as a `retry=` argument
+//! says. Accepted passwords are left for the next module to store, which is why `pam_unix` needs
+//! `use_authtok`.
+//!
+//! Problems with the module itself, like a configuration or wordlist that can't be read, are
+//! logged to syslog and fail the change, so a broken install doesn't let weak passwords through. So
+//! does a panic, which would otherwise take down `passwd` or sshd with it.
+
+extern crate passablewords_core;
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic;
+use std::ptr;
+use std::slice;
+
+use passablewords_core::{CheckContext, PassablewordResult, PasswordError, PolicyConfig};
+
+/// Where the module reads its policy from, unless it's given a `config=` argument.
+pub const DEFAULT_CONFIG_PATH: &str = "/etc/passablewords.toml";
+
+/// How many times the user is asked for a new password before the change fails, unless the
+/// module is given a `retry=` argument.
+pub const DEFAULT_RETRY: u32 = 3;
+
+/// The state libpam keeps for a single transaction.
+#[repr(C)]
+pub struct PamHandle {
+    _private: [u8; 0],
+}
+
+const PAM_SUCCESS: c_int = 0;
+const PAM_SERVICE_ERR: c_int = 3;
+const PAM_AUTHTOK_ERR: c_int = 20;
+
+const PAM_AUTHTOK: c_int = 6;
+const PAM_UPDATE_AUTHTOK: c_int = 0x2000;
+const PAM_PRELIM_CHECK: c_int = 0x4000;
+
+const LOG_ERR: c_int = 3;
+
+// These come from libpam, which is already loaded by the application that loads the module, so
+// they aren't linked here. That also means the module builds without libpam's headers.
+extern "C" {
+    fn pam_get_user(pamh: *mut PamHandle, user: *mut *const c_char, prompt: *const c_char)
+        -> c_int;
+    fn pam_get_authtok(
+        pamh: *mut PamHandle,
+        item: c_int,
+        authtok: *mut *const c_char,
+        prompt: *const c_char,
+    ) -> c_int;
+    fn pam_set_item(pamh: *mut PamHandle, item: c_int, value: *const c_void) -> c_int;
+    fn pam_error(pamh: *mut PamHandle, format: *const c_char, ...) -> c_int;
+    fn pam_syslog(pamh: *mut PamHandle, priority: c_int, format: *const c_char, ...);
+}
+
+/// The module's arguments, like `config=/etc/security/passablewords.toml retry=5`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Args {
+    config: String,
+    retry: u32,
+}
+
+impl Args {
+    /// Parse the arguments from the PAM configuration, returning the ones that aren't known too.
+    fn parse<'a, I: IntoIterator<Item = &'a str>>(args: I) -> (Args, Vec<&'a str>) {
+        let mut parsed = Args {
+            config: DEFAULT_CONFIG_PATH.to_string(),
+            retry: DEFAULT_RETRY,
+        };
+        let mut unknown = Vec::new();
+
+        for arg in args {
+            let retry = arg
+                .strip_prefix("retry=")
+                .and_then(|retry| retry.parse().ok());
+
+            if let Some(path) = arg.strip_prefix("config=") {
+                parsed.config = path.to_string();
+            } else if let Some(retry) = retry {
+                parsed.retry = retry;
+            } else {
+                unknown.push(arg);
+            }
+        }
+
+        (parsed, unknown)
+    }
+}
+
+unsafe fn log(pamh: *mut PamHandle, message: &str) {
+    if let Ok(message) = CString::new(message) {
+        pam_syslog(
+            pamh,
+            LOG_ERR,
+            b"%s\0".as_ptr() as *const c_char,
+            message.as_ptr(),
+        );
+    }
+}
+
+unsafe fn tell_user(pamh: *mut PamHandle, message: &str) {
+    if let Ok(message) = CString::new(message) {
+        pam_error(pamh, b"%s\0".as_ptr() as *const c_char, message.as_ptr());
+    }
+}
+
+/// The password group's entry point, which libpam calls twice when a password is changed: first
+/// with `PAM_PRELIM_CHECK` to see whether the modules are ready, and then with
+/// `PAM_UPDATE_AUTHTOK` to change it. Only the second does anything.
+///
+/// # Safety
+///
+/// This must only be called by libpam, with the handle for the current transaction and the
+/// module's arguments.
+#[no_mangle]
+pub unsafe extern "C" fn pam_sm_chauthtok(
+    pamh: *mut PamHandle,
+    flags: c_int,
+    argc: c_int,
+    argv: *const *const c_char,
+) -> c_int {
+    // unwinding out of an `extern "C"` function aborts the process that loaded the module
+    match panic::catch_unwind(|| change_authtok(pamh, flags, argc, argv)) {
+        Ok(status) => status,
+        Err(_) => {
+            log(pamh, "panicked while checking the new password");
+            PAM_SERVICE_ERR
+        }
+    }
+}
+
+/// `pam_sm_chauthtok`, which may panic.
+unsafe fn change_authtok(
+    pamh: *mut PamHandle,
+    flags: c_int,
+    argc: c_int,
+    argv: *const *const c_char,
+) -> c_int {
+    if flags & PAM_PRELIM_CHECK != 0 || flags & PAM_UPDATE_AUTHTOK == 0 {
+        return PAM_SUCCESS;
+    }
+
+    let argv = if argv.is_null() || argc <= 0 {
+        &[][..]
+    } else {
+        slice::from_raw_parts(argv, argc as usize)
+    };
+    let (args, unknown) = Args::parse(
+        argv.iter()
+            .filter(|arg| !arg.is_null())
+            .filter_map(|&arg| CStr::from_ptr(arg).to_str().ok()),
+    );
+
+    for arg in unknown {
+        log(pamh, &format!("ignoring unknown argument {}", arg));
+    }
+
+    let config = match PolicyConfig::load(&args.config) {
+        Ok(config) => config,
+        Err(err) => {
+            log(pamh, &format!("couldn't read {}: {}", args.config, err));
+            return PAM_SERVICE_ERR;
+        }
+    };
+    let checker = match config.checker() {
+        Ok(checker) => checker,
+        Err(PasswordError::WordlistUnavailable { path, source }) => {
+            log(
+                pamh,
+                &format!(
+                    "couldn't read the list of common passwords{}: {}",
+                    path.map(|path| format!(" from {}", path))
+                        .unwrap_or_default(),
+                    source
+                ),
+            );
+            return PAM_SERVICE_ERR;
+        }
+        Err(err) => {
+            log(pamh, err.explanation());
+            return PAM_SERVICE_ERR;
+        }
+    };
+
+    let mut user: *const c_char = ptr::null();
+    let status = pam_get_user(pamh, &mut user, ptr::null());
+
+    if status != PAM_SUCCESS || user.is_null() {
+        return if status == PAM_SUCCESS {
+            PAM_SERVICE_ERR
+        } else {
+            status
+        };
+    }
+
+    let context = CheckContext::new().with_username(&CStr::from_ptr(user).to_string_lossy());
+
+    for _ in 0..args.retry.max(1) {
+        let mut password: *const c_char = ptr::null();
+        let status = pam_get_authtok(pamh, PAM_AUTHTOK, &mut password, ptr::null());
+
+        if status != PAM_SUCCESS || password.is_null() {
+            return if status == PAM_SUCCESS {
+                PAM_AUTHTOK_ERR
+            } else {
+                status
+            };
+        }
+
+        let result: PassablewordResult = CStr::from_ptr(password)
+            .to_str()
+            .map_err(|_| PasswordError::InvalidEncoding)
+            .and_then(|password| checker.check_password_with_context(password, &context));
+
+        match result {
+            Ok(()) => return PAM_SUCCESS,
+            Err(err) => {
+                tell_user(pamh, &format!("BAD PASSWORD: {}", err.explanation()));
+                // Forget the rejected password, so the next attempt prompts for a new one
+                pam_set_item(pamh, PAM_AUTHTOK, ptr::null());
+            }
+        }
+    }
+
+    PAM_AUTHTOK_ERR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, DEFAULT_CONFIG_PATH, DEFAULT_RETRY};
+
+    #[test]
+    fn it_parses_the_arguments() {
+        assert_eq!(
+            Args::parse(vec![]),
+            (
+                Args {
+                    config: DEFAULT_CONFIG_PATH.to_string(),
+                    retry: DEFAULT_RETRY,
+                },
+                vec![]
+            )
+        );
+        assert_eq!(
+            Args::parse(vec![
+                "config=/etc/security/passablewords.toml",
+                "retry=5",
+                "retry=many",
+                "debug",
+            ]),
+            (
+                Args {
+                    config: "/etc/security/passablewords.toml".to_string(),
+                    retry: 5,
+                },
+                vec!["retry=many", "debug"]
+            )
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Reading the accounts to audit, from a plain inventory or a password manager's CSV export.
+//!
+//! Passwords are only ever held in memory, in `SecretString`s which are zeroed when they're
+//! dropped, and never written anywhere.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use csv::{self, StringRecord};
+use secrecy::SecretString;
+
+/// The first eight bytes of a KeePass database.
+pub const KDBX_SIGNATURE: [u8; 8] = [0x03, 0xD9, 0xA2, 0x9A, 0x67, 0xFB, 0x4B, 0xB5];
+
+pub struct Account {
+    pub name: String,
+    pub password: SecretString,
+}
+
+/// How an account is named in reports, from its title and username, or `None` if it has
+/// neither.
+pub fn account_name(title: &str, username: &str) -> Option<String> {
+    match (title.trim(), username.trim()) {
+        ("", "") => None,
+        (title, "") => Some(title.to_string()),
+        ("", username) => Some(username.to_string()),
+        (title, username) => Some(format!("{} ({})", title, username)),
+    }
+}
+
+/// Whether a file is a KeePass database rather than a CSV file, going by its first eight bytes.
+pub fn is_kdbx(path: &Path) -> bool {
+    let mut start = [0; 8];
+
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut start))
+        .is_ok()
+        && start == KDBX_SIGNATURE
+}
+
+/// Where the interesting fields are in each row of an export.
+#[derive(Debug, Default)]
+struct Columns {
+    title: Option<usize>,
+    username: Option<usize>,
+    password: usize,
+    /// Bitwarden's item type, since only logins have passwords.
+    kind: Option<usize>,
+}
+
+impl Columns {
+    /// Work out the columns from the header row. Bitwarden, 1Password, KeePass, KeePassXC, and
+    /// browser exports are all recognized by their column names. Anything else is treated as an
+    /// inventory where the first column is the account and the second is its password.
+    fn from_headers(headers: &StringRecord) -> Columns {
+        let find = |names: &[&str]| {
+            headers
+                .iter()
+                .position(|header| names.contains(&header.trim().to_lowercase().as_str()))
+        };
+
+        match find(&["password", "login_password"]) {
+            Some(password) => Columns {
+                title: find(&["name", "title", "account"]),
+                username: find(&["username", "login_username", "login name", "user name"]),
+                password,
+                kind: find(&["type"]),
+            },
+            None => Columns {
+                title: Some(0),
+                username: None,
+                password: 1,
+                kind: None,
+            },
+        }
+    }
+
+    /// The account in a row, or `None` if the row isn't a login, like a secure note or a card.
+    /// Logins without a password are skipped too when the export has a username column, since
+    /// then it's a password manager's export rather than an inventory of accounts.
+    fn account(&self, record: &StringRecord, row: usize) -> Option<Account> {
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .unwrap_or_default()
+                .trim()
+        };
+
+        if self.kind.is_some() && !field(self.kind).eq_ignore_ascii_case("login") {
+            return None;
+        }
+
+        let password = record.get(self.password).unwrap_or_default();
+
+        if self.username.is_some() && password.is_empty() {
+            return None;
+        }
+
+        Some(Account {
+            name: account_name(field(self.title), field(self.username))
+                .unwrap_or_else(|| format!("row {}", row)),
+            password: SecretString::from(password),
+        })
+    }
+}
+
+/// Read the accounts in a CSV file with a header row, skipping any entries without passwords.
+/// Rows are read as they're needed, so the file is never all in memory at once.
+pub fn read_accounts(
+    path: &Path,
+) -> Result<impl Iterator<Item = Result<Account, csv::Error>> + Send, csv::Error> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_path(path)?;
+    let columns = Columns::from_headers(reader.headers()?);
+
+    Ok(reader
+        .into_records()
+        .enumerate()
+        .filter_map(move |(index, record)| match record {
+            // Rows are numbered as they are in a spreadsheet, after the header.
+            Ok(record) => columns.account(&record, index + 2).map(Ok),
+            Err(err) => Some(Err(err)),
+        }))
+}
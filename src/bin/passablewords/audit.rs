@@ -0,0 +1,723 @@
+//! `passablewords audit`, which checks every password in a credential inventory and reports the
+//! accounts with weak or shared passwords.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use accounts::{is_kdbx, read_accounts, Account};
+use breaches::{Breach, Breaches};
+use checkpoint::{self, Checkpoint};
+use csv;
+#[cfg(feature = "kdbx")]
+use kdbx;
+use output::{Status, SCHEMA_VERSION};
+use passablewords::audit::{
+    check_stream, find_similar_passwords, AccountRisk, SharedPasswordFinder,
+};
+use passablewords::{AnalysisProfile, CheckContext, PasswordChecker, PasswordError};
+#[cfg(feature = "kdbx")]
+use rpassword;
+use secrecy::{ExposeSecret, SecretString};
+use serde_json;
+use sink::{open_sinks, send_all, Finding};
+use watch;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// A CSV file with a header row, either a password manager's export or an inventory where the
+    /// first column is the account and the second is its password, or a KeePass database
+    #[arg(required_unless_present = "watch")]
+    input: Option<PathBuf>,
+
+    /// The format of the report
+    #[arg(long, value_enum, default_value_t = Format::Json)]
+    format: Format,
+
+    /// Keep running and audit each new CSV file that appears in a directory, appending the
+    /// findings to the report as JSON lines
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["input", "format"])]
+    pub watch: Option<PathBuf>,
+
+    /// Where to write the report, instead of standard output
+    #[arg(long, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+
+    /// Also post each finding as JSON to this URL, like a Slack incoming webhook or a SIEM's HTTP
+    /// collector
+    #[cfg(feature = "webhook")]
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+
+    /// Also send each finding to the local syslog daemon
+    #[cfg(unix)]
+    #[arg(long)]
+    pub syslog: bool,
+
+    /// How thoroughly to analyze each password's entropy. `fast` is much quicker on large
+    /// inventories, at the cost of occasionally rejecting a password `thorough` would accept
+    #[arg(long, value_enum, default_value_t = Profile::Thorough)]
+    pub profile: Profile,
+
+    /// How many threads to check passwords on, defaulting to one per CPU
+    #[arg(long)]
+    pub workers: Option<usize>,
+
+    /// Save the audit's progress to a file as it runs, so that if it's interrupted, running it
+    /// again with the same file picks up where it left off. The file is removed once the report
+    /// has been written
+    #[arg(long, value_name = "PATH", conflicts_with = "watch")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// Don't show progress on standard error
+    #[arg(long)]
+    pub quiet: bool,
+
+    /// Also report groups of accounts whose passwords are nearly the same, like per-site variants
+    /// of one password. Every pair of passwords is compared, so this is meant for vaults rather
+    /// than large inventories
+    #[arg(long)]
+    pub similar: bool,
+
+    /// How many edits apart passwords can be to count as similar
+    #[arg(
+        long,
+        value_name = "EDITS",
+        default_value_t = 2,
+        requires = "similar",
+        value_parser = clap::value_parser!(u8).range(1..=2),
+    )]
+    pub max_distance: u8,
+
+    /// Look every password up in Have I Been Pwned, which is only sent the first five characters
+    /// of each password's SHA-1 hash
+    #[cfg(feature = "hibp")]
+    #[arg(long)]
+    pub breached: bool,
+
+    /// A key file to unlock a KeePass database with, along with its master password
+    #[cfg(feature = "kdbx")]
+    #[arg(long, value_name = "PATH")]
+    key_file: Option<PathBuf>,
+
+    /// Read a KeePass database's master password from the first line of standard input, instead
+    /// of prompting for it
+    #[cfg(feature = "kdbx")]
+    #[arg(long)]
+    password_stdin: bool,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+}
+
+/// How thoroughly each password's entropy is analyzed.
+#[derive(Clone, Copy, clap::ValueEnum)]
+pub enum Profile {
+    /// Analyze the whole of every password
+    Thorough,
+    /// Skip the full analysis for obviously weak passwords, and only analyze the start of long
+    /// ones
+    Fast,
+}
+
+impl From<Profile> for AnalysisProfile {
+    fn from(profile: Profile) -> AnalysisProfile {
+        match profile {
+            Profile::Thorough => AnalysisProfile::Thorough,
+            Profile::Fast => AnalysisProfile::Fast,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    schema_version: u32,
+    checked: usize,
+    accounts: Vec<Ranked<'a>>,
+    rejected: Vec<Rejected<'a>>,
+    shared: Vec<Vec<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    similar: Option<Vec<Vec<&'a str>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    breached: Option<Vec<&'a str>>,
+}
+
+/// An account with findings, in the order they should be fixed.
+#[derive(Serialize)]
+struct Ranked<'a> {
+    #[serde(skip)]
+    index: usize,
+    account: &'a str,
+    priority: u8,
+    findings: Vec<Kind>,
+}
+
+/// A kind of finding.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Breached,
+    Shared,
+    Similar,
+    Rejected,
+}
+
+impl Kind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Kind::Breached => "breached",
+            Kind::Shared => "shared",
+            Kind::Similar => "similar",
+            Kind::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Rejected<'a> {
+    account: &'a str,
+    error: PasswordError,
+    reason: &'static str,
+    priority: u8,
+}
+
+/// Everything an audit found, with accounts referred to by their index in the input.
+pub struct Findings {
+    /// How many accounts were checked.
+    pub checked: usize,
+    pub shared: Vec<Vec<usize>>,
+    pub similar: Option<Vec<Vec<usize>>>,
+    /// The accounts with findings, in the order they were read.
+    pub risks: BTreeMap<usize, AccountRisk>,
+    /// The accounts whose passwords couldn't be looked up, in the order they were read.
+    pub failed: Vec<usize>,
+}
+
+impl Findings {
+    /// The highest priority of any of the accounts.
+    pub fn priority(&self, accounts: &[usize]) -> u8 {
+        accounts
+            .iter()
+            .map(|index| self.risks[index].priority())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// What was found about an account, most serious first.
+    fn kinds(&self, index: usize) -> Vec<Kind> {
+        let risk = &self.risks[&index];
+        let kinds = [
+            (risk.breached, Kind::Breached),
+            (risk.shared_with > 0, Kind::Shared),
+            (risk.similar, Kind::Similar),
+            (risk.error.is_some(), Kind::Rejected),
+        ];
+
+        kinds
+            .iter()
+            .filter(|&&(found, _)| found)
+            .map(|&(_, kind)| kind)
+            .collect()
+    }
+
+    /// The accounts that need a name in a report, in no particular order.
+    fn named(&self) -> impl Iterator<Item = usize> + '_ {
+        self.risks.keys().chain(&self.failed).copied()
+    }
+}
+
+/// Check every account, then write the report. Fails with a policy failure if there were any
+/// findings, and an internal error if the audit couldn't be run or some passwords couldn't be
+/// looked up, so it can be used as a gate in CI. With `--watch`, it runs until it's interrupted
+/// instead.
+pub fn run(args: &Args, checker: &PasswordChecker) -> Status {
+    let input = match (&args.input, &args.watch) {
+        (_, Some(dir)) => return watch::run(args, checker, dir),
+        (Some(input), None) => input,
+        (None, None) => unreachable!("clap requires an input without --watch"),
+    };
+    let breaches = open_breaches(args);
+    let mut sinks = match open_sinks(args) {
+        Ok(sinks) => sinks,
+        Err(err) => {
+            eprintln!("couldn't open the syslog socket: {}", err);
+            return Status::InternalError;
+        }
+    };
+    let show_progress = !args.quiet && io::stderr().is_terminal();
+    let (findings, names) =
+        match audit_input(args, checker, breaches.as_ref(), input, show_progress) {
+            Ok(audited) => audited,
+            Err(err) => {
+                eprintln!("couldn't read {}: {}", input.display(), err);
+                return Status::InternalError;
+            }
+        };
+    let name = |index: &usize| names[index].as_str();
+    let mut ranked: Vec<Ranked> = findings
+        .risks
+        .iter()
+        .filter_map(|(&index, risk)| {
+            let kinds = findings.kinds(index);
+
+            if kinds.is_empty() {
+                return None;
+            }
+
+            Some(Ranked {
+                index,
+                account: name(&index),
+                priority: risk.priority(),
+                findings: kinds,
+            })
+        })
+        .collect();
+
+    // A stable sort keeps accounts with the same priority in the order they were read
+    ranked.sort_by_key(|ranked| Reverse(ranked.priority));
+
+    let names_of = |group: &Vec<usize>| -> Vec<&str> { group.iter().map(name).collect() };
+    let report = Report {
+        schema_version: SCHEMA_VERSION,
+        checked: findings.checked,
+        accounts: ranked,
+        rejected: findings
+            .risks
+            .iter()
+            .filter_map(|(index, risk)| {
+                risk.error.clone().map(|error| Rejected {
+                    account: name(index),
+                    reason: error.explanation(),
+                    error,
+                    priority: risk.priority(),
+                })
+            })
+            .collect(),
+        shared: findings.shared.iter().map(names_of).collect(),
+        similar: findings
+            .similar
+            .as_ref()
+            .map(|similar| similar.iter().map(names_of).collect()),
+        breached: breaches.as_ref().map(|_| {
+            findings
+                .risks
+                .iter()
+                .filter(|&(_, risk)| risk.breached)
+                .map(|(index, _)| name(index))
+                .collect()
+        }),
+    };
+
+    if let Err(err) = write_report(args, &report, &findings) {
+        eprintln!("couldn't write the report: {}", err);
+        return Status::InternalError;
+    }
+
+    let file = input.display().to_string();
+    let delivered = send_all(&mut sinks, &Finding::all(&file, &findings, &names));
+
+    if let Some(ref path) = args.checkpoint {
+        if let Err(err) = checkpoint::remove(path) {
+            eprintln!("couldn't remove the checkpoint {}: {}", path.display(), err);
+        }
+    }
+
+    if !findings.failed.is_empty() {
+        eprintln!(
+            "couldn't look up {} passwords in Have I Been Pwned",
+            findings.failed.len()
+        );
+        Status::InternalError
+    } else if !delivered {
+        Status::InternalError
+    } else if report.rejected.is_empty()
+        && report.shared.is_empty()
+        && report.similar.as_ref().is_none_or(Vec::is_empty)
+        && report.breached.as_ref().is_none_or(Vec::is_empty)
+    {
+        Status::Pass
+    } else {
+        Status::PolicyFailure
+    }
+}
+
+/// What checking an account's password found.
+pub struct Outcome {
+    pub error: Option<PasswordError>,
+    pub breach: Breach,
+}
+
+/// Use Have I Been Pwned if `--breached` was given.
+#[cfg(feature = "hibp")]
+pub fn open_breaches(args: &Args) -> Option<Breaches> {
+    if args.breached {
+        Some(Breaches::new())
+    } else {
+        None
+    }
+}
+
+#[cfg(not(feature = "hibp"))]
+pub fn open_breaches(_args: &Args) -> Option<Breaches> {
+    None
+}
+
+/// Audit a CSV file or KeePass database, returning what was found and the names of the accounts
+/// with findings.
+///
+/// CSV files are streamed, so they're never all in memory at once. The accounts are read a second
+/// time to name the ones with findings, rather than holding every name for the whole audit.
+pub fn audit_input(
+    args: &Args,
+    checker: &PasswordChecker,
+    breaches: Option<&Breaches>,
+    input: &Path,
+    show_progress: bool,
+) -> io::Result<(Findings, HashMap<usize, String>)> {
+    let mut checkpoint = match args.checkpoint {
+        Some(ref path) => Some(Checkpoint::open(path, input, breaches.is_some())?),
+        None => None,
+    };
+
+    if is_kdbx(input) {
+        let accounts = read_kdbx(args, input)?;
+        let mut names: Vec<Option<String>> = accounts
+            .iter()
+            .map(|account| Some(account.name.clone()))
+            .collect();
+        let findings = check_accounts(
+            args,
+            checker,
+            breaches,
+            accounts.into_iter().map(Ok),
+            checkpoint.as_mut(),
+            show_progress,
+        )?;
+        let named = findings
+            .named()
+            .filter_map(|index| Some((index, names[index].take()?)))
+            .collect();
+
+        return Ok((findings, named));
+    }
+
+    let findings = check_accounts(
+        args,
+        checker,
+        breaches,
+        read_accounts(input)?.map(|account| account.map_err(io::Error::from)),
+        checkpoint.as_mut(),
+        show_progress,
+    )?;
+    let wanted: HashSet<usize> = findings.named().collect();
+    let mut names = HashMap::with_capacity(wanted.len());
+
+    for (index, account) in read_accounts(input)?.enumerate() {
+        let account = account?;
+
+        if wanted.contains(&index) {
+            names.insert(index, account.name);
+        }
+    }
+
+    if names.len() < wanted.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the file changed while it was being audited",
+        ));
+    }
+
+    Ok((findings, names))
+}
+
+#[cfg(feature = "kdbx")]
+fn read_kdbx(args: &Args, input: &Path) -> io::Result<Vec<Account>> {
+    let password = if args.password_stdin {
+        let mut line = String::new();
+
+        io::stdin().read_line(&mut line)?;
+        line.truncate(line.trim_end_matches(['\r', '\n']).len());
+        line
+    } else {
+        rpassword::prompt_password(format!("Master password for {}: ", input.display()))?
+    };
+
+    kdbx::read_accounts(
+        input,
+        &SecretString::from(password),
+        args.key_file.as_deref(),
+    )
+}
+
+#[cfg(not(feature = "kdbx"))]
+fn read_kdbx(_args: &Args, _input: &Path) -> io::Result<Vec<Account>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading KeePass databases needs the kdbx feature",
+    ))
+}
+
+/// How often progress is drawn.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What's been found so far, as accounts are checked.
+struct Tally {
+    finder: SharedPasswordFinder,
+    /// Every password, with `--similar`, since every pair has to be compared.
+    passwords: Option<Vec<(usize, SecretString)>>,
+    risks: BTreeMap<usize, AccountRisk>,
+    failed: Vec<usize>,
+    checked: usize,
+}
+
+impl Tally {
+    /// Take note of an account's password, for finding shared and similar passwords.
+    fn add(&mut self, index: usize, account: Account) -> io::Result<()> {
+        self.checked += 1;
+        self.finder
+            .add(index as u64, account.password.expose_secret())?;
+
+        if let Some(ref mut passwords) = self.passwords {
+            passwords.push((index, account.password));
+        }
+
+        Ok(())
+    }
+
+    /// Take note of what checking an account's password found.
+    fn record(&mut self, index: usize, outcome: &Outcome) {
+        if outcome.breach == Breach::Failed {
+            self.failed.push(index);
+        }
+        if outcome.error.is_some() || outcome.breach == Breach::Breached {
+            self.risks.insert(
+                index,
+                AccountRisk {
+                    breached: outcome.breach == Breach::Breached,
+                    error: outcome.error.clone(),
+                    ..AccountRisk::default()
+                },
+            );
+        }
+    }
+}
+
+/// Check every password across the worker threads, and look it up if there's somewhere to look
+/// breaches up. Accounts are read as the workers are ready for them, and only kept afterwards
+/// with `--similar`, which has to compare every pair. Stops at the first account which can't be
+/// read. If a worker panics, the panic is passed on.
+///
+/// With a checkpoint, the accounts it already covers are only read again to find shared and
+/// similar passwords, and the checkpoint is kept up to date as the rest are checked.
+pub fn check_accounts<I>(
+    args: &Args,
+    checker: &PasswordChecker,
+    breaches: Option<&Breaches>,
+    accounts: I,
+    mut checkpoint: Option<&mut Checkpoint>,
+    show_progress: bool,
+) -> io::Result<Findings>
+where
+    I: Iterator<Item = io::Result<Account>> + Send,
+{
+    let workers = args
+        .workers
+        .or_else(|| thread::available_parallelism().ok().map(usize::from))
+        .unwrap_or(1);
+    let resumed = checkpoint
+        .as_ref()
+        .map_or(0, |checkpoint| checkpoint.checked());
+    let mut read_error = None;
+    let mut write_error = None;
+    let mut tally = Tally {
+        finder: SharedPasswordFinder::new(),
+        passwords: if args.similar { Some(Vec::new()) } else { None },
+        risks: BTreeMap::new(),
+        failed: Vec::new(),
+        checked: 0,
+    };
+    let context = CheckContext::new().with_profile(args.profile.into());
+    let mut drawn = Instant::now();
+    let mut accounts = accounts.scan(&mut read_error, |read_error, account| match account {
+        Ok(account) => Some(account),
+        Err(err) => {
+            **read_error = Some(err);
+            None
+        }
+    });
+
+    if let Some(ref checkpoint) = checkpoint {
+        for (index, outcome) in checkpoint.outcomes() {
+            tally.record(index, &outcome);
+        }
+    }
+    for (index, account) in accounts.by_ref().take(resumed).enumerate() {
+        tally.add(index, account)?;
+    }
+
+    check_stream(
+        accounts
+            .enumerate()
+            .map(|(index, account)| (resumed + index, account)),
+        workers,
+        |account: &Account| Outcome {
+            error: checker
+                .check_password_secret_with_context(&account.password, &context)
+                .err(),
+            breach: breaches.map_or(Breach::Unchecked, |breaches| {
+                breaches.lookup(&account.password)
+            }),
+        },
+        |index, account, outcome| {
+            tally.record(index, &outcome);
+
+            let written = tally.add(index, account).and_then(|()| match checkpoint {
+                Some(ref mut checkpoint) => checkpoint.record(index, &outcome),
+                None => Ok(()),
+            });
+
+            if let Err(err) = written {
+                write_error.get_or_insert(err);
+            }
+            if show_progress && drawn.elapsed() >= PROGRESS_INTERVAL {
+                draw_progress(tally.checked);
+                drawn = Instant::now();
+            }
+        },
+    );
+
+    if show_progress {
+        draw_progress(tally.checked);
+        eprintln!();
+    }
+    if let Some(err) = read_error.or(write_error) {
+        return Err(err);
+    }
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.save()?;
+    }
+
+    let Tally {
+        finder,
+        passwords,
+        mut risks,
+        mut failed,
+        checked,
+    } = tally;
+    let shared: Vec<Vec<usize>> = finder
+        .finish()?
+        .into_iter()
+        .map(|shared| {
+            shared
+                .users
+                .into_iter()
+                .map(|index| index as usize)
+                .collect()
+        })
+        .collect();
+    let similar: Option<Vec<Vec<usize>>> = passwords.map(|mut passwords| {
+        // Results arrive in the order they're finished, but groups are in the order accounts
+        // were read
+        passwords.sort_unstable_by_key(|&(index, _)| index);
+
+        let similar = find_similar_passwords(
+            passwords
+                .iter()
+                .map(|(index, password)| (*index, password.expose_secret())),
+            usize::from(args.max_distance),
+        );
+
+        similar.into_iter().map(|similar| similar.users).collect()
+    });
+
+    for group in &shared {
+        for &index in group {
+            risks.entry(index).or_default().shared_with = group.len() - 1;
+        }
+    }
+    for group in similar.iter().flatten() {
+        for &index in group {
+            risks.entry(index).or_default().similar = true;
+        }
+    }
+    failed.sort_unstable();
+
+    Ok(Findings {
+        checked,
+        shared,
+        similar,
+        risks,
+        failed,
+    })
+}
+
+fn draw_progress(checked: usize) {
+    eprint!("\rchecked {} accounts", checked);
+}
+
+fn write_report(args: &Args, report: &Report, findings: &Findings) -> io::Result<()> {
+    let out: Box<dyn Write> = match args.out {
+        Some(ref path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout()),
+    };
+
+    match args.format {
+        Format::Json => {
+            let mut out = out;
+
+            serde_json::to_writer_pretty(&mut out, report)?;
+            writeln!(out)
+        }
+        Format::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+
+            let shared = group_numbers(&findings.shared);
+            let similar = group_numbers(findings.similar.as_deref().unwrap_or_default());
+
+            // One row per finding, with the most urgent accounts first
+            writer.write_record(["account", "finding", "detail", "priority"])?;
+            for ranked in &report.accounts {
+                let priority = ranked.priority.to_string();
+
+                for &kind in &ranked.findings {
+                    let detail = match kind {
+                        Kind::Breached => "seen in a data breach".to_string(),
+                        Kind::Shared => format!("shared password group {}", shared[&ranked.index]),
+                        Kind::Similar => {
+                            format!("similar password group {}", similar[&ranked.index])
+                        }
+                        Kind::Rejected => findings.risks[&ranked.index]
+                            .error
+                            .as_ref()
+                            .map_or("", PasswordError::explanation)
+                            .to_string(),
+                    };
+
+                    writer.write_record([ranked.account, kind.as_str(), &detail, &priority])?;
+                }
+            }
+
+            writer.flush()
+        }
+    }
+}
+
+/// The group each account is in, numbered from 1. Accounts which aren't in one are left out.
+fn group_numbers(groups: &[Vec<usize>]) -> HashMap<usize, usize> {
+    let mut numbers = HashMap::new();
+
+    for (number, group) in groups.iter().enumerate() {
+        for &index in group {
+            numbers.insert(index, number + 1);
+        }
+    }
+
+    numbers
+}
@@ -0,0 +1,65 @@
+//! Looking up whether audited passwords have been breached. Lookups are only made with the
+//! `hibp` feature; without it, there's no way to make a `Breaches`.
+
+#[cfg(feature = "hibp")]
+use std::time::Duration;
+
+#[cfg(feature = "hibp")]
+use passablewords::{BreachProvider, CachedBreachProvider, HibpProvider};
+#[cfg(feature = "hibp")]
+use secrecy::ExposeSecret;
+use secrecy::SecretString;
+
+/// Whether an account's password has been breached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(feature = "hibp"), allow(dead_code))]
+pub enum Breach {
+    /// The password wasn't looked up.
+    Unchecked,
+    /// The password hasn't been seen in any breaches.
+    Clean,
+    /// The password has been seen in a breach.
+    Breached,
+    /// The password couldn't be looked up.
+    Failed,
+}
+
+/// Where breached passwords are looked up.
+#[cfg(feature = "hibp")]
+pub struct Breaches {
+    provider: CachedBreachProvider<HibpProvider>,
+}
+
+/// Where breached passwords are looked up, which can't be made without the `hibp` feature.
+#[cfg(not(feature = "hibp"))]
+pub enum Breaches {}
+
+#[cfg(feature = "hibp")]
+impl Breaches {
+    /// Look passwords up in Have I Been Pwned. Answers are cached for the run, since reused
+    /// passwords come up more than once.
+    pub fn new() -> Breaches {
+        Breaches {
+            provider: CachedBreachProvider::new(
+                HibpProvider::new(),
+                100_000,
+                Duration::from_secs(60 * 60),
+            ),
+        }
+    }
+
+    pub fn lookup(&self, password: &SecretString) -> Breach {
+        match self.provider.breach_count(password.expose_secret()) {
+            Ok(0) => Breach::Clean,
+            Ok(_) => Breach::Breached,
+            Err(_) => Breach::Failed,
+        }
+    }
+}
+
+#[cfg(not(feature = "hibp"))]
+impl Breaches {
+    pub fn lookup(&self, _password: &SecretString) -> Breach {
+        match *self {}
+    }
+}
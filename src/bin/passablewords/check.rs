@@ -0,0 +1,135 @@
+//! `passablewords check`, which checks passwords and explains why any were rejected.
+
+use std::io::{self, BufRead};
+
+use output::{Status, SCHEMA_VERSION};
+use passablewords::{PasswordChecker, PasswordError};
+use rpassword;
+use serde_json;
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Passwords to check. Passwords given here end up in your shell history and can be seen by
+    /// other users in `ps`, so leave them out to be prompted instead
+    #[arg(conflicts_with = "stdin")]
+    passwords: Vec<String>,
+
+    /// Read passwords from standard input, one per line, instead of prompting for one
+    #[arg(long)]
+    stdin: bool,
+
+    /// The format of the results
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Serialize)]
+struct Report {
+    schema_version: u32,
+    results: Vec<CheckResult>,
+}
+
+#[derive(Serialize)]
+struct CheckResult {
+    password: usize,
+    accepted: bool,
+    error: Option<PasswordError>,
+    reason: Option<&'static str>,
+}
+
+/// Check each password, printing `ok` or why it was rejected. Passwords are numbered rather than
+/// echoed back. Fails with a policy failure if any were rejected, and an internal error if they
+/// couldn't be read or checked.
+pub fn run(args: &Args, checker: &PasswordChecker) -> Status {
+    let passwords = match read_passwords(args) {
+        Ok(passwords) => passwords,
+        Err(err) => {
+            eprintln!("couldn't read the password: {}", err);
+            return Status::InternalError;
+        }
+    };
+    let results: Vec<_> = passwords
+        .iter()
+        .enumerate()
+        .map(|(index, password)| {
+            let error = checker.check_password(password).err();
+
+            CheckResult {
+                password: index + 1,
+                accepted: error.is_none(),
+                reason: error.as_ref().map(PasswordError::explanation),
+                error,
+            }
+        })
+        .collect();
+    let status = status(&results);
+
+    match args.format {
+        Format::Text => {
+            for result in &results {
+                println!(
+                    "password {}: {}",
+                    result.password,
+                    result.reason.unwrap_or("ok")
+                );
+            }
+        }
+        Format::Json => {
+            let report = Report {
+                schema_version: SCHEMA_VERSION,
+                results,
+            };
+
+            match serde_json::to_string_pretty(&report) {
+                Ok(json) => println!("{}", json),
+                Err(err) => {
+                    eprintln!("couldn't write the results: {}", err);
+                    return Status::InternalError;
+                }
+            }
+        }
+    }
+
+    status
+}
+
+/// An internal error if any password couldn't be checked, since then there's no answer for it,
+/// or otherwise a policy failure if any were rejected.
+fn status(results: &[CheckResult]) -> Status {
+    if results.iter().any(|result| {
+        matches!(
+            result.error,
+            Some(PasswordError::InternalError) | Some(PasswordError::WordlistUnavailable { .. })
+        )
+    }) {
+        Status::InternalError
+    } else if results.iter().any(|result| !result.accepted) {
+        Status::PolicyFailure
+    } else {
+        Status::Pass
+    }
+}
+
+/// The passwords given as arguments, read from standard input, or typed at a prompt without being
+/// echoed, in that order of preference.
+fn read_passwords(args: &Args) -> io::Result<Vec<String>> {
+    if !args.passwords.is_empty() {
+        return Ok(args.passwords.clone());
+    }
+
+    if args.stdin {
+        return io::stdin()
+            .lock()
+            .lines()
+            .map(|line| line.map(|line| line.trim_end_matches('\r').to_string()))
+            .collect();
+    }
+
+    rpassword::prompt_password("Password: ").map(|password| vec![password])
+}
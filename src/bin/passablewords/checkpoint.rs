@@ -0,0 +1,209 @@
+//! `passablewords audit --checkpoint`, which records how far an audit has got so that an
+//! interrupted audit can pick up where it left off.
+//!
+//! The checkpoint holds how many accounts from the start of the input have been checked, and
+//! what was found about them. Nothing derived from a password is written to it. When an audit
+//! resumes, the accounts it already checked are read again to find shared passwords, which is
+//! quick, but they aren't checked or looked up again, which is the slow part.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+use audit::Outcome;
+use breaches::Breach;
+use passablewords::PasswordError;
+use serde_json;
+
+/// The version of the checkpoint format. Checkpoints from other versions aren't resumed.
+const VERSION: u32 = 1;
+
+/// How often the checkpoint is saved while an audit is running.
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The state of an audit, as it's saved.
+#[derive(Serialize, Deserialize)]
+struct State {
+    version: u32,
+    input: Fingerprint,
+    breached: bool,
+    /// How many accounts from the start of the input have been checked.
+    checked: usize,
+    rejected: Vec<(usize, PasswordError)>,
+    breaches: Vec<usize>,
+    failed: Vec<usize>,
+}
+
+/// Enough about the input to tell whether it's changed since the checkpoint was saved.
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Fingerprint {
+    len: u64,
+    modified: Option<(u64, u32)>,
+}
+
+impl Fingerprint {
+    fn of(input: &Path) -> io::Result<Fingerprint> {
+        let metadata = fs::metadata(input)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|modified| (modified.as_secs(), modified.subsec_nanos()));
+
+        Ok(Fingerprint {
+            len: metadata.len(),
+            modified,
+        })
+    }
+}
+
+/// Keeps a checkpoint up to date as accounts are checked.
+pub struct Checkpoint {
+    path: PathBuf,
+    state: State,
+    /// Accounts which were checked ahead of the first unchecked one, since results arrive out of
+    /// order. They're saved once every account before them has been checked.
+    ahead: BTreeMap<usize, (Option<PasswordError>, Breach)>,
+    saved: Instant,
+}
+
+impl Checkpoint {
+    /// Resume from the checkpoint at `path`, or start a new one if there isn't one yet. Fails if
+    /// the checkpoint is for a different input, the input has changed since it was saved, or it
+    /// was saved with or without `--breached` when this audit is the other way.
+    pub fn open(path: &Path, input: &Path, breached: bool) -> io::Result<Checkpoint> {
+        let fingerprint = Fingerprint::of(input)?;
+        let state = match File::open(path) {
+            Ok(file) => {
+                let state: State = serde_json::from_reader(BufReader::new(file))?;
+
+                if state.version != VERSION {
+                    return Err(invalid(path, "was saved by a different version"));
+                }
+                if state.input != fingerprint {
+                    return Err(invalid(path, "is for a different or changed input"));
+                }
+                if state.breached != breached {
+                    return Err(invalid(
+                        path,
+                        "was saved with a different --breached setting",
+                    ));
+                }
+
+                state
+            }
+            Err(ref err) if err.kind() == io::ErrorKind::NotFound => State {
+                version: VERSION,
+                input: fingerprint,
+                breached,
+                checked: 0,
+                rejected: Vec::new(),
+                breaches: Vec::new(),
+                failed: Vec::new(),
+            },
+            Err(err) => return Err(err),
+        };
+
+        Ok(Checkpoint {
+            path: path.to_path_buf(),
+            state,
+            ahead: BTreeMap::new(),
+            saved: Instant::now(),
+        })
+    }
+
+    /// How many accounts from the start of the input were checked before this run.
+    pub fn checked(&self) -> usize {
+        self.state.checked
+    }
+
+    /// What was found about the accounts checked before this run, in the order they were read.
+    /// Accounts with nothing to report are left out.
+    pub fn outcomes(&self) -> Vec<(usize, Outcome)> {
+        let mut outcomes: BTreeMap<usize, Outcome> = BTreeMap::new();
+        let unchecked = || Outcome {
+            error: None,
+            breach: Breach::Unchecked,
+        };
+
+        for (index, error) in &self.state.rejected {
+            outcomes.entry(*index).or_insert_with(unchecked).error = Some(error.clone());
+        }
+        for &index in &self.state.breaches {
+            outcomes.entry(index).or_insert_with(unchecked).breach = Breach::Breached;
+        }
+        for &index in &self.state.failed {
+            outcomes.entry(index).or_insert_with(unchecked).breach = Breach::Failed;
+        }
+
+        outcomes.into_iter().collect()
+    }
+
+    /// Record what was found about an account, saving the checkpoint if it hasn't been saved for
+    /// a while.
+    pub fn record(&mut self, index: usize, outcome: &Outcome) -> io::Result<()> {
+        self.ahead
+            .insert(index, (outcome.error.clone(), outcome.breach));
+
+        while let Some((error, breach)) = self.ahead.remove(&self.state.checked) {
+            let index = self.state.checked;
+
+            if let Some(error) = error {
+                self.state.rejected.push((index, error));
+            }
+
+            match breach {
+                Breach::Breached => self.state.breaches.push(index),
+                Breach::Failed => self.state.failed.push(index),
+                Breach::Unchecked | Breach::Clean => {}
+            }
+
+            self.state.checked += 1;
+        }
+
+        if self.saved.elapsed() >= SAVE_INTERVAL {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the checkpoint. It's written next to where it belongs and then moved into place, so
+    /// an interruption part way through doesn't leave a broken checkpoint behind.
+    pub fn save(&mut self) -> io::Result<()> {
+        let mut partial = self.path.clone().into_os_string();
+
+        partial.push(".partial");
+
+        let mut out = BufWriter::new(File::create(&partial)?);
+
+        serde_json::to_writer(&mut out, &self.state)?;
+        out.flush()?;
+        drop(out);
+        fs::rename(&partial, &self.path)?;
+        self.saved = Instant::now();
+
+        Ok(())
+    }
+}
+
+/// Remove a checkpoint once its audit's report has been written, so the next audit starts afresh.
+pub fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+fn invalid(path: &Path, problem: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!(
+            "the checkpoint {} {}; remove it to start over",
+            path.display(),
+            problem
+        ),
+    )
+}
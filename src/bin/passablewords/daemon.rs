@@ -0,0 +1,36 @@
+//! `passablewords daemon`, which keeps a checker loaded and answers checks on a Unix socket.
+
+use std::path::PathBuf;
+
+use output::Status;
+use passablewords::{Daemon, PasswordChecker};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// Where to create the socket. A socket left behind by a daemon that's no longer running is
+    /// replaced
+    #[arg(long, value_name = "PATH", default_value = "/run/passablewords.sock")]
+    socket: PathBuf,
+}
+
+/// Serve checks on the socket until the daemon is stopped. Fails with an internal error if the
+/// socket can't be created, such as when another daemon is already listening on it.
+pub fn run(args: &Args, checker: &PasswordChecker) -> Status {
+    let daemon = match Daemon::bind(&args.socket, checker.clone()) {
+        Ok(daemon) => daemon,
+        Err(err) => {
+            eprintln!("couldn't listen on {}: {}", args.socket.display(), err);
+            return Status::InternalError;
+        }
+    };
+
+    eprintln!("listening on {}", args.socket.display());
+
+    match daemon.serve() {
+        Ok(()) => Status::Pass,
+        Err(err) => {
+            eprintln!("stopped listening on {}: {}", args.socket.display(), err);
+            Status::InternalError
+        }
+    }
+}
@@ -0,0 +1,51 @@
+//! `passablewords generate`, which prints random passwords or passphrases that pass the checks,
+//! such as for provisioning service accounts.
+
+use output::Status;
+use passablewords::{PasswordChecker, Policy};
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The number of characters in each password, or the minimum for passphrases
+    #[arg(long, default_value_t = 20)]
+    length: usize,
+
+    /// Generate passphrases of random words instead of random characters
+    #[arg(long)]
+    passphrase: bool,
+
+    /// The number of words in each passphrase
+    #[arg(long, default_value_t = 5, requires = "passphrase")]
+    words: usize,
+
+    /// How many to generate
+    #[arg(long, default_value_t = 1)]
+    count: usize,
+}
+
+/// Print `count` passwords, one per line. Every one is checked before it's printed, so this fails
+/// with an internal error without printing anything more if one can't be generated, such as when
+/// `--length` is too short for the policy.
+pub fn run(args: &Args, checker: &PasswordChecker) -> Status {
+    let checker = checker
+        .clone()
+        .with_policy(Policy::new().min_length(args.length));
+
+    for _ in 0..args.count {
+        let generated = if args.passphrase {
+            checker.generate_passphrase(args.words)
+        } else {
+            checker.generate_password(args.length)
+        };
+
+        match generated {
+            Some(password) => println!("{}", password),
+            None => {
+                eprintln!("couldn't generate a password that passes the checks");
+                return Status::InternalError;
+            }
+        }
+    }
+
+    Status::Pass
+}
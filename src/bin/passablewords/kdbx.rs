@@ -0,0 +1,620 @@
+//! Reading the entries of a KeePass database for `audit`, without exporting it. Only built with
+//! the `kdbx` feature.
+//!
+//! Only KDBX 4 databases are supported, which is what KeePass 2.35 and KeePassXC 2.7 and later
+//! save by default. The database is decrypted in memory, and each entry's password is only
+//! decrypted as it's read into a `SecretString`.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use accounts::{account_name, Account, KDBX_SIGNATURE};
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use cbc::cipher::block_padding::Pkcs7;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit, StreamCipher};
+use chacha20::ChaCha20;
+use flate2::read::GzDecoder;
+use hmac::{Hmac, Mac};
+use roxmltree::{Document, Node, NodeId};
+use salsa20::Salsa20;
+use secrecy::{ExposeSecret, SecretString};
+use sha2::{Digest, Sha256, Sha512};
+
+const AES256_CBC: [u8; 16] = uuid(0x31c1_f2e6_bf71_4350_be58_0521_6afc_5aff);
+const CHACHA20: [u8; 16] = uuid(0xd603_8a2b_8b6f_4cb5_a524_339a_31db_b59a);
+const AES_KDF: [u8; 16] = uuid(0xc9d9_f39a_628a_4460_bf74_0d08_c18a_4fea);
+const ARGON2D: [u8; 16] = uuid(0xef63_6ddf_8c29_444b_91f7_a9a4_03e3_0a0c);
+const ARGON2ID: [u8; 16] = uuid(0x9e29_8b19_56db_4773_b23d_fc3e_c6f0_a1e6);
+
+const SALSA20_NONCE: [u8; 8] = [0xE8, 0x30, 0x09, 0x4B, 0x97, 0x20, 0x5D, 0x2A];
+
+const fn uuid(value: u128) -> [u8; 16] {
+    value.to_be_bytes()
+}
+
+/// Read the entries of the database at `path`, unlocking it with a master password and,
+/// optionally, a key file. Entries are named by their title and username. Old versions of
+/// entries, entries in the recycle bin, and entries without passwords are skipped.
+pub fn read_accounts(
+    path: &Path,
+    password: &SecretString,
+    key_file: Option<&Path>,
+) -> io::Result<Vec<Account>> {
+    let key_file = match key_file {
+        Some(key_file) => Some(read_key_file(&fs::read(key_file)?)?),
+        None => None,
+    };
+    let payload = decrypt(&fs::read(path)?, password.expose_secret(), key_file)?;
+    let (mut stream, start) = inner_header(&payload)?;
+    let xml = std::str::from_utf8(&payload[start..])
+        .map_err(|_| invalid("the database isn't valid UTF-8"))?;
+    let document = Document::parse(xml)
+        .map_err(|err| invalid(&format!("the database is corrupt: {}", err)))?;
+
+    entries(&document, &mut stream)
+}
+
+/// Decrypt a KDBX 4 file, returning its payload: the inner header followed by the XML.
+fn decrypt(file: &[u8], password: &str, key_file: Option<[u8; 32]>) -> io::Result<Vec<u8>> {
+    let mut bytes = Bytes(file);
+
+    if bytes.take(8)? != KDBX_SIGNATURE {
+        return Err(invalid("not a KeePass database"));
+    }
+
+    let major = bytes.u32()? >> 16;
+
+    if major != 4 {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "KDBX {}.x databases aren't supported; save it as KDBX 4 in KeePass or KeePassXC",
+                major
+            ),
+        ));
+    }
+
+    let header = Header::read(&mut bytes)?;
+    let header_bytes = &file[..file.len() - bytes.0.len()];
+    let header_hash = bytes.take(32)?;
+    let header_mac = bytes.take(32)?;
+
+    if Sha256::digest(header_bytes).as_slice() != header_hash {
+        return Err(invalid("the database is corrupt"));
+    }
+
+    let composite = composite_key(password, key_file);
+    let transformed = header.kdf.transform(&composite)?;
+    let cipher_key = Sha256::new()
+        .chain_update(&header.master_seed)
+        .chain_update(transformed)
+        .finalize();
+    let mac_key: [u8; 64] = Sha512::new()
+        .chain_update(&header.master_seed)
+        .chain_update(transformed)
+        .chain_update([1])
+        .finalize()
+        .into();
+
+    if block_mac(&mac_key, u64::MAX, &[header_bytes])
+        .verify_slice(header_mac)
+        .is_err()
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the master password or key file is wrong",
+        ));
+    }
+
+    let mut payload = Vec::new();
+
+    for index in 0.. {
+        let mac = bytes.take(32)?;
+        let size = bytes.take(4)?;
+        let block = bytes.take(Bytes(size).u32()? as usize)?;
+
+        if block_mac(&mac_key, index, &[&index.to_le_bytes(), size, block])
+            .verify_slice(mac)
+            .is_err()
+        {
+            return Err(invalid("the database is corrupt"));
+        }
+        if block.is_empty() {
+            break;
+        }
+
+        payload.extend_from_slice(block);
+    }
+
+    let mut payload = if header.cipher == AES256_CBC {
+        cbc::Decryptor::<Aes256>::new_from_slices(&cipher_key, &header.iv)
+            .map_err(|_| invalid("the database is corrupt"))?
+            .decrypt_padded_mut::<Pkcs7>(&mut payload)
+            .map_err(|_| invalid("the database is corrupt"))?
+            .to_vec()
+    } else {
+        ChaCha20::new_from_slices(&cipher_key, &header.iv)
+            .map_err(|_| invalid("the database is corrupt"))?
+            .apply_keystream(&mut payload);
+        payload
+    };
+
+    if header.compressed {
+        let mut decompressed = Vec::new();
+
+        GzDecoder::new(payload.as_slice()).read_to_end(&mut decompressed)?;
+        payload = decompressed;
+    }
+
+    Ok(payload)
+}
+
+/// The fields of the outer header that are needed to decrypt the database.
+struct Header {
+    cipher: [u8; 16],
+    compressed: bool,
+    master_seed: Vec<u8>,
+    iv: Vec<u8>,
+    kdf: Kdf,
+}
+
+impl Header {
+    fn read(bytes: &mut Bytes) -> io::Result<Header> {
+        let mut fields = HashMap::new();
+
+        loop {
+            let id = bytes.u8()?;
+            let size = bytes.u32()? as usize;
+            let data = bytes.take(size)?;
+
+            if id == 0 {
+                break;
+            }
+
+            fields.insert(id, data);
+        }
+
+        let field = |id| {
+            fields
+                .get(&id)
+                .cloned()
+                .ok_or_else(|| invalid("the database header is incomplete"))
+        };
+        let cipher = <[u8; 16]>::try_from(field(2)?)
+            .map_err(|_| invalid("the database header is corrupt"))?;
+
+        if cipher != AES256_CBC && cipher != CHACHA20 {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "only databases encrypted with AES or ChaCha20 are supported",
+            ));
+        }
+
+        Ok(Header {
+            cipher,
+            compressed: Bytes(field(3)?).u32()? == 1,
+            master_seed: field(4)?.to_vec(),
+            iv: field(7)?.to_vec(),
+            kdf: Kdf::read(field(11)?)?,
+        })
+    }
+}
+
+/// How the master key is stretched.
+enum Kdf {
+    Aes {
+        seed: Vec<u8>,
+        rounds: u64,
+    },
+    Argon2 {
+        algorithm: Algorithm,
+        version: Version,
+        salt: Vec<u8>,
+        params: Params,
+    },
+}
+
+impl Kdf {
+    /// Read the KDF's parameters, which are stored as a dictionary of typed values.
+    fn read(data: &[u8]) -> io::Result<Kdf> {
+        let mut bytes = Bytes(data);
+        let mut values = HashMap::new();
+
+        if bytes.take(2)?[1] != 1 {
+            return Err(invalid("the database's KDF parameters are corrupt"));
+        }
+
+        loop {
+            let kind = bytes.u8()?;
+
+            if kind == 0 {
+                break;
+            }
+
+            let name_length = bytes.u32()? as usize;
+            let name = bytes.take(name_length)?;
+            let value_length = bytes.u32()? as usize;
+
+            values.insert(name, bytes.take(value_length)?);
+        }
+
+        let value = |name: &str| {
+            values
+                .get(name.as_bytes())
+                .cloned()
+                .ok_or_else(|| invalid("the database's KDF parameters are incomplete"))
+        };
+        let number = |name: &str| -> io::Result<u64> {
+            match value(name)?.len() {
+                4 => Bytes(value(name)?).u32().map(u64::from),
+                _ => Bytes(value(name)?).u64(),
+            }
+        };
+        let kdf = value("$UUID")?;
+
+        if kdf == AES_KDF {
+            return Ok(Kdf::Aes {
+                seed: value("S")?.to_vec(),
+                rounds: number("R")?,
+            });
+        }
+
+        let algorithm = if kdf == ARGON2D {
+            Algorithm::Argon2d
+        } else if kdf == ARGON2ID {
+            Algorithm::Argon2id
+        } else {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the database uses an unknown KDF",
+            ));
+        };
+        let version = match number("V")? {
+            0x10 => Version::V0x10,
+            _ => Version::V0x13,
+        };
+        let params = Params::new(
+            (number("M")? / 1024) as u32,
+            number("I")? as u32,
+            number("P")? as u32,
+            Some(32),
+        )
+        .map_err(|err| {
+            invalid(&format!(
+                "the database's KDF parameters are invalid: {}",
+                err
+            ))
+        })?;
+
+        Ok(Kdf::Argon2 {
+            algorithm,
+            version,
+            salt: value("S")?.to_vec(),
+            params,
+        })
+    }
+
+    fn transform(&self, key: &[u8; 32]) -> io::Result<[u8; 32]> {
+        match *self {
+            Kdf::Aes { ref seed, rounds } => {
+                let cipher = Aes256::new_from_slice(seed)
+                    .map_err(|_| invalid("the database's KDF parameters are invalid"))?;
+                let mut blocks = [
+                    *GenericArray::from_slice(&key[..16]),
+                    *GenericArray::from_slice(&key[16..]),
+                ];
+
+                for _ in 0..rounds {
+                    cipher.encrypt_blocks(&mut blocks);
+                }
+
+                Ok(Sha256::new()
+                    .chain_update(blocks[0])
+                    .chain_update(blocks[1])
+                    .finalize()
+                    .into())
+            }
+            Kdf::Argon2 {
+                algorithm,
+                version,
+                ref salt,
+                ref params,
+            } => {
+                let mut transformed = [0; 32];
+
+                Argon2::new(algorithm, version, params.clone())
+                    .hash_password_into(key, salt, &mut transformed)
+                    .map_err(|err| invalid(&format!("couldn't derive the key: {}", err)))?;
+
+                Ok(transformed)
+            }
+        }
+    }
+}
+
+/// Combine the master password and key file the way KeePass does. The password is left out if
+/// it's empty and there's a key file, since that's how a database without one is opened.
+fn composite_key(password: &str, key_file: Option<[u8; 32]>) -> [u8; 32] {
+    let mut composite = Sha256::new();
+
+    if !password.is_empty() || key_file.is_none() {
+        composite.update(Sha256::digest(password.as_bytes()));
+    }
+    if let Some(key_file) = key_file {
+        composite.update(key_file);
+    }
+
+    composite.finalize().into()
+}
+
+/// The key in a key file. KeePass's XML key files are read, 32 byte and hex files are used as
+/// they are, and anything else is hashed.
+fn read_key_file(data: &[u8]) -> io::Result<[u8; 32]> {
+    let xml = std::str::from_utf8(data)
+        .ok()
+        .and_then(|text| Document::parse(text).ok());
+
+    if let Some(document) = xml {
+        let root = document.root_element();
+        let version = find_text(root, &["Meta", "Version"]).unwrap_or_default();
+        let data = find_text(root, &["Key", "Data"]).unwrap_or_default();
+        let key = if version.starts_with('1') {
+            BASE64.decode(data.trim()).ok()
+        } else {
+            decode_hex(data)
+        };
+
+        return key
+            .and_then(|key| <[u8; 32]>::try_from(key).ok())
+            .ok_or_else(|| invalid("the key file is corrupt"));
+    }
+
+    if let Ok(key) = <[u8; 32]>::try_from(data) {
+        return Ok(key);
+    }
+
+    let hex = std::str::from_utf8(data).ok().and_then(decode_hex);
+
+    match hex.and_then(|key| <[u8; 32]>::try_from(key).ok()) {
+        Some(key) if data.len() == 64 => Ok(key),
+        _ => Ok(Sha256::digest(data).into()),
+    }
+}
+
+fn find_text<'a>(node: Node<'a, '_>, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(node, |node, name| {
+            node.children().find(|child| child.has_tag_name(*name))
+        })
+        .and_then(|node| node.text())
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .map(|character| character.to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<_>>()?;
+
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+
+    Some(
+        digits
+            .chunks(2)
+            .map(|pair| (pair[0] << 4) | pair[1])
+            .collect(),
+    )
+}
+
+/// The MAC of a block, or of the header with `u64::MAX` as its index. Each block gets its own key.
+fn block_mac(key: &[u8; 64], index: u64, data: &[&[u8]]) -> Hmac<Sha256> {
+    let block_key = Sha512::new()
+        .chain_update(index.to_le_bytes())
+        .chain_update(key)
+        .finalize();
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&block_key).expect("any key length");
+
+    for data in data {
+        mac.update(data);
+    }
+
+    mac
+}
+
+/// The cipher that protects values like passwords inside the decrypted database.
+enum InnerStream {
+    ChaCha20(ChaCha20),
+    Salsa20(Salsa20),
+}
+
+impl InnerStream {
+    fn new(id: u32, key: &[u8]) -> io::Result<InnerStream> {
+        match id {
+            2 => {
+                let key = Sha256::digest(key);
+
+                Ok(InnerStream::Salsa20(Salsa20::new(
+                    &key,
+                    GenericArray::from_slice(&SALSA20_NONCE),
+                )))
+            }
+            3 => {
+                let hash = Sha512::digest(key);
+
+                Ok(InnerStream::ChaCha20(ChaCha20::new(
+                    GenericArray::from_slice(&hash[..32]),
+                    GenericArray::from_slice(&hash[32..44]),
+                )))
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "the database protects its values with an unknown cipher",
+            )),
+        }
+    }
+
+    fn decrypt(&mut self, value: &mut [u8]) {
+        match *self {
+            InnerStream::ChaCha20(ref mut cipher) => cipher.apply_keystream(value),
+            InnerStream::Salsa20(ref mut cipher) => cipher.apply_keystream(value),
+        }
+    }
+}
+
+/// Split the inner header off the decrypted payload, returning the cipher for protected values
+/// and where the XML starts.
+fn inner_header(payload: &[u8]) -> io::Result<(InnerStream, usize)> {
+    let mut bytes = Bytes(payload);
+    let mut stream = None;
+    let mut key = None;
+
+    loop {
+        let id = bytes.u8()?;
+        let size = bytes.u32()? as usize;
+        let data = bytes.take(size)?;
+
+        match id {
+            0 => break,
+            1 => stream = Some(Bytes(data).u32()?),
+            2 => key = Some(data),
+            _ => {}
+        }
+    }
+
+    match (stream, key) {
+        (Some(stream), Some(key)) => Ok((
+            InnerStream::new(stream, key)?,
+            payload.len() - bytes.0.len(),
+        )),
+        _ => Err(invalid("the database's inner header is incomplete")),
+    }
+}
+
+/// The accounts in the database's XML. Protected values have to be decrypted in the order they
+/// appear, including the ones that are skipped.
+fn entries(document: &Document, stream: &mut InnerStream) -> io::Result<Vec<Account>> {
+    let mut protected: HashMap<NodeId, SecretString> = HashMap::new();
+
+    for node in document.descendants() {
+        if node.has_tag_name("Value") && node.attribute("Protected") == Some("True") {
+            let mut value = BASE64
+                .decode(node.text().unwrap_or_default().trim())
+                .map_err(|_| invalid("the database is corrupt"))?;
+
+            stream.decrypt(&mut value);
+
+            let value =
+                String::from_utf8(value).map_err(|_| invalid("the database isn't valid UTF-8"))?;
+
+            protected.insert(node.id(), SecretString::from(value));
+        }
+    }
+
+    let meta = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("Meta"));
+    let recycle_bin = meta
+        .filter(|&meta| find_text(meta, &["RecycleBinEnabled"]) != Some("False"))
+        .and_then(|meta| find_text(meta, &["RecycleBinUUID"]));
+    let mut accounts = Vec::new();
+
+    for (index, entry) in document
+        .descendants()
+        .filter(|node| node.has_tag_name("Entry"))
+        .enumerate()
+    {
+        let skipped = entry.ancestors().skip(1).any(|ancestor| {
+            ancestor.has_tag_name("History")
+                || (ancestor.has_tag_name("Group")
+                    && recycle_bin.is_some()
+                    && find_text(ancestor, &["UUID"]) == recycle_bin)
+        });
+
+        if skipped {
+            continue;
+        }
+
+        let mut title = "";
+        let mut username = "";
+        let mut password = "";
+
+        for string in entry.children().filter(|node| node.has_tag_name("String")) {
+            let value = match string.children().find(|node| node.has_tag_name("Value")) {
+                Some(value) => value,
+                None => continue,
+            };
+            let text = match protected.get(&value.id()) {
+                Some(secret) => secret.expose_secret(),
+                None => value.text().unwrap_or_default(),
+            };
+
+            match find_text(string, &["Key"]) {
+                Some("Title") => title = text,
+                Some("UserName") => username = text,
+                Some("Password") => password = text,
+                _ => {}
+            }
+        }
+
+        if password.is_empty() {
+            continue;
+        }
+
+        accounts.push(Account {
+            name: account_name(title, username)
+                .unwrap_or_else(|| format!("untitled entry {}", index + 1)),
+            password: SecretString::from(password),
+        });
+    }
+
+    Ok(accounts)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// A little-endian reader over a byte slice.
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Bytes<'a> {
+    fn take(&mut self, length: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < length {
+            return Err(invalid("the database is truncated"));
+        }
+
+        let (taken, rest) = self.0.split_at(length);
+
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let mut value = [0; 4];
+
+        value.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(value))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut value = [0; 8];
+
+        value.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(value))
+    }
+}
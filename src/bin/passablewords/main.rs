@@ -0,0 +1,131 @@
+//! The `passablewords` command line tool, for checking and generating passwords without writing
+//! any code. Only built with the `cli` feature.
+
+#[cfg(feature = "kdbx")]
+extern crate aes;
+#[cfg(feature = "kdbx")]
+extern crate argon2;
+#[cfg(feature = "kdbx")]
+extern crate base64;
+#[cfg(feature = "kdbx")]
+extern crate cbc;
+#[cfg(feature = "kdbx")]
+extern crate chacha20;
+extern crate clap;
+extern crate csv;
+#[cfg(feature = "kdbx")]
+extern crate flate2;
+#[cfg(feature = "kdbx")]
+extern crate hmac;
+extern crate notify;
+extern crate passablewords;
+#[cfg(feature = "kdbx")]
+extern crate roxmltree;
+extern crate rpassword;
+#[cfg(feature = "kdbx")]
+extern crate salsa20;
+extern crate secrecy;
+#[macro_use]
+extern crate serde;
+extern crate serde_json;
+#[cfg(feature = "kdbx")]
+extern crate sha2;
+#[cfg(feature = "webhook")]
+extern crate ureq;
+
+mod accounts;
+mod audit;
+mod breaches;
+mod check;
+mod checkpoint;
+#[cfg(unix)]
+mod daemon;
+mod generate;
+#[cfg(feature = "kdbx")]
+mod kdbx;
+mod output;
+mod sink;
+mod watch;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use output::Status;
+use passablewords::{PasswordChecker, PasswordError, Wordlist};
+
+#[derive(Parser)]
+#[command(name = "passablewords", version, about)]
+struct Cli {
+    /// A file of common passwords, one per line, to use instead of the bundled list
+    #[arg(long, global = true, value_name = "PATH")]
+    wordlist: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check every password in a CSV file of accounts and report weak or shared ones
+    Audit(audit::Args),
+    /// Check passwords against the default policy
+    Check(check::Args),
+    /// Keep the list of common passwords loaded and answer checks on a Unix socket
+    #[cfg(unix)]
+    Daemon(daemon::Args),
+    /// Generate passwords or passphrases which pass the checks
+    Generate(generate::Args),
+}
+
+fn main() -> ExitCode {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+
+            return if err.use_stderr() {
+                Status::Usage.into()
+            } else {
+                // --help and --version
+                Status::Pass.into()
+            };
+        }
+    };
+    let checker = match cli.wordlist {
+        Some(ref path) => match Wordlist::from_file(path) {
+            Ok(wordlist) => PasswordChecker::with_wordlist(wordlist),
+            Err(err) => {
+                eprintln!("couldn't read {}: {}", path.display(), err);
+                return Status::WordlistMissing.into();
+            }
+        },
+        None => PasswordChecker::new(),
+    };
+
+    if checker.wordlist().is_none() {
+        match Wordlist::load_bundled() {
+            Err(PasswordError::WordlistUnavailable { path, source }) => eprintln!(
+                "couldn't read the bundled list of common passwords{}: {}. Or pass --wordlist",
+                path.map(|path| format!(" from {}", path))
+                    .unwrap_or_default(),
+                source
+            ),
+            _ => eprintln!(
+                "couldn't read the bundled list of common passwords; run from the root of the \
+                 repository or pass --wordlist"
+            ),
+        }
+        return Status::WordlistMissing.into();
+    }
+
+    let status = match cli.command {
+        Command::Audit(ref args) => audit::run(args, &checker),
+        Command::Check(ref args) => check::run(args, &checker),
+        #[cfg(unix)]
+        Command::Daemon(ref args) => daemon::run(args, &checker),
+        Command::Generate(ref args) => generate::run(args, &checker),
+    };
+
+    status.into()
+}
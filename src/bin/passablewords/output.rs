@@ -0,0 +1,38 @@
+//! The parts of the command line tool's output that scripts rely on. These only change in a new
+//! major version.
+
+use std::process::ExitCode;
+
+/// The version of the JSON written by every subcommand, in its `schema_version` field. It's
+/// bumped whenever a field is removed or changes meaning. Adding a field doesn't bump it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Why the tool exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Every password passed, or the subcommand succeeded. Exits with 0.
+    Pass,
+    /// At least one password was rejected by the policy, or an audit found something. Exits
+    /// with 2.
+    PolicyFailure,
+    /// Something went wrong, like an unreadable input file or a password that couldn't be
+    /// checked, so there's no answer. Exits with 3.
+    InternalError,
+    /// The list of common passwords couldn't be read. Exits with 4.
+    WordlistMissing,
+    /// The arguments couldn't be parsed. Exits with 64, from `sysexits.h`, rather than clap's
+    /// usual 2, which would look like a policy failure.
+    Usage,
+}
+
+impl From<Status> for ExitCode {
+    fn from(status: Status) -> ExitCode {
+        ExitCode::from(match status {
+            Status::Pass => 0,
+            Status::PolicyFailure => 2,
+            Status::InternalError => 3,
+            Status::WordlistMissing => 4,
+            Status::Usage => 64,
+        })
+    }
+}
@@ -0,0 +1,286 @@
+//! Where audit findings are sent. Every finding goes to each sink as it's reported, so they can
+//! land in a chat channel or a SIEM as well as the report.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::net::UnixDatagram;
+#[cfg(feature = "webhook")]
+use std::time::Duration;
+
+use audit::{Args, Findings, Kind};
+use output::SCHEMA_VERSION;
+use passablewords::PasswordError;
+use serde_json;
+#[cfg(feature = "webhook")]
+use ureq;
+
+/// One finding about one file. A file has a finding for every rejected account, every group of
+/// accounts sharing a password or with similar passwords, and every breached account.
+#[derive(Serialize)]
+pub struct Finding<'a> {
+    pub schema_version: u32,
+    pub file: &'a str,
+    pub finding: Kind,
+    pub accounts: Vec<&'a str>,
+    pub priority: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<PasswordError>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<&'static str>,
+}
+
+impl<'a> Finding<'a> {
+    /// Every finding in an audit of `file`, most urgent first.
+    pub fn all(
+        file: &'a str,
+        findings: &Findings,
+        names: &'a HashMap<usize, String>,
+    ) -> Vec<Finding<'a>> {
+        let mut groups: Vec<(Kind, Vec<usize>)> = Vec::new();
+
+        for (&index, risk) in &findings.risks {
+            if risk.error.is_some() {
+                groups.push((Kind::Rejected, vec![index]));
+            }
+            if risk.breached {
+                groups.push((Kind::Breached, vec![index]));
+            }
+        }
+
+        groups.extend(
+            findings
+                .shared
+                .iter()
+                .map(|group| (Kind::Shared, group.clone())),
+        );
+        groups.extend(
+            findings
+                .similar
+                .iter()
+                .flatten()
+                .map(|group| (Kind::Similar, group.clone())),
+        );
+        // A stable sort keeps findings with the same priority in the order they were found
+        groups.sort_by_key(|(_, group)| Reverse(findings.priority(group)));
+
+        groups
+            .into_iter()
+            .map(|(kind, group)| {
+                let error = match kind {
+                    Kind::Rejected => findings.risks[&group[0]].error.clone(),
+                    _ => None,
+                };
+
+                Finding {
+                    schema_version: SCHEMA_VERSION,
+                    file,
+                    finding: kind,
+                    accounts: group.iter().map(|index| names[index].as_str()).collect(),
+                    priority: findings.priority(&group),
+                    reason: error.as_ref().map(PasswordError::explanation),
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// A line describing the finding for people, naming the first few accounts.
+    fn summary(&self) -> String {
+        const SHOWN: usize = 5;
+
+        let mut accounts = self.accounts[..self.accounts.len().min(SHOWN)].join(", ");
+
+        if self.accounts.len() > SHOWN {
+            accounts.push_str(&format!(" and {} more", self.accounts.len() - SHOWN));
+        }
+
+        format!(
+            "{} finding in {} (priority {}): {}",
+            self.finding.as_str(),
+            self.file,
+            self.priority,
+            accounts
+        )
+    }
+}
+
+/// Somewhere findings are sent.
+pub trait OutputSink {
+    /// What the sink is called in error messages. It shouldn't include anything secret, like a
+    /// webhook's URL.
+    fn name(&self) -> &'static str;
+
+    /// Send one finding.
+    fn send(&mut self, finding: &Finding) -> io::Result<()>;
+
+    /// Make sure everything sent so far has arrived.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes each finding as a line of JSON, to standard output or a file.
+pub struct JsonLines<W> {
+    out: W,
+}
+
+impl<W: Write> JsonLines<W> {
+    pub fn new(out: W) -> JsonLines<W> {
+        JsonLines { out }
+    }
+}
+
+impl<W: Write> OutputSink for JsonLines<W> {
+    fn name(&self) -> &'static str {
+        "report"
+    }
+
+    fn send(&mut self, finding: &Finding) -> io::Result<()> {
+        serde_json::to_writer(&mut self.out, finding)?;
+        writeln!(self.out)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Posts each finding as JSON to a webhook, like a Slack incoming webhook or a SIEM's HTTP
+/// collector. Along with the finding's fields, the JSON has a `text` field describing it, which
+/// is what Slack shows.
+#[cfg(feature = "webhook")]
+pub struct Webhook {
+    agent: ureq::Agent,
+    url: String,
+}
+
+#[cfg(feature = "webhook")]
+impl Webhook {
+    pub fn new(url: &str) -> Webhook {
+        Webhook {
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("passablewords/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(30))
+                .build(),
+            url: url.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl OutputSink for Webhook {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send(&mut self, finding: &Finding) -> io::Result<()> {
+        let mut body = serde_json::to_value(finding)?;
+
+        body["text"] = serde_json::Value::String(finding.summary());
+
+        match self
+            .agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+        {
+            Ok(_) => Ok(()),
+            // The error's description includes the URL, which is usually a secret
+            Err(ureq::Error::Status(status, _)) => Err(io::Error::other(format!(
+                "the webhook answered with status {}",
+                status
+            ))),
+            Err(ureq::Error::Transport(transport)) => Err(io::Error::other(format!(
+                "couldn't reach the webhook: {}",
+                transport.kind()
+            ))),
+        }
+    }
+}
+
+/// Sends each finding to the local syslog daemon, under the `authpriv` facility at `warning`
+/// severity. The message is the finding's summary followed by its JSON.
+#[cfg(unix)]
+pub struct Syslog {
+    socket: UnixDatagram,
+}
+
+#[cfg(unix)]
+impl Syslog {
+    /// Connect to the syslog daemon's socket, at `/dev/log` on Linux and `/var/run/syslog` on
+    /// macOS.
+    pub fn connect() -> io::Result<Syslog> {
+        let socket = UnixDatagram::unbound()?;
+
+        socket
+            .connect("/dev/log")
+            .or_else(|_| socket.connect("/var/run/syslog"))?;
+
+        Ok(Syslog { socket })
+    }
+}
+
+#[cfg(unix)]
+impl OutputSink for Syslog {
+    fn name(&self) -> &'static str {
+        "syslog"
+    }
+
+    fn send(&mut self, finding: &Finding) -> io::Result<()> {
+        // authpriv is facility 10 and warning is severity 4
+        const PRIORITY: u8 = 10 * 8 + 4;
+
+        let message = format!(
+            "<{}>passablewords[{}]: {} {}",
+            PRIORITY,
+            std::process::id(),
+            finding.summary(),
+            serde_json::to_string(finding)?
+        );
+
+        self.socket.send(message.as_bytes()).map(|_| ())
+    }
+}
+
+/// The sinks findings are sent to besides the report, from `--webhook` and `--syslog`.
+pub fn open_sinks(args: &Args) -> io::Result<Vec<Box<dyn OutputSink>>> {
+    let mut sinks: Vec<Box<dyn OutputSink>> = Vec::new();
+
+    #[cfg(feature = "webhook")]
+    {
+        if let Some(ref url) = args.webhook {
+            sinks.push(Box::new(Webhook::new(url)));
+        }
+    }
+    #[cfg(unix)]
+    {
+        if args.syslog {
+            sinks.push(Box::new(Syslog::connect()?));
+        }
+    }
+
+    Ok(sinks)
+}
+
+/// Send every finding to every sink. A sink which fails is reported on standard error and
+/// skipped for the rest of the findings, so one unreachable sink doesn't hold up the others.
+/// Returns whether every sink got every finding.
+pub fn send_all(sinks: &mut [Box<dyn OutputSink>], findings: &[Finding]) -> bool {
+    let mut delivered = true;
+
+    for sink in sinks.iter_mut() {
+        let sent = findings
+            .iter()
+            .try_for_each(|finding| sink.send(finding))
+            .and_then(|()| sink.flush());
+
+        if let Err(err) = sent {
+            eprintln!("couldn't send findings to the {}: {}", sink.name(), err);
+            delivered = false;
+        }
+    }
+
+    delivered
+}
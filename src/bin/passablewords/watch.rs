@@ -0,0 +1,186 @@
+//! `passablewords audit --watch`, which audits credential exports as they're dropped into a
+//! directory, for scheduled jobs that export to a shared location.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use audit::{audit_input, open_breaches, Args};
+use breaches::Breaches;
+use notify::event::{AccessKind, AccessMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use output::Status;
+use passablewords::PasswordChecker;
+use sink::{open_sinks, send_all, Finding, JsonLines, OutputSink};
+
+/// How long a file has to go without changing before it's audited, so that files which are
+/// still being written aren't read half way through.
+const SETTLE: Duration = Duration::from_secs(1);
+
+/// How often to look for files which have settled.
+const POLL: Duration = Duration::from_millis(250);
+
+/// Audit every CSV file that's created in or moved into `dir` until the process is interrupted.
+/// Files which were there before it started, hidden files, and files that have already been
+/// audited are skipped. Only returns if the directory can't be watched or the report can't be
+/// written.
+pub fn run(args: &Args, checker: &PasswordChecker, dir: &Path) -> Status {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = match notify::recommended_watcher(move |event| {
+        let _ = sender.send(event);
+    }) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            eprintln!("couldn't watch {}: {}", dir.display(), err);
+            return Status::InternalError;
+        }
+    };
+
+    if let Err(err) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+        eprintln!("couldn't watch {}: {}", dir.display(), err);
+        return Status::InternalError;
+    }
+
+    let out: Box<dyn Write> = match args.out {
+        Some(ref path) => match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => Box::new(file),
+            Err(err) => {
+                eprintln!("couldn't open {}: {}", path.display(), err);
+                return Status::InternalError;
+            }
+        },
+        None => Box::new(io::stdout()),
+    };
+    let mut out = JsonLines::new(out);
+    let mut sinks = match open_sinks(args) {
+        Ok(sinks) => sinks,
+        Err(err) => {
+            eprintln!("couldn't open the syslog socket: {}", err);
+            return Status::InternalError;
+        }
+    };
+    let breaches = open_breaches(args);
+    let mut pending = HashMap::new();
+    let mut audited = HashSet::new();
+
+    if !args.quiet {
+        eprintln!("watching {} for new CSV files", dir.display());
+    }
+
+    loop {
+        match receiver.recv_timeout(POLL) {
+            Ok(Ok(event)) => {
+                let changed = matches!(
+                    event.kind,
+                    EventKind::Create(_)
+                        | EventKind::Modify(_)
+                        | EventKind::Access(AccessKind::Close(AccessMode::Write))
+                );
+
+                if changed {
+                    for path in event.paths {
+                        if is_export(&path) && !audited.contains(&path) {
+                            pending.insert(path, Instant::now());
+                        }
+                    }
+                }
+            }
+            Ok(Err(err)) => eprintln!("error while watching {}: {}", dir.display(), err),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => {
+                eprintln!("stopped watching {}", dir.display());
+                return Status::InternalError;
+            }
+        }
+
+        let now = Instant::now();
+        let settled: Vec<PathBuf> = pending
+            .iter()
+            .filter(|&(_, &changed)| now.duration_since(changed) >= SETTLE)
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        for path in settled {
+            pending.remove(&path);
+
+            if let Err(err) = audit_file(
+                args,
+                checker,
+                breaches.as_ref(),
+                &path,
+                &mut out,
+                &mut sinks,
+            ) {
+                eprintln!("couldn't write the report: {}", err);
+                return Status::InternalError;
+            }
+
+            audited.insert(path);
+        }
+    }
+}
+
+/// Whether a file looks like a finished export. Hidden files are usually temporary files that'll
+/// be renamed once they're written.
+fn is_export(path: &Path) -> bool {
+    let hidden = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_none_or(|name| name.starts_with('.'));
+    let csv = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+
+    path.is_file() && !hidden && csv
+}
+
+/// Audit one file, append its findings to `out`, and send them to the other sinks. Files which
+/// can't be read and sinks which fail are reported on standard error and skipped, so only
+/// problems with the report itself are returned.
+fn audit_file(
+    args: &Args,
+    checker: &PasswordChecker,
+    breaches: Option<&Breaches>,
+    path: &Path,
+    out: &mut dyn OutputSink,
+    sinks: &mut [Box<dyn OutputSink>],
+) -> io::Result<()> {
+    let file = path.display().to_string();
+    let (findings, names) = match audit_input(args, checker, breaches, path, false) {
+        Ok(audited) => audited,
+        Err(err) => {
+            eprintln!("couldn't read {}: {}", file, err);
+            return Ok(());
+        }
+    };
+    for index in &findings.failed {
+        eprintln!(
+            "couldn't look up the password for {} in {}",
+            names[index], file
+        );
+    }
+
+    let lines = Finding::all(&file, &findings, &names);
+
+    for finding in &lines {
+        out.send(finding)?;
+    }
+
+    out.flush()?;
+    send_all(sinks, &lines);
+
+    if !args.quiet {
+        eprintln!(
+            "audited {}: {} accounts, {} findings",
+            file,
+            findings.checked,
+            lines.len()
+        );
+    }
+
+    Ok(())
+}
@@ -15,6 +15,15 @@
 //! gaining access to, decrypting, or guessing your user's passwords. It just makes it a little
 //! harder.
 //!
+//! # Panics
+//!
+//! Passwords come from untrusted users, so no public function in this crate panics, whatever the
+//! input. Problems that prevent a check from finishing are returned as errors instead: a list of
+//! common passwords that's missing as `PasswordError::WordlistUnavailable`, and anything else,
+//! like zxcvbn failing unexpectedly, as `PasswordError::InternalError`. The only exception is the
+//! `testing` module, which is meant for tests and documents when it panics. The crate also doesn't
+//! use any `unsafe` code.
+//!
 //! # Example
 //!
 //! ```
@@ -34,191 +43,38 @@
 //!     }
 //! }
 //! ```
-
+//!
+//! # Crates
+//!
+//! This crate re-exports everything from `passablewords-core`, which holds the checks themselves,
+//! along with whichever integrations are enabled. The lists of common passwords live in
+//! `passablewords-data`, and the Have I Been Pwned client in `passablewords-hibp`. Depend on
+//! those directly to build less.
+
+#![forbid(unsafe_code)]
+
+#[cfg(feature = "axum")]
+extern crate axum;
+extern crate passablewords_core;
+#[cfg(feature = "hibp")]
+extern crate passablewords_hibp;
+#[cfg(feature = "axum")]
 #[macro_use]
-extern crate lazy_static;
-extern crate zxcvbn;
-
-use std::fs::File;
-use std::io::prelude::*;
-use std::collections::HashSet;
-use std::iter::FromIterator;
-use zxcvbn::{zxcvbn, ZxcvbnError};
-
-lazy_static! {
-    static ref FILE_CONTENTS: String = {
-        let mut f = File::open("src/common-passwords.txt")
-            .expect("There was a problem opening the list of passwords");
-        let mut file_contents = String::new();
-
-        f.read_to_string(&mut file_contents)
-            .expect("There was a problem reading the common passwords file");
-
-        file_contents
-    };
-    static ref PASSWORDS: HashSet<&'static str> = {
-        HashSet::from_iter(FILE_CONTENTS.lines())
-    };
-}
-
-/// The suite of possible errors returned from passablewords. These represent the three checks made
-/// for length, uniqueness, and entropy. If something goes wrong during the request, an
-/// `InternalError` error is returned.
-#[derive(Debug, PartialEq)]
-pub enum PasswordError {
-    /// The password is less than 8 characters and is therefore too short.
-    TooShort,
-    /// The password is within the list of 1,000,000 most common passwords and should not be used.
-    TooCommon,
-    /// The entropy of the password is too low, which means it could be easily guessable/crackable.
-    /// A more random password should be used instead.
-    TooSimple,
-    /// The password isn't using ascii characters, a requirement that zxcvbn has
-    NonAsciiPassword,
-    /// Something went wrong during the password checks and a normal error couldn't be returned.
-    InternalError,
-}
-
-/// The result type that will be returned from all public functions. It's simply a `Result` type
-/// that either returns `Ok` or a `PasswordError`.
-pub type PassablewordResult = Result<(), PasswordError>;
-
-/// Check a password to make sure it's at least 8 characters long. While this shouldn't be used as
-/// the only password check, it's a good baseline to start from.
-///
-/// # Example (using rocket.rs)
-///
-/// ```
-/// match check_length(password) {
-///     Ok() => status::Ok
-///     Err(err) => match(err) {
-///         PassablewordResult::TooShort => status::BadRequest("Your password should be longer than 8 characters")
-///     }
-/// }
-/// ```
-pub fn check_length(password: &str) -> PassablewordResult {
-    if password.len() >= 8 {
-        Ok(())
-    } else {
-        Err(PasswordError::TooShort)
-    }
-}
-
-/// Check a password to make sure it's not within the top million most common passwords.
-///
-/// # Example (using rocket.rs)
-///
-/// ```
-/// match check_uniqueness(password) {
-///     Ok() => status::Ok
-///     Err(err) => match(err) {
-///         PassablewordResult::TooCommon => status::BadRequest("Your should be more unique")
-///     }
-/// }
-/// ```
-pub fn check_uniqueness(password: &str) -> PassablewordResult {
-    if PASSWORDS.contains(password) {
-        Err(PasswordError::TooCommon)
-    } else {
-        Ok(())
-    }
-}
-
-/// Check a password to make sure random enough that it would take a lot of effort to crack/guess.
-/// This uses the awesome zxcvbn library behind the scenes.
-///
-/// # Example (using rocket.rs)
-///
-/// ```
-/// match check_entropy(password) {
-///     Ok() => status::Ok
-///     Err(err) => match(err) {
-///         PassablewordResult::TooSimple => status::BadRequest("Your should be more random")
-///     }
-/// }
-/// ```
-pub fn check_entropy(password: &str) -> PassablewordResult {
-    match zxcvbn(password, &[]) {
-        Ok(result) => {
-            if result.score >= 3 {
-                Ok(())
-            } else {
-                Err(PasswordError::TooSimple)
-            }
-        }
-        Err(zxcvbn_error) => {
-            match zxcvbn_error {
-                ZxcvbnError::NonAsciiPassword => Err(PasswordError::NonAsciiPassword),
-                _ => Err(PasswordError::InternalError),
-            }
-        }
-    }
-}
-
-/// Check a password's length, uniqueness, and entropy all in a single call. This is a convenience
-/// method and simply calls `check_length`, `check_uniqueness`, and `check_entropy` with the
-/// password supplied.
-///
-/// # Example (using rocket.rs)
-///
-/// ```
-/// match check_password(password) {
-///     Ok() => status::Ok
-///     Err(err) => match(err) {
-///         PassablewordResult::TooShort => status::BadRequest("Your password should be longer than 8 characters"),
-///         PassablewordResult::TooCommon => status::BadRequest("Your should be more unique"),
-///         PassablewordResult::TooSimple => status::BadRequest("Your should be more random"),
-///         PassablewordResult::InternalError => status::InternalServerError
-///     }
-/// }
-/// ```
-pub fn check_password(password: &str) -> PassablewordResult {
-    check_length(password).and(check_uniqueness(password)).and(
-        check_entropy(password),
-    )
-}
-
-#[cfg(test)]
-mod tests {
-    use super::{check_entropy, check_length, check_password, check_uniqueness, PasswordError};
-
-    #[test]
-    fn it_validates_length() {
-        let too_short = check_length("short");
-        let long_enough = check_length("this is a long password");
-
-        assert_eq!(too_short, Err(PasswordError::TooShort));
-        assert_eq!(long_enough, Ok(()));
-    }
-
-    #[test]
-    fn it_validates_uniqueness() {
-        let too_common = check_uniqueness("password");
-        let unique_enough = check_uniqueness("this is a unique password");
-
-        assert_eq!(too_common, Err(PasswordError::TooCommon));
-        assert_eq!(unique_enough, Ok(()));
-    }
-
-    #[test]
-    fn it_validates_entropy() {
-        let too_simple = check_entropy("NotTooRandom");
-        let random_enough = check_entropy("Th1s iS a Sup3rR4ndom PassW0rd!");
-
-        assert_eq!(too_simple, Err(PasswordError::TooSimple));
-        assert_eq!(random_enough, Ok(()));
-    }
-
-    #[test]
-    fn it_validates_a_password() {
-        let too_short = check_password("short");
-        let too_common = check_password("password");
-        let too_simple = check_password("NotTooRandom");
-        let ok_password = check_password("Th1s iS a Sup3rR4ndom PassW0rd!");
-
-        assert_eq!(too_short, Err(PasswordError::TooShort));
-        assert_eq!(too_common, Err(PasswordError::TooCommon));
-        assert_eq!(too_simple, Err(PasswordError::TooSimple));
-        assert_eq!(ok_password, Ok(()));
-    }
+extern crate serde;
+#[cfg(feature = "axum")]
+extern crate tokio;
+
+#[cfg(feature = "axum")]
+pub mod web;
+
+pub use passablewords_core::*;
+#[cfg(feature = "hibp")]
+pub use passablewords_hibp::HibpProvider;
+
+/// Everything a typical integration needs, in a single import, which is `passablewords_core`'s
+/// prelude along with whichever integrations are enabled.
+pub mod prelude {
+    pub use passablewords_core::prelude::*;
+    #[cfg(feature = "hibp")]
+    pub use passablewords_hibp::HibpProvider;
 }
@@ -0,0 +1,555 @@
+//! Handlers for "set password" and "change password" endpoints in axum applications, built on
+//! `PasswordFlow`, along with the responses for its errors and a password policy endpoint for
+//! identity providers. Only available with the `axum` feature.
+//!
+//! # Example
+//!
+//! The handlers look up and store password hashes through a `PasswordStore`, and act on the
+//! account whose `Username` the application's authentication middleware added to the request.
+//!
+//! ```rust,ignore
+//! struct Accounts(PgPool);
+//!
+//! impl PasswordStore for Accounts {
+//!     fn hashes(&self, username: &str) -> io::Result<Option<PasswordHashes>> {
+//!         // Select the account's current and previous password hashes
+//!     }
+//!
+//!     fn store(&self, username: &str, password: &str) -> io::Result<()> {
+//!         // Hash and store `password`, adding the old hash to the account's previous hashes
+//!     }
+//! }
+//!
+//! let flow = PasswordFlow::new(PasswordChecker::new(), BcryptVerifier);
+//! let app = Router::new()
+//!     .merge(password_router(flow, Accounts(pool)))
+//!     .layer(middleware::from_fn(authenticate));
+//! ```
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Extension, Json, Router};
+use passablewords_core::{
+    ChangePassword, FlowError, PasswordChecker, PasswordError, PasswordFlow, PolicyCheckRequest,
+    PolicyCheckResponse, SetPassword,
+};
+use tokio::task::{self, JoinHandle};
+
+/// The path `policy_router` serves `check_policy` on.
+pub const POLICY_ENDPOINT_PATH: &str = "/password-policy";
+
+/// The path `password_router` serves `set_password` on.
+pub const SET_PASSWORD_PATH: &str = "/password/set";
+
+/// The path `password_router` serves `change_password` on.
+pub const CHANGE_PASSWORD_PATH: &str = "/password/change";
+
+/// A `FlowError` that axum can return as a response. The status says what kind of problem it is,
+/// and the body is JSON with the error's code, from `FlowError::code`, and its explanation:
+///
+/// ```json
+/// { "error": "CONFIRMATION_MISMATCH", "message": "The passwords you entered don't match." }
+/// ```
+///
+/// Nothing else about the error is sent, so details like the path of a wordlist that couldn't be
+/// read stay on the server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowRejection(pub FlowError);
+
+impl FlowRejection {
+    /// The status the response is sent with. It's `403 Forbidden` for a wrong current password,
+    /// `422 Unprocessable Entity` for a confirmation that doesn't match or a change that's too
+    /// small, and `PasswordError::http_status` for passwords the checker rejected.
+    pub fn status(&self) -> StatusCode {
+        match self.0 {
+            FlowError::IncorrectCurrentPassword => StatusCode::FORBIDDEN,
+            FlowError::ConfirmationMismatch | FlowError::TooSimilarToCurrent => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            FlowError::Rejected(ref error) => StatusCode::from_u16(error.http_status())
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        }
+    }
+}
+
+impl From<FlowError> for FlowRejection {
+    fn from(error: FlowError) -> FlowRejection {
+        FlowRejection(error)
+    }
+}
+
+impl From<PasswordError> for FlowRejection {
+    fn from(error: PasswordError) -> FlowRejection {
+        FlowRejection(FlowError::Rejected(error))
+    }
+}
+
+#[derive(Serialize)]
+struct Body {
+    error: &'static str,
+    message: &'static str,
+}
+
+impl IntoResponse for FlowRejection {
+    fn into_response(self) -> Response {
+        let body = Body {
+            error: self.0.code(),
+            message: self.0.explanation(),
+        };
+
+        (self.status(), Json(body)).into_response()
+    }
+}
+
+/// The username of the account a request acts on. The application's authentication middleware
+/// adds it to the request's extensions, after checking the session or the reset link, and
+/// `set_password` and `change_password` answer `401 Unauthorized` without it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Username(pub String);
+
+/// An account's password hashes, as `PasswordFlow::change_password` needs them.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PasswordHashes {
+    /// The hash of the account's current password.
+    pub current: String,
+    /// The hashes of the account's previous passwords, which can't be used again.
+    pub previous: Vec<String>,
+}
+
+/// Where `set_password` and `change_password` load an account's password hashes from and store
+/// its new password, such as a table of users. Both are called on tokio's blocking thread pool,
+/// so they can block on a database.
+pub trait PasswordStore: Send + Sync {
+    /// The account's password hashes, or `None` if it doesn't have a password.
+    fn hashes(&self, username: &str) -> io::Result<Option<PasswordHashes>>;
+
+    /// Hash and store the account's new password, which the flow has accepted, adding the old
+    /// hash to its previous ones.
+    fn store(&self, username: &str, password: &str) -> io::Result<()>;
+}
+
+/// The state `set_password` and `change_password` need: the flow that checks requests and the
+/// store that holds the hashes.
+#[derive(Debug)]
+pub struct PasswordEndpoints<T> {
+    flow: PasswordFlow,
+    store: Arc<T>,
+}
+
+impl<T> PasswordEndpoints<T> {
+    /// Create the state for the handlers, checking requests with `flow` and keeping hashes in
+    /// `store`.
+    pub fn new(flow: PasswordFlow, store: T) -> PasswordEndpoints<T> {
+        PasswordEndpoints {
+            flow,
+            store: Arc::new(store),
+        }
+    }
+
+    /// The store hashes are kept in.
+    pub fn store(&self) -> &T {
+        &self.store
+    }
+}
+
+impl<T> Clone for PasswordEndpoints<T> {
+    fn clone(&self) -> PasswordEndpoints<T> {
+        PasswordEndpoints {
+            flow: self.flow.clone(),
+            store: self.store.clone(),
+        }
+    }
+}
+
+/// A handler for setting the password of the account in the request's `Username` without the old
+/// one, such as when signing up or following a reset link. It answers `204 No Content` once the
+/// new password is stored, and a `FlowRejection` if the flow turned it down or the store failed.
+pub fn set_password<T>(
+    State(endpoints): State<PasswordEndpoints<T>>,
+    username: Option<Extension<Username>>,
+    Json(request): Json<SetPassword>,
+) -> SavePassword
+where
+    T: PasswordStore + 'static,
+{
+    SavePassword::spawn(username, move |username| {
+        endpoints.flow.set_password(username, &request)?;
+        endpoints
+            .store
+            .store(username, &request.password)
+            .map_err(|_| PasswordError::InternalError)?;
+        Ok(())
+    })
+}
+
+/// A handler for changing the password of the account in the request's `Username`, which has to
+/// send its current password. It answers like `set_password`, and an account without a password
+/// is treated like a wrong current password.
+pub fn change_password<T>(
+    State(endpoints): State<PasswordEndpoints<T>>,
+    username: Option<Extension<Username>>,
+    Json(request): Json<ChangePassword>,
+) -> SavePassword
+where
+    T: PasswordStore + 'static,
+{
+    SavePassword::spawn(username, move |username| {
+        let hashes = endpoints
+            .store
+            .hashes(username)
+            .map_err(|_| PasswordError::InternalError)?
+            .ok_or(FlowError::IncorrectCurrentPassword)?;
+
+        endpoints
+            .flow
+            .change_password(username, &request, &hashes.current, &hashes.previous)?;
+        endpoints
+            .store
+            .store(username, &request.new_password)
+            .map_err(|_| PasswordError::InternalError)?;
+        Ok(())
+    })
+}
+
+/// The future `set_password` and `change_password` return. The flow and the store run on tokio's
+/// blocking thread pool, since hashing is slow, and a panic there is answered with a
+/// `FlowRejection` for `PasswordError::InternalError`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct SavePassword(Option<JoinHandle<Result<(), FlowRejection>>>);
+
+impl SavePassword {
+    fn spawn<F>(username: Option<Extension<Username>>, save: F) -> SavePassword
+    where
+        F: FnOnce(&str) -> Result<(), FlowError> + Send + 'static,
+    {
+        SavePassword(username.map(|Extension(Username(username))| {
+            task::spawn_blocking(move || save(&username).map_err(FlowRejection))
+        }))
+    }
+}
+
+impl Future for SavePassword {
+    type Output = Response;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let saving = match self.0 {
+            Some(ref mut saving) => saving,
+            None => return Poll::Ready(StatusCode::UNAUTHORIZED.into_response()),
+        };
+
+        Pin::new(saving).poll(cx).map(|result| match result {
+            Ok(Ok(())) => StatusCode::NO_CONTENT.into_response(),
+            Ok(Err(rejection)) => rejection.into_response(),
+            Err(_) => FlowRejection::from(PasswordError::InternalError).into_response(),
+        })
+    }
+}
+
+/// A router serving `set_password` at `SET_PASSWORD_PATH` and `change_password` at
+/// `CHANGE_PASSWORD_PATH`, both as `POST`, to `merge` into an application's router behind its
+/// authentication middleware.
+pub fn password_router<S, T>(flow: PasswordFlow, store: T) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+    T: PasswordStore + 'static,
+{
+    Router::new()
+        .route(SET_PASSWORD_PATH, post(set_password::<T>))
+        .route(CHANGE_PASSWORD_PATH, post(change_password::<T>))
+        .with_state(PasswordEndpoints::new(flow, store))
+}
+
+/// A handler for the password policy endpoint that identity providers like Keycloak call before
+/// accepting a new password, following the contract described in `PolicyCheckRequest`'s module.
+/// It answers every well-formed request with `200 OK` and a `PolicyCheckResponse`, so any other
+/// status means the endpoint itself failed. Requests that aren't JSON, or don't have a password,
+/// get axum's usual `4xx` rejections.
+///
+/// The password is checked on tokio's blocking thread pool, so a slow check doesn't hold up the
+/// runtime's other tasks. Mount it yourself to add authentication, or use `policy_router`.
+pub fn check_policy(
+    State(checker): State<PasswordChecker>,
+    Json(request): Json<PolicyCheckRequest>,
+) -> CheckPolicy {
+    CheckPolicy(task::spawn_blocking(move || {
+        checker.check_policy_request(&request)
+    }))
+}
+
+/// The future `check_policy` returns. It resolves to `500 Internal Server Error` if the check
+/// panicked.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct CheckPolicy(JoinHandle<PolicyCheckResponse>);
+
+impl Future for CheckPolicy {
+    type Output = Result<Json<PolicyCheckResponse>, StatusCode>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx).map(|result| {
+            result
+                .map(Json)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+        })
+    }
+}
+
+/// A router serving `check_policy` at `POLICY_ENDPOINT_PATH` with `checker`, to `merge` into an
+/// application's router or serve on its own.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let app = Router::new()
+///     .merge(policy_router(PasswordChecker::new()))
+///     .layer(ValidateRequestHeaderLayer::bearer(&token));
+/// let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
+///
+/// axum::serve(listener, app).await?;
+/// ```
+pub fn policy_router<S>(checker: PasswordChecker) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::new()
+        .route(POLICY_ENDPOINT_PATH, post(check_policy))
+        .with_state(checker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        change_password, check_policy, password_router, policy_router, set_password, FlowRejection,
+        PasswordEndpoints, PasswordHashes, PasswordStore, Username,
+    };
+    use axum::body::to_bytes;
+    use axum::extract::State;
+    use axum::http::{header, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use axum::{Extension, Json, Router};
+    use passablewords_core::{
+        ChangePassword, FlowError, PasswordChecker, PasswordError, PasswordFlow,
+        PolicyCheckRequest, SetPassword, Wordlist,
+    };
+    use std::collections::HashMap;
+    use std::future::Future;
+    use std::io;
+    use std::sync::Mutex;
+    use std::task::{Context, Poll, Waker};
+    use tokio::runtime::Builder;
+
+    const STRONG: &str = "Th1s iS a Sup3rR4ndom PassW0rd!";
+
+    /// Keeps "hashes" that are the password with `hashed:` in front, in memory.
+    #[derive(Default)]
+    struct Accounts(Mutex<HashMap<String, PasswordHashes>>);
+
+    impl PasswordStore for Accounts {
+        fn hashes(&self, username: &str) -> io::Result<Option<PasswordHashes>> {
+            Ok(self.0.lock().unwrap().get(username).cloned())
+        }
+
+        fn store(&self, username: &str, password: &str) -> io::Result<()> {
+            let mut accounts = self.0.lock().unwrap();
+            let hashes = accounts.entry(username.to_string()).or_default();
+            let previous = std::mem::replace(&mut hashes.current, format!("hashed:{}", password));
+
+            if !previous.is_empty() {
+                hashes.previous.push(previous);
+            }
+
+            Ok(())
+        }
+    }
+
+    fn endpoints() -> PasswordEndpoints<Accounts> {
+        let verifier = |password: &str, hash: &str| format!("hashed:{}", password) == hash;
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+
+        PasswordEndpoints::new(PasswordFlow::new(checker, verifier), Accounts::default())
+    }
+
+    fn respond<F, R>(handle: F) -> StatusCode
+    where
+        F: FnOnce() -> R,
+        R: Future<Output = Response>,
+    {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let _entered = runtime.enter();
+
+        runtime.block_on(handle()).status()
+    }
+
+    fn mike() -> Option<Extension<Username>> {
+        Some(Extension(Username("mike".to_string())))
+    }
+
+    #[test]
+    fn it_picks_a_status() {
+        let statuses = [
+            (FlowError::IncorrectCurrentPassword, StatusCode::FORBIDDEN),
+            (
+                FlowError::ConfirmationMismatch,
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                FlowError::TooSimilarToCurrent,
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                FlowError::Rejected(PasswordError::TooCommon),
+                StatusCode::UNPROCESSABLE_ENTITY,
+            ),
+            (
+                FlowError::Rejected(PasswordError::InputTooLarge),
+                StatusCode::PAYLOAD_TOO_LARGE,
+            ),
+            (
+                FlowError::Rejected(PasswordError::InternalError),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            ),
+        ];
+
+        for &(ref error, status) in statuses.iter() {
+            let response = FlowRejection(error.clone()).into_response();
+
+            assert_eq!(response.status(), status, "{:?}", error);
+            assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        }
+    }
+
+    #[test]
+    fn it_only_sends_the_code_and_explanation() {
+        let error = PasswordError::WordlistUnavailable {
+            path: Some("/srv/secret/wordlist.txt".to_string()),
+            source: "permission denied".to_string(),
+        };
+        let response = FlowRejection::from(error).into_response();
+        let mut body = Box::pin(to_bytes(response.into_body(), usize::MAX));
+        let body = match body.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(body) => body.unwrap(),
+            Poll::Pending => panic!("the body isn't ready"),
+        };
+
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            "{\"error\":\"WORDLIST_UNAVAILABLE\",\"message\":\"We couldn't check your password. \
+             Please try again.\"}"
+        );
+    }
+
+    #[test]
+    fn it_sets_passwords() {
+        let endpoints = endpoints();
+        let request = |password: &str, confirmation: &str| {
+            Json(SetPassword {
+                password: password.to_string(),
+                confirmation: confirmation.to_string(),
+            })
+        };
+        let set = |username, password, confirmation| {
+            respond(|| {
+                set_password(
+                    State(endpoints.clone()),
+                    username,
+                    request(password, confirmation),
+                )
+            })
+        };
+
+        assert_eq!(set(None, STRONG, STRONG), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            set(mike(), STRONG, "something else"),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            set(mike(), "password123", "password123"),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(endpoints.store().hashes("mike").unwrap(), None);
+        assert_eq!(set(mike(), STRONG, STRONG), StatusCode::NO_CONTENT);
+        assert_eq!(
+            endpoints.store().hashes("mike").unwrap().unwrap().current,
+            format!("hashed:{}", STRONG)
+        );
+    }
+
+    #[test]
+    fn it_changes_passwords() {
+        let endpoints = endpoints();
+        let new_password = "Correct Horse Battery Staple 42!";
+        let change = |username, current: &str, new: &str| {
+            respond(|| {
+                change_password(
+                    State(endpoints.clone()),
+                    username,
+                    Json(ChangePassword {
+                        current_password: current.to_string(),
+                        new_password: new.to_string(),
+                        confirmation: new.to_string(),
+                    }),
+                )
+            })
+        };
+
+        assert_eq!(change(mike(), STRONG, new_password), StatusCode::FORBIDDEN);
+
+        endpoints.store().store("mike", STRONG).unwrap();
+
+        assert_eq!(change(None, STRONG, new_password), StatusCode::UNAUTHORIZED);
+        assert_eq!(change(mike(), "wrong", new_password), StatusCode::FORBIDDEN);
+        assert_eq!(
+            change(mike(), STRONG, "password123"),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(change(mike(), STRONG, new_password), StatusCode::NO_CONTENT);
+        assert_eq!(
+            endpoints.store().hashes("mike").unwrap(),
+            Some(PasswordHashes {
+                current: format!("hashed:{}", new_password),
+                previous: vec![format!("hashed:{}", STRONG)],
+            })
+        );
+        assert_eq!(
+            change(mike(), new_password, STRONG),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let _: Router = password_router(
+            PasswordFlow::new(PasswordChecker::new(), |_: &str, _: &str| false),
+            Accounts::default(),
+        );
+    }
+
+    #[test]
+    fn it_checks_policy_requests() {
+        let checker = PasswordChecker::with_wordlist(Wordlist::parse("password123"));
+        let request = PolicyCheckRequest {
+            username: Some("mike".to_string()),
+            password: "password123".to_string(),
+            ..PolicyCheckRequest::default()
+        };
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let _entered = runtime.enter();
+        let Json(response) = runtime
+            .block_on(check_policy(State(checker.clone()), Json(request)))
+            .unwrap();
+
+        assert!(!response.valid);
+        assert!(response
+            .errors
+            .iter()
+            .any(|error| error.code == "TOO_COMMON"));
+
+        let _: Router = policy_router(checker);
+    }
+}
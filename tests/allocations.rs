@@ -0,0 +1,79 @@
+//! Makes sure the uniqueness checks don't allocate for typical passwords, even with normalization
+//! and affix stripping turned on, and that checking a strong password doesn't allocate either.
+//! This lives in its own test binary because it replaces the global allocator.
+
+extern crate passablewords;
+
+use passablewords::{
+    Analysis, CheckContext, CheckOutcome, PasswordChecker, PasswordError, Policy, Wordlist,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// Held while counting, so that tests running on other threads don't add to the count.
+static COUNTING: Mutex<()> = Mutex::new(());
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[test]
+fn it_checks_uniqueness_without_allocating() {
+    let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+        .with_policy(Policy::new().strip_common_affixes(true));
+    let context = CheckContext::new().deny_exact(&["Temp Pass 2931"]);
+    let passwords = [
+        "password",
+        "password2024!",
+        "!1letmein99",
+        "Temp Pass 2931",
+        "TEMPPASS2931",
+        "Th1s iS a Sup3rR4ndom PassW0rd!",
+        "Ünïcödé pässwörd wïth äccents, üp tö 64 bÿtes",
+    ];
+    let _counting = COUNTING.lock().unwrap();
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+
+    for password in &passwords {
+        assert!(password.len() <= 64);
+
+        let _ = checker.check_uniqueness(password);
+        let _ = context.check_denied(password);
+    }
+
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst) - before, 0);
+}
+
+#[test]
+fn it_accepts_strong_passwords_without_allocating() {
+    let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+        .with_analyzer(|_: &str, _: &[&str]| -> Result<Analysis, PasswordError> {
+            Ok(Analysis {
+                score: 4,
+                guesses_log10: 14.0,
+            })
+        });
+    let password = "Th1s iS a Sup3rR4ndom PassW0rd!";
+    let _counting = COUNTING.lock().unwrap();
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+
+    assert!(checker.accepts(password));
+    assert!(checker.check_password_detailed(password) == CheckOutcome::Pass);
+    assert!(checker.feedback(password).items.is_empty());
+    assert_eq!(ALLOCATIONS.load(Ordering::SeqCst) - before, 0);
+}
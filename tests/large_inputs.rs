@@ -0,0 +1,95 @@
+//! Makes sure giant inputs are turned away at every entry point before normalization or zxcvbn
+//! get to see them.
+
+extern crate passablewords;
+
+use passablewords::{
+    check_entropy, check_entropy_with, check_password, AnalysisProfile, Check, CheckOutcome,
+    Normalizer, Outcome, PasswordChecker, PasswordError, Policy, Wordlist, DEFAULT_MAX_INPUT_BYTES,
+};
+
+/// A megabyte of something that isn't a common password, in multi-byte characters so that
+/// anything counting characters instead of bytes would let it through.
+fn megabyte() -> String {
+    "Zé9!".repeat(1 << 18)
+}
+
+fn checker_with(policy: Policy) -> PasswordChecker {
+    PasswordChecker::with_wordlist(Wordlist::parse("password")).with_policy(
+        policy
+            .with_normalizer(Normalizer::new())
+            .strip_common_affixes(true),
+    )
+}
+
+#[test]
+fn it_rejects_giant_inputs_with_the_free_functions() {
+    let password = megabyte();
+
+    assert_eq!(check_password(&password), Err(PasswordError::InputTooLarge));
+    assert_eq!(check_entropy(&password), Err(PasswordError::InputTooLarge));
+    assert_eq!(
+        check_entropy_with(&password, AnalysisProfile::Thorough),
+        Err(PasswordError::InputTooLarge)
+    );
+}
+
+#[test]
+fn it_rejects_giant_inputs_with_a_checker() {
+    let password = megabyte();
+    // Warning about length mustn't let a giant input through to the other checks
+    let checker = checker_with(Policy::new().warn_only(Check::Length));
+
+    assert_eq!(
+        checker.check_password(&password),
+        Err(PasswordError::InputTooLarge)
+    );
+    assert_eq!(
+        checker.report(&password).outcome,
+        Outcome::Rejected(PasswordError::InputTooLarge)
+    );
+    match checker.check_password_detailed(&password) {
+        CheckOutcome::Fail(failures) => {
+            assert_eq!(failures.len(), 1);
+            assert_eq!(failures[0].check, Check::Length);
+            assert_eq!(failures[0].error, PasswordError::InputTooLarge);
+        }
+        outcome => panic!("unexpected outcome {:?}", outcome),
+    }
+}
+
+#[test]
+fn it_checks_inputs_up_to_the_limit() {
+    let policy = Policy::new();
+    let at_limit = "x".repeat(DEFAULT_MAX_INPUT_BYTES);
+    let over_limit = "x".repeat(DEFAULT_MAX_INPUT_BYTES + 1);
+
+    assert_eq!(policy.input_limit(), DEFAULT_MAX_INPUT_BYTES);
+    assert_eq!(policy.check_input_size(&at_limit), Ok(()));
+    assert_eq!(
+        policy.check_input_size(&over_limit),
+        Err(PasswordError::InputTooLarge)
+    );
+    // A two byte character straddling the limit puts it over
+    assert_eq!(
+        policy.check_input_size(&format!("{}é", &at_limit[1..])),
+        Err(PasswordError::InputTooLarge)
+    );
+
+    let checker = checker_with(Policy::new().max_input_bytes(16));
+
+    assert_ne!(
+        checker.check_password("correct horse ba"),
+        Err(PasswordError::InputTooLarge)
+    );
+    assert_eq!(
+        checker.check_password("correct horse bat"),
+        Err(PasswordError::InputTooLarge)
+    );
+    assert_eq!(
+        Policy::new()
+            .max_input_bytes(2 << 20)
+            .check_input_size(&megabyte()),
+        Ok(())
+    );
+}
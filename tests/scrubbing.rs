@@ -0,0 +1,72 @@
+//! Makes sure the copies of a password made while checking it are wiped before they're freed
+//! with the `zeroize` feature. This lives in its own test binary because it replaces the global
+//! allocator, which looks through every freed buffer for the password.
+
+#![cfg(feature = "zeroize")]
+
+extern crate passablewords;
+
+use passablewords::{Analysis, CheckContext, Normalizer, PasswordChecker, Policy, Wordlist};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::slice;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+struct GuardAllocator;
+
+/// The lowercased core of every password below, which only the checks' own copies contain.
+const NEEDLE: &[u8] = b"zqxjvorpal";
+
+static WATCHING: AtomicBool = AtomicBool::new(false);
+static LEAKS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for GuardAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if WATCHING.load(Ordering::SeqCst) {
+            let freed = slice::from_raw_parts(ptr, layout.size());
+
+            if freed.windows(NEEDLE.len()).any(|window| window == NEEDLE) {
+                LEAKS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: GuardAllocator = GuardAllocator;
+
+#[test]
+fn it_wipes_copies_of_the_password() {
+    // zxcvbn makes copies of its own, so it's swapped for an analyzer that doesn't
+    let checker = PasswordChecker::with_wordlist(Wordlist::parse("password\nletmein"))
+        .with_analyzer(|_: &str, _: &[&str]| {
+            Ok(Analysis {
+                score: 4,
+                guesses_log10: 12.0,
+            })
+        })
+        .with_policy(
+            Policy::new()
+                .strip_common_affixes(true)
+                .with_normalizer(Normalizer::new())
+                .reject_word_shells(true),
+        );
+    let context = CheckContext::new().deny_exact(&["Temp Pass 2931"]);
+    let long = format!("ZQXJVORPAL {}", "X".repeat(200));
+    let passwords = ["ZQXJVORPAL2024!", "!ZQXJVORPAL", "ZqXjVoRpAl", long.as_str()];
+
+    WATCHING.store(true, Ordering::SeqCst);
+
+    for password in &passwords {
+        let _ = checker.check_password_with_context(password, &context);
+    }
+
+    WATCHING.store(false, Ordering::SeqCst);
+
+    assert_eq!(LEAKS.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,86 @@
+//! Makes sure audits of huge inputs run in a fixed amount of memory, however many accounts there
+//! are. This lives in its own test binary because it replaces the global allocator.
+
+extern crate passablewords;
+
+use passablewords::audit::{check_stream, SharedPasswordFinder};
+use passablewords::check_length;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+struct TrackingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for TrackingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+
+        PEAK.fetch_max(allocated, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: TrackingAllocator = TrackingAllocator;
+
+#[test]
+fn it_audits_ten_million_accounts_in_fixed_memory() {
+    const ACCOUNTS: u64 = 10_000_000;
+    const CEILING: usize = 32 * 1024 * 1024;
+
+    // Every account has a different password, apart from one in every million, which all share
+    // one. The passwords are made as they're read, like rows from a file.
+    let accounts = (0..ACCOUNTS).map(|user| {
+        let password = if user % 1_000_000 == 0 {
+            "hunter22".to_string()
+        } else {
+            format!("{:x}-{}", user, user % 7)
+        };
+
+        (user, password)
+    });
+    let mut finder = SharedPasswordFinder::new().with_memory_limit(8 * 1024 * 1024);
+    let mut checked = 0;
+    let mut rejected = 0;
+    let before = ALLOCATED.load(Ordering::SeqCst);
+
+    PEAK.store(before, Ordering::SeqCst);
+    check_stream(
+        accounts,
+        4,
+        |password| check_length(password),
+        |user, password, result| {
+            checked += 1;
+            if result.is_err() {
+                rejected += 1;
+            }
+            finder.add(user, &password).unwrap();
+        },
+    );
+
+    let shared = finder.finish().unwrap();
+    let peak = PEAK.load(Ordering::SeqCst) - before;
+
+    assert_eq!(checked, ACCOUNTS);
+    // Users below 0x100000 have passwords shorter than eight characters, apart from the two
+    // sharing `hunter22`
+    assert_eq!(rejected, 0x100000 - 2);
+    assert_eq!(shared.len(), 1);
+    assert_eq!(
+        shared[0].users,
+        (0..10).map(|user| user * 1_000_000).collect::<Vec<_>>()
+    );
+    assert!(
+        peak < CEILING,
+        "used {}MB at most, over the {}MB ceiling",
+        peak / 1024 / 1024,
+        CEILING / 1024 / 1024
+    );
+}